@@ -1,14 +1,16 @@
 use bevy::prelude::*;
+use bevy_egui::EguiContext;
 use iyes_loopless::prelude::*;
 use std::f32::consts::PI;
 use std::time::Duration;
 
 use battleship_plus_common::{
-    game::ship::Ship,
+    game::{direction::direction_step, ship::Ship},
     types::{Coordinate, Direction},
 };
 
 use crate::game_state::{Config, GameState};
+use crate::grid_labels::world_to_egui;
 
 pub struct EffectsPlugin;
 
@@ -22,6 +24,8 @@ impl Plugin for EffectsPlugin {
             .add_system(initialize_torpedo_effects.run_in_state(GameState::Game))
             .add_system(initialize_hit_effects.run_in_state(GameState::Game))
             .add_system(animate_hit_material.run_in_state(GameState::Game))
+            .add_system(initialize_damage_number_effects.run_in_state(GameState::Game))
+            .add_system(draw_damage_numbers.run_in_state(GameState::Game))
             .add_system(initialize_splash_effects.run_in_state(GameState::Game))
             .add_system(animate_splash_material.run_in_state(GameState::Game))
             .add_system(check_lifetimes);
@@ -551,12 +555,8 @@ fn initialize_torpedo_effects(
 }
 
 fn direction_to_vector(direction: Direction) -> Vec2 {
-    match direction {
-        Direction::North => Vec2::Y,
-        Direction::East => Vec2::X,
-        Direction::South => Vec2::NEG_Y,
-        Direction::West => Vec2::NEG_X,
-    }
+    let [x, y] = direction_step(direction);
+    Vec2::new(x as f32, y as f32)
 }
 
 #[derive(Bundle)]
@@ -634,6 +634,111 @@ fn animate_hit_material(
     hit_material.base_color.set_a(alpha);
 }
 
+/// Time a damage number stays on screen.
+const DAMAGE_NUMBER_DURATION: Duration = Duration::from_millis(1500);
+/// Tiles a damage number rises until it vanishes.
+const DAMAGE_NUMBER_RISE: f32 = 2.0;
+const DAMAGE_NUMBER_FONT_SIZE: f32 = 18.0;
+
+#[derive(Bundle)]
+pub struct DamageNumberEffect {
+    data: DamageNumberEffectData,
+    name: Name,
+}
+
+#[derive(Component)]
+pub struct DamageNumberEffectData {
+    position: Vec2,
+    damage: u32,
+    started_at: Option<Duration>,
+}
+
+impl DamageNumberEffect {
+    pub fn new(position: &Coordinate, damage: u32) -> Self {
+        let position = Vec2::new(position.x as f32, position.y as f32);
+
+        Self {
+            data: DamageNumberEffectData {
+                position,
+                damage,
+                started_at: None,
+            },
+            name: Name::new("Damage Number Effect"),
+        }
+    }
+}
+
+impl DamageNumberEffectData {
+    pub fn damage(&self) -> u32 {
+        self.damage
+    }
+}
+
+fn initialize_damage_number_effects(
+    mut commands: Commands,
+    mut effects: Query<(Entity, &mut DamageNumberEffectData)>,
+    time: Res<Time>,
+) {
+    for (entity, mut effect) in effects.iter_mut() {
+        if effect.started_at.is_some() {
+            continue;
+        }
+
+        commands.entity(entity).insert(Lifetime {
+            ends_at: time.elapsed() + DAMAGE_NUMBER_DURATION,
+        });
+
+        effect.started_at = Some(time.elapsed());
+    }
+}
+
+/// Draws the damage numbers above the board, they rise and fade out until their lifetime ends.
+fn draw_damage_numbers(
+    mut egui_context: ResMut<EguiContext>,
+    camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    effects: Query<&DamageNumberEffectData>,
+    time: Res<Time>,
+) {
+    if effects.is_empty() {
+        return;
+    }
+    let (camera, camera_transform) = camera.single();
+
+    let ctx = egui_context.ctx_mut();
+    let screen_height = ctx.screen_rect().height();
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    for effect in effects.iter() {
+        let started_at = match effect.started_at {
+            Some(started_at) => started_at,
+            None => continue,
+        };
+        let progress = ((time.elapsed() - started_at).as_secs_f32()
+            / DAMAGE_NUMBER_DURATION.as_secs_f32())
+        .min(1.0);
+
+        let height = 10.0;
+        let position = Vec3::new(
+            effect.position.x,
+            effect.position.y + progress * DAMAGE_NUMBER_RISE,
+            height,
+        );
+        if let Some(position) = world_to_egui(camera, camera_transform, screen_height, position) {
+            painter.text(
+                position,
+                egui::Align2::CENTER_BOTTOM,
+                effect.damage().to_string(),
+                egui::FontId::proportional(DAMAGE_NUMBER_FONT_SIZE),
+                egui::Color32::from_rgba_unmultiplied(
+                    255,
+                    64,
+                    64,
+                    ((1.0 - progress) * 255.0) as u8,
+                ),
+            );
+        }
+    }
+}
+
 #[derive(Bundle)]
 pub struct SplashEffect {
     data: SplashEffectData,
@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 use std::f32::consts::FRAC_PI_2;
+use std::time::Duration;
 
 use bevy::prelude::*;
 use bevy_egui::EguiContext;
@@ -9,8 +10,11 @@ use rstar::AABB;
 
 use battleship_plus_common::{
     game::{
+        ability::{AbilityDescriptor, TargetConstraints, Targeting},
+        direction::direction_step,
         ship::{Cooldown, GetShipID, Orientation, Ship, ShipID},
         ship_manager::ShipManager,
+        starting_action_points,
     },
     messages::{self, ship_action_request::ActionProperties, EventMessage, StatusCode},
     types::{self, CommonBalancing, GameEndReason, Teams},
@@ -20,15 +24,23 @@ use bevy_quinnet_client::Client;
 use crate::{
     effects,
     game_state::{CachedEvents, Config, GameState, PlayerId, PlayerTeam, Ships},
+    grid_labels::world_to_egui,
     lobby,
     models::{
-        get_ship_model_transform, GameAssets, HostileShipBundle, HostileShipTile, OceanBundle,
-        Ship as ModelShip, ShipBundle, ShipMeshes, CLICK_PLANE_OFFSET_Z,
+        get_ship_model_transform, GameAssets, HostileShipBundle, HostileShipTile, IslandBundle,
+        OceanBundle, Ship as ModelShip, ShipBundle, ShipMeshes, TileRecency, CLICK_PLANE_OFFSET_Z,
     },
     networking, RaycastSet,
 };
 
-pub struct GamePlugin;
+pub struct GamePlugin {
+    /// Whether predator missiles and torpedoes have to be confirmed before they are fired.
+    pub confirm_abilities: bool,
+    /// Whether the ranges of the selected ship are shown initially, players can toggle them.
+    pub show_range_overlay: bool,
+    /// Whether the damage of each hit rises from the hit tile.
+    pub show_damage_numbers: bool,
+}
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
@@ -45,6 +57,7 @@ impl Plugin for GamePlugin {
                 }),
         )
         .add_enter_system(GameState::Game, spawn_components)
+        .add_enter_system(GameState::Game, acknowledge_game_start)
         .add_exit_system(GameState::Game, despawn_components)
         // raycast system has been added in PlacementPhasePlugin already
         .add_system(process_responses.run_in_state(GameState::Game))
@@ -55,7 +68,16 @@ impl Plugin for GamePlugin {
         .add_system(select_ship.run_in_state(GameState::Game))
         .add_system(select_target.run_in_state(GameState::Game))
         .add_system(update_ships.run_in_state(GameState::Game))
+        .add_system(fade_hostile_tiles.run_in_state(GameState::Game))
+        .add_system(process_objective_events.run_in_state(GameState::Game))
         .add_system(draw_menu.run_in_state(GameState::Game))
+        .add_system(draw_announcements.run_in_state(GameState::Game))
+        .add_system(draw_confirmation.run_in_state(GameState::Game))
+        .add_system(draw_selected_targets.run_in_state(GameState::Game))
+        .add_system(draw_range_overlay.run_in_state(GameState::Game))
+        .insert_resource(ConfirmAbilities(self.confirm_abilities))
+        .insert_resource(ShowRangeOverlay(self.show_range_overlay))
+        .insert_resource(ShowDamageNumbers(self.show_damage_numbers))
         .add_system(send_actions.run_in_state(GameState::Game));
     }
 }
@@ -69,13 +91,62 @@ struct SelectedShip(u32);
 #[derive(Resource, Deref, DerefMut)]
 struct SelectedTargets(Vec<types::Coordinate>);
 
-type TargetCount = usize;
+/// How many targets the player selects for an action and the rules they follow.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct TargetSelection {
+    count: usize,
+    constraints: TargetConstraints,
+}
+
+impl TargetSelection {
+    fn single() -> Self {
+        TargetSelection {
+            count: 1,
+            constraints: TargetConstraints::DISTINCT,
+        }
+    }
+
+    fn is_complete(&self, selected_targets: &[types::Coordinate]) -> bool {
+        selected_targets.len() >= self.count
+    }
+
+    /// Adds the target unless the selection is complete, it lies off the board or it breaks
+    /// the constraints, so repeated clicks on a tile do not use up the targets of an ability.
+    fn add(
+        &self,
+        selected_targets: &mut Vec<types::Coordinate>,
+        target: types::Coordinate,
+        board_size: u32,
+    ) -> bool {
+        if self.is_complete(selected_targets)
+            || target.x >= board_size
+            || target.y >= board_size
+            || !self.constraints.allows(selected_targets, &target)
+        {
+            return false;
+        }
+        selected_targets.push(target);
+        true
+    }
+}
+
+impl From<&AbilityDescriptor> for TargetSelection {
+    fn from(ability: &AbilityDescriptor) -> Self {
+        TargetSelection {
+            count: ability.target_count(),
+            constraints: ability.constraints,
+        }
+    }
+}
+
 type PositionInQueue = Option<u32>;
 
 enum State {
     WaitingForTurn(PositionInQueue),
     ChoosingAction,
-    ChoosingTargets(TargetCount, ActionProperties),
+    ChoosingTargets(TargetSelection, ActionProperties),
+    /// The targets are chosen, the action is sent once the player confirms it.
+    ConfirmingAction(ActionProperties),
     ChoseAction(Option<ActionProperties>),
     WaitingForResponse,
 }
@@ -83,15 +154,34 @@ enum State {
 #[derive(Resource, Deref, DerefMut)]
 struct TurnState(State);
 
+#[derive(Resource, Deref)]
+struct ConfirmAbilities(bool);
+
+#[derive(Resource, Deref, DerefMut)]
+struct ShowRangeOverlay(bool);
+
+#[derive(Resource, Deref)]
+struct ShowDamageNumbers(bool);
+
 #[derive(Resource, Deref, DerefMut)]
 struct CurrentPlayer(Option<battleship_plus_common::game::PlayerID>);
 
+/// Number of the current turn as announced by the server, 0 before the first turn.
+#[derive(Resource, Deref, DerefMut)]
+struct TurnNumber(u32);
+
 #[derive(Component)]
 struct DespawnOnExit;
 
 #[derive(Resource, Deref, DerefMut)]
 struct ActionPoints(u32);
 
+const ANNOUNCEMENT_LIFETIME: Duration = Duration::from_secs(5);
+
+// Texts shown on top of the board until they expire.
+#[derive(Resource, Deref, DerefMut, Default)]
+struct Announcements(Vec<(String, Duration)>);
+
 fn create_resources(
     mut commands: Commands,
     initial_game_state: Res<InitialGameState>,
@@ -101,8 +191,10 @@ fn create_resources(
 ) {
     commands.insert_resource(TurnState(State::WaitingForTurn(None)));
     commands.insert_resource(CurrentPlayer(None));
+    commands.insert_resource(TurnNumber(0));
     commands.insert_resource(ActionPoints(0));
     commands.insert_resource(SelectedTargets(Vec::with_capacity(3)));
+    commands.insert_resource(Announcements::default());
 
     let team_state = match **player_team {
         Teams::TeamA => &lobby.team_state_a,
@@ -112,13 +204,15 @@ fn create_resources(
     let allied_players: HashSet<_> = team_state.iter().map(|player| player.player_id).collect();
     let mut ships = Vec::with_capacity(initial_game_state.team_ships.len());
 
-    for allied_player in allied_players {
-        let allied_ship_count = match **player_team {
-            Teams::TeamA => config.ship_set_team_a.len(),
-            Teams::TeamB => config.ship_set_team_b.len(),
-            Teams::None => unreachable!(),
-        };
+    // both teams may have differently composed ship sets
+    let allied_ship_set = match **player_team {
+        Teams::TeamA => &config.ship_set_team_a,
+        Teams::TeamB => &config.ship_set_team_b,
+        Teams::None => unreachable!(),
+    };
+    let allied_ship_count = allied_ship_set.len();
 
+    for allied_player in allied_players {
         let ship_states: Vec<&types::ShipState> = initial_game_state
             .team_ships
             .iter()
@@ -128,6 +222,13 @@ fn create_resources(
         if ship_states.len() != allied_ship_count {
             error!("Received wrong number of ships for player {allied_player}");
             commands.insert_resource(NextState(GameState::Unconnected));
+        } else if ship_states
+            .iter()
+            .zip(allied_ship_set.iter())
+            .any(|(ship_state, &ship_type)| ship_state.ship_type != ship_type)
+        {
+            error!("Received ships not matching the ship set for player {allied_player}");
+            commands.insert_resource(NextState(GameState::Unconnected));
         }
 
         for (ship_index, ship_state) in ship_states.iter().enumerate().take(allied_ship_count) {
@@ -149,7 +250,10 @@ fn create_resources(
         }
     }
 
-    commands.insert_resource(Ships(ShipManager::new_with_ships(ships)));
+    let mut ships = ShipManager::new_with_ships(ships);
+    ships.set_islands(&config.islands);
+    ships.set_line_of_sight_vision(config.line_of_sight_vision);
+    commands.insert_resource(Ships(ships));
 }
 
 fn spawn_components(
@@ -164,6 +268,11 @@ fn spawn_components(
     commands
         .spawn(OceanBundle::new(&assets, config.clone()))
         .insert(DespawnOnExit);
+    for island in config.islands.iter() {
+        commands
+            .spawn(IslandBundle::new(&assets, island))
+            .insert(DespawnOnExit);
+    }
     commands
         .spawn(DirectionalLightBundle {
             transform: Transform::from_rotation(Quat::from_axis_angle(
@@ -266,6 +375,7 @@ fn draw_menu(
     player_id: Res<PlayerId>,
     (action_points, mut turn_state): (Res<ActionPoints>, ResMut<TurnState>),
     config: Res<Config>,
+    (mut show_range_overlay, turn_number): (ResMut<ShowRangeOverlay>, Res<TurnNumber>),
 ) {
     let selected = match selected {
         Some(selected) => ships.get_by_id(&(**player_id, **selected)),
@@ -279,19 +389,33 @@ fn draw_menu(
             ui.horizontal(|ui| {
                 ui.set_height(50.0);
 
-                ui.horizontal(|ui| {
+                ui.vertical(|ui| {
                     ui.set_width(150.0);
+                    if **turn_number > 0 {
+                        ui.label(format!("Turn {}", **turn_number));
+                    }
                     match **turn_state {
-                        State::WaitingForTurn(Some(1)) => ui.label("1 turn before you".to_string()),
+                        State::WaitingForTurn(Some(1)) => {
+                            ui.label("1 turn before you".to_string());
+                        }
                         State::WaitingForTurn(Some(remaining_turns)) => {
-                            ui.label(format!("{remaining_turns} turns before you"))
+                            ui.label(format!("{remaining_turns} turns before you"));
+                        }
+                        State::WaitingForTurn(None) => {
+                            ui.label("Waiting for turn...");
+                        }
+                        _ => {
+                            ui.label(format!(
+                                "Action Points: {} (+{}/turn)",
+                                **action_points, config.action_point_gain
+                            ));
+                            let (filled, total) = action_point_pips(**action_points, &config);
+                            if total <= MAX_ACTION_POINT_PIPS {
+                                draw_action_point_pips(ui, filled, total);
+                            }
                         }
-                        State::WaitingForTurn(None) => ui.label("Waiting for turn..."),
-                        _ => ui.label(format!(
-                            "Action Points: {} (+{}/turn)",
-                            **action_points, config.action_point_gain
-                        )),
                     }
+                    ui.checkbox(&mut **show_range_overlay, "Show ranges");
                 });
 
                 ui.separator();
@@ -346,8 +470,10 @@ fn draw_menu(
 
                         if shoot_button.clicked() {
                             trace!("Initiating shot, waiting for target selection...");
-                            **turn_state =
-                                State::ChoosingTargets(1, types::ShootProperties::default().into());
+                            **turn_state = State::ChoosingTargets(
+                                TargetSelection::single(),
+                                types::ShootProperties::default().into(),
+                            );
                         }
                     }
 
@@ -355,10 +481,10 @@ fn draw_menu(
                         let may_use_special =
                             may_execute_action && may_use_special(ship, &action_points, &config);
                         let cooldown = get_special_cooldown(ship);
-                        // TODO: Display the actual ability name here.
+                        let ability = ship.ship_type().abilities()[0];
                         let button_text = match cooldown {
-                            Some(cooldown) => format!("Special ({cooldown})"),
-                            None => "Special".to_string(),
+                            Some(cooldown) => format!("{} ({cooldown})", ability.name),
+                            None => ability.name.to_string(),
                         };
                         let special_button = ui.add_enabled(
                             may_use_special,
@@ -378,50 +504,19 @@ fn draw_menu(
                             .on_disabled_hover_text(hover_text);
 
                         if special_button.clicked() {
-                            trace!("Initiating special ability...");
-                            let action_properties = match ship.ship_type() {
-                                types::ShipType::Carrier => {
-                                    trace!("Waiting for target selection...");
-                                    selected_targets.clear();
-                                    **turn_state = State::ChoosingTargets(
-                                        1,
-                                        types::ScoutPlaneProperties::default().into(),
-                                    );
-                                    None
-                                }
-                                types::ShipType::Submarine => {
-                                    trace!("Waiting for target direction selection...");
-                                    **turn_state = State::ChoosingTargets(
-                                        1,
-                                        types::TorpedoProperties::default().into(),
-                                    );
-                                    None
-                                }
-                                types::ShipType::Cruiser => {
-                                    Some(types::EngineBoostProperties {}.into())
-                                }
-                                types::ShipType::Battleship => {
-                                    trace!("Waiting for target selection...");
+                            trace!("Initiating {}...", ability.name);
+                            let action_properties = ability.kind.default_properties();
+                            **turn_state = match ability.targeting {
+                                Targeting::None => State::ChoseAction(Some(action_properties)),
+                                targeting => {
+                                    trace!("Waiting for target selection ({targeting:?})...");
                                     selected_targets.clear();
-                                    **turn_state = State::ChoosingTargets(
-                                        1,
-                                        types::PredatorMissileProperties::default().into(),
-                                    );
-                                    None
-                                }
-                                types::ShipType::Destroyer => {
-                                    trace!("Waiting for three target selections...");
-                                    selected_targets.clear();
-                                    **turn_state = State::ChoosingTargets(
-                                        3,
-                                        types::MultiMissileProperties::default().into(),
-                                    );
-                                    None
+                                    State::ChoosingTargets(
+                                        TargetSelection::from(&ability),
+                                        action_properties,
+                                    )
                                 }
                             };
-                            if let Some(action_properties) = action_properties {
-                                **turn_state = State::ChoseAction(Some(action_properties));
-                            }
                         }
                     }
 
@@ -554,6 +649,63 @@ fn draw_menu(
     );
 }
 
+fn process_objective_events(
+    mut events: EventReader<messages::EventMessage>,
+    mut announcements: ResMut<Announcements>,
+    player_team: Res<PlayerTeam>,
+    time: Res<Time>,
+) {
+    for event in events.iter() {
+        let event = match event {
+            EventMessage::ObjectiveEvent(event) => event,
+            _ => continue,
+        };
+        let allied = event.team == **player_team as i32;
+        let text = match types::Objective::from_i32(event.objective) {
+            Some(types::Objective::FirstBlood) if allied => {
+                "First blood for your team!".to_string()
+            }
+            Some(types::Objective::FirstBlood) => "First blood for the enemy!".to_string(),
+            Some(types::Objective::LastShip) if allied => format!(
+                "Your team is down to its last ship, {} health left!",
+                event.remaining_health
+            ),
+            Some(types::Objective::LastShip) => format!(
+                "The enemy is down to its last ship, {} health left!",
+                event.remaining_health
+            ),
+            None => {
+                warn!("Received unknown objective {}", event.objective);
+                continue;
+            }
+        };
+        info!("{text}");
+        announcements.push((text, time.elapsed() + ANNOUNCEMENT_LIFETIME));
+    }
+}
+
+fn draw_announcements(
+    mut egui_context: ResMut<EguiContext>,
+    mut announcements: ResMut<Announcements>,
+    time: Res<Time>,
+) {
+    announcements.retain(|(_, expires_at)| *expires_at > time.elapsed());
+    if announcements.is_empty() {
+        return;
+    }
+    egui::Area::new("announcements")
+        .anchor(egui::Align2::CENTER_TOP, egui::Vec2::new(0.0, 20.0))
+        .show(egui_context.ctx_mut(), |ui| {
+            for (text, _) in announcements.iter() {
+                ui.label(
+                    egui::RichText::new(text)
+                        .color(egui::Color32::GOLD)
+                        .heading(),
+                );
+            }
+        });
+}
+
 fn get_shoot_cooldown(ship: &Ship) -> Option<u32> {
     ship.cool_downs().iter().find_map(|x| {
         if let &Cooldown::Cannon { remaining_rounds } = x {
@@ -564,6 +716,47 @@ fn get_shoot_cooldown(ship: &Ship) -> Option<u32> {
     })
 }
 
+/// Beyond this many pips the action points are only shown as text.
+const MAX_ACTION_POINT_PIPS: u32 = 20;
+const ACTION_POINT_PIP_SPACING: f32 = 7.0;
+
+/// Filled and total pips for the action points of the current turn. A full bar holds the most
+/// action points a turn can start with, unless more were carried over.
+fn action_point_pips(action_points: u32, config: &types::Config) -> (u32, u32) {
+    let max_action_points =
+        match types::ActionPointCarryover::from_i32(config.action_point_carryover) {
+            Some(types::ActionPointCarryover::Cap) => {
+                config.action_point_cap.max(config.action_point_gain)
+            }
+            _ => config.action_point_gain,
+        };
+    (action_points, max_action_points.max(action_points))
+}
+
+fn draw_action_point_pips(ui: &mut egui::Ui, filled: u32, total: u32) {
+    let (rect, _) = ui.allocate_exact_size(
+        egui::Vec2::new(
+            total as f32 * ACTION_POINT_PIP_SPACING,
+            ACTION_POINT_PIP_SPACING,
+        ),
+        egui::Sense::hover(),
+    );
+    let radius = ACTION_POINT_PIP_SPACING / 2.0 - 1.0;
+    for pip in 0..total {
+        let center = egui::Pos2::new(
+            rect.left() + (pip as f32 + 0.5) * ACTION_POINT_PIP_SPACING,
+            rect.center().y,
+        );
+        if pip < filled {
+            ui.painter()
+                .circle_filled(center, radius, egui::Color32::GOLD);
+        } else {
+            ui.painter()
+                .circle_stroke(center, radius, (1.0, egui::Color32::GRAY));
+        }
+    }
+}
+
 fn may_shoot(ship: &Ship, action_points: &Res<ActionPoints>, config: &Res<Config>) -> bool {
     let cooldown = get_shoot_cooldown(ship);
     let available_action_points = ***action_points;
@@ -740,14 +933,38 @@ fn get_common_balancing<'a>(ship: &Ship, config: &'a Res<Config>) -> &'a CommonB
     .expect("Ships must have a CommonBalancing")
 }
 
+/// Fades out enemy tiles that went out of sight, they are removed once they expired.
+fn fade_hostile_tiles(
+    mut commands: Commands,
+    mut tiles: Query<(Entity, &HostileShipTile, &mut Handle<StandardMaterial>)>,
+    turn_number: Res<TurnNumber>,
+    assets: Res<GameAssets>,
+) {
+    for (entity, tile, mut material) in tiles.iter_mut() {
+        match tile.recency(**turn_number) {
+            TileRecency::Expired => commands.entity(entity).despawn_recursive(),
+            recency => {
+                let faded = assets.hostile_tile_material(recency);
+                if *material != faded {
+                    *material = faded;
+                }
+            }
+        }
+    }
+}
+
 fn process_game_events(
     mut commands: Commands,
     mut events: EventReader<messages::EventMessage>,
     (player_id, player_team): (Res<PlayerId>, Res<PlayerTeam>),
     (mut current_player, selected_ship): (ResMut<CurrentPlayer>, Option<Res<SelectedShip>>),
-    (mut turn_state, mut action_points): (ResMut<TurnState>, ResMut<ActionPoints>),
-    (mut ships, enemy_ship_tiles): (ResMut<Ships>, Query<(Entity, &HostileShipTile)>),
-    (config, assets): (Res<Config>, Res<GameAssets>),
+    (mut turn_state, mut action_points, mut current_turn_number): (
+        ResMut<TurnState>,
+        ResMut<ActionPoints>,
+        ResMut<TurnNumber>,
+    ),
+    (mut ships, mut enemy_ship_tiles): (ResMut<Ships>, Query<&mut HostileShipTile>),
+    (config, assets, show_damage_numbers): (Res<Config>, Res<GameAssets>, Res<ShowDamageNumbers>),
 ) {
     let mut transition_happened = false;
     for event in events.iter() {
@@ -755,17 +972,23 @@ fn process_game_events(
             EventMessage::NextTurn(messages::NextTurn {
                 next_player_id,
                 position_in_queue,
+                turn_number,
+                ..
             }) => {
                 **current_player = Some(*next_player_id);
+                **current_turn_number = *turn_number;
                 if **player_id == *next_player_id {
                     info!("Turn started");
                     **turn_state = State::ChoosingAction;
-                    **action_points += config.action_point_gain;
+                    **action_points = starting_action_points(&config, **action_points);
+                    let ticks = 1 + config.owner_cooldown_reduction;
                     ships.iter_ships_mut().for_each(|(_, ship)| {
                         let cooldowns = ship.cool_downs_mut();
                         *cooldowns = cooldowns
-                            .iter_mut()
-                            .filter_map(|cooldown| cooldown.decremented())
+                            .iter()
+                            .filter_map(|cooldown| {
+                                (0..ticks).try_fold(*cooldown, |cooldown, _| cooldown.decremented())
+                            })
                             .collect();
                     });
                 } else {
@@ -773,7 +996,7 @@ fn process_game_events(
                         State::WaitingForTurn(_)
                         | State::ChoosingAction
                         | State::ChoosingTargets(_, _) => {}
-                        State::ChoseAction(_) => {
+                        State::ConfirmingAction(_) | State::ChoseAction(_) => {
                             debug!("Action is aborted, the turn ended");
                         }
                         State::WaitingForResponse => {
@@ -811,9 +1034,7 @@ fn process_game_events(
             EventMessage::HitEvent(hit) => {
                 if let Some(position @ types::Coordinate { x, y }) = &hit.coordinate {
                     debug!("Hit at ({x}, {y}) for {} damage", hit.damage);
-                    commands
-                        .spawn(effects::HitEffect::new(position))
-                        .insert(DespawnOnExit);
+                    spawn_hit_effects(&mut commands, position, hit.damage, **show_damage_numbers);
                     let ship = match ships.get_by_position_mut(position.clone()) {
                         Some(ship) => ship,
                         None => {
@@ -850,18 +1071,26 @@ fn process_game_events(
                 }
             }
             EventMessage::VisionEvent(vision) => {
+                // tiles out of sight stay as the last known positions until they expired
                 for position @ types::Coordinate { x, y } in &vision.vanished_ship_fields {
                     debug!("Lost sight of ship at ({x}, {y})");
                     enemy_ship_tiles
-                        .iter()
-                        .filter(|(_, tile)| &tile.position == position)
-                        .for_each(|(entity, _)| commands.entity(entity).despawn_recursive());
+                        .iter_mut()
+                        .filter(|tile| &tile.position == position)
+                        .for_each(|mut tile| tile.last_seen_turn = Some(**current_turn_number));
                 }
                 for position @ types::Coordinate { x, y } in &vision.discovered_ship_fields {
                     debug!("Sighted ship at ({x}, {y})");
-                    commands
-                        .spawn(HostileShipBundle::new(&assets, position))
-                        .insert(DespawnOnExit);
+                    let mut known_tiles = enemy_ship_tiles
+                        .iter_mut()
+                        .filter(|tile| &tile.position == position)
+                        .peekable();
+                    if known_tiles.peek().is_none() {
+                        commands
+                            .spawn(HostileShipBundle::new(&assets, position))
+                            .insert(DespawnOnExit);
+                    }
+                    known_tiles.for_each(|mut tile| tile.last_seen_turn = None);
                 }
             }
             EventMessage::ShipActionEvent(action) => {
@@ -890,11 +1119,16 @@ fn process_game_events(
                     action_properties,
                     &mut ships,
                     &config,
-                    &mut action_points,
                     &player_id,
                 );
             }
-            EventMessage::GameOverEvent(event @ messages::GameOverEvent { reason, winner }) => {
+            EventMessage::GameOverEvent(
+                event @ messages::GameOverEvent {
+                    reason,
+                    winner,
+                    revealed_ships,
+                },
+            ) => {
                 let reason = types::GameEndReason::from_i32(*reason);
                 let winner = types::Teams::from_i32(*winner);
                 if Some(types::GameEndReason::Disconnect) == reason {
@@ -929,11 +1163,22 @@ fn process_game_events(
                         Teams::None
                     }
                 };
+                // Allied ships are known already, only the ones of the enemy are of interest.
+                let revealed_enemy_ships = revealed_ships
+                    .iter()
+                    .filter(|ship| {
+                        !ships
+                            .iter_ships()
+                            .any(|((owner_id, _), _)| *owner_id == ship.owner_id)
+                    })
+                    .cloned()
+                    .collect();
                 info!("Returning to lobby");
                 commands.insert_resource(NextState(lobby::GameEndDetails {
                     reason,
                     winner,
                     player_team: **player_team,
+                    revealed_enemy_ships,
                 }));
                 commands.insert_resource(NextState(GameState::Lobby));
                 transition_happened = true;
@@ -951,6 +1196,23 @@ fn process_game_events(
     }
 }
 
+/// Marks the hit tile, the damage dealt rises from it if damage numbers are shown.
+fn spawn_hit_effects(
+    commands: &mut Commands,
+    position: &types::Coordinate,
+    damage: u32,
+    show_damage_numbers: bool,
+) {
+    commands
+        .spawn(effects::HitEffect::new(position))
+        .insert(DespawnOnExit);
+    if show_damage_numbers {
+        commands
+            .spawn(effects::DamageNumberEffect::new(position, damage))
+            .insert(DespawnOnExit);
+    }
+}
+
 // Move and rotate ships, but do not check for collisions.
 // For the other events, do not deal any damage either, only initiate visualization.
 // Damage is handled by the server, the client only reacts to HitEvents and
@@ -961,18 +1223,17 @@ fn process_action_event(
     action_properties: messages::ship_action_event::ActionProperties,
     ships: &mut ResMut<Ships>,
     config: &Res<Config>,
-    action_points: &mut ResMut<ActionPoints>,
     player_id: &Res<PlayerId>,
 ) {
-    // Fake an action point account.
+    // Fake an action point account, the server reports the points actually left in its response
+    // to the own action request.
     let mut enough_action_points = u32::MAX;
     let is_player = ship_id.0 == ***player_id;
-    let action_points = if is_player {
-        &mut **action_points
-    } else {
-        &mut enough_action_points
-    };
-    let bounds = AABB::from_corners([0, 0], [config.board_size as i32, config.board_size as i32]);
+    let action_points = &mut enough_action_points;
+    let bounds = AABB::from_corners(
+        [0, 0],
+        [config.board_size as i32 - 1, config.board_size as i32 - 1],
+    );
     use messages::ship_action_event::ActionProperties;
 
     let ship = match ships.get_by_id_mut(&ship_id) {
@@ -991,10 +1252,17 @@ fn process_action_event(
                 &ship_id,
                 properties.direction(),
                 &bounds,
+                config.wrap_edges,
             )
             .err(),
         ActionProperties::RotateProperties(ref properties) => ships
-            .rotate_ship(action_points, &ship_id, properties.direction(), &bounds)
+            .rotate_ship(
+                action_points,
+                &ship_id,
+                properties.direction(),
+                &bounds,
+                config.wrap_edges,
+            )
             .err(),
         ActionProperties::ShootProperties(ref properties) => {
             if is_player {
@@ -1155,6 +1423,15 @@ fn process_action_event(
 
             None
         }
+        ActionProperties::ShipSwapProperties(ref properties) => ships
+            .swap_ships(
+                action_points,
+                &ship_id,
+                &(ship_id.0, properties.partner_ship_number),
+                &bounds,
+                config.ship_swap_range,
+            )
+            .err(),
     };
     if let Some(error) = error {
         error!("Could not process event for ship {ship_id:?}: {error:?}\nEvent contained: {action_properties:?}");
@@ -1165,6 +1442,7 @@ fn process_responses(
     mut commands: Commands,
     mut events: EventReader<networking::ResponseReceivedEvent>,
     mut turn_state: ResMut<TurnState>,
+    mut action_points: ResMut<ActionPoints>,
 ) {
     for networking::ResponseReceivedEvent(messages::StatusMessage {
         code,
@@ -1176,7 +1454,7 @@ fn process_responses(
         let code = StatusCode::from_i32(*code);
         match code {
             Some(StatusCode::Ok) => {
-                process_response_data(data, message, &mut turn_state);
+                process_response_data(data, message, &mut turn_state, &mut action_points);
             }
             Some(StatusCode::OkWithWarning) => {
                 if message.is_empty() {
@@ -1184,7 +1462,7 @@ fn process_responses(
                 } else {
                     warn!("Received OK response with warning: {message}");
                 }
-                process_response_data(data, message, &mut turn_state);
+                process_response_data(data, message, &mut turn_state, &mut action_points);
             }
             Some(StatusCode::InsufficientResources) => {
                 if message.is_empty() {
@@ -1249,10 +1527,12 @@ fn process_response_data(
     data: &Option<messages::status_message::Data>,
     message: &str,
     turn_state: &mut ResMut<TurnState>,
+    action_points: &mut ResMut<ActionPoints>,
 ) {
     match data {
-        Some(messages::status_message::Data::ShipActionResponse(_)) => {
+        Some(messages::status_message::Data::ShipActionResponse(response)) => {
             ***turn_state = State::ChoosingAction;
+            ***action_points = response.action_points_left;
         }
         Some(_other_response) => {
             // ignore
@@ -1316,19 +1596,20 @@ fn select_target(
     ships: Res<Ships>,
     mut selected_targets: ResMut<SelectedTargets>,
     mouse_input: Res<Input<MouseButton>>,
+    (confirm_abilities, config): (Res<ConfirmAbilities>, Res<Config>),
 ) {
     // TODO: Allow aborting selection mode.
 
     if !mouse_input.just_pressed(MouseButton::Left) {
         return;
     }
-    let (&target_count, action_properties) = match &**turn_state {
-        State::ChoosingTargets(target_count, action_properties) => {
-            (target_count, action_properties)
+    let (&target_selection, action_properties) = match &**turn_state {
+        State::ChoosingTargets(target_selection, action_properties) => {
+            (target_selection, action_properties)
         }
         _ => return,
     };
-    if selected_targets.len() >= target_count {
+    if target_selection.is_complete(&selected_targets) {
         return;
     }
 
@@ -1337,10 +1618,13 @@ fn select_target(
         None => return,
     };
 
+    if !target_selection.add(&mut selected_targets, target.clone(), config.board_size) {
+        trace!("Ignored target: ({}, {})", target.x, target.y);
+        return;
+    }
     trace!("Selected target: ({}, {})", target.x, target.y);
-    selected_targets.push(target.clone());
 
-    if selected_targets.len() >= target_count {
+    if target_selection.is_complete(&selected_targets) {
         // This position was the last one.
         let mut action_properties = action_properties.clone();
         match &mut action_properties {
@@ -1384,7 +1668,278 @@ fn select_target(
             }
             _ => unreachable!("Only actions with targets are allowed here"),
         }
-        **turn_state = State::ChoseAction(Some(action_properties));
+        **turn_state = chosen_action_state(action_properties, **confirm_abilities);
+    }
+}
+
+/// The state once the targets of an action are chosen. Abilities which are costly and
+/// cannot be taken back wait for a confirmation first, if enabled.
+fn chosen_action_state(action_properties: ActionProperties, confirm_abilities: bool) -> State {
+    let needs_confirmation = matches!(
+        action_properties,
+        ActionProperties::PredatorMissileProperties(_) | ActionProperties::TorpedoProperties(_)
+    );
+    if confirm_abilities && needs_confirmation {
+        State::ConfirmingAction(action_properties)
+    } else {
+        State::ChoseAction(Some(action_properties))
+    }
+}
+
+fn draw_selected_targets(
+    mut egui_context: ResMut<EguiContext>,
+    turn_state: Res<TurnState>,
+    selected_targets: Res<SelectedTargets>,
+    camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+) {
+    if !matches!(**turn_state, State::ChoosingTargets(..)) {
+        return;
+    }
+
+    let ctx = egui_context.ctx_mut();
+    let (camera, camera_transform) = camera.single();
+    let screen_height = ctx.screen_rect().height();
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    for target in selected_targets.iter() {
+        let position = Vec3::new(target.x as f32, target.y as f32, 0.0);
+        if let Some(position) = world_to_egui(camera, camera_transform, screen_height, position) {
+            painter.circle_stroke(position, 8.0, (2.0, egui::Color32::YELLOW));
+        }
+    }
+}
+
+/// Sends the action awaiting confirmation with its targets, or drops it and lets the player
+/// choose another action. `None` if no action awaits confirmation.
+fn resolve_confirmation(turn_state: &State, confirmed: bool) -> Option<State> {
+    match turn_state {
+        State::ConfirmingAction(action_properties) if confirmed => {
+            Some(State::ChoseAction(Some(action_properties.clone())))
+        }
+        State::ConfirmingAction(_) => Some(State::ChoosingAction),
+        _ => None,
+    }
+}
+
+/// Tiles hit by a predator missile or torpedo of the ship, ignoring islands a torpedo stops at.
+fn affected_tiles(
+    ship: &Ship,
+    action_properties: &ActionProperties,
+    board_size: u32,
+) -> Vec<types::Coordinate> {
+    let area = match (ship, action_properties) {
+        (
+            Ship::Battleship { balancing, .. },
+            ActionProperties::PredatorMissileProperties(properties),
+        ) => {
+            let center = match properties.center.as_ref() {
+                Some(center) => [center.x as i32, center.y as i32],
+                None => return vec![],
+            };
+            let radius = balancing.predator_missile_radius as i32;
+            AABB::from_corners(
+                [center[0] - radius, center[1] - radius],
+                [center[0] + radius, center[1] + radius],
+            )
+        }
+        (
+            Ship::Submarine {
+                balancing, data, ..
+            },
+            ActionProperties::TorpedoProperties(properties),
+        ) => {
+            let direction = properties.direction();
+            // the torpedo leaves from the bow if it is fired forward
+            let origin_offset = if direction == data.orientation.into() {
+                ship.len() - 1
+            } else {
+                0
+            };
+            let step = direction_step(direction);
+            let tile_at = |distance: i32| {
+                [
+                    data.pos_x + step[0] * (origin_offset + distance),
+                    data.pos_y + step[1] * (origin_offset + distance),
+                ]
+            };
+            AABB::from_corners(tile_at(0), tile_at(balancing.torpedo_range as i32))
+        }
+        _ => return vec![],
+    };
+
+    let last = board_size as i32 - 1;
+    let (lower, upper) = (area.lower(), area.upper());
+    (lower[0].max(0)..=upper[0].min(last))
+        .flat_map(|x| {
+            (lower[1].max(0)..=upper[1].min(last)).map(move |y| types::Coordinate {
+                x: x as u32,
+                y: y as u32,
+            })
+        })
+        .collect()
+}
+
+fn draw_confirmation(
+    mut egui_context: ResMut<EguiContext>,
+    mut turn_state: ResMut<TurnState>,
+    mut selected_targets: ResMut<SelectedTargets>,
+    selected: Option<Res<SelectedShip>>,
+    (ships, player_id, config): (Res<Ships>, Res<PlayerId>, Res<Config>),
+    camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+) {
+    let action_properties = match &**turn_state {
+        State::ConfirmingAction(action_properties) => action_properties,
+        _ => return,
+    };
+    let ship = selected.and_then(|selected| ships.get_by_id(&(**player_id, **selected)));
+    let tiles = match ship {
+        Some(ship) => affected_tiles(ship, action_properties, config.board_size),
+        None => vec![],
+    };
+
+    let ctx = egui_context.ctx_mut();
+    let (camera, camera_transform) = camera.single();
+    let screen_height = ctx.screen_rect().height();
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    for tile in tiles.iter() {
+        let position = Vec3::new(tile.x as f32, tile.y as f32, 0.0);
+        if let Some(position) = world_to_egui(camera, camera_transform, screen_height, position) {
+            painter.circle_filled(
+                position,
+                4.0,
+                egui::Color32::from_rgba_unmultiplied(255, 64, 0, 160),
+            );
+        }
+    }
+
+    let mut confirmed = None;
+    egui::Window::new("Confirm action")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::Vec2::new(0.0, 50.0))
+        .show(ctx, |ui| {
+            ui.label(format!("This affects {} tiles.", tiles.len()));
+            ui.horizontal(|ui| {
+                if ui.button("Confirm").clicked() {
+                    confirmed = Some(true);
+                }
+                if ui.button("Cancel").clicked() {
+                    confirmed = Some(false);
+                }
+            });
+        });
+
+    let confirmed = match confirmed {
+        Some(confirmed) => confirmed,
+        None => return,
+    };
+    if let Some(next_state) = resolve_confirmation(&**turn_state, confirmed) {
+        trace!("Action confirmed: {confirmed}");
+        selected_targets.clear();
+        **turn_state = next_state;
+    }
+}
+
+/// Tiles the ship can shoot at and tiles its ability can affect, within the board.
+#[derive(Debug, Default, PartialEq)]
+struct RangeOverlay {
+    shoot: HashSet<types::Coordinate>,
+    ability: HashSet<types::Coordinate>,
+}
+
+fn range_overlay(ship: &Ship, board_size: u32) -> RangeOverlay {
+    let tiles_within = |range: u32| -> HashSet<types::Coordinate> {
+        (0..board_size)
+            .flat_map(|x| (0..board_size).map(move |y| types::Coordinate { x, y }))
+            .filter(|tile| ship.can_reach(&[tile.x as i32, tile.y as i32], range))
+            .collect()
+    };
+
+    let ability: HashSet<_> = match (
+        ship.ship_type().abilities()[0].targeting,
+        ship.ability_range(),
+    ) {
+        // torpedoes travel in a straight line, not anywhere within their range
+        (Targeting::Direction, Some(_)) => [
+            types::Direction::North,
+            types::Direction::East,
+            types::Direction::South,
+            types::Direction::West,
+        ]
+        .into_iter()
+        .flat_map(|direction| {
+            let properties = types::TorpedoProperties {
+                direction: direction.into(),
+            };
+            affected_tiles(ship, &properties.into(), board_size)
+        })
+        .collect(),
+        (Targeting::Positions(_), Some(range)) => {
+            tiles_within(range + ship.ability_radius().unwrap_or(0))
+        }
+        // abilities without a range would cover the whole board
+        _ => HashSet::new(),
+    };
+
+    RangeOverlay {
+        shoot: tiles_within(ship.common_balancing().shoot_range),
+        ability,
+    }
+}
+
+fn draw_range_overlay(
+    mut egui_context: ResMut<EguiContext>,
+    show_range_overlay: Res<ShowRangeOverlay>,
+    selected: Option<Res<SelectedShip>>,
+    (ships, player_id, config): (Res<Ships>, Res<PlayerId>, Res<Config>),
+    camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+) {
+    if !**show_range_overlay {
+        return;
+    }
+    let ship = match selected.and_then(|selected| ships.get_by_id(&(**player_id, **selected))) {
+        Some(ship) => ship,
+        None => return,
+    };
+    let overlay = range_overlay(ship, config.board_size);
+
+    let ctx = egui_context.ctx_mut();
+    let (camera, camera_transform) = camera.single();
+    let screen_height = ctx.screen_rect().height();
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    let to_screen =
+        |position: Vec3| world_to_egui(camera, camera_transform, screen_height, position);
+    let layers = [
+        (
+            &overlay.ability,
+            egui::Color32::from_rgba_unmultiplied(64, 160, 255, 48),
+        ),
+        (
+            &overlay.shoot,
+            egui::Color32::from_rgba_unmultiplied(255, 64, 0, 48),
+        ),
+    ];
+    for (tiles, color) in layers {
+        for tile in tiles {
+            let (x, y) = (tile.x as f32, tile.y as f32);
+            let corners = (
+                to_screen(Vec3::new(x - 0.5, y - 0.5, 0.0)),
+                to_screen(Vec3::new(x + 0.5, y + 0.5, 0.0)),
+            );
+            if let (Some(a), Some(b)) = corners {
+                painter.rect_filled(egui::Rect::from_two_pos(a, b), 0.0, color);
+            }
+        }
+    }
+}
+
+/// Tells the server that the initial game state is set up, it may announce the first turn now.
+fn acknowledge_game_start(mut commands: Commands, client: Res<Client>) {
+    if let Err(error) = client
+        .connection()
+        .send_message(messages::GameStartAck {}.into())
+    {
+        error!("Could not send GameStartAck: {error}, disconnecting");
+        commands.insert_resource(NextState(GameState::Unconnected));
     }
 }
 
@@ -1393,6 +1948,7 @@ fn send_actions(
     mut turn_state: ResMut<TurnState>,
     selected: Option<ResMut<SelectedShip>>,
     client: Res<Client>,
+    mut next_idempotency_key: Local<u64>,
 ) {
     let action_properties = match &**turn_state {
         State::ChoseAction(action) => action.clone(),
@@ -1407,14 +1963,18 @@ fn send_actions(
         // Specify an arbitrary ship number for the end turn message.
         default()
     };
+    // Every action gets a new key, so the server can detect retransmits.
     let message = messages::ShipActionRequest {
         ship_number,
+        end_turn: action_properties.is_none(),
         action_properties,
+        idempotency_key: Some(*next_idempotency_key),
     };
     if let Err(error) = client.connection().send_message(message.into()) {
         error!("Could not send ShipActionRequest: {error}, disonnecting");
         commands.insert_resource(NextState(GameState::Unconnected));
     } else {
+        *next_idempotency_key = next_idempotency_key.wrapping_add(1);
         **turn_state = State::WaitingForResponse;
     }
 }
@@ -1434,6 +1994,8 @@ fn board_position_from_intersection(
     let intersection = intersections.get_single().ok()?;
     intersection
         .position()
+        // Clicks left of or below the board would otherwise end up in its first column or row.
+        .filter(|&&Vec3 { x, y, .. }| x >= -0.5 && y >= -0.5)
         // Shift intersections by (0.5, 0.5) to have integer world coordinates at the center of the
         // tiles.
         .map(|&Vec3 { x, y, .. }| types::Coordinate {
@@ -1441,3 +2003,202 @@ fn board_position_from_intersection(
             y: (y + 0.5) as u32,
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn action_point_pips_match_action_points() {
+        let config = types::Config {
+            action_point_gain: 3,
+            action_point_carryover: types::ActionPointCarryover::Cap.into(),
+            action_point_cap: 5,
+            ..default()
+        };
+        for action_points in 0..=5 {
+            assert_eq!(
+                action_point_pips(action_points, &config),
+                (action_points, 5)
+            );
+        }
+
+        // points carried over without a cap extend the bar
+        let config = types::Config {
+            action_point_carryover: types::ActionPointCarryover::CarryOver.into(),
+            ..config
+        };
+        assert_eq!(action_point_pips(2, &config), (2, 3));
+        assert_eq!(action_point_pips(7, &config), (7, 7));
+    }
+
+    #[test]
+    fn confirming_abilities() {
+        let target = Some(types::Coordinate { x: 3, y: 4 });
+        let missile: ActionProperties = types::PredatorMissileProperties {
+            center: target.clone(),
+        }
+        .into();
+
+        // without the setting the action is sent right away
+        assert!(matches!(
+            chosen_action_state(missile.clone(), false),
+            State::ChoseAction(Some(_))
+        ));
+        // shots are never confirmed
+        let shot: ActionProperties = types::ShootProperties {
+            target: target.clone(),
+        }
+        .into();
+        assert!(matches!(
+            chosen_action_state(shot, true),
+            State::ChoseAction(Some(_))
+        ));
+
+        let confirming = chosen_action_state(missile, true);
+        assert!(matches!(confirming, State::ConfirmingAction(_)));
+
+        // confirming keeps the chosen target
+        match resolve_confirmation(&confirming, true) {
+            Some(State::ChoseAction(Some(ActionProperties::PredatorMissileProperties(
+                properties,
+            )))) => assert_eq!(properties.center, target),
+            _ => panic!("the confirmed action has to be sent"),
+        }
+        // cancelling drops the action with its target
+        assert!(matches!(
+            resolve_confirmation(&confirming, false),
+            Some(State::ChoosingAction)
+        ));
+        assert!(resolve_confirmation(&State::ChoosingAction, true).is_none());
+    }
+
+    #[test]
+    fn range_overlay_matches_ship_range() {
+        use rstar::PointDistance;
+
+        let common_balancing = CommonBalancing {
+            shoot_range: 2,
+            ..default()
+        };
+        let ship = Ship::Battleship {
+            balancing: std::sync::Arc::new(types::BattleshipBalancing {
+                common_balancing: Some(common_balancing),
+                predator_missile_range: 3,
+                predator_missile_radius: 1,
+                ..default()
+            }),
+            data: battleship_plus_common::game::ship::ShipData {
+                id: (1, 0),
+                pos_x: 5,
+                pos_y: 5,
+                orientation: Orientation::North,
+                health: 1,
+            },
+            cooldowns: vec![],
+        };
+
+        let overlay = range_overlay(&ship, 20);
+        for x in 0..20 {
+            for y in 0..20 {
+                let tile = types::Coordinate { x, y };
+                let distance = ship.distance_2(&[x as i32, y as i32]);
+                assert_eq!(overlay.shoot.contains(&tile), distance <= 2, "{tile:?}");
+                // a missile within range still hits tiles one further away
+                assert_eq!(overlay.ability.contains(&tile), distance <= 4, "{tile:?}");
+            }
+        }
+
+        // tiles off the board are left out
+        let on_small_board: HashSet<_> = overlay
+            .shoot
+            .iter()
+            .filter(|tile| tile.x < 6 && tile.y < 6)
+            .cloned()
+            .collect();
+        assert_eq!(range_overlay(&ship, 6).shoot, on_small_board);
+    }
+
+    #[test]
+    fn hits_spawn_damage_numbers() {
+        use bevy::ecs::system::CommandQueue;
+
+        let spawned_damage = |show_damage_numbers: bool| {
+            let mut world = World::new();
+            let mut queue = CommandQueue::default();
+            let mut commands = Commands::new(&mut queue, &world);
+            spawn_hit_effects(
+                &mut commands,
+                &types::Coordinate { x: 3, y: 4 },
+                33,
+                show_damage_numbers,
+            );
+            queue.apply(&mut world);
+
+            // the hit is marked either way
+            assert_eq!(
+                world
+                    .query::<&effects::HitEffectData>()
+                    .iter(&world)
+                    .count(),
+                1
+            );
+            world
+                .query::<&effects::DamageNumberEffectData>()
+                .iter(&world)
+                .map(|effect| effect.damage())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(spawned_damage(true), vec![33]);
+        assert!(spawned_damage(false).is_empty());
+    }
+
+    #[test]
+    fn repeated_targets_are_ignored() {
+        let mut selected_targets = vec![];
+        let target = |x, y| types::Coordinate { x, y };
+        let selection = TargetSelection::from(&types::ShipType::Destroyer.abilities()[0]);
+
+        assert!(selection.add(&mut selected_targets, target(3, 4), 10));
+        // a second click on the tile does not select it again
+        assert!(!selection.add(&mut selected_targets, target(3, 4), 10));
+        assert!(!selection.add(&mut selected_targets, target(10, 4), 10));
+        assert!(!selection.add(&mut selected_targets, target(3, 10), 10));
+        assert!(selection.add(&mut selected_targets, target(4, 3), 10));
+        assert_eq!(selected_targets, vec![target(3, 4), target(4, 3)]);
+    }
+
+    #[test]
+    fn declared_targets_drive_the_selection() {
+        let target = |x, y| types::Coordinate { x, y };
+        let ability = AbilityDescriptor {
+            targeting: Targeting::Positions(3),
+            constraints: TargetConstraints {
+                distinct: true,
+                max_spread: Some(2),
+            },
+            ..types::ShipType::Destroyer.abilities()[0]
+        };
+        let selection = TargetSelection::from(&ability);
+
+        let clicks = [
+            target(3, 3),
+            target(3, 3),
+            target(6, 3),
+            target(4, 4),
+            target(5, 3),
+            target(4, 3),
+        ];
+        let mut selected_targets = vec![];
+        let accepted: Vec<_> = clicks
+            .into_iter()
+            .filter(|click| selection.add(&mut selected_targets, click.clone(), 10))
+            .collect();
+
+        // repeated and distant tiles are skipped, clicks after the last target are ignored
+        assert_eq!(accepted, vec![target(3, 3), target(4, 4), target(5, 3)]);
+        assert_eq!(selected_targets, accepted);
+        assert!(selection.is_complete(&selected_targets));
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use bevy_egui::EguiContext;
+use iyes_loopless::prelude::*;
+
+use crate::game_state::{Config, GameState};
+
+/// At most this many coordinates are labeled along each edge of the board.
+const MAX_LABELS_PER_EDGE: u32 = 32;
+const LABEL_FONT_SIZE: f32 = 12.0;
+
+/// Labels the columns below the board and the rows left of it with their coordinates.
+pub struct GridLabelsPlugin {
+    pub enabled_in: HashSet<GameState>,
+    /// Size of a tile on screen relative to the default zoom of the camera.
+    pub tile_scale: f32,
+}
+
+impl Plugin for GridLabelsPlugin {
+    fn build(&self, app: &mut App) {
+        let enabled_in = self.enabled_in.clone();
+        let is_in_enabled_state =
+            move |state: Res<CurrentState<GameState>>| enabled_in.contains(&state.0);
+        app.insert_resource(TileScale(self.tile_scale))
+            .add_system(draw_grid_labels.run_if(is_in_enabled_state));
+    }
+}
+
+#[derive(Resource, Deref)]
+pub struct TileScale(pub f32);
+
+/// Where a point of the board is drawn by egui, `None` if the camera does not see it.
+pub fn world_to_egui(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    screen_height: f32,
+    position: Vec3,
+) -> Option<egui::Pos2> {
+    // the viewport starts at the bottom of the screen, egui at the top
+    camera
+        .world_to_viewport(camera_transform, position)
+        .map(|position| egui::Pos2::new(position.x, screen_height - position.y))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct GridLabel {
+    text: String,
+    position: Vec3,
+}
+
+/// Labels for every n-th column and row, placed one tile outside the board.
+/// Large boards skip coordinates so the labels do not overlap.
+fn grid_labels(board_size: u32) -> Vec<GridLabel> {
+    let interval = ((board_size + MAX_LABELS_PER_EDGE - 1) / MAX_LABELS_PER_EDGE).max(1);
+    (0..board_size)
+        .step_by(interval as usize)
+        .flat_map(|coordinate| {
+            [
+                GridLabel {
+                    text: coordinate.to_string(),
+                    position: Vec3::new(coordinate as f32, -1.0, 0.0),
+                },
+                GridLabel {
+                    text: coordinate.to_string(),
+                    position: Vec3::new(-1.0, coordinate as f32, 0.0),
+                },
+            ]
+        })
+        .collect()
+}
+
+fn draw_grid_labels(
+    mut egui_context: ResMut<EguiContext>,
+    camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    config: Option<Res<Config>>,
+) {
+    let config = match config {
+        Some(config) => config,
+        None => return,
+    };
+    let (camera, camera_transform) = camera.single();
+
+    let ctx = egui_context.ctx_mut();
+    let screen_height = ctx.screen_rect().height();
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    for label in grid_labels(config.board_size) {
+        if let Some(position) =
+            world_to_egui(camera, camera_transform, screen_height, label.position)
+        {
+            painter.text(
+                position,
+                egui::Align2::CENTER_CENTER,
+                label.text,
+                egui::FontId::proportional(LABEL_FONT_SIZE),
+                egui::Color32::WHITE,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_match_board_coordinates() {
+        let labels = grid_labels(10);
+        assert_eq!(labels.len(), 20);
+        for coordinate in 0..10 {
+            let text = coordinate.to_string();
+            let column = Vec3::new(coordinate as f32, -1.0, 0.0);
+            let row = Vec3::new(-1.0, coordinate as f32, 0.0);
+            assert!(labels.contains(&GridLabel {
+                text: text.clone(),
+                position: column
+            }));
+            assert!(labels.contains(&GridLabel {
+                text,
+                position: row
+            }));
+        }
+
+        // large boards only label every n-th coordinate, starting at the first one
+        let labels = grid_labels(128);
+        assert_eq!(labels.len(), 2 * MAX_LABELS_PER_EDGE as usize);
+        for label in labels {
+            let coordinate: u32 = label.text.parse().unwrap();
+            assert_eq!(coordinate % 4, 0);
+            assert!(
+                label.position == Vec3::new(coordinate as f32, -1.0, 0.0)
+                    || label.position == Vec3::new(-1.0, coordinate as f32, 0.0)
+            );
+        }
+    }
+}
@@ -41,6 +41,7 @@ pub struct GameEndDetails {
     pub reason: GameEndReason,
     pub winner: types::Teams,
     pub player_team: types::Teams,
+    pub revealed_enemy_ships: Vec<types::ShipState>,
 }
 
 #[derive(Resource, Deref, Default)]
@@ -184,6 +185,12 @@ fn draw_lobby_screen(
                         .color(heading_color)
                         .heading(),
                 );
+                if !details.revealed_enemy_ships.is_empty() {
+                    ui.label("Remaining enemy ships:");
+                    for ship in details.revealed_enemy_ships.iter() {
+                        draw_revealed_ship(ui, ship);
+                    }
+                }
             } else {
                 ui.heading("Lobby");
             }
@@ -280,6 +287,21 @@ fn process_lobby_events(mut commands: Commands, mut events: EventReader<messages
     }
 }
 
+fn draw_revealed_ship(ui: &mut egui::Ui, ship: &types::ShipState) {
+    let ship_type = match types::ShipType::from_i32(ship.ship_type) {
+        Some(ship_type) => format!("{ship_type:?}"),
+        None => "Unknown ship".to_string(),
+    };
+    let position = match &ship.position {
+        Some(position) => format!("({}, {})", position.x, position.y),
+        None => "an unknown position".to_string(),
+    };
+    ui.label(format!(
+        "{ship_type} of player {} at {position} with {} health",
+        ship.owner_id, ship.health
+    ));
+}
+
 fn get_readiness_from_event(lobby_state: &messages::LobbyChangeEvent, player_id: u32) -> bool {
     let mut player_state = lobby_state
         .team_state_a
@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use std::path::PathBuf;
 
 use bevy::{
     diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin},
@@ -12,11 +13,13 @@ use iyes_loopless::prelude::*;
 mod effects;
 mod game;
 mod game_state;
+mod grid_labels;
 mod lobby;
 mod models;
 mod navigation;
 mod networking;
 mod placement_phase;
+mod replay;
 mod server_selection;
 
 use game_state::GameState;
@@ -44,11 +47,22 @@ fn main() {
         .add_plugin(server_selection::ServerSelectionPlugin)
         .add_plugin(lobby::LobbyPlugin)
         .add_plugin(placement_phase::PlacementPhasePlugin)
-        .add_plugin(game::GamePlugin)
+        .add_plugin(game::GamePlugin {
+            confirm_abilities: true,
+            show_range_overlay: false,
+            show_damage_numbers: true,
+        })
         .add_plugin(navigation::NavigationPlugin {
             enabled_in: HashSet::from([GameState::PlacementPhase, GameState::Game]),
         })
+        .add_plugin(grid_labels::GridLabelsPlugin {
+            enabled_in: HashSet::from([GameState::PlacementPhase, GameState::Game]),
+            tile_scale: 1.0,
+        })
         .add_plugin(effects::EffectsPlugin)
+        .add_plugin(replay::ReplayPlugin {
+            directory: std::env::var_os("BATTLESHIP_PLUS_REPLAY_DIR").map(PathBuf::from),
+        })
         .add_startup_system(fps_counter)
         .add_startup_system(camera_setup)
         .insert_resource(lobby::UserName("Userus Namus XXVII.".to_string()))
@@ -99,16 +99,52 @@ impl ShipBundle {
 pub struct GameAssets {
     ocean_scene: Handle<Scene>,
     enemy_ship_mesh: Handle<Mesh>,
+    /// Materials of enemy tiles out of sight, indexed by the number of turns since they were seen.
+    stale_enemy_ship_materials: Vec<Handle<StandardMaterial>>,
+    island_mesh: Handle<Mesh>,
+    island_material: Handle<StandardMaterial>,
+}
+
+impl GameAssets {
+    pub fn hostile_tile_material(&self, recency: TileRecency) -> Handle<StandardMaterial> {
+        match recency {
+            TileRecency::Stale(turns) => self.stale_enemy_ship_materials[turns as usize].clone(),
+            TileRecency::Visible | TileRecency::Expired => Handle::default(),
+        }
+    }
 }
 
 pub fn load_assets(
     mut commands: Commands,
     assets: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
     commands.insert_resource(GameAssets {
         ocean_scene: assets.load("models/ocean.glb#Scene0"),
         enemy_ship_mesh: meshes.add(shape::Cube { size: 1.0 }.into()),
+        stale_enemy_ship_materials: (0..STALE_TILE_TURNS)
+            .map(|turns| {
+                let alpha = 0.6 * (STALE_TILE_TURNS - turns) as f32 / STALE_TILE_TURNS as f32;
+                materials.add(StandardMaterial {
+                    base_color: Color::rgba(1.0, 1.0, 1.0, alpha),
+                    alpha_mode: AlphaMode::Blend,
+                    ..default()
+                })
+            })
+            .collect(),
+        island_mesh: meshes.add(
+            shape::Box {
+                min_x: -0.5,
+                max_x: 0.5,
+                min_y: -0.5,
+                max_y: 0.5,
+                min_z: -0.5,
+                max_z: 0.2,
+            }
+            .into(),
+        ),
+        island_material: materials.add(Color::rgb(0.76, 0.7, 0.5).into()),
     });
 }
 
@@ -140,9 +176,36 @@ impl OceanBundle {
     }
 }
 
+/// Number of turns an enemy tile is still shown after it went out of sight, it fades out meanwhile.
+pub const STALE_TILE_TURNS: u32 = 3;
+
 #[derive(Component)]
 pub struct HostileShipTile {
     pub position: Coordinate,
+    /// Turn in which the tile went out of sight, `None` while it is in sight.
+    pub last_seen_turn: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileRecency {
+    Visible,
+    /// Out of sight for the given number of turns, the ship may have moved on since.
+    Stale(u32),
+    /// Out of sight for too long to be shown anymore.
+    Expired,
+}
+
+impl HostileShipTile {
+    pub fn recency(&self, turn_number: u32) -> TileRecency {
+        match self
+            .last_seen_turn
+            .map(|turn| turn_number.saturating_sub(turn))
+        {
+            None => TileRecency::Visible,
+            Some(turns) if turns >= STALE_TILE_TURNS => TileRecency::Expired,
+            Some(turns) => TileRecency::Stale(turns),
+        }
+    }
 }
 
 #[derive(Bundle)]
@@ -162,8 +225,61 @@ impl HostileShipBundle {
             },
             tile: HostileShipTile {
                 position: position.clone(),
+                last_seen_turn: None,
             },
             name: Name::new("Hostile Ship"),
         }
     }
 }
+
+#[derive(Bundle)]
+pub struct IslandBundle {
+    model: PbrBundle,
+    name: Name,
+}
+
+impl IslandBundle {
+    pub fn new(assets: &Res<GameAssets>, position: &Coordinate) -> Self {
+        Self {
+            model: PbrBundle {
+                mesh: assets.island_mesh.clone(),
+                material: assets.island_material.clone(),
+                transform: Transform::from_xyz(position.x as f32, position.y as f32, 0.0),
+                ..default()
+            },
+            name: Name::new("Island"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hostile_tiles_go_stale_without_refresh() {
+        let mut tile = HostileShipTile {
+            position: Coordinate { x: 3, y: 4 },
+            last_seen_turn: None,
+        };
+        assert_eq!(tile.recency(7), TileRecency::Visible);
+
+        // out of sight since turn 7
+        tile.last_seen_turn = Some(7);
+        let recency: Vec<_> = (7..12).map(|turn| tile.recency(turn)).collect();
+        assert_eq!(
+            recency,
+            vec![
+                TileRecency::Stale(0),
+                TileRecency::Stale(1),
+                TileRecency::Stale(2),
+                TileRecency::Expired,
+                TileRecency::Expired,
+            ]
+        );
+
+        // sighted again
+        tile.last_seen_turn = None;
+        assert_eq!(tile.recency(12), TileRecency::Visible);
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     net::{Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, ToSocketAddrs},
     str::FromStr,
     sync::mpsc,
@@ -15,6 +16,7 @@ use tokio_util::codec::Decoder;
 
 use battleship_plus_common::{
     codec::BattleshipPlusCodec,
+    config::MAX_BOARD_SIZE,
     messages::{self, EventMessage, ProtocolMessage, ServerAdvertisement, StatusCode},
     protocol_name, protocol_name_with_version, types,
 };
@@ -34,6 +36,9 @@ impl Plugin for NetworkingPlugin {
             .add_event::<ConfigReceivedEvent>()
             .add_event::<messages::EventMessage>()
             .add_event::<ResponseReceivedEvent>()
+            .init_resource::<RequestedGame>()
+            .init_resource::<SeatToken>()
+            .init_resource::<ConfigCache>()
             .register_inspectable::<ServerInformation>()
             .register_inspectable::<Connection>()
             .add_startup_system(set_up_advertisement_listener)
@@ -49,6 +54,7 @@ impl Plugin for NetworkingPlugin {
             .add_system(receive_advertisements)
             .add_system(request_server_configurations)
             .add_system(process_server_configurations)
+            .add_system(send_keepalives.run_not_in_state(GameState::Unconnected))
             .add_enter_system(GameState::Joining, join_server)
             .add_enter_system(GameState::JoiningFailed, try_leave_server)
             .add_enter_system(GameState::Unconnected, try_leave_server)
@@ -66,6 +72,32 @@ pub struct ConfigReceivedEvent(pub messages::StatusMessage, pub ConnectionId);
 #[derive(Resource, Deref)]
 pub struct CurrentServer(pub Entity);
 
+// The game to request when joining, the server picks one if there is none.
+#[derive(Resource, Deref, Default)]
+pub struct RequestedGame(pub Option<u32>);
+
+// The token of the seat taken on the last join, it keeps the seat after a restart of the server.
+#[derive(Resource, Deref, Default)]
+pub struct SeatToken(pub Option<String>);
+
+/// Configs received from servers by address and config hash. A server that advertises a config
+/// hash found here is not asked for its config again, e.g. when reconnecting after a game.
+#[derive(Resource, Default)]
+pub struct ConfigCache(HashMap<(SocketAddr, u64), types::Config>);
+
+impl ConfigCache {
+    fn insert(&mut self, address: SocketAddr, config: types::Config) {
+        // a server only uses one config at a time, older ones are outdated
+        self.0
+            .retain(|(cached_address, _), _| *cached_address != address);
+        self.0.insert((address, config.config_hash()), config);
+    }
+
+    fn get(&self, address: SocketAddr, config_hash: u64) -> Option<&types::Config> {
+        self.0.get(&(address, config_hash))
+    }
+}
+
 const ADVERTISEMENT_LIFETIME: Duration = Duration::from_secs(10);
 const CONFIGURATION_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
 
@@ -134,6 +166,7 @@ pub struct ServerInformation {
     pub name: String,
     pub config_last_requested: Option<Duration>,
     pub config: Option<types::Config>,
+    pub games: Option<Vec<types::GameSummary>>,
     pub security: Empirical<SecurityLevel>,
     pub remove_at: Duration,
 }
@@ -153,6 +186,7 @@ impl Inspectable for ServerInformation {
         modified |= self.remove_at.ui(ui, (), context);
         ui.label(format!("Security: {:#?}", self.security));
         ui.label(format!("Config: {:#?}", self.config));
+        ui.label(format!("Games: {:#?}", self.games));
         modified
     }
 }
@@ -218,6 +252,7 @@ fn construct_server_information(address_string: &str, address: SocketAddr) -> Se
         name: host,
         config_last_requested: None,
         config: None,
+        games: None,
         security: Empirical::Unconfirmed(SecurityLevel::AuthoritySigned),
         remove_at: Duration::MAX,
     }
@@ -446,6 +481,7 @@ fn receive_advertisements(
     time: Res<Time>,
     mut servers: Query<&mut ServerInformation>,
     mut client: ResMut<Client>,
+    config_cache: Res<ConfigCache>,
 ) {
     for mut receiver in receivers.iter_mut() {
         loop {
@@ -457,6 +493,7 @@ fn receive_advertisements(
                     &time,
                     &mut servers,
                     &mut client,
+                    &config_cache,
                 ),
                 Err(mpsc::RecvTimeoutError::Timeout) => break,
                 Err(mpsc::RecvTimeoutError::Disconnected) => {
@@ -474,6 +511,7 @@ fn process_advertisement(
     time: &Res<Time>,
     servers: &mut Query<&mut ServerInformation>,
     client: &mut ResMut<Client>,
+    config_cache: &ConfigCache,
 ) {
     // Update server if it already has a ServerInformation.
     if let Some(mut server) = servers.iter_mut().find(|server| {
@@ -495,15 +533,12 @@ fn process_advertisement(
             .into(),
         };
 
-        let server_information = ServerInformation {
-            host: None,
-            address: server_address,
-            name: advertisement.display_name.clone(),
-            config: None,
-            config_last_requested: None,
-            security: Empirical::Unconfirmed(SecurityLevel::AuthoritySigned),
-            remove_at: time.elapsed() + ADVERTISEMENT_LIFETIME,
-        };
+        let server_information = discovered_server_information(
+            &advertisement,
+            server_address,
+            config_cache,
+            time.elapsed() + ADVERTISEMENT_LIFETIME,
+        );
         let server = commands.spawn(server_information.clone()).id();
 
         // Connect to the server to request its configuration.
@@ -511,6 +546,27 @@ fn process_advertisement(
     }
 }
 
+// Helper function for process_advertisement(), takes the config from the cache if it is known.
+fn discovered_server_information(
+    advertisement: &ServerAdvertisement,
+    address: SocketAddr,
+    config_cache: &ConfigCache,
+    remove_at: Duration,
+) -> ServerInformation {
+    ServerInformation {
+        host: None,
+        address,
+        name: advertisement.display_name.clone(),
+        config: config_cache
+            .get(address, advertisement.config_hash)
+            .cloned(),
+        games: None,
+        config_last_requested: None,
+        security: Empirical::Unconfirmed(SecurityLevel::AuthoritySigned),
+        remove_at,
+    }
+}
+
 #[derive(Component, Clone, Inspectable, Deref)]
 pub struct Connection(pub ConnectionId);
 
@@ -553,7 +609,7 @@ fn request_server_configurations_from(
     client: &mut ResMut<Client>,
     time: &Res<Time>,
 ) {
-    if server_information.config.is_some() {
+    if server_information.config.is_some() && server_information.games.is_some() {
         return;
     }
 
@@ -571,13 +627,23 @@ fn request_server_configurations_from(
         }
     };
 
-    let message: messages::ProtocolMessage = messages::ServerConfigRequest {}.into();
-
-    if let Err(error) = connection.send_message(message) {
-        warn!(
-            "Failed to send server configuration request to {}: {error}",
-            server_information.address
-        );
+    if server_information.config.is_none() {
+        let message: messages::ProtocolMessage = messages::ServerConfigRequest {}.into();
+        if let Err(error) = connection.send_message(message) {
+            warn!(
+                "Failed to send server configuration request to {}: {error}",
+                server_information.address
+            );
+        }
+    }
+    if server_information.games.is_none() {
+        let message: messages::ProtocolMessage = messages::GameListRequest {}.into();
+        if let Err(error) = connection.send_message(message) {
+            warn!(
+                "Failed to send game list request to {}: {error}",
+                server_information.address
+            );
+        }
     }
 
     server_information.config_last_requested = Some(time.elapsed());
@@ -655,10 +721,12 @@ fn listen_for_messages_from(
                 }
                 if let Some(config_response_events) = &mut config_response_events {
                     // Only send a ConfigReceivedEvent for the current server if the status message
-                    // actually contains a configuration.
+                    // actually contains a configuration or a game list.
                     let mut send_config_response_event = !is_current_server;
-                    if let Some(messages::status_message::Data::ServerConfigResponse(_)) =
-                        status_message.data
+                    if let Some(
+                        messages::status_message::Data::ServerConfigResponse(_)
+                        | messages::status_message::Data::GameListResponse(_),
+                    ) = status_message.data
                     {
                         send_config_response_event = true;
                     }
@@ -702,6 +770,7 @@ fn process_server_configurations(
     mut servers: Query<(Entity, &mut ServerInformation, &Connection)>,
     mut client: ResMut<Client>,
     current_server: Option<Res<CurrentServer>>,
+    mut config_cache: ResMut<ConfigCache>,
 ) {
     for ConfigReceivedEvent(
         messages::StatusMessage {
@@ -720,18 +789,17 @@ fn process_server_configurations(
             None => continue,
         };
 
-        let mut close_connection = true;
+        let mut is_discovery = true;
         if let Some(current_server) = &current_server {
-            close_connection = entity != ***current_server;
+            is_discovery = entity != ***current_server;
         }
-
-        if close_connection {
-            trace!("Received some response after config request during discovery, closing connection {}.", **connection);
+        let mut close_connection = || {
+            trace!("Received all responses after config request during discovery, closing connection {}.", **connection);
             if let Err(error) = client.close_connection(**connection) {
                 warn!("Failed to close connection properly: {error}");
             }
             commands.entity(entity).remove::<Connection>();
-        }
+        };
 
         let original_code = code;
         let code = StatusCode::from_i32(*code);
@@ -758,6 +826,9 @@ fn process_server_configurations(
                 } else {
                     warn!("Received inappropriate error code {other_code:?} from server at {sender} with message: {message}");
                 }
+                if is_discovery {
+                    close_connection();
+                }
                 continue;
             }
             None => {
@@ -766,24 +837,41 @@ fn process_server_configurations(
                 } else {
                     warn!("Received unknown error code {original_code} from server at {sender} with message: {message}");
                 }
+                if is_discovery {
+                    close_connection();
+                }
                 continue;
             }
         }
-        let response = match data {
-            Some(messages::status_message::Data::ServerConfigResponse(response)) => response,
+        match data {
+            Some(messages::status_message::Data::ServerConfigResponse(response)) => {
+                if response.config.is_none() {
+                    warn!("Received empty ServerConfigResponse from {sender}. This indicates an error in that server");
+                } else if response
+                    .config
+                    .as_ref()
+                    .map_or(false, |config| config.board_size > MAX_BOARD_SIZE)
+                {
+                    // the board would be too large to build, so the server cannot be joined
+                    warn!("Refusing the config of {sender}, its board is larger than {MAX_BOARD_SIZE}x{MAX_BOARD_SIZE}");
+                } else {
+                    server.config = response.config.to_owned();
+                    config_cache.insert(server.address, server.config.clone().unwrap());
+                }
+            }
+            Some(messages::status_message::Data::GameListResponse(response)) => {
+                server.games = Some(response.games.to_owned());
+            }
             Some(_other_response) => {
                 warn!("No data in response after ConfigRequest but OK status code");
                 // ignore
-                continue;
             }
-            None => continue,
+            None => {}
         };
-        if response.config.is_none() {
-            warn!("Received empty ServerConfigResponse from {sender}. This indicates an error in that server");
-            continue;
-        }
 
-        server.config = response.config.to_owned();
+        if is_discovery && server.config.is_some() && server.games.is_some() {
+            close_connection();
+        }
     }
 }
 
@@ -824,6 +912,8 @@ fn join_server(
     connections: Query<(Entity, &Connection)>,
     mut client: ResMut<Client>,
     user_name: Res<crate::lobby::UserName>,
+    requested_game: Res<RequestedGame>,
+    seat_token: Res<SeatToken>,
 ) {
     info!("Joining server");
     let server = server.expect("There must always exist a CurrentServer in GameState::Joining");
@@ -848,6 +938,9 @@ fn join_server(
 
     let message = messages::JoinRequest {
         username: user_name.clone(),
+        game_id: **requested_game,
+        team: None,
+        seat_token: (**seat_token).clone(),
     };
     if let Err(error) = connection.send_message(message.into()) {
         warn!("Could not send join request: {error}");
@@ -868,6 +961,11 @@ fn try_leave_server(
     };
     info!("Leaving server");
     if let Ok(connection) = connections.get_component::<Connection>(**server) {
+        if let Some(quinnet_connection) = client.get_connection_mut_by_id(**connection) {
+            if let Err(error) = quinnet_connection.disconnect("leaving the server") {
+                warn!("Failed to tell the server about leaving: {error}");
+            }
+        }
         if let Err(error) = client.close_connection(**connection) {
             warn!("Failed to close connection properly: {error}");
         }
@@ -887,3 +985,112 @@ fn cleanup_on_exit(
         try_leave_server(commands, server, connections, client)
     }
 }
+
+/// Sends keepalives to the current server in the interval it configured, 0 disables them.
+/// Unlike the keep-alive of QUIC, the messages are seen by the server application and keep NAT
+/// mappings open while the player is thinking.
+fn send_keepalives(
+    time: Res<Time>,
+    mut timer: Local<KeepaliveTimer>,
+    server: Option<Res<CurrentServer>>,
+    servers: Query<&ServerInformation>,
+    client: Res<Client>,
+) {
+    let interval = server
+        .and_then(|server| servers.get(**server).ok())
+        .and_then(|server| server.config.as_ref())
+        .map_or(0, |config| config.keepalive_interval);
+    if !timer.tick(time.delta(), interval) {
+        return;
+    }
+
+    if let Some(connection) = client.get_connection() {
+        if let Err(error) = connection.send_message(messages::Keepalive {}.into()) {
+            warn!("Could not send keepalive: {error}");
+        }
+    }
+}
+
+#[derive(Default)]
+struct KeepaliveTimer {
+    interval: u32,
+    timer: Timer,
+}
+
+impl KeepaliveTimer {
+    /// Advances the timer and returns whether a keepalive is due.
+    /// A changed interval in milliseconds restarts the timer.
+    fn tick(&mut self, delta: Duration, interval: u32) -> bool {
+        if interval != self.interval {
+            self.interval = interval;
+            self.timer = Timer::new(Duration::from_millis(interval as u64), TimerMode::Repeating);
+        }
+        interval > 0 && self.timer.tick(delta).just_finished()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keepalives_follow_interval() {
+        let mut timer = KeepaliveTimer::default();
+        let due: Vec<_> = (0..10)
+            .map(|_| timer.tick(Duration::from_millis(40), 100))
+            .collect();
+        // due after 120, 200, 320 and 400 ms
+        assert_eq!(
+            due,
+            vec![false, false, true, false, true, false, false, true, false, true]
+        );
+
+        // an interval of 0 disables keepalives
+        assert!((0..10).all(|_| !timer.tick(Duration::from_millis(40), 0)));
+
+        // a new interval restarts the timer
+        assert!(!timer.tick(Duration::from_millis(40), 50));
+        assert!(timer.tick(Duration::from_millis(40), 50));
+    }
+
+    #[test]
+    fn cached_config_skips_request() {
+        let address = SocketAddr::from_str("[::1]:30305").unwrap();
+        let config = types::Config {
+            board_size: 16,
+            ..Default::default()
+        };
+        let advertisement = ServerAdvertisement {
+            port: 30305,
+            display_name: "server".to_string(),
+            config_hash: config.config_hash(),
+        };
+        let mut config_cache = ConfigCache::default();
+        let server =
+            discovered_server_information(&advertisement, address, &config_cache, Duration::ZERO);
+        assert_eq!(server.config, None);
+
+        // servers that already have a config are only asked for their games
+        config_cache.insert(address, config.clone());
+        let server =
+            discovered_server_information(&advertisement, address, &config_cache, Duration::ZERO);
+        assert_eq!(server.config, Some(config.clone()));
+
+        // a changed config or another server on the same host is fetched again
+        let changed = ServerAdvertisement {
+            config_hash: config.config_hash() ^ 1,
+            ..advertisement.clone()
+        };
+        let server =
+            discovered_server_information(&changed, address, &config_cache, Duration::ZERO);
+        assert_eq!(server.config, None);
+        let other_address = SocketAddr::from_str("[::1]:30306").unwrap();
+        let server = discovered_server_information(
+            &advertisement,
+            other_address,
+            &config_cache,
+            Duration::ZERO,
+        );
+        assert_eq!(server.config, None);
+    }
+}
@@ -8,7 +8,10 @@ use iyes_loopless::prelude::*;
 use rstar::{Envelope, RTreeObject, AABB};
 
 use battleship_plus_common::{
-    game::ship::{Orientation, Ship, ShipID},
+    game::{
+        ship::{Orientation, Ship, ShipID},
+        ship_manager::ShipManager,
+    },
     messages::{self, EventMessage, GameStart, SetPlacementRequest, StatusCode, StatusMessage},
     types::{self, ShipAssignment, ShipType, Teams},
     util,
@@ -19,9 +22,10 @@ use crate::game_state::CachedEvents;
 use crate::{
     game,
     game_state::{Config, GameState, PlayerId, PlayerTeam, Ships},
+    grid_labels::TileScale,
     lobby::LobbyState,
     models::{
-        load_assets, new_ship_model, GameAssets, OceanBundle, ShipBundle, ShipMeshes,
+        load_assets, new_ship_model, GameAssets, IslandBundle, OceanBundle, ShipBundle, ShipMeshes,
         CLICK_PLANE_OFFSET_Z,
     },
     networking::{self, CurrentServer, ServerInformation},
@@ -116,7 +120,6 @@ fn create_resources(
     servers: Query<&ServerInformation>,
     mut meshes: ResMut<Assets<Mesh>>,
 ) {
-    commands.insert_resource(Ships::default());
     commands.insert_resource(PlacementState::default());
 
     let is_team_a = lobby_state
@@ -136,6 +139,9 @@ fn create_resources(
         .config
         .clone()
         .expect("Joined server always has a configuration");
+    let mut ships = ShipManager::new();
+    ships.set_islands(&config.islands);
+    commands.insert_resource(Ships(ships));
     commands.insert_resource(Config(Arc::new(config)));
 
     commands.insert_resource(ShipMeshes::new(&mut meshes));
@@ -152,6 +158,11 @@ fn spawn_components(
     commands
         .spawn(OceanBundle::new(&assets, config.clone()))
         .insert(DespawnOnExit);
+    for island in config.islands.iter() {
+        commands
+            .spawn(IslandBundle::new(&assets, island))
+            .insert(DespawnOnExit);
+    }
     commands
         .spawn(DirectionalLightBundle {
             transform: Transform::from_rotation(Quat::from_axis_angle(
@@ -195,7 +206,11 @@ fn spawn_components(
         .insert(DespawnOnExit);
 }
 
-fn move_camera(mut camera: Query<(&mut Transform, With<Camera3d>)>, quadrant: Res<Quadrant>) {
+fn move_camera(
+    mut camera: Query<(&mut Transform, With<Camera3d>)>,
+    quadrant: Res<Quadrant>,
+    tile_scale: Res<TileScale>,
+) {
     // TODO: Scale the camera so that the quadrant is entirely visible (?).
     let half_quadrant_size = quadrant.side_length() as f32 / 2.0;
     let mut camera_transform = camera.single_mut().0;
@@ -204,7 +219,8 @@ fn move_camera(mut camera: Query<(&mut Transform, With<Camera3d>)>, quadrant: Re
         quadrant.lower()[1] as f32 + half_quadrant_size,
         camera_transform.translation.z,
     );
-    camera_transform.scale = Vec3::new(0.7, 0.7, 1.0);
+    let scale = 0.7 / **tile_scale;
+    camera_transform.scale = Vec3::new(scale, scale, 1.0);
 }
 
 fn despawn_components(
@@ -239,6 +255,9 @@ fn draw_menu(
     mut placement_state: ResMut<PlacementState>,
     key_input: Res<Input<KeyCode>>,
 ) {
+    // checked before the panel is drawn, so focus gained during this frame does not count
+    let egui_wants_keyboard = egui_context.ctx_mut().wants_keyboard_input();
+
     egui::TopBottomPanel::bottom(egui::Id::new("placement_menu")).show(
         egui_context.ctx_mut(),
         |ui| {
@@ -246,7 +265,11 @@ fn draw_menu(
                 ui.horizontal_centered(|mut ui| {
                     ui.set_height(50.0);
 
-                    let mut resources = (&mut ui, &mut commands, &mut selected, &key_input);
+                    let hotkeys = Hotkeys {
+                        key_input: &key_input,
+                        egui_wants_keyboard,
+                    };
+                    let mut resources = (&mut ui, &mut commands, &mut selected, &hotkeys);
                     add_selection_button(ShipType::Destroyer, KeyCode::Key1, &mut resources);
                     add_selection_button(ShipType::Submarine, KeyCode::Key2, &mut resources);
                     add_selection_button(ShipType::Cruiser, KeyCode::Key3, &mut resources);
@@ -262,7 +285,7 @@ fn draw_menu(
                         update_ship_rotation(&mut selected, false);
                     } else if counter_clockwise_button.clicked() {
                         update_ship_rotation(&mut selected, true);
-                    } else if key_input.just_pressed(KeyCode::R) {
+                    } else if hotkeys.just_pressed(KeyCode::R) {
                         let counter_clockwise = key_input.pressed(KeyCode::LShift);
                         update_ship_rotation(&mut selected, counter_clockwise);
                     }
@@ -296,14 +319,27 @@ fn draw_menu(
     );
 }
 
+/// Keyboard input meant for the game rather than for egui widgets.
+struct Hotkeys<'a> {
+    key_input: &'a Input<KeyCode>,
+    egui_wants_keyboard: bool,
+}
+
+impl Hotkeys<'_> {
+    /// Typing into a focused text field must not trigger game actions as well.
+    fn just_pressed(&self, key: KeyCode) -> bool {
+        !self.egui_wants_keyboard && self.key_input.just_pressed(key)
+    }
+}
+
 fn add_selection_button(
     ship: ShipType,
     key: KeyCode,
-    (ui, commands, selected, key_input): &mut (
+    (ui, commands, selected, hotkeys): &mut (
         &mut &mut egui::Ui,
         &mut Commands,
         &mut Option<ResMut<SelectedShip>>,
-        &Res<Input<KeyCode>>,
+        &Hotkeys,
     ),
 ) {
     let color = match selected {
@@ -314,7 +350,7 @@ fn add_selection_button(
     let mut text = egui::text::LayoutJob::default();
     text.append(&format!("{ship:?}"), 0.0, format);
 
-    if (ui.button(text)).clicked() || key_input.just_pressed(key) {
+    if (ui.button(text)).clicked() || hotkeys.just_pressed(key) {
         match selected {
             None => commands.insert_resource(SelectedShip {
                 ship,
@@ -332,21 +368,11 @@ fn update_ship_rotation(selected: &mut Option<ResMut<SelectedShip>>, counter_clo
         None => return,
     };
 
-    selected.orientation = if counter_clockwise {
-        match selected.orientation {
-            Orientation::North => Orientation::West,
-            Orientation::East => Orientation::North,
-            Orientation::South => Orientation::East,
-            Orientation::West => Orientation::South,
-        }
+    selected.orientation = selected.orientation.rotated(if counter_clockwise {
+        types::RotateDirection::CounterClockwise
     } else {
-        match selected.orientation {
-            Orientation::North => Orientation::East,
-            Orientation::East => Orientation::South,
-            Orientation::South => Orientation::West,
-            Orientation::West => Orientation::North,
-        }
-    };
+        types::RotateDirection::Clockwise
+    });
 }
 
 fn preview_ship(
@@ -493,8 +519,8 @@ fn next_ship_id(
     };
     (0..ship_set.len())
         .map(|i| (i as u32, ShipType::from_i32(ship_set[i])))
-        .map(|(id, ship_type)| (id, ship_type.expect("Ship sets contain ShipTypes")))
-        .filter(|(_, entry_ship_type)| *entry_ship_type == ship_type)
+        // entries of unknown ship types can never be placed
+        .filter(|(_, entry_ship_type)| *entry_ship_type == Some(ship_type))
         .map(|(id, _)| id)
         .find(|id| !used_ship_ids.contains(id))
         .map(|ship_id| (***player_id, ship_id))
@@ -706,3 +732,27 @@ fn repeat_cached_events(
     event_writer.send_batch(cached_events.into_iter());
     commands.remove_resource::<CachedEvents>();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hotkeys_are_ignored_while_egui_wants_keyboard_input() {
+        let mut key_input = Input::<KeyCode>::default();
+        key_input.press(KeyCode::R);
+
+        let hotkeys = Hotkeys {
+            key_input: &key_input,
+            egui_wants_keyboard: false,
+        };
+        assert!(hotkeys.just_pressed(KeyCode::R));
+        assert!(!hotkeys.just_pressed(KeyCode::Key1));
+
+        let hotkeys = Hotkeys {
+            key_input: &key_input,
+            egui_wants_keyboard: true,
+        };
+        assert!(!hotkeys.just_pressed(KeyCode::R));
+    }
+}
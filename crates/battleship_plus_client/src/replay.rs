@@ -0,0 +1,185 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use bytes::BytesMut;
+use iyes_loopless::prelude::*;
+use tokio_util::codec::Encoder;
+
+use battleship_plus_common::codec::BattleshipPlusCodec;
+use battleship_plus_common::messages::{EventMessage, ProtocolMessage};
+
+use crate::game_state::GameState;
+
+const REPLAY_FILE_EXTENSION: &str = "bsreplay";
+
+/// Records the events received during a match into a replay file, so matches can be kept and
+/// shared without the replay log of the server. The events are framed like on the wire.
+pub struct ReplayPlugin {
+    /// Directory the replay files are written to, nothing is recorded without one.
+    pub directory: Option<PathBuf>,
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let directory = match &self.directory {
+            Some(directory) => directory.clone(),
+            None => return,
+        };
+        app.insert_resource(ReplayDirectory(directory))
+            .add_enter_system(GameState::PlacementPhase, start_recording)
+            .add_enter_system(GameState::Unconnected, stop_recording)
+            .add_system(record_events);
+    }
+}
+
+#[derive(Resource, Deref)]
+struct ReplayDirectory(PathBuf);
+
+#[derive(Resource, Deref, DerefMut)]
+struct ReplayRecorder(ReplayWriter<BufWriter<File>>);
+
+/// Writes events framed by the [`BattleshipPlusCodec`].
+pub struct ReplayWriter<W: Write> {
+    sink: W,
+    codec: BattleshipPlusCodec,
+    buffer: BytesMut,
+}
+
+impl<W: Write> ReplayWriter<W> {
+    pub fn new(sink: W) -> Self {
+        ReplayWriter {
+            sink,
+            codec: BattleshipPlusCodec::default(),
+            buffer: BytesMut::new(),
+        }
+    }
+
+    pub fn record(&mut self, event: EventMessage) -> io::Result<()> {
+        self.buffer.clear();
+        self.codec
+            .encode(ProtocolMessage::from(event), &mut self.buffer)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        self.sink.write_all(&self.buffer)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.sink.flush()
+    }
+
+    #[cfg(test)]
+    fn into_inner(self) -> W {
+        self.sink
+    }
+}
+
+fn start_recording(
+    mut commands: Commands,
+    directory: Res<ReplayDirectory>,
+    recorder: Option<Res<ReplayRecorder>>,
+) {
+    if recorder.is_some() {
+        return;
+    }
+    let started_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let path = directory.join(format!("match-{started_at}.{REPLAY_FILE_EXTENSION}"));
+    match File::create(&path) {
+        Ok(file) => {
+            info!("Recording the match to {}", path.display());
+            commands.insert_resource(ReplayRecorder(ReplayWriter::new(BufWriter::new(file))));
+        }
+        Err(error) => warn!("Unable to record the match to {}: {error}", path.display()),
+    }
+}
+
+fn record_events(
+    mut commands: Commands,
+    mut events: EventReader<EventMessage>,
+    recorder: Option<ResMut<ReplayRecorder>>,
+) {
+    let mut recorder = match recorder {
+        Some(recorder) => recorder,
+        None => return,
+    };
+    for event in events.iter() {
+        if let Err(error) = recorder.record(event.clone()) {
+            warn!("Stopped recording the match: {error}");
+            commands.remove_resource::<ReplayRecorder>();
+            return;
+        }
+    }
+}
+
+fn stop_recording(mut commands: Commands, recorder: Option<ResMut<ReplayRecorder>>) {
+    if let Some(mut recorder) = recorder {
+        if let Err(error) = recorder.flush() {
+            warn!("Unable to finish the replay of the match: {error}");
+        }
+        commands.remove_resource::<ReplayRecorder>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_util::codec::Decoder;
+
+    use battleship_plus_common::codec::CodecError;
+    use battleship_plus_common::messages::{GameOverEvent, HitEvent, NextTurn};
+    use battleship_plus_common::types::Coordinate;
+
+    use super::*;
+
+    /// Reads the events of a replay in the order they were received.
+    fn read_replay(replay: &[u8]) -> Result<Vec<EventMessage>, CodecError> {
+        let mut codec = BattleshipPlusCodec::default();
+        let mut buffer = BytesMut::from(replay);
+        let mut events = vec![];
+        while let Some(message) = codec.decode_eof(&mut buffer)? {
+            if let Some(event) = message.and_then(|message| EventMessage::try_from(message).ok()) {
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+
+    #[test]
+    fn recorded_events_decode_to_the_same_events() {
+        let events = vec![
+            EventMessage::NextTurn(NextTurn {
+                next_player_id: 2,
+                ..Default::default()
+            }),
+            EventMessage::HitEvent(HitEvent {
+                coordinate: Some(Coordinate { x: 3, y: 4 }),
+                ..Default::default()
+            }),
+            EventMessage::GameOverEvent(GameOverEvent::default()),
+        ];
+
+        let mut writer = ReplayWriter::new(Vec::new());
+        for event in &events {
+            writer.record(event.clone()).unwrap();
+        }
+        let replay = writer.into_inner();
+
+        let decoded = read_replay(&replay).expect("the replay is well-formed");
+        assert_eq!(
+            decoded
+                .into_iter()
+                .map(ProtocolMessage::from)
+                .collect::<Vec<_>>(),
+            events
+                .into_iter()
+                .map(ProtocolMessage::from)
+                .collect::<Vec<_>>()
+        );
+
+        // a file cut off while writing is reported rather than silently missing its last event
+        let truncated = read_replay(&replay[..replay.len() - 1]);
+        assert!(truncated.is_err());
+    }
+}
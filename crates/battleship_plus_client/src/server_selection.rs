@@ -6,6 +6,7 @@ use iyes_loopless::prelude::*;
 use std::str::FromStr;
 
 use battleship_plus_common::messages::{self, StatusCode};
+use battleship_plus_common::types::{self, GamePhase};
 
 use crate::game_state::{CachedEvents, GameState, PlayerId};
 use crate::lobby;
@@ -110,6 +111,17 @@ fn draw_selection_screen(
                                 &mut commands,
                             );
                         });
+                        for game in server_information.games.iter().flatten() {
+                            body.row(20.0, |mut row| {
+                                draw_game_list_row(
+                                    &mut row,
+                                    server,
+                                    server_information,
+                                    game,
+                                    &mut commands,
+                                );
+                            });
+                        }
                     }
                 });
 
@@ -205,25 +217,60 @@ fn draw_server_list_row(
         };
     });
     row.col(|ui| {
-        let mut enabled = true;
-        if let networking::Empirical::Unconfirmed(_) = server_information.security {
-            enabled = false;
-        } else if let networking::Empirical::Confirmed(
-            networking::SecurityLevel::ConnectionFailed,
-        ) = server_information.security
-        {
-            enabled = false;
-        } else if server_information.config.is_none() {
-            enabled = false;
+        let enabled = is_joinable(server_information);
+        let join_button = ui.add_enabled(enabled, egui::Button::new("Join"));
+        if join_button.clicked() {
+            commands.insert_resource(networking::RequestedGame(None));
+            commands.insert_resource(networking::CurrentServer(server));
+            commands.insert_resource(NextState(GameState::Joining));
         }
+    });
+}
+
+fn draw_game_list_row(
+    row: &mut egui_extras::TableRow,
+    server: Entity,
+    server_information: &networking::ServerInformation,
+    game: &types::GameSummary,
+    commands: &mut Commands,
+) {
+    let phase = GamePhase::from_i32(game.phase);
+    row.col(|ui| {
+        ui.label(format!("    \u{21B3} {}", game.name));
+    });
+    row.col(|ui| {
+        ui.label(format!(
+            "{}/{} players",
+            game.player_count, game.max_player_count
+        ));
+    });
+    row.col(|ui| {
+        match phase {
+            Some(GamePhase::Lobby) => ui.label("open"),
+            Some(GamePhase::Preparation) => ui.label("placing ships"),
+            Some(GamePhase::InGame) => ui.label("in progress"),
+            None => ui.label("unknown"),
+        };
+    });
+    row.col(|ui| {
+        let enabled = is_joinable(server_information) && phase == Some(GamePhase::Lobby);
         let join_button = ui.add_enabled(enabled, egui::Button::new("Join"));
         if join_button.clicked() {
+            commands.insert_resource(networking::RequestedGame(Some(game.game_id)));
             commands.insert_resource(networking::CurrentServer(server));
             commands.insert_resource(NextState(GameState::Joining));
         }
     });
 }
 
+fn is_joinable(server_information: &networking::ServerInformation) -> bool {
+    use networking::{Empirical::*, SecurityLevel::*};
+    match server_information.security {
+        Unconfirmed(_) | Confirmed(ConnectionFailed) => false,
+        _ => server_information.config.is_some(),
+    }
+}
+
 fn draw_joining_screen(mut egui_context: ResMut<EguiContext>) {
     egui::CentralPanel::default().show(egui_context.ctx_mut(), |ui| {
         ui.vertical_centered(|ui| {
@@ -308,6 +355,10 @@ fn process_join_response(
                 error!("User name is taken, disconnecting");
                 commands.insert_resource(NextState(GameState::Unconnected));
             }
+            Some(StatusCode::GameInProgress) => {
+                error!("The game is already in progress, disconnecting");
+                commands.insert_resource(NextState(GameState::Unconnected));
+            }
             Some(StatusCode::LobbyIsFull) => {
                 info!("The lobby is full, disconnecting");
                 continue;
@@ -356,10 +407,13 @@ fn process_join_response_data(
     match data {
         Some(messages::status_message::Data::JoinResponse(messages::JoinResponse {
             player_id,
+            game_id,
+            seat_token,
         })) => {
-            debug!("Join successful, got player ID {player_id}");
+            debug!("Join successful, got player ID {player_id} in game {game_id}");
             commands.insert_resource(NextState(GameState::Lobby));
             commands.insert_resource(PlayerId(*player_id));
+            commands.insert_resource(networking::SeatToken(Some(seat_token.clone())));
             trace!("Repeating events that happened during state transition");
             let events = Vec::from_iter(event_reader.iter().map(|event| (*event).clone()));
             commands.insert_resource(CachedEvents(events));
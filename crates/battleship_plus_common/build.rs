@@ -2,22 +2,42 @@ use std::env;
 use std::fs;
 use std::io::Result;
 
+const SERIALIZABLE_CONFIG_TYPES: [&str; 9] = [
+    "Config",
+    "Coordinate",
+    "Costs",
+    "CommonBalancing",
+    "CarrierBalancing",
+    "BattleshipBalancing",
+    "CruiserBalancing",
+    "SubmarineBalancing",
+    "DestroyerBalancing",
+];
+
 fn main() -> Result<()> {
     println!("cargo:rerun-if-changed=build.rs");
 
-    let specification_directory = String::from(env!("RESOURCE_DIR")) + "/rfc/encoding";
+    let specification_directory = String::from(env!("RESOURCE_DIR")) + "/encoding";
     let proto_file_messages = specification_directory.clone() + "/messages.proto";
     let proto_file_types = specification_directory.clone() + "/datatypes.proto";
     println!("cargo:rerun-if-changed={}", proto_file_messages.as_str());
     println!("cargo:rerun-if-changed={}", proto_file_types.as_str());
 
     // build protobuf structs from rfc
-    prost_build::Config::new()
-        .type_attribute(".battleshipplus.types.Coordinate", "#[derive(Eq, Hash)]")
-        .compile_protos(
-            &[proto_file_messages.as_str()],
-            &[specification_directory.as_str()],
-        )?;
+    let mut builder = prost_build::Config::new();
+    builder.type_attribute(".battleshipplus.types.Coordinate", "#[derive(Eq, Hash)]");
+    // the config and everything it is made of can be written as TOML
+    for config_type in SERIALIZABLE_CONFIG_TYPES {
+        builder.type_attribute(
+            format!(".battleshipplus.types.{config_type}"),
+            "#[cfg_attr(feature = \"toml\", derive(serde::Serialize, serde::Deserialize))]\n\
+             #[cfg_attr(feature = \"toml\", serde(default))]",
+        );
+    }
+    builder.compile_protos(
+        &[proto_file_messages.as_str()],
+        &[specification_directory.as_str()],
+    )?;
 
     let messages_rust_source_path = std::env::var("OUT_DIR")
         .expect("OUT_DIR is provided for build scripts")
@@ -0,0 +1,245 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+use prost::Message;
+
+use crate::types::{ActionPointCarryover, CommonBalancing, Config, Coordinate, ShipType};
+use crate::util;
+
+/// Largest board length accepted by servers and clients. The client builds a mesh and the server
+/// spatial indices that grow with the area of the board, so a huge board would exhaust them.
+pub const MAX_BOARD_SIZE: u32 = 1024;
+
+/// A single problem found in a [`Config`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    BoardTooSmall { min_board_length: u32 },
+    BoardTooLarge { max_board_length: u32 },
+    MinPlayersExceedTeamSizes { min_players: u32, player_count: u32 },
+    EmptyShipSet { team: &'static str },
+    ShipSetExceedsCap { team: &'static str, cap: u32 },
+    UnknownShipType { team: &'static str, ship_type: i32 },
+    ShipExceedsQuadrant { team: &'static str, ship: ShipType },
+    MissingBalancing(ShipType),
+    IslandOutOfBoard(Coordinate),
+    ActionPointCapTooLow { cap: u32, gain: u32 },
+    InvalidHealthMultiplier,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            // TODO Improve: Suggest actual minimal board size including placement of ships
+            ConfigError::BoardTooSmall { min_board_length } => write!(
+                f,
+                "board is too small. Requires at least {min_board_length}x{min_board_length}"
+            ),
+            ConfigError::BoardTooLarge { max_board_length } => write!(
+                f,
+                "board is too large. Allows at most {max_board_length}x{max_board_length}"
+            ),
+            ConfigError::MinPlayersExceedTeamSizes {
+                min_players,
+                player_count,
+            } => write!(
+                f,
+                "minimum of {min_players} players exceeds the team sizes of {player_count} players"
+            ),
+            ConfigError::EmptyShipSet { team } => write!(f, "ship set of team {team} is empty"),
+            ConfigError::ShipSetExceedsCap { team, cap } => {
+                write!(f, "ship set of team {team} has more than {cap} ships")
+            }
+            ConfigError::UnknownShipType { team, ship_type } => write!(
+                f,
+                "ship set of team {team} contains unknown ship type {ship_type}"
+            ),
+            ConfigError::ShipExceedsQuadrant { team, ship } => write!(
+                f,
+                "ship set of team {team} contains a {ship:?}, which is longer than a quadrant"
+            ),
+            ConfigError::MissingBalancing(ship_type) => {
+                write!(f, "balancing of ship type {ship_type:?} is missing")
+            }
+            ConfigError::IslandOutOfBoard(island) => write!(
+                f,
+                "island at ({}, {}) is outside of the board",
+                island.x, island.y
+            ),
+            ConfigError::ActionPointCapTooLow { cap, gain } => write!(
+                f,
+                "action point cap {cap} is lower than the action point gain {gain}"
+            ),
+            ConfigError::InvalidHealthMultiplier => {
+                write!(f, "health multiplier is negative or not a number")
+            }
+        }
+    }
+}
+
+/// Error reading or writing a [`Config`] as TOML.
+#[cfg(feature = "toml")]
+#[derive(Debug)]
+pub enum ConfigFileError {
+    Syntax(String),
+    /// The file is well-formed, but describes an unusable config.
+    Invalid(Vec<ConfigError>),
+}
+
+#[cfg(feature = "toml")]
+impl Display for ConfigFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigFileError::Syntax(e) => write!(f, "Syntax: {e}"),
+            ConfigFileError::Invalid(errors) => {
+                let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+                write!(f, "Invalid: {}", errors.join(", "))
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Checks the config for problems that would prevent a game from being played.
+    /// All problems are reported at once.
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = vec![];
+
+        // check that the board is big enough to host all players
+        let player_count = self.team_size_a + self.team_size_b;
+        let quadrant_size = util::quadrant_size(self.board_size, player_count);
+        if quadrant_size == 0 {
+            errors.push(ConfigError::BoardTooSmall {
+                min_board_length: util::quadrants_per_row(player_count),
+            });
+        }
+        if self.board_size > MAX_BOARD_SIZE {
+            errors.push(ConfigError::BoardTooLarge {
+                max_board_length: MAX_BOARD_SIZE,
+            });
+        }
+        if self.min_players > player_count {
+            errors.push(ConfigError::MinPlayersExceedTeamSizes {
+                min_players: self.min_players,
+                player_count,
+            });
+        }
+
+        // check that both teams have a valid ship set, the sets do not have to match
+        let mut used_ship_types = BTreeSet::new();
+        for (team, ship_set) in [("A", &self.ship_set_team_a), ("B", &self.ship_set_team_b)] {
+            if ship_set.is_empty() {
+                errors.push(ConfigError::EmptyShipSet { team });
+            }
+            // a cap of 0 allows ship sets of any size
+            let cap = self.max_ships_per_player;
+            if cap > 0 && ship_set.len() > cap as usize {
+                errors.push(ConfigError::ShipSetExceedsCap { team, cap });
+            }
+            for &ship_type in ship_set {
+                match ShipType::from_i32(ship_type) {
+                    Some(known) => {
+                        used_ship_types.insert(known);
+                    }
+                    None => errors.push(ConfigError::UnknownShipType { team, ship_type }),
+                }
+            }
+            // every ship has to be placed inside the quadrant of its player, a board too small
+            // for any quadrant is reported above already
+            let longest_ship = ship_set
+                .iter()
+                .filter_map(|&ship_type| ShipType::from_i32(ship_type))
+                .max_by_key(ShipType::length);
+            if let Some(ship) = longest_ship {
+                if quadrant_size > 0 && ship.length() > quadrant_size {
+                    errors.push(ConfigError::ShipExceedsQuadrant { team, ship });
+                }
+            }
+        }
+        errors.extend(
+            used_ship_types
+                .into_iter()
+                .filter(|&ship_type| self.common_balancing(ship_type).is_none())
+                .map(ConfigError::MissingBalancing),
+        );
+
+        errors.extend(
+            self.islands
+                .iter()
+                .filter(|island| island.x >= self.board_size || island.y >= self.board_size)
+                .cloned()
+                .map(ConfigError::IslandOutOfBoard),
+        );
+
+        if self.action_point_carryover == ActionPointCarryover::Cap as i32
+            && self.action_point_cap < self.action_point_gain
+        {
+            errors.push(ConfigError::ActionPointCapTooLow {
+                cap: self.action_point_cap,
+                gain: self.action_point_gain,
+            });
+        }
+
+        // a multiplier of 0 leaves the health unscaled
+        if !self.health_multiplier.is_finite() || self.health_multiplier < 0.0 {
+            errors.push(ConfigError::InvalidHealthMultiplier);
+        }
+
+        // TODO Implementation: Implement more config checks
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// A hash of the encoded config that is the same on every platform and every run, so clients
+    /// can tell whether a server still uses a config they fetched before.
+    pub fn config_hash(&self) -> u64 {
+        // FNV-1a, the std hashers are not guaranteed to be stable across releases
+        self.encode_to_vec()
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+                (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+            })
+    }
+
+    /// Scales the initial health of a ship by `health_multiplier`. Ships keep at least 1 health,
+    /// a multiplier of 0 leaves the health as it is.
+    pub fn scaled_health(&self, initial_health: u32) -> u32 {
+        if self.health_multiplier == 0.0 {
+            return initial_health;
+        }
+        (initial_health as f32 * self.health_multiplier)
+            .round()
+            .max(1.0) as u32
+    }
+
+    fn common_balancing(&self, ship_type: ShipType) -> Option<&CommonBalancing> {
+        match ship_type {
+            ShipType::Carrier => self.carrier_balancing.as_ref()?.common_balancing.as_ref(),
+            ShipType::Battleship => self
+                .battleship_balancing
+                .as_ref()?
+                .common_balancing
+                .as_ref(),
+            ShipType::Cruiser => self.cruiser_balancing.as_ref()?.common_balancing.as_ref(),
+            ShipType::Submarine => self.submarine_balancing.as_ref()?.common_balancing.as_ref(),
+            ShipType::Destroyer => self.destroyer_balancing.as_ref()?.common_balancing.as_ref(),
+        }
+    }
+
+    /// Reads and validates a config, fields missing in the file keep their protobuf defaults.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(source: &str) -> Result<Config, ConfigFileError> {
+        let config: Config =
+            toml::from_str(source).map_err(|e| ConfigFileError::Syntax(e.to_string()))?;
+        config.validate().map_err(ConfigFileError::Invalid)?;
+        Ok(config)
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> Result<String, ConfigFileError> {
+        toml::to_string(self).map_err(|e| ConfigFileError::Syntax(e.to_string()))
+    }
+}
@@ -0,0 +1,201 @@
+//! Framed connections to a Battleship Plus server for bots, tools and tests
+//! that do not need the full client.
+//!
+//! The example below needs a running server and is only compiled. The end-to-end tests of the
+//! server in `battleship_plus_server/src/server_test.rs` use [`ServerConnection`] against an
+//! in-process server.
+//!
+//! ```no_run
+//! use battleship_plus_common::connection::{ConnectionError, ServerConnection};
+//!
+//! # async fn example(client_config: quinn::ClientConfig) -> Result<(), ConnectionError> {
+//!
+//! let mut connection = ServerConnection::connect(
+//!     "0.0.0.0:0".parse().unwrap(),
+//!     "127.0.0.1:30305".parse().unwrap(),
+//!     client_config,
+//! )
+//! .await?;
+//! let joined = connection.join("bot", None).await?;
+//! println!("joined game {} as player {}", joined.game_id, joined.player_id);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use quinn::{ClientConfig, Connection, Endpoint, RecvStream, SendStream, VarInt};
+use tokio_util::codec::{FramedRead, FramedWrite};
+
+use crate::codec::{BattleshipPlusCodec, CodecError};
+use crate::messages::status_message::Data;
+use crate::messages::{JoinRequest, JoinResponse, ProtocolMessage, StatusCode, StatusMessage};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub enum ConnectionError {
+    Connect(String),
+    Stream(String),
+    Codec(CodecError),
+    Timeout,
+    Closed,
+    /// The server answered a request with an error.
+    Rejected(Box<StatusMessage>),
+    UnexpectedMessage(Box<ProtocolMessage>),
+}
+
+impl Display for ConnectionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionError::Connect(e) => write!(f, "unable to connect: {e}"),
+            ConnectionError::Stream(e) => write!(f, "unable to open a stream: {e}"),
+            ConnectionError::Codec(e) => write!(f, "{e}"),
+            ConnectionError::Timeout => f.write_str("the server did not respond in time"),
+            ConnectionError::Closed => f.write_str("the connection was closed"),
+            ConnectionError::Rejected(status) => write!(
+                f,
+                "request rejected with status {}: {}",
+                status.code, status.message
+            ),
+            ConnectionError::UnexpectedMessage(msg) => write!(f, "unexpected message {msg:?}"),
+        }
+    }
+}
+
+impl From<CodecError> for ConnectionError {
+    fn from(error: CodecError) -> Self {
+        ConnectionError::Codec(error)
+    }
+}
+
+/// A connection with a single bidirectional stream, which carries all messages of the protocol.
+pub struct ServerConnection {
+    connection: Connection,
+    reader: FramedRead<RecvStream, BattleshipPlusCodec>,
+    writer: FramedWrite<SendStream, BattleshipPlusCodec>,
+    timeout: Duration,
+}
+
+impl ServerConnection {
+    /// Connects from `bind_address` to the server. The client config has to offer the protocol
+    /// name via ALPN and accept the certificate of the server.
+    pub async fn connect(
+        bind_address: SocketAddr,
+        server_address: SocketAddr,
+        client_config: ClientConfig,
+    ) -> Result<ServerConnection, ConnectionError> {
+        let mut endpoint =
+            Endpoint::client(bind_address).map_err(|e| ConnectionError::Connect(e.to_string()))?;
+        endpoint.set_default_client_config(client_config);
+        let connection = endpoint
+            .connect(server_address, &server_address.ip().to_string())
+            .map_err(|e| ConnectionError::Connect(e.to_string()))?
+            .await
+            .map_err(|e| ConnectionError::Connect(e.to_string()))?;
+
+        let (tx, rx) = connection
+            .open_bi()
+            .await
+            .map_err(|e| ConnectionError::Stream(e.to_string()))?;
+
+        Ok(ServerConnection {
+            connection,
+            reader: FramedRead::new(rx, BattleshipPlusCodec::default()),
+            writer: FramedWrite::new(tx, BattleshipPlusCodec::default()),
+            timeout: DEFAULT_TIMEOUT,
+        })
+    }
+
+    /// Sets how long sending or receiving a single message may take, 5 seconds by default.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    pub async fn send(&mut self, msg: ProtocolMessage) -> Result<(), ConnectionError> {
+        tokio::time::timeout(self.timeout, self.writer.send(msg))
+            .await
+            .map_err(|_| ConnectionError::Timeout)??;
+        tokio::time::timeout(
+            self.timeout,
+            SinkExt::<ProtocolMessage>::flush(&mut self.writer),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout)??;
+        Ok(())
+    }
+
+    /// Waits for the next message. Frames without a message are skipped.
+    pub async fn receive(&mut self) -> Result<ProtocolMessage, ConnectionError> {
+        loop {
+            match tokio::time::timeout(self.timeout, self.reader.next()).await {
+                Err(_) => return Err(ConnectionError::Timeout),
+                Ok(None) => return Err(ConnectionError::Closed),
+                Ok(Some(frame)) => {
+                    if let Some(msg) = frame? {
+                        return Ok(msg);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Joins the requested game, or any game with free seats if `game_id` is `None`.
+    /// The lobby state the server sends afterwards is left to the caller.
+    pub async fn join(
+        &mut self,
+        username: &str,
+        game_id: Option<u32>,
+    ) -> Result<JoinResponse, ConnectionError> {
+        self.send_join(JoinRequest {
+            username: username.to_string(),
+            game_id,
+            team: None,
+            seat_token: None,
+        })
+        .await
+    }
+
+    /// Takes the seat handed out with an earlier `JoinResponse` again.
+    /// The server follows up with the state of the game, which is left to the caller as well.
+    pub async fn rejoin(
+        &mut self,
+        username: &str,
+        seat_token: &str,
+    ) -> Result<JoinResponse, ConnectionError> {
+        self.send_join(JoinRequest {
+            username: username.to_string(),
+            game_id: None,
+            team: None,
+            seat_token: Some(seat_token.to_string()),
+        })
+        .await
+    }
+
+    async fn send_join(&mut self, request: JoinRequest) -> Result<JoinResponse, ConnectionError> {
+        self.send(request.into()).await?;
+
+        match self.receive().await? {
+            ProtocolMessage::StatusMessage(StatusMessage {
+                code,
+                data: Some(Data::JoinResponse(response)),
+                ..
+            }) if code == StatusCode::Ok as i32 => Ok(response),
+            ProtocolMessage::StatusMessage(status) => {
+                Err(ConnectionError::Rejected(Box::new(status)))
+            }
+            msg => Err(ConnectionError::UnexpectedMessage(Box::new(msg))),
+        }
+    }
+
+    pub fn close(&self) {
+        self.connection.close(VarInt::from_u32(0), &[]);
+    }
+}
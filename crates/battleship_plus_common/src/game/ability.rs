@@ -0,0 +1,191 @@
+use crate::messages::ship_action_request::ActionProperties;
+use crate::types::{
+    Coordinate, EngineBoostProperties, MultiMissileProperties, PredatorMissileProperties,
+    ScoutPlaneProperties, ShipType, TorpedoProperties,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AbilityKind {
+    ScoutPlane,
+    PredatorMissile,
+    EngineBoost,
+    Torpedo,
+    MultiMissile,
+}
+
+impl AbilityKind {
+    /// Properties of an action request using the ability, targets still have to be filled in.
+    pub fn default_properties(&self) -> ActionProperties {
+        match self {
+            AbilityKind::ScoutPlane => ScoutPlaneProperties::default().into(),
+            AbilityKind::PredatorMissile => PredatorMissileProperties::default().into(),
+            AbilityKind::EngineBoost => EngineBoostProperties {}.into(),
+            AbilityKind::Torpedo => TorpedoProperties::default().into(),
+            AbilityKind::MultiMissile => MultiMissileProperties::default().into(),
+        }
+    }
+}
+
+/// How the player aims an ability.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Targeting {
+    None,
+    /// Board positions, in the order the action properties expect them.
+    Positions(usize),
+    /// A single position the direction from the ship towards is taken.
+    Direction,
+}
+
+/// Rules a target has to follow with respect to the targets selected before it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TargetConstraints {
+    /// No tile may be selected twice.
+    pub distinct: bool,
+    /// Largest distance between two targets, diagonal steps count as one tile.
+    pub max_spread: Option<u32>,
+}
+
+impl TargetConstraints {
+    /// Different tiles anywhere on the board.
+    pub const DISTINCT: TargetConstraints = TargetConstraints {
+        distinct: true,
+        max_spread: None,
+    };
+
+    /// Whether the target may be added to the targets selected so far.
+    pub fn allows(&self, selected: &[Coordinate], target: &Coordinate) -> bool {
+        selected.iter().all(|other| {
+            let distance = other.x.abs_diff(target.x).max(other.y.abs_diff(target.y));
+            !(self.distinct && other == target)
+                && self.max_spread.is_none_or(|spread| distance <= spread)
+        })
+    }
+}
+
+/// Describes a special ability independently of the balancing of a game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AbilityDescriptor {
+    pub kind: AbilityKind,
+    pub name: &'static str,
+    pub targeting: Targeting,
+    /// Name of the balancing field limiting the distance to the targets.
+    pub range_field: Option<&'static str>,
+    /// Name of the balancing field defining the affected area around a target.
+    pub radius_field: Option<&'static str>,
+    pub constraints: TargetConstraints,
+}
+
+impl AbilityDescriptor {
+    /// Number of board positions the player selects to use the ability.
+    pub fn target_count(&self) -> usize {
+        match self.targeting {
+            Targeting::None => 0,
+            Targeting::Positions(count) => count,
+            Targeting::Direction => 1,
+        }
+    }
+}
+
+const CARRIER_ABILITIES: [AbilityDescriptor; 1] = [AbilityDescriptor {
+    kind: AbilityKind::ScoutPlane,
+    name: "Scout Plane",
+    targeting: Targeting::Positions(1),
+    range_field: Some("scout_plane_range"),
+    radius_field: Some("scout_plane_radius"),
+    constraints: TargetConstraints::DISTINCT,
+}];
+
+const BATTLESHIP_ABILITIES: [AbilityDescriptor; 1] = [AbilityDescriptor {
+    kind: AbilityKind::PredatorMissile,
+    name: "Predator Missile",
+    targeting: Targeting::Positions(1),
+    range_field: Some("predator_missile_range"),
+    radius_field: Some("predator_missile_radius"),
+    constraints: TargetConstraints::DISTINCT,
+}];
+
+const CRUISER_ABILITIES: [AbilityDescriptor; 1] = [AbilityDescriptor {
+    kind: AbilityKind::EngineBoost,
+    name: "Engine Boost",
+    targeting: Targeting::None,
+    range_field: None,
+    radius_field: None,
+    constraints: TargetConstraints::DISTINCT,
+}];
+
+const SUBMARINE_ABILITIES: [AbilityDescriptor; 1] = [AbilityDescriptor {
+    kind: AbilityKind::Torpedo,
+    name: "Torpedo",
+    targeting: Targeting::Direction,
+    range_field: Some("torpedo_range"),
+    radius_field: None,
+    constraints: TargetConstraints::DISTINCT,
+}];
+
+const DESTROYER_ABILITIES: [AbilityDescriptor; 1] = [AbilityDescriptor {
+    kind: AbilityKind::MultiMissile,
+    name: "Multi Missile",
+    targeting: Targeting::Positions(3),
+    range_field: None,
+    radius_field: Some("multi_missile_radius"),
+    constraints: TargetConstraints::DISTINCT,
+}];
+
+impl ShipType {
+    /// The special abilities of the ship type, currently every type has exactly one.
+    pub fn abilities(&self) -> &'static [AbilityDescriptor] {
+        match self {
+            ShipType::Carrier => &CARRIER_ABILITIES,
+            ShipType::Battleship => &BATTLESHIP_ABILITIES,
+            ShipType::Cruiser => &CRUISER_ABILITIES,
+            ShipType::Submarine => &SUBMARINE_ABILITIES,
+            ShipType::Destroyer => &DESTROYER_ABILITIES,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::ability::{AbilityKind, TargetConstraints};
+    use crate::types::{Coordinate, ShipType};
+
+    #[test]
+    fn target_counts() {
+        let target_counts = [
+            (ShipType::Carrier, 1),
+            (ShipType::Battleship, 1),
+            (ShipType::Cruiser, 0),
+            (ShipType::Submarine, 1),
+            (ShipType::Destroyer, 3),
+        ];
+        for (ship_type, target_count) in target_counts {
+            let abilities = ship_type.abilities();
+            assert_eq!(abilities.len(), 1, "{ship_type:?}");
+            assert_eq!(abilities[0].target_count(), target_count, "{ship_type:?}");
+        }
+        assert_eq!(
+            ShipType::Destroyer.abilities()[0].kind,
+            AbilityKind::MultiMissile
+        );
+    }
+
+    #[test]
+    fn target_constraints() {
+        let target = |x, y| Coordinate { x, y };
+        let selected = [target(3, 3), target(4, 5)];
+
+        let distinct = TargetConstraints::DISTINCT;
+        assert!(!distinct.allows(&selected, &target(4, 5)));
+        assert!(distinct.allows(&selected, &target(20, 20)));
+
+        let clustered = TargetConstraints {
+            distinct: false,
+            max_spread: Some(2),
+        };
+        assert!(clustered.allows(&selected, &target(4, 5)));
+        assert!(clustered.allows(&selected, &target(5, 4)));
+        // too far from the first target
+        assert!(!clustered.allows(&selected, &target(6, 5)));
+        assert!(clustered.allows(&[], &target(20, 20)));
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+use crate::game::ship::{Orientation, ShipID};
+use crate::game::ship_manager::ShipManager;
+
+/// A way in which the ships of two boards disagree, e.g. those of a client and of the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShipDifference {
+    /// The ship is only on the expected board.
+    Missing(ShipID),
+    /// The ship is only on the actual board.
+    Unexpected(ShipID),
+    Position {
+        id: ShipID,
+        expected: (i32, i32),
+        actual: (i32, i32),
+    },
+    Orientation {
+        id: ShipID,
+        expected: Orientation,
+        actual: Orientation,
+    },
+    Health {
+        id: ShipID,
+        expected: u32,
+        actual: u32,
+    },
+}
+
+impl Display for ShipDifference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShipDifference::Missing(id) => write!(f, "ship {id:?} is missing"),
+            ShipDifference::Unexpected(id) => write!(f, "ship {id:?} is unexpected"),
+            ShipDifference::Position {
+                id,
+                expected,
+                actual,
+            } => write!(f, "ship {id:?} is at {actual:?} instead of {expected:?}"),
+            ShipDifference::Orientation {
+                id,
+                expected,
+                actual,
+            } => write!(f, "ship {id:?} faces {actual:?} instead of {expected:?}"),
+            ShipDifference::Health {
+                id,
+                expected,
+                actual,
+            } => write!(f, "ship {id:?} has {actual} health instead of {expected}"),
+        }
+    }
+}
+
+/// Compares the ships of two boards, ordered by ship id. Nothing is reported if they agree.
+/// Cooldowns and balancing are not compared, only what the players see on the board.
+pub fn diff_ships(expected: &ShipManager, actual: &ShipManager) -> Vec<ShipDifference> {
+    let ids: BTreeSet<ShipID> = expected
+        .iter_ships()
+        .chain(actual.iter_ships())
+        .map(|(id, _)| *id)
+        .collect();
+
+    let mut differences = vec![];
+    for id in ids {
+        let (expected, actual) = match (expected.get_by_id(&id), actual.get_by_id(&id)) {
+            (Some(expected), Some(actual)) => (expected, actual),
+            (Some(_), None) => {
+                differences.push(ShipDifference::Missing(id));
+                continue;
+            }
+            (None, _) => {
+                differences.push(ShipDifference::Unexpected(id));
+                continue;
+            }
+        };
+        if expected.position() != actual.position() {
+            differences.push(ShipDifference::Position {
+                id,
+                expected: expected.position(),
+                actual: actual.position(),
+            });
+        }
+        if expected.orientation() != actual.orientation() {
+            differences.push(ShipDifference::Orientation {
+                id,
+                expected: expected.orientation(),
+                actual: actual.orientation(),
+            });
+        }
+        if expected.health() != actual.health() {
+            differences.push(ShipDifference::Health {
+                id,
+                expected: expected.health(),
+                actual: actual.health(),
+            });
+        }
+    }
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::game::diff::{diff_ships, ShipDifference};
+    use crate::game::ship::{Orientation, Ship, ShipData, ShipID};
+    use crate::game::ship_manager::ShipManager;
+    use crate::types::DestroyerBalancing;
+
+    fn destroyer(id: ShipID, pos_x: i32, health: u32) -> Ship {
+        Ship::Destroyer {
+            balancing: Arc::new(DestroyerBalancing::default()),
+            data: ShipData {
+                id,
+                health,
+                pos_x,
+                pos_y: 0,
+                orientation: Orientation::North,
+            },
+            cooldowns: Default::default(),
+        }
+    }
+
+    #[test]
+    fn position_mismatch_is_reported() {
+        let expected =
+            ShipManager::new_with_ships(vec![destroyer((1, 0), 0, 5), destroyer((2, 0), 5, 5)]);
+        let actual =
+            ShipManager::new_with_ships(vec![destroyer((1, 0), 0, 5), destroyer((2, 0), 6, 5)]);
+
+        assert!(diff_ships(&expected, &expected.clone()).is_empty());
+        assert_eq!(
+            diff_ships(&expected, &actual),
+            vec![ShipDifference::Position {
+                id: (2, 0),
+                expected: (5, 0),
+                actual: (6, 0),
+            }]
+        );
+    }
+
+    #[test]
+    fn ships_on_one_board_only_are_reported() {
+        let expected =
+            ShipManager::new_with_ships(vec![destroyer((1, 0), 0, 5), destroyer((1, 1), 5, 5)]);
+        let actual =
+            ShipManager::new_with_ships(vec![destroyer((1, 0), 0, 3), destroyer((2, 0), 10, 5)]);
+
+        assert_eq!(
+            diff_ships(&expected, &actual),
+            vec![
+                ShipDifference::Health {
+                    id: (1, 0),
+                    expected: 5,
+                    actual: 3,
+                },
+                ShipDifference::Missing((1, 1)),
+                ShipDifference::Unexpected((2, 0)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use crate::types::{Direction, MoveDirection, RotateDirection};
+
+/// The way a ship faces, its bow points in this direction.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Orientation {
+    /// All orientations in clockwise order, starting with north.
+    pub const CLOCKWISE: [Orientation; 4] = [
+        Orientation::North,
+        Orientation::East,
+        Orientation::South,
+        Orientation::West,
+    ];
+
+    /// Returns the orientation after a quarter turn in the given direction.
+    pub fn rotated(self, direction: RotateDirection) -> Orientation {
+        match (direction, self) {
+            (RotateDirection::Clockwise, Orientation::North) => Orientation::East,
+            (RotateDirection::Clockwise, Orientation::East) => Orientation::South,
+            (RotateDirection::Clockwise, Orientation::South) => Orientation::West,
+            (RotateDirection::Clockwise, Orientation::West) => Orientation::North,
+            (RotateDirection::CounterClockwise, Orientation::North) => Orientation::West,
+            (RotateDirection::CounterClockwise, Orientation::West) => Orientation::South,
+            (RotateDirection::CounterClockwise, Orientation::South) => Orientation::East,
+            (RotateDirection::CounterClockwise, Orientation::East) => Orientation::North,
+        }
+    }
+
+    /// Offset of one tile in this orientation, north is towards positive y.
+    pub fn step(self) -> [i32; 2] {
+        match self {
+            Orientation::North => [0, 1],
+            Orientation::East => [1, 0],
+            Orientation::South => [0, -1],
+            Orientation::West => [-1, 0],
+        }
+    }
+
+    /// Returns the position reached from `position` after moving `distance` tiles forward or
+    /// backward while facing this way.
+    pub fn moved(
+        self,
+        position: (i32, i32),
+        direction: MoveDirection,
+        distance: i32,
+    ) -> (i32, i32) {
+        let distance = match direction {
+            MoveDirection::Forward => distance,
+            MoveDirection::Backward => -distance,
+        };
+        let [dx, dy] = self.step();
+        (position.0 + dx * distance, position.1 + dy * distance)
+    }
+}
+
+/// Offset of one tile in the direction, see [`Orientation::step`].
+pub fn direction_step(direction: Direction) -> [i32; 2] {
+    Orientation::from(direction).step()
+}
+
+impl From<Orientation> for Direction {
+    fn from(orientation: Orientation) -> Self {
+        match orientation {
+            Orientation::North => Direction::North,
+            Orientation::East => Direction::East,
+            Orientation::South => Direction::South,
+            Orientation::West => Direction::West,
+        }
+    }
+}
+
+impl From<Direction> for Orientation {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::North => Orientation::North,
+            Direction::East => Orientation::East,
+            Direction::South => Orientation::South,
+            Direction::West => Orientation::West,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::direction::{direction_step, Orientation};
+    use crate::types::{Direction, MoveDirection, RotateDirection};
+
+    const DIRECTIONS: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    #[test]
+    fn orientation_and_direction_convert_both_ways() {
+        for (orientation, direction) in Orientation::CLOCKWISE.into_iter().zip(DIRECTIONS) {
+            assert_eq!(Direction::from(orientation), direction);
+            assert_eq!(Orientation::from(direction), orientation);
+            assert_eq!(Orientation::from(Direction::from(orientation)), orientation);
+            assert_eq!(direction_step(direction), orientation.step());
+        }
+    }
+
+    #[test]
+    fn rotations_follow_the_compass() {
+        for (i, &orientation) in Orientation::CLOCKWISE.iter().enumerate() {
+            assert_eq!(
+                orientation.rotated(RotateDirection::Clockwise),
+                Orientation::CLOCKWISE[(i + 1) % 4]
+            );
+            assert_eq!(
+                orientation.rotated(RotateDirection::CounterClockwise),
+                Orientation::CLOCKWISE[(i + 3) % 4]
+            );
+        }
+    }
+
+    #[test]
+    fn rotations_undo_each_other() {
+        for orientation in Orientation::CLOCKWISE {
+            for (there, back) in [
+                (
+                    RotateDirection::Clockwise,
+                    RotateDirection::CounterClockwise,
+                ),
+                (
+                    RotateDirection::CounterClockwise,
+                    RotateDirection::Clockwise,
+                ),
+            ] {
+                assert_eq!(orientation.rotated(there).rotated(back), orientation);
+                let full_turn = (0..4).fold(orientation, |current, _| current.rotated(there));
+                assert_eq!(full_turn, orientation);
+            }
+        }
+    }
+
+    #[test]
+    fn steps_are_unit_offsets_opposite_to_their_reverse() {
+        assert_eq!(Orientation::North.step(), [0, 1]);
+        assert_eq!(Orientation::East.step(), [1, 0]);
+        assert_eq!(Orientation::South.step(), [0, -1]);
+        assert_eq!(Orientation::West.step(), [-1, 0]);
+        for orientation in Orientation::CLOCKWISE {
+            let [dx, dy] = orientation.step();
+            let turned_around = orientation
+                .rotated(RotateDirection::Clockwise)
+                .rotated(RotateDirection::Clockwise);
+            assert_eq!(turned_around.step(), [-dx, -dy]);
+        }
+    }
+
+    #[test]
+    fn moves_go_forward_and_backward_along_the_orientation() {
+        let start = (3, -2);
+        for orientation in Orientation::CLOCKWISE {
+            let [dx, dy] = orientation.step();
+            for distance in 0..4 {
+                let forward = orientation.moved(start, MoveDirection::Forward, distance);
+                let backward = orientation.moved(start, MoveDirection::Backward, distance);
+                assert_eq!(forward, (start.0 + dx * distance, start.1 + dy * distance));
+                assert_eq!(backward, (start.0 - dx * distance, start.1 - dy * distance));
+                assert_eq!(
+                    orientation.moved(forward, MoveDirection::Backward, distance),
+                    start
+                );
+            }
+        }
+    }
+}
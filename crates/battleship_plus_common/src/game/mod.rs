@@ -1,8 +1,26 @@
+pub mod ability;
+pub mod diff;
+pub mod direction;
 pub mod ship;
 pub mod ship_manager;
 
+use crate::types::{ActionPointCarryover, Config};
+
 pub type PlayerID = u32;
 
+/// Returns the action points a player starts a turn with, given the points the player left
+/// unspent at the end of their previous turn.
+pub fn starting_action_points(config: &Config, unspent: u32) -> u32 {
+    let gain = config.action_point_gain;
+    match ActionPointCarryover::from_i32(config.action_point_carryover) {
+        Some(ActionPointCarryover::Reset) => gain,
+        Some(ActionPointCarryover::Cap) => unspent
+            .saturating_add(gain)
+            .min(config.action_point_cap.max(gain)),
+        Some(ActionPointCarryover::CarryOver) | None => unspent.saturating_add(gain),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ActionValidationError {
     NonExistentPlayer { id: PlayerID },
@@ -14,5 +32,9 @@ pub enum ActionValidationError {
     InvalidShipPlacement(ship_manager::ShipPlacementError),
     InvalidShipType,
     NotPlayersTurn,
+    PlayerEliminated,
+    MoveLimitReached,
+    AbilityRepeated,
+    Collision,
     Ignored,
 }
@@ -1,12 +1,40 @@
 use std::cmp::max;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
 use rstar::{Envelope, PointDistance, RTreeObject, SelectionFunction, AABB};
 
+pub use crate::game::direction::Orientation;
+use crate::game::ship_manager::envelope_to_points;
 use crate::game::{ActionValidationError, PlayerID};
+use crate::messages::ship_action_request::ActionProperties;
 use crate::types::*;
 
-#[derive(Debug, Clone, PartialEq)]
+/// Applies `Config.health_multiplier` to the initial health of a ship type, which is also the
+/// most health its ships can have. Returns the scaled initial health.
+fn scale_health(common_balancing: &mut Option<CommonBalancing>, cfg: &Config) -> u32 {
+    let common_balancing = common_balancing.as_mut().unwrap();
+    common_balancing.initial_health = cfg.scaled_health(common_balancing.initial_health);
+    common_balancing.initial_health
+}
+
+impl ShipType {
+    /// Number of tiles covered by ships of the type.
+    pub fn length(&self) -> u32 {
+        match self {
+            ShipType::Carrier => 5,
+            ShipType::Battleship => 4,
+            ShipType::Cruiser => 3,
+            ShipType::Submarine => 3,
+            ShipType::Destroyer => 2,
+        }
+    }
+}
+
+/// Two ships are equal if they are of the same type and match in their data, cooldowns and
+/// balancing. Cooldowns are compared in order.
+/// The hash only covers the type and the data, the balancing is shared by all ships of a type.
+#[derive(Debug, Clone)]
 pub enum Ship {
     Carrier {
         balancing: Arc<CarrierBalancing>,
@@ -53,8 +81,8 @@ impl Ship {
 
         match ship_type {
             ShipType::Carrier => {
-                let balancing = cfg.carrier_balancing.clone().unwrap();
-                data.health = balancing.common_balancing.as_ref().unwrap().initial_health;
+                let mut balancing = cfg.carrier_balancing.clone().unwrap();
+                data.health = scale_health(&mut balancing.common_balancing, &cfg);
                 Ship::Carrier {
                     balancing: Arc::from(balancing),
                     data,
@@ -62,8 +90,8 @@ impl Ship {
                 }
             }
             ShipType::Battleship => {
-                let balancing = cfg.battleship_balancing.clone().unwrap();
-                data.health = balancing.common_balancing.as_ref().unwrap().initial_health;
+                let mut balancing = cfg.battleship_balancing.clone().unwrap();
+                data.health = scale_health(&mut balancing.common_balancing, &cfg);
                 Ship::Battleship {
                     balancing: Arc::from(balancing),
                     data,
@@ -71,8 +99,8 @@ impl Ship {
                 }
             }
             ShipType::Cruiser => {
-                let balancing = cfg.cruiser_balancing.clone().unwrap();
-                data.health = balancing.common_balancing.as_ref().unwrap().initial_health;
+                let mut balancing = cfg.cruiser_balancing.clone().unwrap();
+                data.health = scale_health(&mut balancing.common_balancing, &cfg);
                 Ship::Cruiser {
                     balancing: Arc::from(balancing),
                     data,
@@ -80,8 +108,8 @@ impl Ship {
                 }
             }
             ShipType::Submarine => {
-                let balancing = cfg.submarine_balancing.clone().unwrap();
-                data.health = balancing.common_balancing.as_ref().unwrap().initial_health;
+                let mut balancing = cfg.submarine_balancing.clone().unwrap();
+                data.health = scale_health(&mut balancing.common_balancing, &cfg);
                 Ship::Submarine {
                     balancing: Arc::from(balancing),
                     data,
@@ -89,8 +117,8 @@ impl Ship {
                 }
             }
             ShipType::Destroyer => {
-                let balancing = cfg.destroyer_balancing.clone().unwrap();
-                data.health = balancing.common_balancing.as_ref().unwrap().initial_health;
+                let mut balancing = cfg.destroyer_balancing.clone().unwrap();
+                data.health = scale_health(&mut balancing.common_balancing, &cfg);
                 Ship::Destroyer {
                     balancing: Arc::from(balancing),
                     data,
@@ -102,13 +130,7 @@ impl Ship {
 
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> i32 {
-        match self {
-            Ship::Carrier { .. } => 5,
-            Ship::Battleship { .. } => 4,
-            Ship::Cruiser { .. } => 3,
-            Ship::Submarine { .. } => 3,
-            Ship::Destroyer { .. } => 2,
-        }
+        self.ship_type().length() as i32
     }
 
     pub fn data(&self) -> ShipData {
@@ -196,7 +218,11 @@ impl Ship {
     }
 
     pub fn vision_envelope(&self) -> AABB<[i32; 2]> {
-        let vision_range = self.vision_range();
+        self.vision_envelope_with_range(self.vision_range())
+    }
+
+    /// Like `vision_envelope`, but for a range deviating from the balancing of the ship type.
+    pub fn vision_envelope_with_range(&self, vision_range: u32) -> AABB<[i32; 2]> {
         let mut envelope = self.envelope();
         let lower = envelope.lower();
         let upper = envelope.upper();
@@ -211,6 +237,33 @@ impl Ship {
         envelope
     }
 
+    /// Whether the target is at most `range` tiles away from the closest tile of the ship,
+    /// diagonal steps count as one tile.
+    pub fn can_reach(&self, target: &[i32; 2], range: u32) -> bool {
+        self.distance_2(target) <= range as i32
+    }
+
+    /// Maximum distance between the ship and the target of its ability.
+    /// `None` if the ability has no target or it may lie anywhere on the board.
+    pub fn ability_range(&self) -> Option<u32> {
+        match self {
+            Ship::Carrier { balancing, .. } => Some(balancing.scout_plane_range),
+            Ship::Battleship { balancing, .. } => Some(balancing.predator_missile_range),
+            Ship::Submarine { balancing, .. } => Some(balancing.torpedo_range),
+            Ship::Cruiser { .. } | Ship::Destroyer { .. } => None,
+        }
+    }
+
+    /// Size of the area around each target the ability of the ship affects.
+    pub fn ability_radius(&self) -> Option<u32> {
+        match self {
+            Ship::Carrier { balancing, .. } => Some(balancing.scout_plane_radius),
+            Ship::Battleship { balancing, .. } => Some(balancing.predator_missile_radius),
+            Ship::Destroyer { balancing, .. } => Some(balancing.multi_missile_radius),
+            Ship::Cruiser { .. } | Ship::Submarine { .. } => None,
+        }
+    }
+
     pub fn common_balancing(&self) -> CommonBalancing {
         match self {
             Ship::Carrier { balancing, .. } => balancing.common_balancing.clone().unwrap(),
@@ -304,59 +357,82 @@ impl Ship {
         &mut self,
         direction: MoveDirection,
         bounds: &AABB<[i32; 2]>,
+        wrap_edges: bool,
     ) -> Result<AABB<[i32; 2]>, ActionValidationError> {
-        let orientation = self.orientation();
-        let movement = match direction {
-            MoveDirection::Forward => 1,
-            MoveDirection::Backward => -1,
-        };
-
-        let (new_x, new_y) = match self {
-            Ship::Carrier { data, .. }
-            | Ship::Battleship { data, .. }
-            | Ship::Cruiser { data, .. }
-            | Ship::Submarine { data, .. }
-            | Ship::Destroyer { data, .. } => match orientation {
-                Orientation::North => (data.pos_x, data.pos_y + movement),
-                Orientation::South => (data.pos_x, data.pos_y - movement),
-                Orientation::East => (data.pos_x + movement, data.pos_y),
-                Orientation::West => (data.pos_x - movement, data.pos_y),
-            },
-        };
-
-        if bounds.contains_envelope(&self.get_envelope(new_x, new_y)) {
+        let (new_x, new_y) = self.moved_position(direction, 1);
+        let new_envelope = self.get_envelope(new_x, new_y);
+        if bounds.contains_envelope(&new_envelope) {
             self.set_position(new_x, new_y);
             Ok(self.envelope())
+        } else if let Some([offset_x, offset_y]) = wrap_offset(&new_envelope, bounds, wrap_edges) {
+            self.set_position(new_x + offset_x, new_y + offset_y);
+            Ok(self.envelope())
         } else {
             Err(ActionValidationError::OutOfMap)
         }
     }
 
+    /// The ship moved to the position, keeping its orientation.
+    pub fn relocated(&self, (x, y): (i32, i32)) -> Ship {
+        let mut ship = self.clone();
+        ship.set_position(x, y);
+        ship
+    }
+
     pub fn do_rotation(
         &mut self,
         direction: RotateDirection,
         bounds: &AABB<[i32; 2]>,
+        wrap_edges: bool,
     ) -> Result<AABB<[i32; 2]>, ActionValidationError> {
         let (x, y) = self.position();
-        let new_orientation = match (direction, self.orientation()) {
-            (RotateDirection::Clockwise, Orientation::North) => Orientation::East,
-            (RotateDirection::Clockwise, Orientation::West) => Orientation::North,
-            (RotateDirection::Clockwise, Orientation::South) => Orientation::West,
-            (RotateDirection::Clockwise, Orientation::East) => Orientation::South,
-            (RotateDirection::CounterClockwise, Orientation::North) => Orientation::West,
-            (RotateDirection::CounterClockwise, Orientation::West) => Orientation::South,
-            (RotateDirection::CounterClockwise, Orientation::South) => Orientation::East,
-            (RotateDirection::CounterClockwise, Orientation::East) => Orientation::North,
-        };
-
-        if bounds.contains_envelope(&self.get_envelope_with_orientation(x, y, new_orientation)) {
+        let new_orientation = self.rotated_orientation(direction);
+        let new_envelope = self.get_envelope_with_orientation(x, y, new_orientation);
+        if bounds.contains_envelope(&new_envelope) {
             self.set_orientation(new_orientation);
             Ok(self.envelope())
+        } else if let Some([offset_x, offset_y]) = wrap_offset(&new_envelope, bounds, wrap_edges) {
+            self.set_orientation(new_orientation);
+            self.set_position(x + offset_x, y + offset_y);
+            Ok(self.envelope())
         } else {
             Err(ActionValidationError::OutOfMap)
         }
     }
 
+    /// The tiles the ship would occupy after the action, so collisions can be checked before
+    /// committing to it. Bounds, wrapping edges and costs are not considered.
+    /// Actions that do not move the ship, like shooting, leave it on its current tiles.
+    pub fn tiles_after(&self, action: &ActionProperties) -> Vec<Coordinate> {
+        let (x, y) = self.position();
+        let envelope = match (action, self) {
+            (ActionProperties::MoveProperties(properties), _) => {
+                let (x, y) = self.moved_position(properties.direction(), 1);
+                self.get_envelope(x, y)
+            }
+            (ActionProperties::RotateProperties(properties), _) => {
+                let orientation = self.rotated_orientation(properties.direction());
+                self.get_envelope_with_orientation(x, y, orientation)
+            }
+            (ActionProperties::EngineBoostProperties(_), Ship::Cruiser { balancing, .. }) => {
+                let distance = balancing.engine_boost_distance as i32;
+                let (x, y) = self.moved_position(MoveDirection::Forward, distance);
+                self.get_envelope(x, y)
+            }
+            _ => self.envelope(),
+        };
+        envelope_to_points(envelope).collect()
+    }
+
+    fn moved_position(&self, direction: MoveDirection, distance: i32) -> (i32, i32) {
+        self.orientation()
+            .moved(self.position(), direction, distance)
+    }
+
+    fn rotated_orientation(&self, direction: RotateDirection) -> Orientation {
+        self.orientation().rotated(direction)
+    }
+
     fn set_position(&mut self, x: i32, y: i32) {
         match self {
             Ship::Carrier { data, .. }
@@ -421,7 +497,7 @@ impl PointDistance for Ship {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct ShipData {
     pub id: ShipID,
     pub pos_x: i32,
@@ -430,6 +506,32 @@ pub struct ShipData {
     pub health: u32,
 }
 
+impl PartialEq for Ship {
+    fn eq(&self, other: &Self) -> bool {
+        let same_balancing = match (self, other) {
+            (Ship::Carrier { balancing: a, .. }, Ship::Carrier { balancing: b, .. }) => a == b,
+            (Ship::Battleship { balancing: a, .. }, Ship::Battleship { balancing: b, .. }) => {
+                a == b
+            }
+            (Ship::Cruiser { balancing: a, .. }, Ship::Cruiser { balancing: b, .. }) => a == b,
+            (Ship::Submarine { balancing: a, .. }, Ship::Submarine { balancing: b, .. }) => a == b,
+            (Ship::Destroyer { balancing: a, .. }, Ship::Destroyer { balancing: b, .. }) => a == b,
+            _ => false,
+        };
+        same_balancing && self.data() == other.data() && self.cool_downs() == other.cool_downs()
+    }
+}
+
+// balancings only consist of integers, so their equality is reflexive
+impl Eq for Ship {}
+
+impl Hash for Ship {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ship_type().hash(state);
+        self.data().hash(state);
+    }
+}
+
 impl Default for ShipData {
     fn default() -> Self {
         ShipData {
@@ -442,37 +544,7 @@ impl Default for ShipData {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum Orientation {
-    North,
-    South,
-    East,
-    West,
-}
-
-impl From<Orientation> for Direction {
-    fn from(orientation: Orientation) -> Self {
-        match orientation {
-            Orientation::North => Direction::North,
-            Orientation::East => Direction::East,
-            Orientation::South => Direction::South,
-            Orientation::West => Direction::West,
-        }
-    }
-}
-
-impl From<Direction> for Orientation {
-    fn from(direction: Direction) -> Self {
-        match direction {
-            Direction::North => Orientation::North,
-            Direction::East => Orientation::East,
-            Direction::South => Orientation::South,
-            Direction::West => Orientation::West,
-        }
-    }
-}
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Cooldown {
     Movement { remaining_rounds: u32 },
     Rotate { remaining_rounds: u32 },
@@ -544,7 +616,226 @@ pub trait GetShipID {
     fn id(&self) -> ShipID;
 }
 
+/// Computes the offset that moves an envelope which left the board over one edge flush against
+/// the opposite edge. Returns `None` if wrapping is disabled or the envelope does not fit.
+fn wrap_offset(
+    envelope: &AABB<[i32; 2]>,
+    bounds: &AABB<[i32; 2]>,
+    wrap_edges: bool,
+) -> Option<[i32; 2]> {
+    if !wrap_edges {
+        return None;
+    }
+
+    let mut offset = [0; 2];
+    for (axis, axis_offset) in offset.iter_mut().enumerate() {
+        if envelope.lower()[axis] < bounds.lower()[axis] {
+            *axis_offset = bounds.upper()[axis] - envelope.upper()[axis];
+        } else if envelope.upper()[axis] > bounds.upper()[axis] {
+            *axis_offset = bounds.lower()[axis] - envelope.lower()[axis];
+        }
+    }
+
+    let wrapped = AABB::from_corners(
+        [
+            envelope.lower()[0] + offset[0],
+            envelope.lower()[1] + offset[1],
+        ],
+        [
+            envelope.upper()[0] + offset[0],
+            envelope.upper()[1] + offset[1],
+        ],
+    );
+    if bounds.contains_envelope(&wrapped) {
+        Some(offset)
+    } else {
+        None
+    }
+}
+
 pub fn ship_distance(envelope: &AABB<[i32; 2]>, point: &[i32; 2]) -> i32 {
     let p = envelope.min_point(point);
     max((point[0] - p[0]).abs(), (point[1] - p[1]).abs())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use crate::game::ship::{Cooldown, Orientation, Ship, ShipData, ShipID};
+    use crate::messages::ship_action_request::ActionProperties;
+    use crate::types::{
+        BattleshipBalancing, CarrierBalancing, CommonBalancing, Config, Coordinate,
+        CruiserBalancing, DestroyerBalancing, EngineBoostProperties, MoveDirection, MoveProperties,
+        RotateDirection, RotateProperties, ShipType, ShootProperties, SubmarineBalancing,
+    };
+
+    fn destroyer(id: ShipID, health: u32) -> Ship {
+        Ship::Destroyer {
+            balancing: Arc::new(DestroyerBalancing::default()),
+            data: ShipData {
+                id,
+                health,
+                pos_x: 3,
+                pos_y: 4,
+                orientation: Orientation::East,
+            },
+            cooldowns: vec![Cooldown::Cannon {
+                remaining_rounds: 2,
+            }],
+        }
+    }
+
+    #[test]
+    fn equality() {
+        // a copy with separately allocated balancing
+        assert_eq!(destroyer((1, 0), 10), destroyer((1, 0), 10));
+
+        assert_ne!(destroyer((1, 0), 10), destroyer((1, 0), 9));
+        assert_ne!(destroyer((1, 0), 10), destroyer((1, 1), 10));
+
+        let mut cooled_down = destroyer((1, 0), 10);
+        cooled_down.cool_downs_mut().clear();
+        assert_ne!(destroyer((1, 0), 10), cooled_down);
+
+        let cruiser = Ship::Cruiser {
+            balancing: Arc::new(CruiserBalancing::default()),
+            data: destroyer((1, 0), 10).data(),
+            cooldowns: destroyer((1, 0), 10).cool_downs(),
+        };
+        assert_ne!(destroyer((1, 0), 10), cruiser);
+    }
+
+    #[test]
+    fn hash_follows_equality() {
+        let ships = HashSet::from([
+            destroyer((1, 0), 10),
+            destroyer((1, 0), 10),
+            destroyer((1, 0), 9),
+        ]);
+        assert_eq!(ships.len(), 2);
+        assert!(ships.contains(&destroyer((1, 0), 9)));
+    }
+
+    #[test]
+    fn health_multiplier_scales_initial_health() {
+        let common_balancing = || {
+            Some(CommonBalancing {
+                initial_health: 100,
+                ..Default::default()
+            })
+        };
+        let config = |health_multiplier: f32| {
+            Arc::new(Config {
+                carrier_balancing: Some(CarrierBalancing {
+                    common_balancing: common_balancing(),
+                    ..Default::default()
+                }),
+                battleship_balancing: Some(BattleshipBalancing {
+                    common_balancing: common_balancing(),
+                    ..Default::default()
+                }),
+                cruiser_balancing: Some(CruiserBalancing {
+                    common_balancing: common_balancing(),
+                    ..Default::default()
+                }),
+                submarine_balancing: Some(SubmarineBalancing {
+                    common_balancing: common_balancing(),
+                    ..Default::default()
+                }),
+                destroyer_balancing: Some(DestroyerBalancing {
+                    common_balancing: common_balancing(),
+                    ..Default::default()
+                }),
+                health_multiplier,
+                ..Default::default()
+            })
+        };
+        let ship_types = [
+            ShipType::Carrier,
+            ShipType::Battleship,
+            ShipType::Cruiser,
+            ShipType::Submarine,
+            ShipType::Destroyer,
+        ];
+        let new_ship = |ship_type, config| {
+            Ship::new_from_type(ship_type, (1, 0), (0, 0), Orientation::North, config)
+        };
+
+        for ship_type in ship_types {
+            let halved = new_ship(ship_type, config(0.5));
+            assert_eq!(halved.health(), 50);
+            assert_eq!(halved.initial_health(), 50);
+
+            // configs without a multiplier keep the balancing as it is
+            assert_eq!(new_ship(ship_type, config(0.0)).health(), 100);
+            assert_eq!(new_ship(ship_type, config(1.0)).health(), 100);
+            // ships are never created destroyed
+            assert_eq!(new_ship(ship_type, config(0.001)).health(), 1);
+        }
+    }
+
+    fn tiles(tiles: &[(u32, u32)]) -> Vec<Coordinate> {
+        tiles.iter().map(|&(x, y)| Coordinate { x, y }).collect()
+    }
+
+    #[test]
+    fn tiles_after_move() {
+        // the destroyer covers (3, 4) and (4, 4)
+        let ship = destroyer((1, 0), 10);
+        let forward: ActionProperties = MoveProperties {
+            direction: MoveDirection::Forward.into(),
+        }
+        .into();
+        assert_eq!(ship.tiles_after(&forward), tiles(&[(4, 4), (5, 4)]));
+
+        let backward: ActionProperties = MoveProperties {
+            direction: MoveDirection::Backward.into(),
+        }
+        .into();
+        assert_eq!(ship.tiles_after(&backward), tiles(&[(2, 4), (3, 4)]));
+    }
+
+    #[test]
+    fn tiles_after_rotate() {
+        let ship = destroyer((1, 0), 10);
+        let clockwise: ActionProperties = RotateProperties {
+            direction: RotateDirection::Clockwise.into(),
+        }
+        .into();
+        assert_eq!(ship.tiles_after(&clockwise), tiles(&[(3, 3), (3, 4)]));
+
+        let counter_clockwise: ActionProperties = RotateProperties {
+            direction: RotateDirection::CounterClockwise.into(),
+        }
+        .into();
+        assert_eq!(
+            ship.tiles_after(&counter_clockwise),
+            tiles(&[(3, 4), (3, 5)])
+        );
+    }
+
+    #[test]
+    fn tiles_after_engine_boost() {
+        let cruiser = Ship::Cruiser {
+            balancing: Arc::new(CruiserBalancing {
+                engine_boost_distance: 5,
+                ..Default::default()
+            }),
+            data: destroyer((1, 0), 10).data(),
+            cooldowns: vec![],
+        };
+        let engine_boost: ActionProperties = EngineBoostProperties {}.into();
+        assert_eq!(
+            cruiser.tiles_after(&engine_boost),
+            tiles(&[(8, 4), (9, 4), (10, 4)])
+        );
+
+        // other ships and actions stay in place
+        let ship = destroyer((1, 0), 10);
+        assert_eq!(ship.tiles_after(&engine_boost), tiles(&[(3, 4), (4, 4)]));
+        let shot: ActionProperties = ShootProperties::default().into();
+        assert_eq!(ship.tiles_after(&shot), tiles(&[(3, 4), (4, 4)]));
+    }
+}
@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use rstar::{Envelope, PointDistance, RTree, RTreeObject, AABB};
 
+use crate::game::direction::direction_step;
 use crate::game::ship::{ship_distance, Cooldown, GetShipID, Ship, ShipID};
 use crate::game::{ActionValidationError, PlayerID};
 use crate::types::{Coordinate, CruiserBalancing, Direction, MoveDirection, RotateDirection};
@@ -12,6 +13,9 @@ use crate::types::{Coordinate, CruiserBalancing, Direction, MoveDirection, Rotat
 pub struct ShipManager {
     ships: HashMap<ShipID, Ship>,
     ships_geo_lookup: RTree<ShipTreeNode>,
+    islands: HashSet<[i32; 2]>,
+    line_of_sight_vision: bool,
+    vision_modifiers: HashMap<ShipID, i32>,
 }
 
 impl From<ShipManager> for HashMap<ShipID, Ship> {
@@ -25,6 +29,9 @@ impl ShipManager {
         ShipManager {
             ships: Default::default(),
             ships_geo_lookup: Default::default(),
+            islands: Default::default(),
+            line_of_sight_vision: false,
+            vision_modifiers: Default::default(),
         }
     }
 
@@ -37,23 +44,116 @@ impl ShipManager {
                     .map(|ship| ShipTreeNode::new(ship.id(), ship.envelope()))
                     .collect(),
             ),
+            islands: Default::default(),
+            line_of_sight_vision: false,
+            vision_modifiers: Default::default(),
         }
     }
 
-    pub fn get_ship_parts_seen_by(&self, ships_ids: &[ShipID]) -> Vec<Coordinate> {
+    /// Turns the given tiles into islands. Ships can neither enter nor pass them,
+    /// they stop shots and torpedoes and block vision.
+    pub fn set_islands(&mut self, islands: &[Coordinate]) {
+        self.islands = islands
+            .iter()
+            .map(|Coordinate { x, y }| [*x as i32, *y as i32])
+            .collect();
+    }
+
+    /// With line of sight vision, ships hide the ships behind them from an observer.
+    /// Otherwise only islands block vision.
+    pub fn set_line_of_sight_vision(&mut self, enabled: bool) {
+        self.line_of_sight_vision = enabled;
+    }
+
+    /// Changes the vision range of a single ship relative to the default of its type, e.g. for
+    /// temporary buffs or to let damaged ships see less. A modifier of zero removes the change.
+    pub fn set_vision_modifier(&mut self, ship_id: ShipID, modifier: i32) {
+        if modifier == 0 {
+            self.vision_modifiers.remove(&ship_id);
+        } else {
+            self.vision_modifiers.insert(ship_id, modifier);
+        }
+    }
+
+    /// Returns the vision range of the ship including its modifier, it is never negative.
+    pub fn effective_vision_range(&self, ship: &Ship) -> u32 {
+        let modifier = self.vision_modifiers.get(&ship.id()).cloned().unwrap_or(0);
+        (ship.vision_range() as i64 + modifier as i64).clamp(0, u32::MAX as i64) as u32
+    }
+
+    pub fn is_island(&self, point: &[i32; 2]) -> bool {
+        self.islands.contains(point)
+    }
+
+    pub fn overlaps_island(&self, envelope: &AABB<[i32; 2]>) -> bool {
+        !self.islands.is_empty()
+            && envelope_to_points(*envelope)
+                .any(|Coordinate { x, y }| self.islands.contains(&[x as i32, y as i32]))
+    }
+
+    /// Checks whether a straight line without islands connects any tile of the envelope with
+    /// the target. Islands at either end of the line do not count.
+    pub fn has_line_of_sight(&self, from: &AABB<[i32; 2]>, to: &[i32; 2]) -> bool {
+        self.islands.is_empty() || any_clear_line(from, to, |tile| self.islands.contains(tile))
+    }
+
+    /// Checks whether the observer sees a part of another ship. Besides islands, the parts of
+    /// third ships block the view if line of sight vision is enabled.
+    fn sees_part(&self, observer: &Ship, seen_ship_id: &ShipID, part: &[i32; 2]) -> bool {
+        if !self.line_of_sight_vision {
+            return self.has_line_of_sight(&observer.envelope(), part);
+        }
+
+        any_clear_line(&observer.envelope(), part, |tile| {
+            self.islands.contains(tile)
+                || self
+                    .ships_geo_lookup
+                    .locate_all_at_point(tile)
+                    .any(|node| node.ship_id != observer.id() && node.ship_id != *seen_ship_id)
+        })
+    }
+
+    /// Collects all ship parts seen by the given ships.
+    /// If `wrap_bounds` is set, vision reaching over an edge of these bounds continues at the
+    /// opposite edge. Islands, and ships with line of sight vision, only block vision that does
+    /// not reach over an edge.
+    pub fn get_ship_parts_seen_by(
+        &self,
+        ships_ids: &[ShipID],
+        wrap_bounds: Option<&AABB<[i32; 2]>>,
+    ) -> Vec<Coordinate> {
         ships_ids
             .iter()
             .flat_map(|ship_id| {
                 if let Some(ship) = self.get_by_id(ship_id) {
-                    let vision_envelope = ship.vision_envelope();
-
-                    self.ships_geo_lookup
-                        .locate_in_envelope_intersecting(&vision_envelope)
-                        .filter(|ship| ship.ship_id != *ship_id)
-                        .flat_map(|ship| envelope_to_points(ship.envelope))
-                        .filter(move |Coordinate { x, y }| {
-                            vision_envelope.contains_point(&[*x as i32, *y as i32])
+                    let direct_vision =
+                        ship.vision_envelope_with_range(self.effective_vision_range(ship));
+                    let vision_envelopes = match wrap_bounds {
+                        Some(bounds) => wrapped_envelopes(&direct_vision, bounds),
+                        None => vec![direct_vision],
+                    };
+
+                    vision_envelopes
+                        .iter()
+                        .flat_map(|vision_envelope| {
+                            self.ships_geo_lookup
+                                .locate_in_envelope_intersecting(vision_envelope)
+                                .filter(|seen_ship| seen_ship.ship_id != *ship_id)
+                                .flat_map(|seen_ship| {
+                                    envelope_to_points(seen_ship.envelope)
+                                        .filter(move |Coordinate { x, y }| {
+                                            vision_envelope.contains_point(&[*x as i32, *y as i32])
+                                        })
+                                        .filter(move |Coordinate { x, y }| {
+                                            let part = [*x as i32, *y as i32];
+                                            !direct_vision.contains_point(&part)
+                                                || self.sees_part(ship, &seen_ship.ship_id, &part)
+                                        })
+                                })
+                                .collect::<Vec<_>>()
                         })
+                        .collect::<HashSet<_>>()
+                        .into_iter()
                         .collect()
                 } else {
                     vec![]
@@ -71,6 +171,37 @@ impl ShipManager {
             .collect()
     }
 
+    /// The ships of a single player, ordered by their ids.
+    pub fn ships_of(&self, player_id: PlayerID) -> Vec<&Ship> {
+        let mut ships: Vec<_> = self
+            .ships
+            .iter()
+            .filter(|((owner, _), _)| *owner == player_id)
+            .collect();
+        ships.sort_unstable_by_key(|(id, _)| **id);
+        ships.into_iter().map(|(_, ship)| ship).collect()
+    }
+
+    /// Sums up the remaining health of all ships per team.
+    /// Ships of players in neither team are not counted.
+    pub fn health_by_team(
+        &self,
+        team_a: &HashSet<PlayerID>,
+        team_b: &HashSet<PlayerID>,
+    ) -> (u32, u32) {
+        self.ships
+            .iter()
+            .fold((0, 0), |(health_a, health_b), ((player_id, _), ship)| {
+                if team_a.contains(player_id) {
+                    (health_a + ship.health(), health_b)
+                } else if team_b.contains(player_id) {
+                    (health_a, health_b + ship.health())
+                } else {
+                    (health_a, health_b)
+                }
+            })
+    }
+
     pub fn iter_ships(&self) -> impl Iterator<Item = (&ShipID, &Ship)> {
         self.ships.iter()
     }
@@ -107,15 +238,19 @@ impl ShipManager {
         }
     }
 
+    /// The ships covering at least one tile of the envelope.
+    pub fn ships_in_envelope(&self, envelope: &AABB<[i32; 2]>) -> HashSet<ShipID> {
+        self.ships_geo_lookup
+            .locate_in_envelope_intersecting(envelope)
+            .map(|node| node.ship_id)
+            .collect()
+    }
+
     pub fn destroy_colliding_ships_in_envelope(
         &mut self,
         envelope: &AABB<[i32; 2]>,
     ) -> Option<Vec<Ship>> {
-        let colliding_ships: HashSet<_> = self
-            .ships_geo_lookup
-            .locate_in_envelope_intersecting(envelope)
-            .map(|node| node.ship_id)
-            .collect();
+        let colliding_ships = self.ships_in_envelope(envelope);
 
         if colliding_ships.len() > 1 {
             // there is more than one ship in the new position of the moved ship
@@ -155,6 +290,9 @@ impl ShipManager {
         {
             return Err(ShipPlacementError::Collision);
         }
+        if self.overlaps_island(&ship.envelope()) {
+            return Err(ShipPlacementError::IslandCollision);
+        }
 
         self.ships_geo_lookup.insert(ShipTreeNode::from(&ship));
         self.ships.insert(ship_id, ship);
@@ -174,6 +312,10 @@ impl ShipManager {
             // shot out of map
             return Err(ActionValidationError::OutOfMap);
         }
+        let blocked_by_island = self
+            .ships
+            .get(ship_id)
+            .is_some_and(|ship| !self.has_line_of_sight(&ship.envelope(), &target));
 
         let ship = match self.ships.get_mut(ship_id) {
             Some(ship) => ship,
@@ -199,7 +341,7 @@ impl ShipManager {
         }
 
         // check range
-        if ship.distance_2(&target) > balancing.shoot_range as i32 {
+        if !ship.can_reach(&target, balancing.shoot_range) || blocked_by_island {
             return Err(ActionValidationError::Unreachable);
         }
 
@@ -257,8 +399,10 @@ impl ShipManager {
         ship_id: &ShipID,
         direction: MoveDirection,
         bounds: &AABB<[i32; 2]>,
+        wrap_edges: bool,
     ) -> Result<AABB<[i32; 2]>, ActionValidationError> {
-        self.mutate_ship_by_id(
+        let previous_state = (self.ships.get(ship_id).cloned(), *action_points);
+        let new_area = self.mutate_ship_by_id(
             ship_id,
             true,
             ActionValidationError::NonExistentShip { id: *ship_id },
@@ -287,7 +431,7 @@ impl ShipManager {
                     costs = None
                 }
 
-                match ship.do_move(direction, bounds) {
+                match ship.do_move(direction, bounds, wrap_edges) {
                     Err(e) => Err(e),
                     Ok(new_position) => {
                         // enforce costs
@@ -306,7 +450,8 @@ impl ShipManager {
                     }
                 }
             },
-        )
+        )?;
+        self.keep_off_islands(ship_id, new_area, previous_state, action_points)
     }
 
     pub fn engine_boost<R, F>(
@@ -398,8 +543,10 @@ impl ShipManager {
         ship_id: &ShipID,
         direction: RotateDirection,
         bounds: &AABB<[i32; 2]>,
+        wrap_edges: bool,
     ) -> Result<AABB<[i32; 2]>, ActionValidationError> {
-        self.mutate_ship_by_id(
+        let previous_state = (self.ships.get(ship_id).cloned(), *action_points);
+        let new_area = self.mutate_ship_by_id(
             ship_id,
             true,
             ActionValidationError::NonExistentShip { id: *ship_id },
@@ -422,7 +569,7 @@ impl ShipManager {
                 }
 
                 //let old_envelope = ship.envelope();
-                match ship.do_rotation(direction, bounds) {
+                match ship.do_rotation(direction, bounds, wrap_edges) {
                     Err(e) => Err(e),
                     Ok(new_position) => {
                         // enforce costs
@@ -438,7 +585,41 @@ impl ShipManager {
                     }
                 }
             },
-        )
+        )?;
+        self.keep_off_islands(ship_id, new_area, previous_state, action_points)
+    }
+
+    /// Reverts a movement or rotation including its costs if the ship ended up on an island.
+    fn keep_off_islands(
+        &mut self,
+        ship_id: &ShipID,
+        new_area: AABB<[i32; 2]>,
+        (previous_ship, previous_action_points): (Option<Ship>, u32),
+        action_points: &mut u32,
+    ) -> Result<AABB<[i32; 2]>, ActionValidationError> {
+        if !self.overlaps_island(&new_area) {
+            return Ok(new_area);
+        }
+
+        if let (Some(_), Some(previous_ship)) = (self.ships.get(ship_id), previous_ship) {
+            self.restore_ship(previous_ship);
+        }
+        *action_points = previous_action_points;
+
+        Err(ActionValidationError::Unreachable)
+    }
+
+    /// Puts a ship back the way it was before it moved, e.g. to revert a blocked movement.
+    pub fn restore_ship(&mut self, previous_ship: Ship) {
+        let ship_id = previous_ship.id();
+        if let Some(moved_ship) = self.ships.get(&ship_id) {
+            let _ = self
+                .ships_geo_lookup
+                .remove(&ShipTreeNode::from(moved_ship));
+        }
+        self.ships_geo_lookup
+            .insert(ShipTreeNode::from(&previous_ship));
+        self.ships.insert(ship_id, previous_ship);
     }
 
     fn mutate_ship_by_id<F, T, E>(
@@ -523,70 +704,21 @@ impl ShipManager {
         } else {
             0
         };
-        let trajectory = match direction {
-            Direction::North => AABB::from_corners(
-                [origin[0], origin[1] + origin_offset],
-                [
-                    origin[0],
-                    origin[1] + origin_offset + balancing.torpedo_range as i32,
-                ],
-            ),
-            Direction::East => AABB::from_corners(
-                [origin[0] + origin_offset, origin[1]],
-                [
-                    origin[0] + origin_offset + balancing.torpedo_range as i32,
-                    origin[1],
-                ],
-            ),
-            Direction::South => AABB::from_corners(
-                [origin[0], origin[1] - origin_offset],
-                [
-                    origin[0],
-                    origin[1] - origin_offset - balancing.torpedo_range as i32,
-                ],
-            ),
-            Direction::West => AABB::from_corners(
-                [origin[0] - origin_offset, origin[1]],
-                [
-                    origin[0] - origin_offset - balancing.torpedo_range as i32,
-                    origin[1],
-                ],
-            ),
+        let step = direction_step(direction);
+        let tile_at = |distance: i32| {
+            [
+                origin[0] + step[0] * (origin_offset + distance),
+                origin[1] + step[1] * (origin_offset + distance),
+            ]
         };
-
-        let hit_ships = self
-            .ships_geo_lookup
-            .locate_in_envelope_intersecting(&trajectory)
-            .filter_map(|node| {
-                if node.ship_id != *ship_id {
-                    Some(node.ship_id)
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
-
-        let destroyed_ships = hit_ships
-            .iter()
-            .filter_map(|id| {
-                let ship = self.ships.get_mut(id).unwrap();
-                if ship.apply_damage(balancing.torpedo_damage) {
-                    self.ships.remove(id)
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        Ok(AreaOfEffect {
-            hit_ships: hit_ships
-                .iter()
-                .filter_map(|id| self.ships.get(id).cloned())
-                .collect::<Vec<_>>(),
-            destroyed_ships,
-            damage_per_hit: balancing.torpedo_damage,
-            area: trajectory,
-        })
+        // the torpedo stops in front of the first island in its way
+        let range = balancing.torpedo_range as i32;
+        let range = (1..=range)
+            .find(|&distance| self.is_island(&tile_at(distance)))
+            .map_or(range, |island_distance| island_distance - 1);
+        let trajectory = AABB::from_corners(tile_at(0), tile_at(range));
+
+        Ok(self.damage_area(trajectory, balancing.torpedo_damage, Some(ship_id)))
     }
 
     pub fn predator_missile(
@@ -638,7 +770,7 @@ impl ShipManager {
         }
 
         // check range
-        if ship.distance_2(center) > balancing.predator_missile_range as i32 {
+        if !ship.can_reach(center, balancing.predator_missile_range) {
             return Err(ActionValidationError::Unreachable);
         }
 
@@ -661,33 +793,7 @@ impl ShipManager {
             ],
         );
 
-        let hit_ships = self
-            .ships_geo_lookup
-            .locate_in_envelope_intersecting(&blast_area)
-            .map(|node| node.ship_id)
-            .collect::<Vec<_>>();
-
-        let destroyed_ships = hit_ships
-            .iter()
-            .filter_map(|id| {
-                let ship = self.ships.get_mut(id).unwrap();
-                if ship.apply_damage(balancing.predator_missile_damage) {
-                    self.ships.remove(id)
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        Ok(AreaOfEffect {
-            hit_ships: hit_ships
-                .iter()
-                .filter_map(|id| self.ships.get(id).cloned())
-                .collect::<Vec<_>>(),
-            destroyed_ships,
-            damage_per_hit: balancing.predator_missile_damage,
-            area: blast_area,
-        })
+        Ok(self.damage_area(blast_area, balancing.predator_missile_damage, None))
     }
 
     pub fn scout_plane(
@@ -740,7 +846,7 @@ impl ShipManager {
         }
 
         // check range
-        if ship.distance_2(center) > balancing.scout_plane_range as i32 {
+        if !ship.can_reach(center, balancing.scout_plane_range) {
             return Err(ActionValidationError::Unreachable);
         }
 
@@ -763,13 +869,106 @@ impl ShipManager {
             ],
         );
 
-        Ok(self
-            .ships_geo_lookup
-            .locate_in_envelope_intersecting(&scout_area)
-            .filter(|node| enemy_team.contains(&node.ship_id.0))
+        Ok(self.ship_parts_in_area(&scout_area, &enemy_team))
+    }
+
+    /// Swaps the positions of the ship and another ship of its owner, both keep their
+    /// orientation. Only carriers and cruisers can swap, with a ship at most `range` tiles away.
+    /// The ability costs of the swapping ship are charged.
+    /// Returns the areas covered by the two ships afterwards.
+    pub fn swap_ships(
+        &mut self,
+        action_points: &mut u32,
+        ship_id: &ShipID,
+        partner_id: &ShipID,
+        bounds: &AABB<[i32; 2]>,
+        range: u32,
+    ) -> Result<[AABB<[i32; 2]>; 2], ActionValidationError> {
+        let ship = match self.ships.get(ship_id) {
+            None => return Err(ActionValidationError::NonExistentShip { id: *ship_id }),
+            Some(ship @ (Ship::Carrier { .. } | Ship::Cruiser { .. })) => ship,
+            Some(_) => return Err(ActionValidationError::InvalidShipType),
+        };
+        let partner = match self.ships.get(partner_id) {
+            Some(partner) if partner_id.0 == ship_id.0 && partner_id != ship_id => partner,
+            _ => return Err(ActionValidationError::NonExistentShip { id: *partner_id }),
+        };
+
+        // cooldown check
+        let remaining_rounds = ship.cool_downs().iter().find_map(|cd| match cd {
+            Cooldown::Ability { remaining_rounds } => Some(*remaining_rounds),
+            _ => None,
+        });
+        if let Some(remaining_rounds) = remaining_rounds {
+            return Err(ActionValidationError::Cooldown { remaining_rounds });
+        }
+
+        // check action points of player
+        let costs = ship.common_balancing().ability_costs.unwrap_or_default();
+        if *action_points < costs.action_points {
+            return Err(ActionValidationError::InsufficientPoints {
+                required: costs.action_points,
+            });
+        }
+
+        // check range
+        let (partner_x, partner_y) = partner.position();
+        if !ship.can_reach(&[partner_x, partner_y], range) {
+            return Err(ActionValidationError::Unreachable);
+        }
+
+        let swapped = [
+            ship.relocated(partner.position()),
+            partner.relocated(ship.position()),
+        ];
+        let areas = [swapped[0].envelope(), swapped[1].envelope()];
+        if !areas.iter().all(|area| bounds.contains_envelope(area)) {
+            return Err(ActionValidationError::OutOfMap);
+        }
+        if areas.iter().any(|area| self.overlaps_island(area)) {
+            return Err(ActionValidationError::Unreachable);
+        }
+        let blocked = areas[0].intersects(&areas[1])
+            || areas.iter().any(|area| {
+                self.ships_geo_lookup
+                    .locate_in_envelope_intersecting(area)
+                    .any(|node| node.ship_id != *ship_id && node.ship_id != *partner_id)
+            });
+        if blocked {
+            return Err(ActionValidationError::Collision);
+        }
+
+        // enforce costs
+        *action_points -= costs.action_points;
+        for (id, mut swapped_ship) in [ship_id, partner_id].into_iter().zip(swapped) {
+            if id == ship_id && costs.cooldown > 0 {
+                swapped_ship.cool_downs_mut().push(Cooldown::Ability {
+                    remaining_rounds: costs.cooldown,
+                });
+            }
+            if let Some(previous) = self.ships.get(id) {
+                let _ = self.ships_geo_lookup.remove(&ShipTreeNode::from(previous));
+            }
+            self.ships_geo_lookup
+                .insert(ShipTreeNode::from(&swapped_ship));
+            self.ships.insert(*id, swapped_ship);
+        }
+
+        Ok(areas)
+    }
+
+    /// The tiles inside `area` covered by ships of the given players.
+    pub fn ship_parts_in_area(
+        &self,
+        area: &AABB<[i32; 2]>,
+        players: &HashSet<PlayerID>,
+    ) -> HashSet<Coordinate> {
+        self.ships_geo_lookup
+            .locate_in_envelope_intersecting(area)
+            .filter(|node| players.contains(&node.ship_id.0))
             .flat_map(|node| envelope_to_points(node.envelope))
-            .filter(|p| scout_area.contains_point(&[p.x as i32, p.y as i32]))
-            .collect())
+            .filter(|p| area.contains_point(&[p.x as i32, p.y as i32]))
+            .collect()
     }
 
     pub fn multi_missile(
@@ -843,40 +1042,50 @@ impl ShipManager {
                     ],
                 );
 
-                (
-                    self.ships_geo_lookup
-                        .locate_in_envelope_intersecting(&blast_area)
-                        .map(|node| node.ship_id)
-                        .collect::<HashSet<_>>(),
-                    blast_area,
-                )
+                self.damage_area(blast_area, balancing.multi_missile_damage, None)
             })
-            .map(|(hit_ships, blast_area)| {
-                let destroyed_ships = hit_ships
-                    .iter()
-                    .filter_map(|id| match self.ships.get_mut(id) {
-                        Some(ship) => {
-                            if ship.apply_damage(balancing.multi_missile_damage) {
-                                self.ships.remove(id)
-                            } else {
-                                None
-                            }
-                        }
-                        None => None,
-                    })
-                    .collect();
+            .collect::<Vec<_>>())
+    }
 
-                AreaOfEffect {
-                    hit_ships: hit_ships
-                        .iter()
-                        .filter_map(|id| self.ships.get(id).cloned())
-                        .collect::<Vec<_>>(),
-                    destroyed_ships,
-                    damage_per_hit: balancing.multi_missile_damage,
-                    area: blast_area,
+    /// Applies `damage` to every ship intersecting `area`, except for `spared_ship`.
+    /// Destroyed ships are removed from the board.
+    pub fn damage_area(
+        &mut self,
+        area: AABB<[i32; 2]>,
+        damage: u32,
+        spared_ship: Option<&ShipID>,
+    ) -> AreaOfEffect {
+        let hit_ships = self
+            .ships_geo_lookup
+            .locate_in_envelope_intersecting(&area)
+            .map(|node| node.ship_id)
+            .filter(|id| Some(id) != spared_ship)
+            .collect::<Vec<_>>();
+
+        let destroyed_ships: Vec<_> = hit_ships
+            .iter()
+            .filter_map(|id| {
+                let ship = self.ships.get_mut(id)?;
+                if ship.apply_damage(damage) {
+                    self.ships.remove(id)
+                } else {
+                    None
                 }
             })
-            .collect::<Vec<_>>())
+            .collect();
+        destroyed_ships.iter().for_each(|ship| {
+            let _ = self.ships_geo_lookup.remove(&ShipTreeNode::from(ship));
+        });
+
+        AreaOfEffect {
+            hit_ships: hit_ships
+                .iter()
+                .filter_map(|id| self.ships.get(id).cloned())
+                .collect::<Vec<_>>(),
+            destroyed_ships,
+            damage_per_hit: damage,
+            area,
+        }
     }
 }
 
@@ -931,7 +1140,7 @@ impl PointDistance for ShipTreeNode {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ShipPlacementError {
     Collision,
     IdAlreadyPlaced,
@@ -939,10 +1148,14 @@ pub enum ShipPlacementError {
     InvalidShipSet,
     InvalidShipType,
     InvalidShipDirection,
+    DisallowedShipDirection,
     InvalidShipPosition,
+    MissingShipPosition,
     PlayerNotInGame,
     ShipOutOfQuadrant,
+    ShipOutOfBounds,
     PlayerHasAlreadyPlacedShips,
+    IslandCollision,
 }
 
 impl Display for ShipPlacementError {
@@ -954,12 +1167,18 @@ impl Display for ShipPlacementError {
             ShipPlacementError::InvalidShipSet => "provided ship set is invalid",
             ShipPlacementError::InvalidShipType => "Ship type is invalid",
             ShipPlacementError::InvalidShipDirection => "Ship direction is invalid",
+            ShipPlacementError::DisallowedShipDirection => {
+                "Ship direction is not allowed for placement"
+            }
             ShipPlacementError::InvalidShipPosition => "Ship position is invalid",
+            ShipPlacementError::MissingShipPosition => "Ship position is missing",
             ShipPlacementError::PlayerNotInGame => "Player is not in game",
             ShipPlacementError::ShipOutOfQuadrant => "Ship is placed outside the provided quadrant",
+            ShipPlacementError::ShipOutOfBounds => "Ship is placed outside the board",
             ShipPlacementError::PlayerHasAlreadyPlacedShips => {
                 "A player can only place their ships once"
             }
+            ShipPlacementError::IslandCollision => "Ship is placed on an island",
         })
     }
 }
@@ -973,6 +1192,72 @@ pub fn envelope_to_points(envelope: AABB<[i32; 2]>) -> impl Iterator<Item = Coor
     })
 }
 
+/// Checks whether a straight line connects any tile of the envelope with the target without
+/// passing a blocked tile.
+fn any_clear_line<F>(from: &AABB<[i32; 2]>, to: &[i32; 2], is_blocked: F) -> bool
+where
+    F: Fn(&[i32; 2]) -> bool,
+{
+    envelope_to_points(*from).any(|Coordinate { x, y }| {
+        !tiles_between(&[x as i32, y as i32], to)
+            .iter()
+            .any(&is_blocked)
+    })
+}
+
+/// Lists the tiles a straight line between two tiles passes, without the tiles themselves.
+fn tiles_between(from: &[i32; 2], to: &[i32; 2]) -> Vec<[i32; 2]> {
+    // Bresenham's line algorithm
+    let (d_x, d_y) = ((to[0] - from[0]).abs(), -(to[1] - from[1]).abs());
+    let (step_x, step_y) = ((to[0] - from[0]).signum(), (to[1] - from[1]).signum());
+    let mut error = d_x + d_y;
+    let mut tile = *from;
+    let mut tiles = vec![];
+    while tile != *to {
+        let doubled_error = 2 * error;
+        if doubled_error >= d_y {
+            error += d_y;
+            tile[0] += step_x;
+        }
+        if doubled_error <= d_x {
+            error += d_x;
+            tile[1] += step_y;
+        }
+        if tile != *to {
+            tiles.push(tile);
+        }
+    }
+    tiles
+}
+
+/// Returns the envelope together with its copies shifted by the board dimensions, as long as they
+/// still overlap the board. This mirrors areas reaching over an edge onto the opposite side.
+pub fn wrapped_envelopes(
+    envelope: &AABB<[i32; 2]>,
+    bounds: &AABB<[i32; 2]>,
+) -> Vec<AABB<[i32; 2]>> {
+    let width = bounds.upper()[0] - bounds.lower()[0] + 1;
+    let height = bounds.upper()[1] - bounds.lower()[1] + 1;
+
+    [-width, 0, width]
+        .iter()
+        .flat_map(|&offset_x| [-height, 0, height].map(|offset_y| (offset_x, offset_y)))
+        .map(|(offset_x, offset_y)| {
+            AABB::from_corners(
+                [
+                    envelope.lower()[0] + offset_x,
+                    envelope.lower()[1] + offset_y,
+                ],
+                [
+                    envelope.upper()[0] + offset_x,
+                    envelope.upper()[1] + offset_y,
+                ],
+            )
+        })
+        .filter(|shifted| shifted.intersects(bounds))
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 pub struct AreaOfEffect {
     pub hit_ships: Vec<Ship>,
@@ -980,3 +1265,182 @@ pub struct AreaOfEffect {
     pub damage_per_hit: u32,
     pub area: AABB<[i32; 2]>,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use crate::game::ship::{GetShipID, Orientation, Ship, ShipData, ShipID};
+    use crate::game::ship_manager::ShipManager;
+    use crate::types::{CommonBalancing, Coordinate, CruiserBalancing, DestroyerBalancing};
+
+    fn destroyer(id: ShipID, health: u32) -> Ship {
+        Ship::Destroyer {
+            balancing: Arc::new(DestroyerBalancing::default()),
+            data: ShipData {
+                id,
+                health,
+                pos_x: id.0 as i32 * 10,
+                pos_y: id.1 as i32 * 10,
+                orientation: Orientation::North,
+            },
+            cooldowns: Default::default(),
+        }
+    }
+
+    #[test]
+    fn health_by_team() {
+        let cruiser = Ship::Cruiser {
+            balancing: Arc::new(CruiserBalancing::default()),
+            data: ShipData {
+                id: (2, 1),
+                health: 7,
+                pos_x: 40,
+                pos_y: 40,
+                orientation: Orientation::East,
+            },
+            cooldowns: Default::default(),
+        };
+        let ships = ShipManager::new_with_ships(vec![
+            destroyer((1, 0), 10),
+            destroyer((1, 1), 1),
+            destroyer((2, 0), 25),
+            cruiser,
+            destroyer((3, 0), 4),
+            // not part of any team
+            destroyer((9, 0), 100),
+        ]);
+
+        assert_eq!(
+            ships.health_by_team(&HashSet::from([1, 3]), &HashSet::from([2])),
+            (15, 32)
+        );
+        assert_eq!(
+            ships.health_by_team(&HashSet::from([2]), &HashSet::new()),
+            (32, 0)
+        );
+        assert_eq!(
+            ShipManager::new().health_by_team(&HashSet::from([1]), &HashSet::from([2])),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn ships_of_player() {
+        let ships = ShipManager::new_with_ships(vec![
+            destroyer((1, 2), 10),
+            destroyer((2, 0), 25),
+            destroyer((1, 0), 10),
+            destroyer((1, 1), 1),
+        ]);
+
+        let ids: Vec<_> = ships.ships_of(1).iter().map(|ship| ship.id()).collect();
+        assert_eq!(ids, vec![(1, 0), (1, 1), (1, 2)]);
+        assert_eq!(ships.ships_of(2).len(), 1);
+        assert!(ships.ships_of(3).is_empty());
+    }
+
+    #[test]
+    fn line_of_sight_vision() {
+        let destroyer_at = |id: ShipID, x: i32, y: i32, orientation: Orientation| Ship::Destroyer {
+            balancing: Arc::new(DestroyerBalancing {
+                common_balancing: Some(CommonBalancing {
+                    vision_range: 6,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            data: ShipData {
+                id,
+                health: 10,
+                pos_x: x,
+                pos_y: y,
+                orientation,
+            },
+            cooldowns: Default::default(),
+        };
+        let observer = destroyer_at((1, 0), 0, 0, Orientation::East);
+        let blocker = destroyer_at((2, 0), 3, 0, Orientation::North);
+        let hidden = destroyer_at((2, 1), 5, 0, Orientation::East);
+        let beside = destroyer_at((2, 2), 0, 4, Orientation::North);
+        let mut ships =
+            ShipManager::new_with_ships(vec![observer.clone(), blocker, hidden, beside]);
+
+        let seen = |ships: &ShipManager| {
+            let mut seen = ships.get_ship_parts_seen_by(&[observer.id()], None);
+            seen.sort_by_key(|Coordinate { x, y }| (*x, *y));
+            seen
+        };
+        let coordinates = |tiles: &[(u32, u32)]| {
+            tiles
+                .iter()
+                .map(|&(x, y)| Coordinate { x, y })
+                .collect::<Vec<_>>()
+        };
+
+        // radius vision sees through the blocker
+        assert_eq!(
+            seen(&ships),
+            coordinates(&[(0, 4), (0, 5), (3, 0), (3, 1), (5, 0), (6, 0)])
+        );
+
+        // the blocker itself and the ship beside it stay visible, the ship behind it is hidden
+        ships.set_line_of_sight_vision(true);
+        assert_eq!(seen(&ships), coordinates(&[(0, 4), (0, 5), (3, 0), (3, 1)]));
+    }
+
+    #[test]
+    fn vision_modifier() {
+        let destroyer_at = |id: ShipID, x: i32| Ship::Destroyer {
+            balancing: Arc::new(DestroyerBalancing {
+                common_balancing: Some(CommonBalancing {
+                    vision_range: 4,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            data: ShipData {
+                id,
+                health: 10,
+                pos_x: x,
+                pos_y: 0,
+                orientation: Orientation::East,
+            },
+            cooldowns: Default::default(),
+        };
+        let observer = destroyer_at((1, 0), 0);
+        // the observer covers (0, 0) and (1, 0), the other ships are 2 and 4 tiles away
+        let mut ships = ShipManager::new_with_ships(vec![
+            observer.clone(),
+            destroyer_at((2, 0), 3),
+            destroyer_at((2, 1), 5),
+        ]);
+        let seen = |ships: &ShipManager| {
+            let mut seen: Vec<_> = ships
+                .get_ship_parts_seen_by(&[observer.id()], None)
+                .iter()
+                .map(|Coordinate { x, .. }| *x)
+                .collect();
+            seen.sort_unstable();
+            seen
+        };
+
+        assert_eq!(ships.effective_vision_range(&observer), 4);
+        assert_eq!(seen(&ships), vec![3, 4, 5]);
+
+        ships.set_vision_modifier(observer.id(), -2);
+        assert_eq!(ships.effective_vision_range(&observer), 2);
+        assert_eq!(seen(&ships), vec![3]);
+
+        // the range cannot become negative
+        ships.set_vision_modifier(observer.id(), -10);
+        assert_eq!(ships.effective_vision_range(&observer), 0);
+        assert!(seen(&ships).is_empty());
+
+        ships.set_vision_modifier(observer.id(), 1);
+        assert_eq!(seen(&ships), vec![3, 4, 5, 6]);
+        ships.set_vision_modifier(observer.id(), 0);
+        assert_eq!(ships.effective_vision_range(&observer), 4);
+    }
+}
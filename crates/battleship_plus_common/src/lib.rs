@@ -1,5 +1,8 @@
 pub const PROTOCOL_VERSION: u8 = 1;
 
+pub mod config;
+#[cfg(feature = "connection")]
+pub mod connection;
 pub mod game;
 
 pub mod types {
@@ -55,6 +58,38 @@ pub mod codec {
     pub struct BattleshipPlusCodec {
         version: u8,
         length: Option<usize>,
+        /// Longest payload encoded or decoded, longer messages are rejected.
+        max_length: usize,
+    }
+
+    impl BattleshipPlusCodec {
+        /// A codec rejecting payloads longer than `max_length` bytes. The header cannot express
+        /// lengths above `u16::MAX`, so larger limits are lowered to it.
+        pub fn with_max_length(max_length: usize) -> BattleshipPlusCodec {
+            BattleshipPlusCodec {
+                max_length: max_length.min(u16::MAX as usize),
+                ..Default::default()
+            }
+        }
+
+        /// Forgets a partially read frame, so decoding starts with the next header.
+        ///
+        /// After a [`CodecError::UnsupportedVersion`] the header is consumed while its payload is
+        /// not. Callers may discard the buffered bytes and reset the codec to attempt
+        /// resynchronization on a later frame.
+        pub fn reset(&mut self) {
+            self.length = None;
+        }
+
+        fn check_length(&self, length: usize) -> Result<(), CodecError> {
+            if length > self.max_length {
+                return Err(CodecError::Protocol(format!(
+                    "message is too long ({length} bytes, at most {} allowed)",
+                    self.max_length
+                )));
+            }
+            Ok(())
+        }
     }
 
     impl Default for BattleshipPlusCodec {
@@ -62,33 +97,41 @@ pub mod codec {
             BattleshipPlusCodec {
                 version: crate::PROTOCOL_VERSION,
                 length: None,
+                max_length: u16::MAX as usize,
             }
         }
     }
 
-    impl Encoder<messages::ProtocolMessage> for BattleshipPlusCodec {
+    impl Encoder<&messages::ProtocolMessage> for BattleshipPlusCodec {
         type Error = CodecError;
 
         fn encode(
             &mut self,
-            message: messages::ProtocolMessage,
+            message: &messages::ProtocolMessage,
             buffer: &mut BytesMut,
         ) -> Result<(), Self::Error> {
-            let payload = messages::PacketPayload {
-                protocol_message: Some(message),
-            };
-
-            let length = payload.encoded_len();
-            if length > u16::MAX as usize {
-                return Err(CodecError::Protocol(String::from("message is too long")));
-            }
-            let length = length as u16;
+            // A PacketPayload only consists of the message, so encoding the message on its own
+            // yields the same bytes without moving it into a payload first.
+            let length = message.encoded_len();
+            self.check_length(length)?;
 
+            buffer.reserve(HEADER_SIZE + length);
             buffer.put_u8(self.version);
-            buffer.put_u16(length);
-            payload
-                .encode(buffer)
-                .map_err(|error| CodecError::Io(error.to_string()))
+            buffer.put_u16(length as u16);
+            message.encode(buffer);
+            Ok(())
+        }
+    }
+
+    impl Encoder<messages::ProtocolMessage> for BattleshipPlusCodec {
+        type Error = CodecError;
+
+        fn encode(
+            &mut self,
+            message: messages::ProtocolMessage,
+            buffer: &mut BytesMut,
+        ) -> Result<(), Self::Error> {
+            Encoder::<&messages::ProtocolMessage>::encode(self, &message, buffer)
         }
     }
 
@@ -114,8 +157,10 @@ pub mod codec {
                 if version != self.version {
                     return Err(CodecError::UnsupportedVersion(version));
                 }
+                self.check_length(length)?;
 
-                // Reserve enough memory for this message and the next header.
+                // Reserve enough memory for this message and the next header,
+                // the length is within the limit at this point.
                 if length + HEADER_SIZE > buffer.capacity() {
                     buffer.reserve(length + HEADER_SIZE - buffer.len());
                 }
@@ -147,6 +192,9 @@ pub mod codec {
     fn encode() {
         let expected_message = messages::ProtocolMessage::JoinRequest(messages::JoinRequest {
             username: "Example P. Name Sr.".to_string(),
+            game_id: None,
+            team: None,
+            seat_token: None,
         });
 
         let mut codec = BattleshipPlusCodec::default();
@@ -178,6 +226,9 @@ pub mod codec {
     fn decode() {
         let expected_message = messages::ProtocolMessage::JoinRequest(messages::JoinRequest {
             username: "Example P. Name Sr.".to_string(),
+            game_id: None,
+            team: None,
+            seat_token: None,
         });
         let expected_payload = messages::PacketPayload {
             protocol_message: Some(expected_message.clone()),
@@ -200,10 +251,118 @@ pub mod codec {
         assert_eq!(expected_message, decoded_message);
     }
 
+    #[test]
+    fn max_length_is_enforced() {
+        let message = messages::ProtocolMessage::JoinRequest(messages::JoinRequest {
+            username: "Example P. Name Sr.".to_string(),
+            game_id: None,
+            team: None,
+            seat_token: None,
+        });
+        let length = messages::PacketPayload {
+            protocol_message: Some(message.clone()),
+        }
+        .encoded_len();
+
+        let mut codec = BattleshipPlusCodec::with_max_length(length - 1);
+        let mut buffer = BytesMut::new();
+        match codec.encode(message.clone(), &mut buffer) {
+            Err(CodecError::Protocol(error)) => {
+                assert!(error.contains(&length.to_string()), "{error}")
+            }
+            result => panic!("Expected a protocol error, got {result:?}"),
+        }
+        assert!(buffer.is_empty());
+
+        // a length prefix above the limit is rejected before memory is reserved for it
+        buffer.put_u8(crate::PROTOCOL_VERSION);
+        buffer.put_u16(u16::MAX);
+        let mut codec = BattleshipPlusCodec::with_max_length(1024);
+        assert!(matches!(
+            codec.decode(&mut buffer),
+            Err(CodecError::Protocol(_))
+        ));
+        assert!(buffer.capacity() < u16::MAX as usize);
+
+        // messages up to the limit pass in both directions
+        let mut codec = BattleshipPlusCodec::with_max_length(length);
+        codec
+            .encode(message.clone(), &mut buffer)
+            .expect("the message is within the limit");
+        let decoded = codec
+            .decode(&mut buffer)
+            .expect("the message is within the limit")
+            .expect("an entire message is in the buffer");
+        assert_eq!(decoded, Some(message));
+    }
+
+    #[test]
+    fn reset_discards_partial_frames() {
+        let message = messages::ProtocolMessage::JoinRequest(messages::JoinRequest {
+            username: "Example P. Name Sr.".to_string(),
+            game_id: None,
+            team: None,
+            seat_token: None,
+        });
+        let mut codec = BattleshipPlusCodec::default();
+
+        // a header announcing a payload that never arrives
+        let mut buffer = BytesMut::new();
+        buffer.put_u8(crate::PROTOCOL_VERSION);
+        buffer.put_u16(512);
+        assert!(matches!(codec.decode(&mut buffer), Ok(None)));
+
+        codec.reset();
+        buffer.clear();
+        codec
+            .encode(message.clone(), &mut buffer)
+            .expect("Encoding does not fail");
+        let decoded = codec
+            .decode(&mut buffer)
+            .expect("No error occurs during decoding")
+            .expect("An entire message is in the buffer");
+        assert_eq!(decoded, Some(message));
+    }
+
+    #[test]
+    fn encoding_once_serves_many_receivers() {
+        let message = messages::ProtocolMessage::JoinRequest(messages::JoinRequest {
+            username: "Example P. Name Sr.".to_string(),
+            game_id: None,
+            team: None,
+            seat_token: None,
+        });
+        let mut codec = BattleshipPlusCodec::default();
+
+        let mut owned = BytesMut::new();
+        codec
+            .encode(message.clone(), &mut owned)
+            .expect("Encoding does not fail");
+        let mut borrowed = BytesMut::new();
+        codec
+            .encode(&message, &mut borrowed)
+            .expect("Encoding does not fail");
+        assert_eq!(borrowed, owned);
+
+        // the frame is shared by every receiver instead of being encoded for each of them
+        let frame = borrowed.freeze();
+        for _ in 0..40 {
+            let mut buffer = BytesMut::from(&frame[..]);
+            let decoded = codec
+                .decode(&mut buffer)
+                .expect("No error occurs during decoding")
+                .expect("An entire message is in the buffer");
+            assert_eq!(decoded, Some(message.clone()));
+        }
+    }
+
     #[test]
     fn encode_then_decode() {
         let expected_message = messages::ProtocolMessage::JoinRequest(messages::JoinRequest {
             username: "Example P. Name Sr.".to_string(),
+            game_id: None,
+            team: None,
+            seat_token: None,
         });
 
         let mut codec = BattleshipPlusCodec::default();
@@ -0,0 +1,76 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use log::{info, warn};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc;
+
+use bevy_quinnet_server::ClientId;
+
+/// Commands an operator can issue to a running server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum AdminCommand {
+    /// Logs all messages exchanged with a single client.
+    Trace { client_id: ClientId, enabled: bool },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AdminCommandParseError(String);
+
+impl Display for AdminCommandParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+impl FromStr for AdminCommand {
+    type Err = AdminCommandParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next(), words.next(), words.next()) {
+            (Some("trace"), Some(client_id), Some(switch), None) => {
+                let client_id = client_id.parse().map_err(|_| {
+                    AdminCommandParseError(format!("{client_id} is not a client id"))
+                })?;
+                let enabled = match switch {
+                    "on" => true,
+                    "off" => false,
+                    _ => {
+                        return Err(AdminCommandParseError(format!(
+                            "expected on or off, got {switch}"
+                        )))
+                    }
+                };
+                Ok(AdminCommand::Trace { client_id, enabled })
+            }
+            _ => Err(AdminCommandParseError(String::from(
+                "unknown command, usage: trace <client id> on|off",
+            ))),
+        }
+    }
+}
+
+/// Reads admin commands line by line from stdin.
+/// The returned channel closes when stdin does.
+pub(crate) fn spawn_admin_console_task() -> mpsc::UnboundedReceiver<AdminCommand> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match line.parse() {
+                Ok(command) => {
+                    if tx.send(command).is_err() {
+                        return;
+                    }
+                }
+                Err(e) => warn!("Invalid admin command \"{line}\": {e}"),
+            }
+        }
+        info!("Admin console closed");
+    });
+    rx
+}
@@ -1,7 +1,13 @@
-use std::net::{SocketAddrV4, SocketAddrV6};
+use std::fmt::{Display, Formatter};
+use std::net::{SocketAddr, SocketAddrV4, SocketAddrV6};
 use std::sync::Arc;
 use std::time::Duration;
 
+use battleship_plus_common::config::{ConfigError, ConfigFileError};
+use battleship_plus_common::types::Config;
+
+const DEFAULT_GAME_END_DRAIN_TIME: Duration = Duration::from_secs(3);
+
 #[derive(Copy, Clone, Debug)]
 pub struct ServerConfig {
     pub game_address_v4: SocketAddrV4,
@@ -12,11 +18,105 @@ pub struct ServerConfig {
     pub announcement_address_v6: SocketAddrV6,
     pub announcement_interval: Duration,
     pub server_domain: Option<&'static str>,
+    /// Address of the optional HTTP endpoint serving the public game state as JSON.
+    pub status_address: Option<SocketAddr>,
+    /// Number of games that may run at the same time.
+    pub max_games: usize,
+    /// Whether admin commands are read from stdin.
+    pub admin_console: bool,
+    /// Number of broadcasts queued for each endpoint before the oldest ones are dropped.
+    pub broadcast_capacity: usize,
+    /// Path of the optional replay log, every game appends a snapshot of its board to it
+    /// at the end of each turn.
+    pub snapshot_log: Option<&'static str>,
+    /// Path of the optional audit log, every game appends each executed action and its outcome
+    /// to it.
+    pub audit_log: Option<&'static str>,
+    /// Whether joining players are placed into the team they asked for, as long as it has
+    /// capacity left.
+    pub honor_requested_team: bool,
+    /// Bytes of messages queued for a single client before the client is disconnected.
+    pub max_pending_bytes: usize,
+    /// Path of the optional file the lobby is kept in. After a restart, players joining with
+    /// the token of their seat take it again.
+    pub lobby_file: Option<&'static str>,
+    /// Time an ended game is kept around, so the messages queued for its clients are still sent.
+    pub game_end_drain_time: Duration,
+    /// Path of the optional TOML file the game config is read from instead of the defaults.
+    pub config_file: Option<&'static str>,
+}
+
+/// A server setting that cannot be used as given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerConfigError {
+    InvalidGameEndDrainTime(&'static str),
+    UnreadableConfigFile {
+        path: &'static str,
+        error: String,
+    },
+    InvalidConfigFile {
+        path: &'static str,
+        error: ConfigError,
+    },
+}
+
+impl Display for ServerConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerConfigError::InvalidGameEndDrainTime(millis) => write!(
+                f,
+                "game end drain time \"{millis}\" is not a number of milliseconds"
+            ),
+            ServerConfigError::UnreadableConfigFile { path, error } => {
+                write!(f, "unable to read config file \"{path}\": {error}")
+            }
+            ServerConfigError::InvalidConfigFile { path, error } => {
+                write!(f, "config file \"{path}\": {error}")
+            }
+        }
+    }
+}
+
+/// Reads the time ended games are kept around in milliseconds, the default applies without one.
+pub(crate) fn parse_game_end_drain_time(
+    millis: Option<&'static str>,
+) -> Result<Duration, ServerConfigError> {
+    match millis {
+        Some(millis) => millis
+            .parse()
+            .map(Duration::from_millis)
+            .map_err(|_| ServerConfigError::InvalidGameEndDrainTime(millis)),
+        None => Ok(DEFAULT_GAME_END_DRAIN_TIME),
+    }
+}
+
+/// Reads the game config from a TOML file. Every problem of a well-formed file is reported.
+pub(crate) fn load_game_config(path: &'static str) -> Result<Config, Vec<ServerConfigError>> {
+    let source = std::fs::read_to_string(path).map_err(|e| {
+        vec![ServerConfigError::UnreadableConfigFile {
+            path,
+            error: e.to_string(),
+        }]
+    })?;
+    Config::from_toml(&source).map_err(|error| match error {
+        ConfigFileError::Syntax(error) => {
+            vec![ServerConfigError::UnreadableConfigFile { path, error }]
+        }
+        ConfigFileError::Invalid(errors) => errors
+            .into_iter()
+            .map(|error| ServerConfigError::InvalidConfigFile { path, error })
+            .collect(),
+    })
 }
 
 pub trait ConfigProvider {
     fn game_config(&self) -> Arc<battleship_plus_common::types::Config>;
     fn server_config(&self) -> Arc<ServerConfig>;
+
+    /// Checks for server settings that could not be applied, all problems are reported at once.
+    fn validate_server_config(&self) -> Result<(), Vec<ServerConfigError>> {
+        Ok(())
+    }
 }
 
 pub(crate) mod default {
@@ -25,11 +125,15 @@ pub(crate) mod default {
     use std::time::Duration;
 
     use battleship_plus_common::types::{
-        BattleshipBalancing, CarrierBalancing, CommonBalancing, Config, Costs, CruiserBalancing,
-        DestroyerBalancing, ShipType, SubmarineBalancing,
+        ActionPointCarryover, BattleshipBalancing, CarrierBalancing, CommonBalancing, Config,
+        Costs, CruiserBalancing, DestroyerBalancing, ShipType, SubmarineBalancing, Tiebreaker,
     };
+    use bevy_quinnet_server::DEFAULT_MAX_PENDING_BYTES;
 
-    use crate::config_provider::{ConfigProvider, ServerConfig};
+    use crate::config_provider::{
+        parse_game_end_drain_time, ConfigProvider, ServerConfig, ServerConfigError,
+        DEFAULT_GAME_END_DRAIN_TIME,
+    };
 
     fn costs(cooldown: u32, action_points: u32) -> Option<Costs> {
         Some(Costs {
@@ -129,9 +233,45 @@ pub(crate) mod default {
                 ship_set_team_b: default_ship_set(),
                 board_size: if cfg!(test) { 128 } else { 24 },
                 action_point_gain: 20,
+                action_point_carryover: ActionPointCarryover::CarryOver.into(),
+                action_point_cap: 0,
                 team_size_a: if cfg!(test) { 2 } else { 1 },
                 team_size_b: if cfg!(test) { 2 } else { 1 },
+                min_players: 2,
                 turn_time_limit: 0,
+                game_start_ack_timeout: 0,
+                auto_ready_timeout: 0,
+                kick_unready_players: false,
+                keepalive_interval: 15_000,
+                wrap_edges: false,
+                allowed_placement_orientations: vec![],
+                reveal_on_game_end: false,
+                announce_objectives: true,
+                owner_cooldown_reduction: 0,
+                max_moves_per_ship: 0,
+                max_ships_per_player: 0,
+                shots_reveal_shooter: false,
+                symmetric_quadrants: false,
+                share_team_vision: true,
+                strict_protocol: true,
+                islands: vec![],
+                line_of_sight_vision: false,
+                mine_laying_ship_types: vec![],
+                mine_damage: 0,
+                incendiary_ship_types: vec![],
+                burn_damage: 0,
+                burn_turns: 0,
+                reveal_on_destruction_radius: 0,
+                tiebreakers: vec![
+                    Tiebreaker::ShipsRemaining.into(),
+                    Tiebreaker::TotalHealth.into(),
+                ],
+                reveal_opponent_quadrants: false,
+                health_multiplier: 1.0,
+                forbid_repeated_abilities: false,
+                ship_swap_range: 0,
+                action_points_visible: false,
+                friendly_collision: false,
             })
         }
 
@@ -152,11 +292,81 @@ pub(crate) mod default {
                 ),
                 announcement_interval: Duration::from_secs(5),
                 server_domain: option_env!("SERVER_DOMAIN"),
+                status_address: option_env!("STATUS_ADDRESS").map(|address| {
+                    address
+                        .parse()
+                        .expect("STATUS_ADDRESS has to be a valid socket address")
+                }),
+                max_games: option_env!("MAX_GAMES").map_or(1, |max_games| {
+                    max_games
+                        .parse()
+                        .expect("MAX_GAMES has to be a positive number")
+                }),
+                admin_console: !cfg!(test),
+                broadcast_capacity: option_env!("BROADCAST_CAPACITY").map_or(128, |capacity| {
+                    capacity
+                        .parse()
+                        .expect("BROADCAST_CAPACITY has to be a positive number")
+                }),
+                snapshot_log: option_env!("SNAPSHOT_LOG"),
+                audit_log: option_env!("AUDIT_LOG"),
+                honor_requested_team: option_env!("HONOR_REQUESTED_TEAM").is_some(),
+                max_pending_bytes: option_env!("MAX_PENDING_BYTES").map_or(
+                    DEFAULT_MAX_PENDING_BYTES,
+                    |bytes| {
+                        bytes
+                            .parse()
+                            .expect("MAX_PENDING_BYTES has to be a positive number")
+                    },
+                ),
+                lobby_file: option_env!("LOBBY_FILE"),
+                // an invalid drain time is reported by the validation
+                game_end_drain_time: parse_game_end_drain_time(option_env!("GAME_END_DRAIN_MS"))
+                    .unwrap_or(DEFAULT_GAME_END_DRAIN_TIME),
+                config_file: option_env!("CONFIG_FILE"),
             })
         }
+
+        fn validate_server_config(&self) -> Result<(), Vec<ServerConfigError>> {
+            parse_game_end_drain_time(option_env!("GAME_END_DRAIN_MS"))
+                .map(|_| ())
+                .map_err(|error| vec![error])
+        }
     }
 }
 
 pub fn default_config_provider() -> Arc<dyn ConfigProvider + Send + Sync> {
     Arc::from(default::DefaultGameConfig)
 }
+
+/// Serves a game config read from a file, the server settings are the defaults.
+#[derive(Clone, Debug)]
+pub struct FileGameConfig {
+    game_config: Arc<Config>,
+}
+
+impl ConfigProvider for FileGameConfig {
+    fn game_config(&self) -> Arc<Config> {
+        self.game_config.clone()
+    }
+
+    fn server_config(&self) -> Arc<ServerConfig> {
+        default::DefaultGameConfig.server_config()
+    }
+
+    fn validate_server_config(&self) -> Result<(), Vec<ServerConfigError>> {
+        default::DefaultGameConfig.validate_server_config()
+    }
+}
+
+/// The defaults, unless a config file is configured for the game config.
+pub fn server_config_provider(
+) -> Result<Arc<dyn ConfigProvider + Send + Sync>, Vec<ServerConfigError>> {
+    let cfg = default_config_provider();
+    match cfg.server_config().config_file {
+        Some(path) => Ok(Arc::new(FileGameConfig {
+            game_config: Arc::new(load_game_config(path)?),
+        })),
+        None => Ok(cfg),
+    }
+}
@@ -0,0 +1,222 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use battleship_plus_common::config::{ConfigError, ConfigFileError, MAX_BOARD_SIZE};
+use battleship_plus_common::types::{Config, Coordinate, ShipType};
+use battleship_plus_common::util;
+
+use crate::config_provider::{
+    default_config_provider, load_game_config, parse_game_end_drain_time, ServerConfigError,
+};
+use crate::game::data::Game;
+
+#[tokio::test]
+async fn default_config_round_trips_through_toml() {
+    let config = default_config_provider().game_config();
+    let toml = config.to_toml().expect("the default config can be written");
+    let parsed = Config::from_toml(&toml).expect("the default config is valid");
+    assert_eq!(parsed, *config);
+}
+
+#[tokio::test]
+async fn invalid_toml_reports_all_issues() {
+    let destroyer = ShipType::Destroyer as i32;
+    let toml = format!(
+        r#"
+        board_size = 10
+        team_size_a = 1
+        team_size_b = 1
+        min_players = 3
+        ship_set_team_a = [{destroyer}, 42]
+        ship_set_team_b = []
+        islands = [{{ x = 10, y = 0 }}]
+
+        [destroyer_balancing.common_balancing]
+        initial_health = 100
+    "#
+    );
+
+    let errors = match Config::from_toml(&toml) {
+        Err(ConfigFileError::Invalid(errors)) => errors,
+        result => panic!("expected the config to be invalid, got {result:?}"),
+    };
+    let expected = [
+        ConfigError::MinPlayersExceedTeamSizes {
+            min_players: 3,
+            player_count: 2,
+        },
+        ConfigError::UnknownShipType {
+            team: "A",
+            ship_type: 42,
+        },
+        ConfigError::EmptyShipSet { team: "B" },
+        ConfigError::IslandOutOfBoard(Coordinate { x: 10, y: 0 }),
+    ];
+    assert_eq!(errors.len(), expected.len(), "{errors:?}");
+    for error in &expected {
+        assert!(errors.contains(error), "{error} is not reported");
+    }
+
+    // a ship type without balancing is reported as well
+    let toml = toml.replace(
+        &format!("[{destroyer}, 42]"),
+        &format!("[{}, {destroyer}, 42]", ShipType::Carrier as i32),
+    );
+    match Config::from_toml(&toml) {
+        Err(ConfigFileError::Invalid(errors)) => {
+            assert_eq!(errors.len(), expected.len() + 1);
+            assert!(errors.contains(&ConfigError::MissingBalancing(ShipType::Carrier)));
+        }
+        result => panic!("expected the config to be invalid, got {result:?}"),
+    }
+}
+
+#[tokio::test]
+async fn unknown_ship_type_fails_validation() {
+    let config = Config {
+        ship_set_team_b: vec![ShipType::Cruiser as i32, -1],
+        ..(*default_config_provider().game_config()).clone()
+    };
+    assert_eq!(
+        config.validate(),
+        Err(vec![ConfigError::UnknownShipType {
+            team: "B",
+            ship_type: -1
+        }])
+    );
+
+    let error = Game::new(Arc::new(config))
+        .check_game_config()
+        .expect_err("the ship set of team B is invalid");
+    assert_eq!(error, "ship set of team B contains unknown ship type -1");
+}
+
+#[tokio::test]
+async fn ship_set_exceeding_cap_fails_validation() {
+    let default_config = default_config_provider().game_config();
+    let config = Config {
+        max_ships_per_player: default_config.ship_set_team_a.len() as u32 - 1,
+        ship_set_team_b: default_config.ship_set_team_a[..1].to_vec(),
+        ..(*default_config).clone()
+    };
+    assert_eq!(
+        config.validate(),
+        Err(vec![ConfigError::ShipSetExceedsCap {
+            team: "A",
+            cap: config.max_ships_per_player,
+        }])
+    );
+
+    // ship sets that fit the cap are fine
+    let config = Config {
+        max_ships_per_player: default_config.ship_set_team_a.len() as u32,
+        ..config
+    };
+    assert_eq!(config.validate(), Ok(()));
+}
+
+#[tokio::test]
+async fn oversized_board_fails_validation() {
+    let config = Config {
+        board_size: MAX_BOARD_SIZE + 1,
+        ..(*default_config_provider().game_config()).clone()
+    };
+    assert_eq!(
+        config.validate(),
+        Err(vec![ConfigError::BoardTooLarge {
+            max_board_length: MAX_BOARD_SIZE
+        }])
+    );
+    let error = Game::new(Arc::new(config))
+        .check_game_config()
+        .expect_err("the board is too large");
+    assert!(error.contains("too large"), "{error}");
+
+    let config = Config {
+        board_size: MAX_BOARD_SIZE,
+        ..(*default_config_provider().game_config()).clone()
+    };
+    assert_eq!(config.validate(), Ok(()));
+}
+
+#[tokio::test]
+async fn ship_exceeding_quadrant_fails_validation() {
+    let default_config = default_config_provider().game_config();
+    let player_count = default_config.team_size_a + default_config.team_size_b;
+    let quadrants_per_row = util::quadrants_per_row(player_count);
+    // quadrants one tile shorter than a carrier
+    let quadrant_size = ShipType::Carrier.length() - 1;
+    let config = Config {
+        board_size: quadrants_per_row * quadrant_size,
+        ship_set_team_b: vec![ShipType::Battleship as i32, ShipType::Destroyer as i32],
+        ..(*default_config).clone()
+    };
+    assert_eq!(
+        config.validate(),
+        Err(vec![ConfigError::ShipExceedsQuadrant {
+            team: "A",
+            ship: ShipType::Carrier,
+        }])
+    );
+    let error = Game::new(Arc::new(config.clone()))
+        .check_game_config()
+        .expect_err("the carrier does not fit a quadrant");
+    assert!(error.contains("Carrier"), "{error}");
+
+    // a ship as long as the quadrant still fits
+    let config = Config {
+        board_size: quadrants_per_row * ShipType::Carrier.length(),
+        ..config
+    };
+    assert_eq!(config.validate(), Ok(()));
+}
+
+#[tokio::test]
+async fn invalid_game_end_drain_time_is_reported() {
+    assert_eq!(
+        parse_game_end_drain_time(Some("250")),
+        Ok(Duration::from_millis(250))
+    );
+    assert_eq!(parse_game_end_drain_time(None), Ok(Duration::from_secs(3)));
+    for millis in ["-1", "3s", ""] {
+        assert_eq!(
+            parse_game_end_drain_time(Some(millis)),
+            Err(ServerConfigError::InvalidGameEndDrainTime(millis))
+        );
+    }
+}
+
+#[tokio::test]
+async fn config_file_problems_are_reported() {
+    let path = std::env::temp_dir().join(format!("bs_plus_config_{}.toml", std::process::id()));
+    let path: &'static str = Box::leak(path.to_string_lossy().into_owned().into_boxed_str());
+    let _ = std::fs::remove_file(path);
+    assert!(matches!(
+        load_game_config(path).as_ref().map_err(Vec::as_slice),
+        Err([ServerConfigError::UnreadableConfigFile { .. }])
+    ));
+
+    let config = default_config_provider().game_config();
+    std::fs::write(path, config.to_toml().unwrap()).unwrap();
+    assert_eq!(load_game_config(path), Ok((*config).clone()));
+
+    // every problem of the file is reported, not just the first one
+    std::fs::write(
+        path,
+        "team_size_a = 1\nteam_size_b = 1\nmin_players = 3\nship_set_team_a = [1]\n",
+    )
+    .unwrap();
+    let errors = load_game_config(path).expect_err("the config file is invalid");
+    for error in [
+        ConfigError::MinPlayersExceedTeamSizes {
+            min_players: 3,
+            player_count: 2,
+        },
+        ConfigError::EmptyShipSet { team: "B" },
+    ] {
+        let error = ServerConfigError::InvalidConfigFile { path, error };
+        assert!(errors.contains(&error), "{error} is not reported");
+    }
+
+    std::fs::remove_file(path).unwrap();
+}
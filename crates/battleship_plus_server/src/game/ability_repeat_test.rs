@@ -0,0 +1,79 @@
+use battleship_plus_common::game::ActionValidationError;
+use battleship_plus_common::types::{Config, Coordinate, ScoutPlaneProperties, Teams};
+
+use crate::config_provider::default_config_provider;
+use crate::game::actions::{Action, ActionExecutionError};
+use crate::game::data::Game;
+use crate::game::game_builder::GameBuilder;
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+
+fn game_with(forbid_repeated_abilities: bool) -> Game {
+    GameBuilder::new()
+        .config(Config {
+            forbid_repeated_abilities,
+            ..(*default_config_provider().game_config()).clone()
+        })
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamB)
+        .ships([
+            // the scout plane is ready again in every turn
+            GeneralShipBuilder::default()
+                .owner(1)
+                .position(0, 0)
+                .ability(1, 0)
+                .carrier()
+                .scout_plane(32, 2)
+                .build(),
+            GeneralShipBuilder::default()
+                .owner(2)
+                .position(50, 50)
+                .destroyer()
+                .build(),
+        ])
+        .turn_order(&[1, 2])
+        .turn(1, 10)
+        .build()
+}
+
+fn scout(game: &mut Game) -> Result<(), ActionExecutionError> {
+    Action::ScoutPlane {
+        ship_id: (1, 0),
+        properties: ScoutPlaneProperties {
+            center: Some(Coordinate { x: 4, y: 4 }),
+        },
+    }
+    .apply_on(game)
+    .map(|_| ())
+}
+
+fn next_owner_turn(game: &mut Game) {
+    game.advance_turn();
+    assert_eq!(game.advance_turn().player_id, 1);
+}
+
+#[tokio::test]
+async fn ability_repeated_in_next_owner_turn_is_rejected() {
+    let mut game = game_with(true);
+    scout(&mut game).expect("the scout plane is ready");
+
+    next_owner_turn(&mut game);
+    assert!(matches!(
+        scout(&mut game),
+        Err(ActionExecutionError::Validation(
+            ActionValidationError::AbilityRepeated
+        ))
+    ));
+
+    // a turn without the ability resets the rule
+    next_owner_turn(&mut game);
+    assert!(scout(&mut game).is_ok());
+}
+
+#[tokio::test]
+async fn ability_repeated_without_rule_is_allowed() {
+    let mut game = game_with(false);
+    scout(&mut game).expect("the scout plane is ready");
+
+    next_owner_turn(&mut game);
+    assert!(scout(&mut game).is_ok());
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use battleship_plus_common::types::{ActionPointCarryover, Config};
+
+use crate::config_provider::default_config_provider;
+use crate::game::data::{Game, Player};
+use crate::game::states::GameState;
+
+/// Lets the only player of a game leave `unspent` action points at the end of a turn and
+/// returns the action points the next turn starts with.
+fn next_turn_action_points(carryover: ActionPointCarryover, cap: u32, unspent: u32) -> u32 {
+    let mut game = Game {
+        config: Arc::new(Config {
+            action_point_gain: 20,
+            action_point_carryover: carryover.into(),
+            action_point_cap: cap,
+            ..(*default_config_provider().game_config()).clone()
+        }),
+        state: GameState::InGame,
+        players: HashMap::from([(
+            1,
+            Player {
+                id: 1,
+                ..Default::default()
+            },
+        )]),
+        ..Default::default()
+    };
+
+    assert_eq!(game.advance_turn().action_points_left, 20);
+    game.turn.as_mut().unwrap().action_points_left = unspent;
+    game.advance_turn().action_points_left
+}
+
+#[tokio::test]
+async fn action_points_carry_over() {
+    assert_eq!(
+        next_turn_action_points(ActionPointCarryover::CarryOver, 0, 5),
+        25
+    );
+    assert_eq!(
+        next_turn_action_points(ActionPointCarryover::CarryOver, 0, 0),
+        20
+    );
+}
+
+#[tokio::test]
+async fn action_points_reset() {
+    assert_eq!(
+        next_turn_action_points(ActionPointCarryover::Reset, 0, 5),
+        20
+    );
+}
+
+#[tokio::test]
+async fn action_points_capped() {
+    assert_eq!(
+        next_turn_action_points(ActionPointCarryover::Cap, 22, 5),
+        22
+    );
+    assert_eq!(
+        next_turn_action_points(ActionPointCarryover::Cap, 22, 1),
+        21
+    );
+}
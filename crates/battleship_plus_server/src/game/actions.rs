@@ -18,6 +18,7 @@ use battleship_plus_common::messages::*;
 use battleship_plus_common::types::*;
 use bevy_quinnet_server::ClientId;
 
+use crate::game::area_effects::{ignite, resolve_mines, ship_tiles};
 use crate::game::data::{Game, Turn};
 use crate::game::states::GameState;
 
@@ -71,7 +72,14 @@ pub enum Action {
         ship_id: ShipID,
         properties: MultiMissileProperties,
     },
+    ShipSwap {
+        ship_id: ShipID,
+        properties: ShipSwapProperties,
+    },
+    /// Explicitly requested end of the turn, the server advances the turn itself.
+    EndTurn,
 
+    /// A ship action request without properties that does not end the turn either.
     None,
 }
 
@@ -83,8 +91,72 @@ pub enum ActionExecutionError {
     BadRequest(String),
 }
 
+impl std::fmt::Display for ActionExecutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionExecutionError::Validation(e) => write!(f, "invalid action: {e:?}"),
+            ActionExecutionError::OutOfState(state) => {
+                write!(f, "action not allowed in state {state:?}")
+            }
+            ActionExecutionError::InconsistentState(msg) => {
+                write!(f, "inconsistent game state: {msg}")
+            }
+            ActionExecutionError::BadRequest(msg) => write!(f, "bad request: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ActionExecutionError {}
+
 impl Action {
     pub(crate) fn apply_on(&self, game: &mut Game) -> Result<ActionResult, ActionExecutionError> {
+        if let Some(ship_id) = self.ability_ship_id() {
+            check_ability_not_repeated(game, &ship_id).map_err(ActionExecutionError::Validation)?;
+        }
+        let mut result = self.execute(game)?;
+        if let Some((player_id, _)) = self.ship_id() {
+            reveal_around_wrecks(game, player_id, &mut result);
+        }
+        if let Some(ship_id) = self.ability_ship_id() {
+            record_ability(game, &ship_id);
+        }
+        Ok(result)
+    }
+
+    /// The ship using its special ability.
+    fn ability_ship_id(&self) -> Option<ShipID> {
+        match self {
+            Action::ScoutPlane { ship_id, .. }
+            | Action::PredatorMissile { ship_id, .. }
+            | Action::EngineBoost { ship_id, .. }
+            | Action::Torpedo { ship_id, .. }
+            | Action::MultiMissile { ship_id, .. }
+            | Action::ShipSwap { ship_id, .. } => Some(*ship_id),
+            _ => None,
+        }
+    }
+
+    /// The ship performing a game action.
+    pub(crate) fn ship_id(&self) -> Option<ShipID> {
+        match self {
+            Action::Move { ship_id, .. }
+            | Action::Rotate { ship_id, .. }
+            | Action::Shoot { ship_id, .. }
+            | Action::ScoutPlane { ship_id, .. }
+            | Action::PredatorMissile { ship_id, .. }
+            | Action::EngineBoost { ship_id, .. }
+            | Action::Torpedo { ship_id, .. }
+            | Action::MultiMissile { ship_id, .. }
+            | Action::ShipSwap { ship_id, .. } => Some(*ship_id),
+            Action::TeamSwitch { .. }
+            | Action::SetReady { .. }
+            | Action::PlaceShips { .. }
+            | Action::EndTurn
+            | Action::None => None,
+        }
+    }
+
+    fn execute(&self, game: &mut Game) -> Result<ActionResult, ActionExecutionError> {
         // TODO Implementation: implement actions below
         // TODO Implementation: add tests for all actions
         // TODO Refactor: refactor :3
@@ -111,7 +183,11 @@ impl Action {
                 check_player_exists(game, *player_id).map_err(ActionExecutionError::Validation)?;
 
                 match game.players.get_mut(player_id) {
-                    Some(p) => p.is_ready = request.ready_state,
+                    Some(p) => {
+                        p.is_ready = request.ready_state;
+                        // the deadline was only meant for getting ready the first time
+                        p.ready_deadline = None;
+                    }
                     None => panic!("player should exist"),
                 }
                 Ok(ActionResult::None)
@@ -119,58 +195,93 @@ impl Action {
             Action::PlaceShips {
                 player_id,
                 ship_placements,
-            } => match game.validate_placement_request(*player_id, ship_placements) {
-                Ok(ship_placement) => ship_placement,
-                Err(e) => {
-                    debug!("Player {player_id} sent an invalid ship placement: {e:?}");
-                    return Err(ActionExecutionError::Validation(
-                        ActionValidationError::InvalidShipPlacement(e),
-                    ));
+            } => {
+                // rejected before any ship is built from the untrusted list
+                let ship_set_size = game.ship_set_of(*player_id).map_or(0, Vec::len);
+                if ship_placements.len() > ship_set_size {
+                    return Err(ActionExecutionError::BadRequest(format!(
+                        "{} ship placements for a ship set of {ship_set_size} ships",
+                        ship_placements.len()
+                    )));
+                }
+                // validated configs never get here, the cap guards against unchecked ones
+                let cap = game.config.max_ships_per_player;
+                if cap > 0 && ship_placements.len() > cap as usize {
+                    return Err(ActionExecutionError::BadRequest(format!(
+                        "{} ship placements exceed the cap of {cap} ships per player",
+                        ship_placements.len()
+                    )));
+                }
+
+                let ship_placement =
+                    match game.validate_placement_request(*player_id, ship_placements) {
+                        Ok(ship_placement) => ship_placement,
+                        Err(e) => {
+                            debug!("Player {player_id} sent an invalid ship placement: {e:?}");
+                            return Err(ActionExecutionError::Validation(
+                                ActionValidationError::InvalidShipPlacement(e),
+                            ));
+                        }
+                    };
+
+                for (&ship_id, ship) in ship_placement.iter() {
+                    game.ships.place_ship(ship_id, ship.clone()).map_err(|e| {
+                        ActionExecutionError::Validation(
+                            ActionValidationError::InvalidShipPlacement(e),
+                        )
+                    })?;
                 }
+                if let Some(player) = game.players.get_mut(player_id) {
+                    player.placed_ships = true;
+                }
+                Ok(ActionResult::None)
             }
-            .iter()
-            .map(|(&ship_id, ship)| game.ships.place_ship(ship_id, ship.clone()))
-            .find(|res| res.is_err())
-            .map_or(Ok(ActionResult::None), |res| match res {
-                Ok(_) => Ok(ActionResult::None),
-                Err(e) => Err(ActionExecutionError::Validation(
-                    ActionValidationError::InvalidShipPlacement(e),
-                )),
-            }),
 
             Action::Move {
                 ship_id,
                 properties,
-            } => general_movement(
-                game,
-                ship_id,
-                |ship_manager, action_points, ship_id, board_bounds| {
-                    ship_manager.move_ship(
-                        action_points,
-                        true,
-                        ship_id,
-                        properties.direction(),
-                        board_bounds,
-                    )
-                },
-            )
-            .map_err(ActionExecutionError::Validation),
+            } => {
+                check_move_limit(game, ship_id).map_err(ActionExecutionError::Validation)?;
+                let tiles_before = ship_tiles(game, ship_id);
+                let result = general_movement(
+                    game,
+                    ship_id,
+                    |ship_manager, action_points, ship_id, board_bounds, wrap_edges| {
+                        ship_manager.move_ship(
+                            action_points,
+                            true,
+                            ship_id,
+                            properties.direction(),
+                            board_bounds,
+                            wrap_edges,
+                        )
+                    },
+                )
+                .map_err(ActionExecutionError::Validation)?;
+                record_move(game, ship_id);
+                Ok(resolve_mines(game, ship_id, &tiles_before, result))
+            }
             Action::Rotate {
                 ship_id,
                 properties,
-            } => general_movement(
-                game,
-                ship_id,
-                |ship_manager, action_points, ship_id, board_bounds| {
-                    ship_manager.rotate_ship(
-                        action_points,
-                        ship_id,
-                        properties.direction(),
-                        board_bounds,
-                    )
-                },
-            )
-            .map_err(ActionExecutionError::Validation),
+            } => {
+                let tiles_before = ship_tiles(game, ship_id);
+                let result = general_movement(
+                    game,
+                    ship_id,
+                    |ship_manager, action_points, ship_id, board_bounds, wrap_edges| {
+                        ship_manager.rotate_ship(
+                            action_points,
+                            ship_id,
+                            properties.direction(),
+                            board_bounds,
+                            wrap_edges,
+                        )
+                    },
+                )
+                .map_err(ActionExecutionError::Validation)?;
+                Ok(resolve_mines(game, ship_id, &tiles_before, result))
+            }
             Action::Shoot {
                 ship_id,
                 properties,
@@ -183,6 +294,13 @@ impl Action {
 
                 let bounds = game.board_bounds();
                 let player = game.players.get(&player_id).unwrap().clone();
+                let shooter = game.ships.get_by_id(ship_id).cloned();
+                let shooter_tiles = match game.ships.get_by_id(ship_id) {
+                    Some(ship) if game.config.shots_reveal_shooter => {
+                        envelope_to_points(ship.envelope()).collect()
+                    }
+                    _ => vec![],
+                };
 
                 let mut action_points = game.turn.as_ref().unwrap().action_points_left;
                 match game
@@ -195,20 +313,19 @@ impl Action {
                             .insert(player_id, player)
                             .expect("unable to update player");
 
-                        match shot {
-                            ShotResult::Miss => Ok(ActionResult::None),
+                        let result = match shot {
+                            ShotResult::Miss => ActionResult::None,
                             ShotResult::Hit(ship_id, damage) => {
-                                Ok(ActionResult::hit(ship_id, target.clone(), damage))
+                                if let Some(shooter) = shooter.as_ref() {
+                                    ignite(game, shooter, ship_id);
+                                }
+                                ActionResult::hit(ship_id, target.clone(), damage)
                             }
                             ShotResult::Destroyed(ship_id, damage, ship_parts) => {
-                                Ok(ActionResult::destroyed(
-                                    ship_id,
-                                    target.clone(),
-                                    damage,
-                                    ship_parts,
-                                ))
+                                ActionResult::destroyed(ship_id, target.clone(), damage, ship_parts)
                             }
-                        }
+                        };
+                        Ok(reveal_shooter(game, player_id, shooter_tiles, result))
                     }
                     Err(e) => Err(ActionExecutionError::Validation(e)),
                 }
@@ -281,8 +398,12 @@ impl Action {
                 let player_id = ship_id.0;
                 check_player_exists(game, player_id).map_err(ActionExecutionError::Validation)?;
                 check_players_turn(game, player_id).map_err(ActionExecutionError::Validation)?;
+                check_move_limit(game, ship_id).map_err(ActionExecutionError::Validation)?;
 
                 let bounds = game.board_bounds();
+                let wrap_bounds = game.wrap_bounds();
+                let friendly_collision = game.config.friendly_collision;
+                let tiles_before = ship_tiles(game, ship_id);
 
                 let enemy_team = match (
                     game.team_a.contains(&(*ship_id).0),
@@ -300,7 +421,7 @@ impl Action {
                     .cloned()
                     .collect();
 
-                let results = game
+                let (direction, mut results) = game
                     .ships
                     .engine_boost(
                         &mut game.turn.as_mut().unwrap().action_points_left,
@@ -323,8 +444,10 @@ impl Action {
                                         ship_manager,
                                         ship_id,
                                         &bounds,
+                                        wrap_bounds.as_ref(),
                                         &enemy_ships,
-                                        |ship_manager, _, ship_id, board_bounds| {
+                                        friendly_collision,
+                                        |ship_manager, _, ship_id, board_bounds, wrap_edges| {
                                             // move ship without costs
                                             ship_manager.move_ship(
                                                 &mut 0,
@@ -332,6 +455,7 @@ impl Action {
                                                 ship_id,
                                                 MoveDirection::Forward,
                                                 board_bounds,
+                                                wrap_edges,
                                             )
                                         },
                                     )
@@ -365,9 +489,15 @@ impl Action {
                                 .collect::<Vec<_>>(),
                         )
                     })
-                    .map_err(ActionExecutionError::Validation);
+                    .map_err(ActionExecutionError::Validation)?;
 
-                results.map(|(direction, results)| ActionResult::EngineBoost(direction, results))
+                record_move(game, ship_id);
+                // mines are resolved once the ship came to a halt
+                if let Some(last) = results.iter_mut().rev().find_map(|r| r.as_mut().ok()) {
+                    let result = std::mem::replace(last, ActionResult::None);
+                    *last = resolve_mines(game, ship_id, &tiles_before, result);
+                }
+                Ok(ActionResult::EngineBoost(direction, results))
             }
             Action::Torpedo {
                 ship_id,
@@ -425,65 +555,81 @@ impl Action {
                     ship_id,
                     positions,
                 ) {
-                    Ok(affected_areas) => {
-                        let inflicted_damage_at = affected_areas
-                            .iter()
-                            .flat_map(|area| {
-                                area.hit_ships
-                                    .iter()
-                                    .chain(area.destroyed_ships.iter())
-                                    .flat_map(|ship| {
-                                        split_damage(
-                                            envelope_to_points(ship.envelope()).collect(),
-                                            area.damage_per_hit,
-                                            &area.area,
-                                        )
-                                    })
-                            })
-                            .collect::<Vec<_>>();
-                        let inflicted_damage_by_ship = affected_areas
-                            .iter()
-                            .flat_map(|area| {
-                                area.hit_ships
-                                    .iter()
-                                    .chain(area.destroyed_ships.iter())
-                                    .map(|ship| (ship.id(), area.damage_per_hit))
-                            })
-                            .collect::<Vec<_>>();
-                        let ships_destroyed = affected_areas
-                            .iter()
-                            .flat_map(|area| area.destroyed_ships.iter())
-                            .cloned()
-                            .collect::<Vec<_>>();
-                        let lost_vision_at = ships_destroyed
-                            .iter()
-                            .flat_map(|ship| envelope_to_points(ship.envelope()))
-                            .collect::<HashSet<_>>();
-
-                        let inflicted_damage_at = collect_and_sum(&inflicted_damage_at);
-
-                        let splash_tiles = affected_areas
-                            .iter()
-                            .flat_map(|a| envelope_to_points(a.area))
-                            .filter(|c| !inflicted_damage_at.contains_key(c))
-                            .collect();
-
-                        Ok(ActionResult::Single {
-                            inflicted_damage_at,
-                            inflicted_damage_by_ship: collect_and_sum(&inflicted_damage_by_ship),
-                            ships_destroyed,
-                            lost_enemy_vision: lost_vision_at.clone(),
-                            lost_vision_at,
-                            splash_tiles,
-                            gain_enemy_vision: Default::default(),
-                            temp_vision_at: Default::default(),
-                            gain_vision_at: Default::default(),
-                        })
-                    }
+                    Ok(affected_areas) => Ok(AreaDamageResult::from(&affected_areas[..]).into()),
                     Err(e) => Err(ActionExecutionError::Validation(e)),
                 }
             }
-            Action::None => Ok(ActionResult::None),
+            Action::ShipSwap {
+                ship_id,
+                properties,
+            } => {
+                let player_id = ship_id.0;
+                check_player_exists(game, player_id).map_err(ActionExecutionError::Validation)?;
+                check_players_turn(game, player_id).map_err(ActionExecutionError::Validation)?;
+                if game.config.ship_swap_range == 0 {
+                    return Err(ActionExecutionError::BadRequest(String::from(
+                        "ship swapping is disabled",
+                    )));
+                }
+
+                let partner_id = (player_id, properties.partner_ship_number);
+                if partner_id == *ship_id {
+                    return Err(ActionExecutionError::BadRequest(String::from(
+                        "a ship cannot swap with itself",
+                    )));
+                }
+
+                let bounds = game.board_bounds();
+                let wrap_bounds = game.wrap_bounds();
+                let swapped_ships = [*ship_id, partner_id];
+                let enemy_team = if game.team_a.contains(&player_id) {
+                    &game.team_b
+                } else {
+                    &game.team_a
+                };
+                let enemy_ships: Vec<_> =
+                    game.ships.get_for_players(enemy_team).into_iter().collect();
+                let tiles_before = swapped_ships.map(|id| ship_tiles(game, &id));
+                let old_vision = game
+                    .ships
+                    .get_ship_parts_seen_by(&swapped_ships, wrap_bounds.as_ref());
+                let old_enemy_vision = game
+                    .ships
+                    .get_ship_parts_seen_by(&enemy_ships, wrap_bounds.as_ref());
+
+                game.ships
+                    .swap_ships(
+                        &mut game.turn.as_mut().unwrap().action_points_left,
+                        ship_id,
+                        &partner_id,
+                        &bounds,
+                        game.config.ship_swap_range,
+                    )
+                    .map_err(ActionExecutionError::Validation)?;
+
+                let new_vision = game
+                    .ships
+                    .get_ship_parts_seen_by(&swapped_ships, wrap_bounds.as_ref());
+                let new_enemy_vision = game
+                    .ships
+                    .get_ship_parts_seen_by(&enemy_ships, wrap_bounds.as_ref());
+                let result = ActionResult::movement_result(
+                    None,
+                    &old_vision,
+                    &new_vision,
+                    &old_enemy_vision,
+                    &new_enemy_vision,
+                );
+                Ok(
+                    zip(swapped_ships, tiles_before).fold(result, |result, (id, tiles)| {
+                        resolve_mines(game, &id, &tiles, result)
+                    }),
+                )
+            }
+            Action::EndTurn => Ok(ActionResult::None),
+            Action::None => Err(ActionExecutionError::BadRequest(String::from(
+                "action properties are missing",
+            ))),
         }
     }
 }
@@ -494,6 +640,7 @@ fn general_movement<
         &mut u32,
         &ShipID,
         &AABB<[i32; 2]>,
+        bool,
     ) -> Result<AABB<[i32; 2]>, ActionValidationError>,
 >(
     game: &mut Game,
@@ -505,6 +652,7 @@ fn general_movement<
     check_players_turn(game, player_id)?;
 
     let board_bounds = game.board_bounds();
+    let wrap_bounds = game.wrap_bounds();
 
     let enemy_team = match (
         game.team_a.contains(&(*ship_id).0),
@@ -527,36 +675,66 @@ fn general_movement<
         &mut game.ships,
         ship_id,
         &board_bounds,
+        wrap_bounds.as_ref(),
         &enemy_ships,
+        game.config.friendly_collision,
         do_movement,
     )
 }
 
+/// Moves the ship and destroys the ships it collides with, the ship itself included.
+/// With `friendly_collision`, moving into a ship of the own team is blocked and reverted instead.
+#[allow(clippy::too_many_arguments)]
 fn general_movement_inner<
     F: FnOnce(
         &mut ShipManager,
         &mut u32,
         &ShipID,
         &AABB<[i32; 2]>,
+        bool,
     ) -> Result<AABB<[i32; 2]>, ActionValidationError>,
 >(
     action_points: &mut u32,
     ship_manager: &mut ShipManager,
     ship_id: &ShipID,
     board_bounds: &AABB<[i32; 2]>,
+    wrap_bounds: Option<&AABB<[i32; 2]>>,
     enemy_ships: &Vec<ShipID>,
+    friendly_collision: bool,
     do_movement: F,
 ) -> Result<ActionResult, ActionValidationError> {
-    let old_enemy_vision = ship_manager.get_ship_parts_seen_by(enemy_ships);
-    let old_vision = ship_manager.get_ship_parts_seen_by([*ship_id].as_slice());
-    let trajectory = match do_movement(ship_manager, action_points, ship_id, board_bounds) {
+    let old_enemy_vision = ship_manager.get_ship_parts_seen_by(enemy_ships, wrap_bounds);
+    let old_vision = ship_manager.get_ship_parts_seen_by([*ship_id].as_slice(), wrap_bounds);
+    let previous_state = (ship_manager.get_by_id(ship_id).cloned(), *action_points);
+    let trajectory = match do_movement(
+        ship_manager,
+        action_points,
+        ship_id,
+        board_bounds,
+        wrap_bounds.is_some(),
+    ) {
         Ok(trajectory) => trajectory,
         Err(e) => return Err(e),
     };
 
+    let hits_friendly_ship = || {
+        ship_manager
+            .ships_in_envelope(&trajectory)
+            .iter()
+            .any(|id| id != ship_id && !enemy_ships.contains(id))
+    };
+    if friendly_collision && hits_friendly_ship() {
+        let (previous_ship, previous_action_points) = previous_state;
+        if let Some(previous_ship) = previous_ship {
+            ship_manager.restore_ship(previous_ship);
+        }
+        *action_points = previous_action_points;
+        return Err(ActionValidationError::Collision);
+    }
+
     let destroyed_ships = ship_manager.destroy_colliding_ships_in_envelope(&trajectory);
-    let new_vision = ship_manager.get_ship_parts_seen_by([*ship_id].as_slice());
-    let new_enemy_vision = ship_manager.get_ship_parts_seen_by(enemy_ships);
+    let new_vision = ship_manager.get_ship_parts_seen_by([*ship_id].as_slice(), wrap_bounds);
+    let new_enemy_vision = ship_manager.get_ship_parts_seen_by(enemy_ships, wrap_bounds);
 
     Ok(ActionResult::movement_result(
         destroyed_ships,
@@ -580,6 +758,7 @@ impl From<(ClientId, &ShipActionRequest)> for Action {
     fn from((client_id, request): (ClientId, &ShipActionRequest)) -> Self {
         let ship_id: ShipID = (client_id, request.ship_number);
         match request.clone().action_properties {
+            None if request.end_turn => Action::EndTurn,
             None => Action::None,
             Some(p) => match p {
                 ActionProperties::MoveProperties(props) => Action::Move {
@@ -614,6 +793,10 @@ impl From<(ClientId, &ShipActionRequest)> for Action {
                     ship_id,
                     properties: props,
                 },
+                ActionProperties::ShipSwapProperties(props) => Action::ShipSwap {
+                    ship_id,
+                    properties: props,
+                },
             },
         }
     }
@@ -629,6 +812,62 @@ fn check_player_exists(game: &Game, id: PlayerID) -> Result<(), ActionValidation
     }
 }
 
+/// Rejects players without ships during the game, even if a turn would still be given to them.
+pub(crate) fn check_player_not_eliminated(
+    game: &Game,
+    id: PlayerID,
+) -> Result<(), ActionValidationError> {
+    if matches!(game.state, GameState::InGame) && game.ships.ships_of(id).is_empty() {
+        debug!("PlayerID {id} has no ships left");
+        Err(ActionValidationError::PlayerEliminated)
+    } else {
+        Ok(())
+    }
+}
+
+/// Ships may only move a limited number of times per turn if the config says so.
+/// An engine boost counts as a single move.
+fn check_move_limit(game: &Game, ship_id: &ShipID) -> Result<(), ActionValidationError> {
+    let limit = game.config.max_moves_per_ship;
+    let moves = game
+        .turn
+        .as_ref()
+        .and_then(|turn| turn.ship_moves.get(ship_id))
+        .copied()
+        .unwrap_or(0);
+    if limit != 0 && moves >= limit {
+        debug!("Ship {ship_id:?} already moved {moves} times this turn");
+        return Err(ActionValidationError::MoveLimitReached);
+    }
+    Ok(())
+}
+
+fn record_move(game: &mut Game, ship_id: &ShipID) {
+    if let Some(turn) = game.turn.as_mut() {
+        *turn.ship_moves.entry(*ship_id).or_default() += 1;
+    }
+}
+
+/// Ships may not use their ability in two consecutive turns of their owner if the config says
+/// so, even if the ability cooled down in between.
+fn check_ability_not_repeated(game: &Game, ship_id: &ShipID) -> Result<(), ActionValidationError> {
+    let used_last_turn = game
+        .players
+        .get(&ship_id.0)
+        .is_some_and(|player| player.abilities_used_last_turn.contains(ship_id));
+    if game.config.forbid_repeated_abilities && used_last_turn {
+        debug!("Ship {ship_id:?} used its ability during the last turn of its owner");
+        return Err(ActionValidationError::AbilityRepeated);
+    }
+    Ok(())
+}
+
+fn record_ability(game: &mut Game, ship_id: &ShipID) {
+    if let Some(turn) = game.turn.as_mut() {
+        turn.abilities_used.insert(*ship_id);
+    }
+}
+
 fn check_players_turn(game: &Game, id: PlayerID) -> Result<(), ActionValidationError> {
     match game.turn {
         Some(Turn { player_id, .. }) if player_id == id => Ok(()),
@@ -771,45 +1010,279 @@ impl ActionResult {
             splash_tiles: Default::default(),
         }
     }
+
+    /// Adds damage dealt in areas to the result of an action, results of engine boosts stay as
+    /// they are.
+    pub(crate) fn merge_area_damage(self, damage: AreaDamageResult) -> ActionResult {
+        if damage.inflicted_damage_by_ship.is_empty() {
+            return self;
+        }
+        match (self, ActionResult::from(damage)) {
+            (ActionResult::None, damage) => damage,
+            (
+                ActionResult::Single {
+                    inflicted_damage_at,
+                    inflicted_damage_by_ship,
+                    mut ships_destroyed,
+                    mut gain_vision_at,
+                    mut lost_vision_at,
+                    temp_vision_at,
+                    mut gain_enemy_vision,
+                    mut lost_enemy_vision,
+                    mut splash_tiles,
+                },
+                ActionResult::Single {
+                    inflicted_damage_at: damage_at,
+                    inflicted_damage_by_ship: damage_by_ship,
+                    ships_destroyed: destroyed,
+                    lost_vision_at: vision_lost,
+                    lost_enemy_vision: enemy_vision_lost,
+                    splash_tiles: splashes,
+                    ..
+                },
+            ) => {
+                ships_destroyed.extend(destroyed);
+                // destroyed ships cannot be discovered anymore
+                gain_vision_at.retain(|tile| !vision_lost.contains(tile));
+                gain_enemy_vision.retain(|tile| !enemy_vision_lost.contains(tile));
+                lost_vision_at.extend(vision_lost);
+                lost_enemy_vision.extend(enemy_vision_lost);
+                splash_tiles.extend(splashes);
+                ActionResult::Single {
+                    inflicted_damage_at: collect_and_sum(
+                        &inflicted_damage_at
+                            .into_iter()
+                            .chain(damage_at)
+                            .collect::<Vec<_>>(),
+                    ),
+                    inflicted_damage_by_ship: collect_and_sum(
+                        &inflicted_damage_by_ship
+                            .into_iter()
+                            .chain(damage_by_ship)
+                            .collect::<Vec<_>>(),
+                    ),
+                    ships_destroyed,
+                    gain_vision_at,
+                    lost_vision_at,
+                    temp_vision_at,
+                    gain_enemy_vision,
+                    lost_enemy_vision,
+                    splash_tiles,
+                }
+            }
+            (result, _) => result,
+        }
+    }
 }
 
 impl From<AreaOfEffect> for ActionResult {
     fn from(value: AreaOfEffect) -> Self {
-        let inflicted_damage_at: HashMap<Coordinate, u32> = value
-            .hit_ships
+        AreaDamageResult::from(&[value][..]).into()
+    }
+}
+
+/// Accumulated outcome of damage dealt across one or more areas.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AreaDamageResult {
+    pub(crate) inflicted_damage_at: HashMap<Coordinate, u32>,
+    pub(crate) inflicted_damage_by_ship: HashMap<ShipID, u32>,
+    pub(crate) ships_destroyed: Vec<Ship>,
+    pub(crate) splash_tiles: HashSet<Coordinate>,
+}
+
+/// Damages all ships in the given areas and accumulates the result.
+/// Ships hit by several areas take damage once per area.
+pub(crate) fn apply_area_damage(
+    ships: &mut ShipManager,
+    areas: &[AABB<[i32; 2]>],
+    damage: u32,
+) -> AreaDamageResult {
+    let affected_areas = areas
+        .iter()
+        .map(|area| ships.damage_area(*area, damage, None))
+        .collect::<Vec<_>>();
+
+    AreaDamageResult::from(&affected_areas[..])
+}
+
+impl From<&[AreaOfEffect]> for AreaDamageResult {
+    fn from(affected_areas: &[AreaOfEffect]) -> Self {
+        let hits = affected_areas.iter().flat_map(|area| {
+            area.hit_ships
+                .iter()
+                .chain(area.destroyed_ships.iter())
+                .map(move |ship| (ship, area))
+        });
+
+        let inflicted_damage_at = collect_and_sum(
+            &hits
+                .clone()
+                .flat_map(|(ship, area)| {
+                    split_damage(
+                        envelope_to_points(ship.envelope()).collect(),
+                        area.damage_per_hit,
+                        &area.area,
+                    )
+                })
+                .collect::<Vec<_>>(),
+        );
+        let inflicted_damage_by_ship = collect_and_sum(
+            &hits
+                .map(|(ship, area)| (ship.id(), area.damage_per_hit))
+                .collect::<Vec<_>>(),
+        );
+        let splash_tiles = affected_areas
             .iter()
-            .chain(value.destroyed_ships.iter())
-            .flat_map(|s| {
-                split_damage(
-                    envelope_to_points(s.envelope()).collect(),
-                    value.damage_per_hit,
-                    &value.area,
-                )
-            })
+            .flat_map(|area| envelope_to_points(area.area))
+            .filter(|c| !inflicted_damage_at.contains_key(c))
             .collect();
 
-        ActionResult::Single {
-            inflicted_damage_by_ship: HashMap::from_iter(
-                value
-                    .hit_ships
-                    .iter()
-                    .chain(value.destroyed_ships.iter())
-                    .map(|ship| (ship.id(), value.damage_per_hit)),
-            ),
-            lost_vision_at: value
-                .destroyed_ships
+        AreaDamageResult {
+            inflicted_damage_at,
+            inflicted_damage_by_ship,
+            ships_destroyed: affected_areas
                 .iter()
-                .flat_map(|ship| envelope_to_points(ship.envelope()))
-                .collect(),
-            ships_destroyed: value.destroyed_ships,
-            splash_tiles: envelope_to_points(value.area)
-                .filter(|c| !inflicted_damage_at.contains_key(c))
+                .flat_map(|area| area.destroyed_ships.iter())
+                .cloned()
                 .collect(),
-            inflicted_damage_at,
+            splash_tiles,
+        }
+    }
+}
+
+impl From<AreaDamageResult> for ActionResult {
+    fn from(value: AreaDamageResult) -> Self {
+        let lost_vision_at: HashSet<_> = value
+            .ships_destroyed
+            .iter()
+            .flat_map(|ship| envelope_to_points(ship.envelope()))
+            .collect();
+
+        ActionResult::Single {
+            inflicted_damage_at: value.inflicted_damage_at,
+            inflicted_damage_by_ship: value.inflicted_damage_by_ship,
+            ships_destroyed: value.ships_destroyed,
+            lost_enemy_vision: lost_vision_at.clone(),
+            lost_vision_at,
+            splash_tiles: value.splash_tiles,
             temp_vision_at: Default::default(),
             gain_vision_at: Default::default(),
             gain_enemy_vision: Default::default(),
+        }
+    }
+}
+
+/// Shows the tiles of a ship that just fired to the enemies of its owner for the rest of the turn,
+/// tiles they already see are left out.
+fn reveal_shooter(
+    game: &mut Game,
+    player_id: PlayerID,
+    shooter_tiles: Vec<Coordinate>,
+    result: ActionResult,
+) -> ActionResult {
+    if shooter_tiles.is_empty() {
+        return result;
+    }
+
+    let enemy_team = if game.team_a.contains(&player_id) {
+        &game.team_b
+    } else {
+        &game.team_a
+    };
+    let enemy_ships: Vec<_> = game.ships.get_for_players(enemy_team).into_iter().collect();
+    let seen_by_enemies = game
+        .ships
+        .get_ship_parts_seen_by(&enemy_ships, game.wrap_bounds().as_ref());
+    let revealed: HashSet<_> = shooter_tiles
+        .into_iter()
+        .filter(|tile| !seen_by_enemies.contains(tile))
+        .collect();
+    if let Some(turn) = game.turn.as_mut() {
+        turn.revealed_to_enemies.extend(revealed.iter().cloned());
+    }
+
+    match result {
+        ActionResult::None if revealed.is_empty() => ActionResult::None,
+        ActionResult::None => ActionResult::Single {
+            gain_enemy_vision: revealed,
+            inflicted_damage_at: Default::default(),
+            inflicted_damage_by_ship: Default::default(),
+            ships_destroyed: Default::default(),
+            gain_vision_at: Default::default(),
+            lost_vision_at: Default::default(),
+            temp_vision_at: Default::default(),
             lost_enemy_vision: Default::default(),
+            splash_tiles: Default::default(),
+        },
+        ActionResult::Single {
+            mut gain_enemy_vision,
+            inflicted_damage_at,
+            inflicted_damage_by_ship,
+            ships_destroyed,
+            gain_vision_at,
+            lost_vision_at,
+            temp_vision_at,
+            lost_enemy_vision,
+            splash_tiles,
+        } => {
+            gain_enemy_vision.extend(revealed);
+            ActionResult::Single {
+                gain_enemy_vision,
+                inflicted_damage_at,
+                inflicted_damage_by_ship,
+                ships_destroyed,
+                gain_vision_at,
+                lost_vision_at,
+                temp_vision_at,
+                lost_enemy_vision,
+                splash_tiles,
+            }
+        }
+        result => result,
+    }
+}
+
+/// Lets the team of the acting player see the enemy ships around the ships destroyed by an action
+/// for the rest of the turn, if `Config.reveal_on_destruction_radius` is set.
+fn reveal_around_wrecks(game: &Game, player_id: PlayerID, result: &mut ActionResult) {
+    let radius = game.config.reveal_on_destruction_radius as i32;
+    if radius == 0 {
+        return;
+    }
+
+    match result {
+        ActionResult::None => {}
+        ActionResult::EngineBoost(_, results) => results
+            .iter_mut()
+            .filter_map(|result| result.as_mut().ok())
+            .for_each(|result| reveal_around_wrecks(game, player_id, result)),
+        ActionResult::Single {
+            ships_destroyed,
+            temp_vision_at,
+            ..
+        } => {
+            let (allies, enemies) = if game.team_a.contains(&player_id) {
+                (&game.team_a, &game.team_b)
+            } else {
+                (&game.team_b, &game.team_a)
+            };
+            // tiles seen anyway must not vanish at the end of the turn
+            let allies: Vec<_> = allies.iter().cloned().collect();
+            let seen = game.ship_parts_seen_by_players(&allies);
+            for wreck in ships_destroyed.iter() {
+                let envelope = wreck.envelope();
+                let (lower, upper) = (envelope.lower(), envelope.upper());
+                let area = AABB::from_corners(
+                    [lower[0] - radius, lower[1] - radius],
+                    [upper[0] + radius, upper[1] + radius],
+                );
+                temp_vision_at.extend(
+                    game.ships
+                        .ship_parts_in_area(&area, enemies)
+                        .into_iter()
+                        .filter(|tile| !seen.contains(tile)),
+                );
+            }
         }
     }
 }
@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+
+use battleship_plus_common::messages::ship_action_request::ActionProperties;
+use battleship_plus_common::messages::ShipActionRequest;
+use battleship_plus_common::types::{ShootProperties, Teams};
+
+use crate::game::actions::{Action, ActionExecutionError};
+use crate::game::game_builder::GameBuilder;
+
+#[tokio::test]
+async fn actions_missing_properties_do_not_end_turn() {
+    let g = Arc::new(RwLock::new(
+        GameBuilder::new()
+            .player(1, Teams::TeamA)
+            .player(2, Teams::TeamB)
+            .turn(1, 5)
+            .build(),
+    ));
+    let mut g = g.write().await;
+
+    let malformed = ShipActionRequest::default();
+    let action = Action::from((1, &malformed));
+    assert!(matches!(action, Action::None));
+    assert!(matches!(
+        g.get_state().execute_action(action, &mut g),
+        Err(ActionExecutionError::OutOfState(_))
+    ));
+    assert!(matches!(
+        Action::None.apply_on(&mut g),
+        Err(ActionExecutionError::BadRequest(_))
+    ));
+    let turn = g.turn.as_ref().unwrap();
+    assert_eq!((turn.player_id, turn.action_points_left), (1, 5));
+    assert_eq!(g.turns_played, 0);
+
+    // only requests without properties can end the turn
+    let end_turn = ShipActionRequest {
+        end_turn: true,
+        ..Default::default()
+    };
+    assert!(matches!(Action::from((1, &end_turn)), Action::EndTurn));
+    let shot = ShipActionRequest {
+        action_properties: Some(ActionProperties::ShootProperties(ShootProperties::default())),
+        ..end_turn
+    };
+    assert!(matches!(Action::from((1, &shot)), Action::Shoot { .. }));
+}
@@ -6,8 +6,11 @@ use tokio::sync::RwLock;
 use battleship_plus_common::game::ship::{Cooldown, GetShipID, Orientation};
 use battleship_plus_common::game::ship_manager::ShipManager;
 use battleship_plus_common::game::ActionValidationError;
-use battleship_plus_common::types::{Coordinate, EngineBoostProperties};
+use battleship_plus_common::types::{
+    Config, Coordinate, EngineBoostProperties, MoveDirection, MoveProperties,
+};
 
+use crate::config_provider::default_config_provider;
 use crate::game::actions::{Action, ActionExecutionError, ActionResult};
 //noinspection DuplicatedCode
 use crate::game::data::{Game, Player, Turn};
@@ -370,3 +373,69 @@ async fn actions_engine_boost_respect_world_border() {
         assert_eq!(g.turn.as_ref().unwrap().action_points_left, 1);
     }
 }
+
+#[tokio::test]
+async fn actions_engine_boost_move_limit() {
+    let player = Player {
+        id: 0,
+        ..Default::default()
+    };
+    let ship = GeneralShipBuilder::default()
+        .owner(player.id)
+        .orientation(Orientation::East)
+        .ability(1, 0)
+        .movement(1, 0, 1, 0)
+        .position(0, 0)
+        .cruiser()
+        .engine_boost(2)
+        .build();
+
+    let g = Arc::new(RwLock::new(Game {
+        config: Arc::new(Config {
+            max_moves_per_ship: 1,
+            ..(*default_config_provider().game_config()).clone()
+        }),
+        players: HashMap::from([(player.id, player.clone())]),
+        team_a: HashSet::from([player.id]),
+        ships: ShipManager::new_with_ships(vec![ship.clone()]),
+        turn: Some(Turn::new(player.id, 10)),
+        ..Default::default()
+    }));
+    let mut g = g.write().await;
+
+    let result = Action::EngineBoost {
+        ship_id: ship.id(),
+        properties: EngineBoostProperties {},
+    }
+    .apply_on(&mut g);
+    assert!(matches!(result, Ok(ActionResult::EngineBoost { .. })));
+    assert_eq!(g.ships.get_by_id(&ship.id()).unwrap().position(), (2, 0));
+
+    // the boost used up the only move of the ship
+    let result = Action::Move {
+        ship_id: ship.id(),
+        properties: MoveProperties {
+            direction: i32::from(MoveDirection::Forward),
+        },
+    }
+    .apply_on(&mut g);
+    assert!(matches!(
+        result,
+        Err(ActionExecutionError::Validation(
+            ActionValidationError::MoveLimitReached
+        ))
+    ));
+    assert_eq!(g.ships.get_by_id(&ship.id()).unwrap().position(), (2, 0));
+    assert_eq!(g.turn.as_ref().unwrap().action_points_left, 9);
+
+    // the limit is per turn
+    g.turn = Some(Turn::new(player.id, 10));
+    let result = Action::Move {
+        ship_id: ship.id(),
+        properties: MoveProperties {
+            direction: i32::from(MoveDirection::Forward),
+        },
+    }
+    .apply_on(&mut g);
+    assert!(matches!(result, Ok(ActionResult::Single { .. })));
+}
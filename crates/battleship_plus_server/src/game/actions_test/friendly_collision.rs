@@ -0,0 +1,103 @@
+use battleship_plus_common::game::ship::{GetShipID, Orientation, ShipID};
+use battleship_plus_common::game::ActionValidationError;
+use battleship_plus_common::types::{Config, MoveDirection, MoveProperties, Teams};
+
+use crate::config_provider::default_config_provider;
+use crate::game::actions::{Action, ActionExecutionError, ActionResult};
+use crate::game::data::Game;
+use crate::game::game_builder::GameBuilder;
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+
+const MOVER: ShipID = (1, 0);
+const OWN_SHIP: ShipID = (1, 1);
+const ALLIED_SHIP: ShipID = (3, 0);
+const ENEMY_SHIP: ShipID = (2, 0);
+
+/// Players 1 and 3 against player 2. The ship of player 1 at (0, 5) faces east, the obstacle
+/// covers (2, 5) and (2, 6), so moving forward runs into it.
+fn game_with(friendly_collision: bool, obstacle: ShipID) -> Game {
+    GameBuilder::new()
+        .config(Config {
+            friendly_collision,
+            ..(*default_config_provider().game_config()).clone()
+        })
+        .player(1, Teams::TeamA)
+        .player(3, Teams::TeamA)
+        .player(2, Teams::TeamB)
+        .ships([
+            GeneralShipBuilder::default()
+                .id(MOVER)
+                .position(0, 5)
+                .orientation(Orientation::East)
+                .movement(1, 0, 1, 0)
+                .destroyer()
+                .build(),
+            GeneralShipBuilder::default()
+                .id(obstacle)
+                .position(2, 5)
+                .orientation(Orientation::North)
+                .destroyer()
+                .build(),
+        ])
+        .turn(1, 5)
+        .build()
+}
+
+fn move_forward(game: &mut Game) -> Result<ActionResult, ActionExecutionError> {
+    Action::Move {
+        ship_id: MOVER,
+        properties: MoveProperties {
+            direction: MoveDirection::Forward.into(),
+        },
+    }
+    .apply_on(game)
+}
+
+#[tokio::test]
+async fn actions_friendly_collision_is_blocked() {
+    for obstacle in [OWN_SHIP, ALLIED_SHIP] {
+        let mut game = game_with(true, obstacle);
+        let ships_before: Vec<_> = [MOVER, obstacle]
+            .iter()
+            .map(|id| game.ships.get_by_id(id).cloned())
+            .collect();
+
+        assert!(matches!(
+            move_forward(&mut game),
+            Err(ActionExecutionError::Validation(
+                ActionValidationError::Collision
+            ))
+        ));
+
+        // neither ship moved or took damage, the move was not charged
+        let ships_after: Vec<_> = [MOVER, obstacle]
+            .iter()
+            .map(|id| game.ships.get_by_id(id).cloned())
+            .collect();
+        assert_eq!(ships_after, ships_before);
+        assert_eq!(game.turn.as_ref().unwrap().action_points_left, 5);
+    }
+}
+
+#[tokio::test]
+async fn actions_enemy_collision_stays_lethal() {
+    // friendly ships only get in the way if the config says so
+    for (friendly_collision, obstacle) in [(true, ENEMY_SHIP), (false, ALLIED_SHIP)] {
+        let mut game = game_with(friendly_collision, obstacle);
+
+        let result = move_forward(&mut game);
+        let ships_destroyed = match result {
+            Ok(ActionResult::Single {
+                ships_destroyed, ..
+            }) => ships_destroyed,
+            _ => panic!("the ships collide"),
+        };
+        let mut destroyed_ids: Vec<_> = ships_destroyed.iter().map(|ship| ship.id()).collect();
+        destroyed_ids.sort_unstable();
+        let mut expected = vec![MOVER, obstacle];
+        expected.sort_unstable();
+        assert_eq!(destroyed_ids, expected);
+        assert!(game.ships.get_by_id(&MOVER).is_none());
+        assert!(game.ships.get_by_id(&obstacle).is_none());
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::{HashMap, HashSet};
+
+use battleship_plus_common::game::ship::{GetShipID, Orientation};
+use battleship_plus_common::game::ship_manager::ShipManager;
+use battleship_plus_common::game::ActionValidationError;
+use battleship_plus_common::types::*;
+
+use crate::game::actions::{Action, ActionExecutionError, ActionResult};
+use crate::game::data::{Game, Player, Turn};
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+use crate::game::states::GameState;
+
+fn game_with_islands(ships: ShipManager, islands: &[Coordinate]) -> Game {
+    let player = Player::default();
+    let mut ships = ships;
+    ships.set_islands(islands);
+
+    Game {
+        turn: Some(Turn::new(player.id, 10)),
+        state: GameState::InGame,
+        players: HashMap::from([(player.id, player.clone())]),
+        team_a: HashSet::from([player.id]),
+        team_b: HashSet::from([42]),
+        ships,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn actions_islands_block_movement() {
+    let ship = GeneralShipBuilder::default()
+        .movement(1, 0, 1, 0)
+        .position(5, 5)
+        .orientation(Orientation::North)
+        .destroyer()
+        .build();
+    let mut g = game_with_islands(
+        ShipManager::new_with_ships(vec![ship.clone()]),
+        &[Coordinate { x: 5, y: 7 }],
+    );
+
+    let result = Action::Move {
+        ship_id: ship.id(),
+        properties: MoveProperties {
+            direction: MoveDirection::Forward.into(),
+        },
+    }
+    .apply_on(&mut g);
+    assert!(matches!(
+        result,
+        Err(ActionExecutionError::Validation(
+            ActionValidationError::Unreachable
+        ))
+    ));
+
+    // the ship stays where it was and the move costs nothing
+    assert_eq!(g.ships.get_by_id(&ship.id()).unwrap().position(), (5, 5));
+    assert_eq!(
+        g.ships.get_by_position(Coordinate { x: 5, y: 5 }),
+        Some(&ship)
+    );
+    assert_eq!(g.turn.as_ref().unwrap().action_points_left, 10);
+
+    // moving away from the island is still possible
+    let result = Action::Move {
+        ship_id: ship.id(),
+        properties: MoveProperties {
+            direction: MoveDirection::Backward.into(),
+        },
+    }
+    .apply_on(&mut g);
+    assert!(matches!(result, Ok(ActionResult::Single { .. })));
+    assert_eq!(g.ships.get_by_id(&ship.id()).unwrap().position(), (5, 4));
+}
+
+#[tokio::test]
+async fn actions_islands_stop_torpedoes() {
+    let ship = GeneralShipBuilder::default()
+        .ability(1, 0)
+        .position(20, 20)
+        .orientation(Orientation::North)
+        .submarine()
+        .torpedo(10, 5)
+        .build();
+    let in_front = GeneralShipBuilder::default()
+        .health(10)
+        .owner(42)
+        .number(0)
+        .position(20, 23)
+        .orientation(Orientation::West)
+        .destroyer()
+        .build();
+    let behind = GeneralShipBuilder::default()
+        .health(10)
+        .owner(42)
+        .number(1)
+        .position(20, 26)
+        .orientation(Orientation::West)
+        .destroyer()
+        .build();
+    let mut g = game_with_islands(
+        ShipManager::new_with_ships(vec![ship.clone(), in_front.clone(), behind.clone()]),
+        &[Coordinate { x: 20, y: 25 }],
+    );
+
+    let result = Action::Torpedo {
+        ship_id: ship.id(),
+        properties: TorpedoProperties {
+            direction: Direction::North.into(),
+        },
+    }
+    .apply_on(&mut g);
+    assert!(matches!(result, Ok(ActionResult::Single { .. })));
+    if let Ok(ActionResult::Single {
+        inflicted_damage_by_ship,
+        ..
+    }) = result
+    {
+        assert_eq!(inflicted_damage_by_ship.len(), 1);
+        assert!(inflicted_damage_by_ship.contains_key(&in_front.id()));
+    }
+
+    assert_eq!(g.ships.get_by_id(&in_front.id()).unwrap().health(), 5);
+    assert_eq!(g.ships.get_by_id(&behind.id()).unwrap().health(), 10);
+}
+
+#[tokio::test]
+async fn actions_islands_block_vision() {
+    let observer = GeneralShipBuilder::default()
+        .vision(10)
+        .position(10, 10)
+        .orientation(Orientation::East)
+        .destroyer()
+        .build();
+    let hidden = GeneralShipBuilder::default()
+        .owner(42)
+        .number(0)
+        .position(16, 10)
+        .orientation(Orientation::North)
+        .destroyer()
+        .build();
+    let ships = ShipManager::new_with_ships(vec![observer.clone(), hidden.clone()]);
+
+    let g = game_with_islands(ships.clone(), &[]);
+    let seen = g.ships.get_ship_parts_seen_by(&[observer.id()], None);
+    assert!(seen.contains(&Coordinate { x: 16, y: 10 }));
+    assert!(seen.contains(&Coordinate { x: 16, y: 11 }));
+
+    // the island hides the part straight behind it, the other one is seen diagonally past it
+    let g = game_with_islands(ships, &[Coordinate { x: 13, y: 10 }]);
+    let seen = g.ships.get_ship_parts_seen_by(&[observer.id()], None);
+    assert!(!seen.contains(&Coordinate { x: 16, y: 10 }));
+    assert!(seen.contains(&Coordinate { x: 16, y: 11 }));
+}
@@ -1,4 +1,7 @@
+mod end_turn;
 mod engine_boost;
+mod friendly_collision;
+mod islands;
 mod movement;
 mod multi_missile;
 mod place_ships;
@@ -6,6 +9,7 @@ mod player_set_ready_state;
 mod predator_missile;
 mod rotate;
 mod scout_plane;
+mod ship_swap;
 mod shoot;
 mod team_switch;
 mod torpedo;
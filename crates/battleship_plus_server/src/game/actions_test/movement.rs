@@ -632,3 +632,172 @@ async fn actions_movement_not_players_turn() {
         assert_eq!(g.ships.get_by_id(&ship.id()).unwrap().position(), (0, 0))
     }
 }
+
+#[tokio::test]
+async fn actions_movement_wrap_edges() {
+    let config = Arc::new(Config {
+        wrap_edges: true,
+        ..(*default_config_provider().game_config()).clone()
+    });
+    let top = (config.board_size - 1) as i32;
+
+    let player = Player::default();
+    let ship = Ship::Destroyer {
+        balancing: Arc::from(DestroyerBalancing {
+            common_balancing: Some(CommonBalancing {
+                movement_costs: Some(Costs {
+                    cooldown: 0,
+                    action_points: 0,
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        data: ShipData {
+            id: (player.id, 0),
+            pos_x: 5,
+            pos_y: top - 1,
+            orientation: Orientation::North,
+            ..Default::default()
+        },
+        cooldowns: Default::default(),
+    };
+
+    let g = Arc::new(RwLock::new(Game {
+        players: HashMap::from([(player.id, player.clone())]),
+        team_a: HashSet::from([player.id]),
+        ships: ShipManager::new_with_ships(vec![ship.clone()]),
+        turn: Some(Turn::new(player.id, 0)),
+        config,
+        ..Default::default()
+    }));
+    let mut g = g.write().await;
+
+    // move ship forward over the top edge
+    let result = Action::Move {
+        ship_id: ship.id(),
+        properties: MoveProperties {
+            direction: i32::from(MoveDirection::Forward),
+        },
+    }
+    .apply_on(&mut g);
+    assert!(matches!(result, Ok(ActionResult::Single { .. })));
+
+    // ship reappears at the bottom
+    {
+        assert_eq!(g.ships.get_by_id(&ship.id()).unwrap().position(), (5, 0))
+    }
+
+    // move ship backward over the bottom edge
+    let result = Action::Move {
+        ship_id: ship.id(),
+        properties: MoveProperties {
+            direction: i32::from(MoveDirection::Backward),
+        },
+    }
+    .apply_on(&mut g);
+    assert!(matches!(result, Ok(ActionResult::Single { .. })));
+
+    // ship is back at the top
+    {
+        assert_eq!(
+            g.ships.get_by_id(&ship.id()).unwrap().position(),
+            (5, top - 1)
+        )
+    }
+}
+
+#[tokio::test]
+async fn actions_movement_wrap_edges_vision() {
+    let config = Arc::new(Config {
+        wrap_edges: true,
+        ..(*default_config_provider().game_config()).clone()
+    });
+    let top = (config.board_size - 1) as i32;
+
+    let player = Player::default();
+    let enemy = Player {
+        id: 1,
+        ..Default::default()
+    };
+    let ship = Ship::Destroyer {
+        balancing: Arc::from(DestroyerBalancing {
+            common_balancing: Some(CommonBalancing {
+                movement_costs: Some(Costs {
+                    cooldown: 0,
+                    action_points: 0,
+                }),
+                vision_range: 2,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        data: ShipData {
+            id: (player.id, 0),
+            pos_x: 10,
+            pos_y: top - 2,
+            orientation: Orientation::North,
+            ..Default::default()
+        },
+        cooldowns: Default::default(),
+    };
+    let enemy_ship = Ship::Destroyer {
+        data: ShipData {
+            id: (enemy.id, 0),
+            pos_x: 10,
+            pos_y: 1,
+            orientation: Orientation::North,
+            ..Default::default()
+        },
+        cooldowns: Default::default(),
+        balancing: Arc::from(DestroyerBalancing {
+            common_balancing: Some(CommonBalancing {
+                vision_range: 0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+    };
+
+    let g = Arc::new(RwLock::new(Game {
+        players: HashMap::from([(player.id, player.clone()), (enemy.id, enemy.clone())]),
+        team_a: HashSet::from([player.id]),
+        team_b: HashSet::from([enemy.id]),
+        ships: ShipManager::new_with_ships(vec![ship.clone(), enemy_ship]),
+        turn: Some(Turn::new(player.id, 0)),
+        config,
+        ..Default::default()
+    }));
+    let mut g = g.write().await;
+
+    // vision reaches over the top edge, but not far enough yet
+    {
+        let wrap_bounds = g.wrap_bounds();
+        assert!(g
+            .ships
+            .get_ship_parts_seen_by(&[ship.id()], wrap_bounds.as_ref())
+            .is_empty());
+    }
+
+    // move ship forward, vision now wraps onto the enemy ship
+    let result = Action::Move {
+        ship_id: ship.id(),
+        properties: MoveProperties {
+            direction: i32::from(MoveDirection::Forward),
+        },
+    }
+    .apply_on(&mut g);
+    assert!(matches!(result, Ok(ActionResult::Single { .. })));
+    if let Ok(ActionResult::Single {
+        ships_destroyed,
+        gain_vision_at,
+        lost_vision_at,
+        ..
+    }) = result
+    {
+        assert!(ships_destroyed.is_empty());
+        assert!(lost_vision_at.is_empty());
+        assert!(gain_vision_at.contains(&Coordinate { x: 10, y: 1 }));
+        assert!(!gain_vision_at.contains(&Coordinate { x: 10, y: 2 }));
+    }
+}
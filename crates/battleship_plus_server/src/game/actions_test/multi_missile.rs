@@ -11,7 +11,9 @@ use battleship_plus_common::types::*;
 use crate::game::actions::Action::MultiMissile;
 use crate::game::actions::ActionResult;
 use crate::game::data::{Game, Player, Turn};
+use crate::game::game_builder::GameBuilder;
 use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+use crate::game::snapshot::board_snapshot_json;
 use crate::game::states::GameState;
 
 #[tokio::test]
@@ -97,6 +99,7 @@ async fn actions_multi_missile() {
             action_points_left: 42,
             player_id: player.id,
             temp_vision: Default::default(),
+            ..Default::default()
         }),
         state: GameState::InGame,
         players: HashMap::from([(player.id, player.clone())]),
@@ -226,6 +229,7 @@ async fn actions_multi_missile_same_spot() {
             action_points_left: 42,
             player_id: player.id,
             temp_vision: Default::default(),
+            ..Default::default()
         }),
         state: GameState::InGame,
         players: HashMap::from([(player.id, player.clone())]),
@@ -289,3 +293,58 @@ async fn actions_multi_missile_same_spot() {
 
     assert_eq!(g.ships.get_by_id(&destroyed.id()), None);
 }
+
+/// Fires a multi missile and plays the following turns without a turn order,
+/// so each turn goes to a player chosen by the random number generator of the game.
+fn play_seeded_multi_missile_game(seed: u64) -> Vec<String> {
+    let ship = GeneralShipBuilder::default()
+        .ability(10, 10)
+        .owner(1)
+        .number(0)
+        .position(0, 0)
+        .destroyer()
+        .multi_missile(1, 9)
+        .build();
+    let targets = (0..4).map(|number| {
+        GeneralShipBuilder::default()
+            .health(5 + number)
+            .owner(2 + number % 2)
+            .number(number)
+            .position(10 + 2 * number as i32, 0)
+            .destroyer()
+            .build()
+    });
+    let mut game = GameBuilder::new()
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamB)
+        .player(3, Teams::TeamB)
+        .ships([ship.clone()].into_iter().chain(targets))
+        .turn(1, 42)
+        .seed(seed)
+        .build();
+
+    MultiMissile {
+        ship_id: ship.id(),
+        properties: MultiMissileProperties {
+            position_a: Some(Coordinate { x: 10, y: 0 }),
+            position_b: Some(Coordinate { x: 13, y: 0 }),
+            position_c: Some(Coordinate { x: 16, y: 1 }),
+        },
+    }
+    .apply_on(&mut game)
+    .expect("the multi missile is valid");
+
+    (0..10)
+        .map(|_| {
+            game.advance_turn();
+            board_snapshot_json(0, &game)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn actions_multi_missile_replays_with_same_seed() {
+    let snapshots = play_seeded_multi_missile_game(7);
+    assert_eq!(snapshots, play_seeded_multi_missile_game(7));
+    assert!(snapshots[0].ends_with(",\"seed\":7}"));
+}
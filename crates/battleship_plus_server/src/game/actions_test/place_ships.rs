@@ -5,10 +5,13 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use battleship_plus_common::game::ship::{GetShipID, Orientation, Ship};
+use battleship_plus_common::game::ship_manager::ShipPlacementError;
+use battleship_plus_common::game::{ActionValidationError, PlayerID};
 use battleship_plus_common::types::*;
 
-use crate::game::actions::Action;
-use crate::game::data::{Game, Player};
+use crate::config_provider::default_config_provider;
+use crate::game::actions::{Action, ActionExecutionError};
+use crate::game::data::{Game, GameResult, Player};
 use crate::game::states::GameState;
 
 #[tokio::test]
@@ -318,6 +321,94 @@ async fn actions_place_ships_wrong_ship_set_too_many_ships() {
     })
 }
 
+#[tokio::test]
+async fn actions_place_ships_oversized_list() {
+    let player = Player::default();
+
+    let g = Arc::new(RwLock::new(Game {
+        players: HashMap::from([(player.id, player.clone())]),
+        team_a: HashSet::from([player.id]),
+        ..Default::default()
+    }));
+    let mut g = g.write().await;
+    g.state = GameState::Preparation;
+    g.players.get_mut(&player.id).unwrap().quadrant = g.quadrants().first().cloned();
+
+    let ship_assignments = vec![
+        ShipAssignment {
+            coordinate: Some(Coordinate { x: 0, y: 0 }),
+            direction: Direction::East.into(),
+        };
+        100 * g.config.ship_set_team_a.len()
+    ];
+    assert!(matches!(
+        Action::PlaceShips {
+            player_id: player.id,
+            ship_placements: ship_assignments,
+        }
+        .apply_on(&mut g),
+        Err(ActionExecutionError::BadRequest(_))
+    ));
+    assert_eq!(g.ships.iter_ships().count(), 0);
+    assert!(!g.players[&player.id].placed_ships);
+}
+
+#[tokio::test]
+async fn actions_place_ships_malformed_assignments() {
+    let player = Player::default();
+
+    let g = Arc::new(RwLock::new(Game {
+        players: HashMap::from([(player.id, player.clone())]),
+        team_a: HashSet::from([player.id]),
+        ..Default::default()
+    }));
+    let mut g = g.write().await;
+    g.state = GameState::Preparation;
+    g.players.get_mut(&player.id).unwrap().quadrant = g.quadrants().first().cloned();
+    let (corner_x, corner_y, _) = g.players[&player.id].quadrant.unwrap();
+
+    let valid = ShipAssignment {
+        coordinate: Some(Coordinate {
+            x: corner_x,
+            y: corner_y,
+        }),
+        direction: Direction::East.into(),
+    };
+    for (malformed, expected_error) in [
+        (
+            ShipAssignment {
+                coordinate: None,
+                ..valid.clone()
+            },
+            ShipPlacementError::MissingShipPosition,
+        ),
+        (
+            ShipAssignment {
+                direction: 42,
+                ..valid.clone()
+            },
+            ShipPlacementError::InvalidShipDirection,
+        ),
+    ] {
+        let result = Action::PlaceShips {
+            player_id: player.id,
+            ship_placements: vec![malformed],
+        }
+        .apply_on(&mut g);
+        assert!(
+            matches!(
+                &result,
+                Err(ActionExecutionError::Validation(
+                    ActionValidationError::InvalidShipPlacement(e)
+                )) if *e == expected_error
+            ),
+            "{result:?}"
+        );
+        assert_eq!(g.ships.iter_ships().count(), 0);
+        assert!(!g.players[&player.id].placed_ships);
+    }
+}
+
 #[tokio::test]
 async fn actions_place_ships_colliding() {
     let player = Player::default();
@@ -390,3 +481,221 @@ async fn actions_place_ships_colliding() {
         assert!(ship_actual.is_none());
     })
 }
+
+#[tokio::test]
+async fn actions_place_ships_asymmetric_ship_sets() {
+    let player_a = Player {
+        id: 1,
+        ..Default::default()
+    };
+    let player_b = Player {
+        id: 2,
+        ..Default::default()
+    };
+
+    let config = Config {
+        ship_set_team_a: vec![ShipType::Carrier as i32, ShipType::Destroyer as i32],
+        ship_set_team_b: vec![
+            ShipType::Submarine as i32,
+            ShipType::Submarine as i32,
+            ShipType::Cruiser as i32,
+        ],
+        ..(*default_config_provider().game_config()).clone()
+    };
+
+    let g = Arc::new(RwLock::new(Game {
+        players: HashMap::from([
+            (player_a.id, player_a.clone()),
+            (player_b.id, player_b.clone()),
+        ]),
+        team_a: HashSet::from([player_a.id]),
+        team_b: HashSet::from([player_b.id]),
+        ..Game::new(Arc::new(config))
+    }));
+    let mut g = g.write().await;
+    assert!(g.check_game_config().is_ok());
+    g.state = GameState::Preparation;
+    let quadrants = g.quadrants();
+    g.players.get_mut(&player_a.id).unwrap().quadrant = quadrants.first().cloned();
+    g.players.get_mut(&player_b.id).unwrap().quadrant = quadrants.last().cloned();
+
+    let assignments_for = |g: &Game, player_id: PlayerID, ship_count: usize| -> Vec<_> {
+        let (corner_x, corner_y, _) = g.players.get(&player_id).unwrap().quadrant.unwrap();
+        (0..ship_count)
+            .map(|i| ShipAssignment {
+                coordinate: Some(Coordinate {
+                    x: corner_x,
+                    y: corner_y + i as u32,
+                }),
+                direction: Direction::East as i32,
+            })
+            .collect()
+    };
+
+    // each team has to place its own ship set
+    assert!(Action::PlaceShips {
+        player_id: player_a.id,
+        ship_placements: assignments_for(&g, player_a.id, 3),
+    }
+    .apply_on(&mut g)
+    .is_err());
+    assert!(Action::PlaceShips {
+        player_id: player_b.id,
+        ship_placements: assignments_for(&g, player_b.id, 2),
+    }
+    .apply_on(&mut g)
+    .is_err());
+
+    assert!(Action::PlaceShips {
+        player_id: player_a.id,
+        ship_placements: assignments_for(&g, player_a.id, 2),
+    }
+    .apply_on(&mut g)
+    .is_ok());
+    assert!(!g.can_change_into_game_phase());
+    assert!(Action::PlaceShips {
+        player_id: player_b.id,
+        ship_placements: assignments_for(&g, player_b.id, 3),
+    }
+    .apply_on(&mut g)
+    .is_ok());
+    assert!(g.can_change_into_game_phase());
+
+    // every player starts with the composition of their team
+    for (player_id, ship_set) in [
+        (player_a.id, g.config.ship_set_team_a.clone()),
+        (player_b.id, g.config.ship_set_team_b.clone()),
+    ] {
+        let ships = g.ships.get_for_players(&HashSet::from([player_id]));
+        assert_eq!(ships.len(), ship_set.len());
+        for (ship_number, ship_type) in ship_set.iter().enumerate() {
+            let ship = g.ships.get_by_id(&(player_id, ship_number as u32)).unwrap();
+            assert_eq!(ship.ship_type() as i32, *ship_type);
+        }
+    }
+
+    // losing all ships of the smaller set ends the game
+    let ships_a: Vec<_> = g
+        .ships
+        .get_for_players(&HashSet::from([player_a.id]))
+        .into_iter()
+        .collect();
+    g.ships.destroy_ships(ships_a.iter().collect());
+    assert!(matches!(g.game_result(), GameResult::Win(Teams::TeamB)));
+}
+
+#[tokio::test]
+async fn actions_place_ships_allowed_orientations() {
+    let player = Player::default();
+
+    let config = Config {
+        ship_set_team_a: vec![ShipType::Cruiser as i32, ShipType::Destroyer as i32],
+        allowed_placement_orientations: vec![Direction::North as i32, Direction::East as i32],
+        ..(*default_config_provider().game_config()).clone()
+    };
+
+    let g = Arc::new(RwLock::new(Game {
+        players: HashMap::from([(player.id, player.clone())]),
+        team_a: HashSet::from([player.id]),
+        ..Game::new(Arc::new(config))
+    }));
+    let mut g = g.write().await;
+    g.state = GameState::Preparation;
+    g.players.get_mut(&player.id).unwrap().quadrant = g.quadrants().first().cloned();
+    let (corner_x, corner_y, _) = g.players.get(&player.id).unwrap().quadrant.unwrap();
+
+    let assignments = |direction_b: Direction| {
+        vec![
+            ShipAssignment {
+                coordinate: Some(Coordinate {
+                    x: corner_x,
+                    y: corner_y,
+                }),
+                direction: Direction::East as i32,
+            },
+            ShipAssignment {
+                coordinate: Some(Coordinate {
+                    x: corner_x + 4,
+                    y: corner_y + 4,
+                }),
+                direction: direction_b as i32,
+            },
+        ]
+    };
+
+    // disallowed orientation
+    let result = Action::PlaceShips {
+        player_id: player.id,
+        ship_placements: assignments(Direction::West),
+    }
+    .apply_on(&mut g);
+    assert!(matches!(
+        result,
+        Err(ActionExecutionError::Validation(
+            ActionValidationError::InvalidShipPlacement(
+                ShipPlacementError::DisallowedShipDirection
+            )
+        ))
+    ));
+    assert!(g.ships.get_by_id(&(player.id, 0)).is_none());
+
+    // allowed orientations
+    assert!(Action::PlaceShips {
+        player_id: player.id,
+        ship_placements: assignments(Direction::North),
+    }
+    .apply_on(&mut g)
+    .is_ok());
+    assert!(g.ships.get_by_id(&(player.id, 0)).is_some());
+    assert!(g.ships.get_by_id(&(player.id, 1)).is_some());
+}
+
+#[tokio::test]
+async fn actions_place_ships_twice() {
+    let player = Player::default();
+
+    let mut g = Game {
+        players: HashMap::from([(player.id, player.clone())]),
+        team_a: HashSet::from([player.id]),
+        state: GameState::Preparation,
+        ..Default::default()
+    };
+    g.players.get_mut(&player.id).unwrap().quadrant = g.quadrants().first().cloned();
+
+    let ship_assignments = |column: u32| -> Vec<_> {
+        (0..g.config.ship_set_team_a.len() as u32)
+            .map(|row| ShipAssignment {
+                coordinate: Some(Coordinate { x: column, y: row }),
+                direction: Direction::East as i32,
+            })
+            .collect()
+    };
+    let first_placement = ship_assignments(0);
+    let second_placement = ship_assignments(10);
+
+    assert!(Action::PlaceShips {
+        player_id: player.id,
+        ship_placements: first_placement,
+    }
+    .apply_on(&mut g)
+    .is_ok());
+    assert!(g.players[&player.id].placed_ships);
+
+    let result = Action::PlaceShips {
+        player_id: player.id,
+        ship_placements: second_placement,
+    }
+    .apply_on(&mut g);
+    assert!(matches!(
+        result,
+        Err(ActionExecutionError::Validation(
+            ActionValidationError::InvalidShipPlacement(
+                ShipPlacementError::PlayerHasAlreadyPlacedShips
+            )
+        ))
+    ));
+
+    // the first placement is kept
+    let ship = g.ships.get_by_id(&(player.id, 0)).unwrap();
+    assert_eq!(ship.position(), (0, 0));
+}
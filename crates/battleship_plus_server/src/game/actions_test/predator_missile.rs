@@ -2,6 +2,7 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use rstar::AABB;
 use tokio::sync::RwLock;
 
 use battleship_plus_common::game::ship::{Cooldown, GetShipID, Orientation, Ship, ShipData};
@@ -9,7 +10,7 @@ use battleship_plus_common::game::ship_manager::ShipManager;
 use battleship_plus_common::types::*;
 
 use crate::game::actions::Action::PredatorMissile;
-use crate::game::actions::ActionResult;
+use crate::game::actions::{ActionResult, AreaDamageResult};
 use crate::game::data::{Game, Player, Turn};
 use crate::game::states::GameState;
 
@@ -111,6 +112,7 @@ async fn actions_predator_missile() {
             action_points_left: 42,
             player_id: player.id,
             temp_vision: Default::default(),
+            ..Default::default()
         }),
         state: GameState::InGame,
         players: HashMap::from([(player.id, player.clone())]),
@@ -192,3 +194,126 @@ async fn actions_predator_missile() {
     );
     assert_eq!(g.ships.get_by_id(&not_hit.id()).unwrap().data().health, 10);
 }
+
+#[tokio::test]
+async fn actions_predator_missile_matches_area_damage() {
+    let player = Player::default();
+    let ship_id = (player.id, 0);
+
+    let ship = Ship::Battleship {
+        balancing: Arc::from(BattleshipBalancing {
+            predator_missile_radius: 2,
+            predator_missile_range: 10,
+            predator_missile_damage: 7,
+            common_balancing: Some(CommonBalancing {
+                ability_costs: Some(Costs {
+                    cooldown: 0,
+                    action_points: 10,
+                }),
+                ..Default::default()
+            }),
+        }),
+        data: ShipData {
+            id: ship_id,
+            pos_x: 0,
+            pos_y: 0,
+            orientation: Orientation::North,
+            ..Default::default()
+        },
+        cooldowns: Default::default(),
+    };
+    let destroyed = Ship::Destroyer {
+        balancing: Arc::from(DestroyerBalancing {
+            ..Default::default()
+        }),
+        data: ShipData {
+            id: (42, 42),
+            health: 5,
+            pos_x: 7,
+            pos_y: 7,
+            orientation: Orientation::South,
+        },
+        cooldowns: Default::default(),
+    };
+    let hit = Ship::Destroyer {
+        balancing: Arc::from(DestroyerBalancing {
+            ..Default::default()
+        }),
+        data: ShipData {
+            id: (42, 43),
+            health: 10,
+            pos_x: 9,
+            pos_y: 8,
+            orientation: Orientation::North,
+        },
+        cooldowns: Default::default(),
+    };
+
+    let ships = ShipManager::new_with_ships(vec![ship, destroyed.clone(), hit.clone()]);
+    let mut shared_ships = ships.clone();
+
+    let g = Arc::new(RwLock::new(Game {
+        turn: Some(Turn {
+            action_points_left: 42,
+            player_id: player.id,
+            temp_vision: Default::default(),
+            ..Default::default()
+        }),
+        state: GameState::InGame,
+        players: HashMap::from([(player.id, player.clone())]),
+        ships,
+        team_a: HashSet::from([player.id]),
+        ..Default::default()
+    }));
+    let mut g = g.write().await;
+
+    let result = PredatorMissile {
+        ship_id,
+        properties: PredatorMissileProperties {
+            center: Some(Coordinate { x: 8, y: 8 }),
+        },
+    }
+    .apply_on(&mut g);
+    let shared = AreaDamageResult::from(
+        &[shared_ships.damage_area(AABB::from_corners([6, 6], [10, 10]), 7, None)][..],
+    );
+
+    assert!(matches!(result, Ok(ActionResult::Single { .. })));
+    if let Ok(ActionResult::Single {
+        ships_destroyed,
+        inflicted_damage_by_ship,
+        inflicted_damage_at,
+        splash_tiles,
+        lost_vision_at,
+        lost_enemy_vision,
+        ..
+    }) = result
+    {
+        // opponents no longer see the destroyed ship either
+        assert_eq!(lost_enemy_vision, lost_vision_at);
+        assert!(lost_vision_at.contains(&Coordinate { x: 7, y: 7 }));
+        assert_eq!(inflicted_damage_by_ship, shared.inflicted_damage_by_ship);
+        assert_eq!(inflicted_damage_at, shared.inflicted_damage_at);
+        assert_eq!(splash_tiles, shared.splash_tiles);
+        assert_eq!(ships_destroyed, shared.ships_destroyed);
+        assert_eq!(ships_destroyed.len(), 1);
+        assert_eq!(ships_destroyed[0].id(), destroyed.id());
+    }
+
+    for (id, ship) in g.ships.iter_ships() {
+        assert_eq!(shared_ships.get_by_id(id), Some(ship));
+    }
+    assert_eq!(shared_ships.iter_ships().count(), 2);
+    assert_eq!(shared_ships.get_by_id(&hit.id()).unwrap().data().health, 3);
+
+    // destroyed ships are gone from the board and are not hit again
+    let shared = AreaDamageResult::from(
+        &[shared_ships.damage_area(AABB::from_corners([6, 6], [10, 10]), 1, None)][..],
+    );
+    assert_eq!(
+        shared.inflicted_damage_by_ship,
+        HashMap::from([(hit.id(), 1)])
+    );
+    assert!(shared.ships_destroyed.is_empty());
+    assert!(shared_ships.place_ship(destroyed.id(), destroyed).is_ok());
+}
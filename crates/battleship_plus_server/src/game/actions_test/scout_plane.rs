@@ -6,9 +6,10 @@ use tokio::sync::RwLock;
 
 use battleship_plus_common::game::ship::{Cooldown, Orientation, Ship, ShipData};
 use battleship_plus_common::game::ship_manager::ShipManager;
+use battleship_plus_common::game::ActionValidationError;
 use battleship_plus_common::types::*;
 
-use crate::game::actions::{Action, ActionResult};
+use crate::game::actions::{Action, ActionExecutionError, ActionResult};
 use crate::game::data::{Game, Player, Turn};
 use crate::game::states::GameState;
 
@@ -83,6 +84,7 @@ async fn actions_scout_plane() {
             action_points_left: 42,
             player_id: player.id,
             temp_vision: Default::default(),
+            ..Default::default()
         }),
         state: GameState::InGame,
         players: HashMap::from([(player.id, player.clone())]),
@@ -129,3 +131,90 @@ async fn actions_scout_plane() {
         remaining_rounds: 10
     }));
 }
+
+#[tokio::test]
+async fn actions_scout_plane_range() {
+    let player = Player::default();
+    let ship_id = (player.id, 0);
+
+    let ship = Ship::Carrier {
+        balancing: Arc::from(CarrierBalancing {
+            scout_plane_radius: 1,
+            scout_plane_range: 10,
+            common_balancing: Some(CommonBalancing {
+                ability_costs: Some(Costs {
+                    cooldown: 0,
+                    action_points: 10,
+                }),
+                ..Default::default()
+            }),
+        }),
+        data: ShipData {
+            id: ship_id,
+            pos_x: 0,
+            pos_y: 0,
+            orientation: Orientation::North,
+            ..Default::default()
+        },
+        cooldowns: Default::default(),
+    };
+
+    let g = Arc::new(RwLock::new(Game {
+        turn: Some(Turn {
+            action_points_left: 42,
+            player_id: player.id,
+            temp_vision: Default::default(),
+            ..Default::default()
+        }),
+        state: GameState::InGame,
+        players: HashMap::from([(player.id, player.clone())]),
+        ships: ShipManager::new_with_ships(vec![ship]),
+        team_a: HashSet::from([player.id]),
+        team_b: HashSet::from([42]),
+        ..Default::default()
+    }));
+    let mut g = g.write().await;
+
+    // center just beyond the range
+    let result = Action::ScoutPlane {
+        ship_id,
+        properties: ScoutPlaneProperties {
+            center: Some(Coordinate { x: 11, y: 4 }),
+        },
+    }
+    .apply_on(&mut g);
+    assert!(matches!(
+        result,
+        Err(ActionExecutionError::Validation(
+            ActionValidationError::Unreachable
+        ))
+    ));
+    assert_eq!(g.turn.as_ref().unwrap().action_points_left, 42);
+
+    // center beyond the range diagonally
+    let result = Action::ScoutPlane {
+        ship_id,
+        properties: ScoutPlaneProperties {
+            center: Some(Coordinate { x: 10, y: 15 }),
+        },
+    }
+    .apply_on(&mut g);
+    assert!(matches!(
+        result,
+        Err(ActionExecutionError::Validation(
+            ActionValidationError::Unreachable
+        ))
+    ));
+    assert_eq!(g.turn.as_ref().unwrap().action_points_left, 42);
+
+    // center just within the range
+    let result = Action::ScoutPlane {
+        ship_id,
+        properties: ScoutPlaneProperties {
+            center: Some(Coordinate { x: 10, y: 14 }),
+        },
+    }
+    .apply_on(&mut g);
+    assert!(matches!(result, Ok(ActionResult::Single { .. })));
+    assert_eq!(g.turn.as_ref().unwrap().action_points_left, 32);
+}
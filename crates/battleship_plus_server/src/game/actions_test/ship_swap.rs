@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+
+use battleship_plus_common::game::ship::{Cooldown, Ship};
+use battleship_plus_common::game::ActionValidationError;
+use battleship_plus_common::types::{Config, Coordinate, ShipSwapProperties, Teams};
+
+use crate::config_provider::default_config_provider;
+use crate::game::actions::{Action, ActionExecutionError};
+use crate::game::data::Game;
+use crate::game::game_builder::GameBuilder;
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+
+const SWAP_RANGE: u32 = 5;
+const SWAP_COSTS: u32 = 4;
+
+/// A carrier of player 1 at (0, 0) and a destroyer of the same player at (3, 0), both facing
+/// north, together with the given ships.
+fn game_with(
+    ship_swap_range: u32,
+    action_points: u32,
+    ships: impl IntoIterator<Item = Ship>,
+) -> Game {
+    GameBuilder::new()
+        .config(Config {
+            ship_swap_range,
+            ..(*default_config_provider().game_config()).clone()
+        })
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamB)
+        .ships([
+            GeneralShipBuilder::default()
+                .id((1, 0))
+                .position(0, 0)
+                .ability(SWAP_COSTS, 2)
+                .carrier()
+                .build(),
+            GeneralShipBuilder::default()
+                .id((1, 1))
+                .position(3, 0)
+                .destroyer()
+                .build(),
+            GeneralShipBuilder::default()
+                .id((2, 0))
+                .position(50, 50)
+                .destroyer()
+                .build(),
+        ])
+        .ships(ships)
+        .turn(1, action_points)
+        .build()
+}
+
+fn swap_action() -> Action {
+    Action::ShipSwap {
+        ship_id: (1, 0),
+        properties: ShipSwapProperties {
+            partner_ship_number: 1,
+        },
+    }
+}
+
+fn swap(game: &mut Game) -> Result<(), ActionExecutionError> {
+    swap_action().apply_on(game).map(|_| ())
+}
+
+fn position_of(game: &Game, ship_id: (u32, u32)) -> (i32, i32) {
+    game.ships.get_by_id(&ship_id).unwrap().position()
+}
+
+fn action_points_left(game: &Game) -> u32 {
+    game.turn.as_ref().unwrap().action_points_left
+}
+
+#[tokio::test]
+async fn actions_ship_swap() {
+    let mut game = game_with(SWAP_RANGE, 10, []);
+    swap(&mut game).expect("the destroyer is in range and both destinations are free");
+
+    assert_eq!(position_of(&game, (1, 0)), (3, 0));
+    assert_eq!(position_of(&game, (1, 1)), (0, 0));
+    assert_eq!(action_points_left(&game), 10 - SWAP_COSTS);
+    assert!(game
+        .ships
+        .get_by_id(&(1, 0))
+        .unwrap()
+        .cool_downs()
+        .contains(&Cooldown::Ability {
+            remaining_rounds: 2
+        }));
+    // the lookup of ships by position follows the swap
+    assert_eq!(
+        game.ships
+            .get_by_position(Coordinate { x: 3, y: 4 })
+            .map(|ship| ship.data().id),
+        Some((1, 0))
+    );
+
+    // without a range, swapping is disabled
+    let mut game = game_with(0, 10, []);
+    assert!(matches!(
+        swap(&mut game),
+        Err(ActionExecutionError::BadRequest(msg)) if msg == "ship swapping is disabled"
+    ));
+}
+
+#[tokio::test]
+async fn actions_ship_swap_is_allowed_in_game() {
+    let g = Arc::new(RwLock::new(game_with(SWAP_RANGE, 10, [])));
+    let mut g = g.write().await;
+
+    g.get_state()
+        .execute_action(swap_action(), &mut g)
+        .expect("ship swaps are actions of the game phase");
+    assert_eq!(position_of(&g, (1, 0)), (3, 0));
+    assert_eq!(position_of(&g, (1, 1)), (0, 0));
+}
+
+#[tokio::test]
+async fn actions_ship_swap_into_other_ship() {
+    // the carrier would reach up to (3, 4) at the position of the destroyer
+    let blocker = GeneralShipBuilder::default()
+        .id((1, 2))
+        .position(3, 3)
+        .destroyer()
+        .build();
+    let mut game = game_with(SWAP_RANGE, 10, [blocker]);
+
+    assert!(matches!(
+        swap(&mut game),
+        Err(ActionExecutionError::Validation(
+            ActionValidationError::Collision
+        ))
+    ));
+    assert_eq!(position_of(&game, (1, 0)), (0, 0));
+    assert_eq!(position_of(&game, (1, 1)), (3, 0));
+    assert_eq!(action_points_left(&game), 10);
+}
+
+#[tokio::test]
+async fn actions_ship_swap_insufficient_points() {
+    let mut game = game_with(SWAP_RANGE, SWAP_COSTS - 1, []);
+
+    assert!(matches!(
+        swap(&mut game),
+        Err(ActionExecutionError::Validation(
+            ActionValidationError::InsufficientPoints {
+                required: SWAP_COSTS
+            }
+        ))
+    ));
+    assert_eq!(position_of(&game, (1, 0)), (0, 0));
+    assert_eq!(position_of(&game, (1, 1)), (3, 0));
+    assert_eq!(action_points_left(&game), SWAP_COSTS - 1);
+}
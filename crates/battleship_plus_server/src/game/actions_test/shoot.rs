@@ -2,16 +2,19 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use rstar::RTreeObject;
 use tokio::sync::RwLock;
 
 use battleship_plus_common::game::ship::{Cooldown, GetShipID, Ship, ShipData};
-use battleship_plus_common::game::ship_manager::ShipManager;
+use battleship_plus_common::game::ship_manager::{envelope_to_points, ShipManager};
 use battleship_plus_common::game::ActionValidationError;
 use battleship_plus_common::types::*;
 
+use crate::config_provider::default_config_provider;
 use crate::game::actions::ActionResult;
 use crate::game::actions::{Action, ActionExecutionError};
 use crate::game::data::{Game, Player, Turn};
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
 
 #[tokio::test]
 async fn actions_shoot() {
@@ -474,3 +477,148 @@ async fn actions_shoot_not_players_turn() {
         );
     }
 }
+
+#[tokio::test]
+async fn actions_shoot_reveals_shooter() {
+    let shooter = GeneralShipBuilder::default()
+        .owner(1)
+        .position(0, 0)
+        .cannon(10, 128, 1, 0)
+        .destroyer()
+        .build();
+    let enemy = GeneralShipBuilder::default()
+        .owner(2)
+        .position(20, 20)
+        .vision(1)
+        .destroyer()
+        .build();
+    let shoot = Action::Shoot {
+        ship_id: shooter.id(),
+        properties: ShootProperties {
+            target: Some(Coordinate { x: 10, y: 10 }),
+        },
+    };
+
+    for shots_reveal_shooter in [false, true] {
+        let mut game = Game {
+            config: Arc::new(Config {
+                shots_reveal_shooter,
+                ..(*default_config_provider().game_config()).clone()
+            }),
+            players: HashMap::from([
+                (
+                    1,
+                    Player {
+                        id: 1,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    2,
+                    Player {
+                        id: 2,
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            team_a: HashSet::from([1]),
+            team_b: HashSet::from([2]),
+            ships: ShipManager::new_with_ships(vec![shooter.clone(), enemy.clone()]),
+            turn: Some(Turn::new(1, 10)),
+            ..Default::default()
+        };
+
+        let result = shoot.apply_on(&mut game);
+        if !shots_reveal_shooter {
+            assert!(matches!(result, Ok(ActionResult::None)));
+            continue;
+        }
+
+        // the shot missed, but the enemy team sees where it came from
+        let shooter_tiles: HashSet<_> = envelope_to_points(shooter.envelope()).collect();
+        match result {
+            Ok(ActionResult::Single {
+                gain_enemy_vision,
+                inflicted_damage_at,
+                ..
+            }) => {
+                assert!(inflicted_damage_at.is_empty());
+                assert_eq!(gain_enemy_vision, shooter_tiles);
+            }
+            result => panic!("expected the shooter to be revealed, got {result:?}"),
+        }
+        assert_eq!(game.turn.unwrap().revealed_to_enemies, shooter_tiles);
+    }
+}
+
+#[tokio::test]
+async fn actions_shoot_destruction_reveals_surroundings() {
+    let shooter = GeneralShipBuilder::default()
+        .owner(1)
+        .position(0, 0)
+        .cannon(10, 128, 1, 0)
+        .destroyer()
+        .build();
+    let target = GeneralShipBuilder::default()
+        .owner(2)
+        .health(5)
+        .position(20, 20)
+        .destroyer()
+        .build();
+    let neighbour = GeneralShipBuilder::default()
+        .id((2, 1))
+        .health(5)
+        .position(22, 20)
+        .destroyer()
+        .build();
+    let far_away = GeneralShipBuilder::default()
+        .id((2, 2))
+        .health(5)
+        .position(40, 20)
+        .destroyer()
+        .build();
+    let shoot = Action::Shoot {
+        ship_id: shooter.id(),
+        properties: ShootProperties {
+            target: Some(Coordinate { x: 20, y: 20 }),
+        },
+    };
+
+    for reveal_on_destruction_radius in [0, 3] {
+        let mut game = Game {
+            config: Arc::new(Config {
+                reveal_on_destruction_radius,
+                ..(*default_config_provider().game_config()).clone()
+            }),
+            players: HashMap::from([(1, Player::default()), (2, Player::default())]),
+            team_a: HashSet::from([1]),
+            team_b: HashSet::from([2]),
+            ships: ShipManager::new_with_ships(vec![
+                shooter.clone(),
+                target.clone(),
+                neighbour.clone(),
+                far_away.clone(),
+            ]),
+            turn: Some(Turn::new(1, 10)),
+            ..Default::default()
+        };
+
+        match shoot.apply_on(&mut game) {
+            Ok(ActionResult::Single {
+                ships_destroyed,
+                temp_vision_at,
+                ..
+            }) => {
+                assert_eq!(ships_destroyed.len(), 1);
+                // only the ships next to the wreck are revealed
+                let expected: HashSet<_> = if reveal_on_destruction_radius > 0 {
+                    envelope_to_points(neighbour.envelope()).collect()
+                } else {
+                    HashSet::new()
+                };
+                assert_eq!(temp_vision_at, expected);
+            }
+            result => panic!("expected the target to be destroyed, got {result:?}"),
+        }
+    }
+}
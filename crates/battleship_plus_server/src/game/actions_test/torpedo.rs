@@ -63,6 +63,7 @@ async fn actions_torpedo_north() {
             action_points_left: 42,
             player_id: player.id,
             temp_vision: Default::default(),
+            ..Default::default()
         }),
         state: GameState::InGame,
         players: HashMap::from([(player.id, player.clone())]),
@@ -177,6 +178,7 @@ async fn actions_torpedo_south() {
             action_points_left: 42,
             player_id: player.id,
             temp_vision: Default::default(),
+            ..Default::default()
         }),
         state: GameState::InGame,
         players: HashMap::from([(player.id, player.clone())]),
@@ -291,6 +293,7 @@ async fn actions_torpedo_east() {
             action_points_left: 42,
             player_id: player.id,
             temp_vision: Default::default(),
+            ..Default::default()
         }),
         state: GameState::InGame,
         players: HashMap::from([(player.id, player.clone())]),
@@ -405,6 +408,7 @@ async fn actions_torpedo_west() {
             action_points_left: 42,
             player_id: player.id,
             temp_vision: Default::default(),
+            ..Default::default()
         }),
         state: GameState::InGame,
         players: HashMap::from([(player.id, player.clone())]),
@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+
+use rstar::{RTreeObject, AABB};
+
+use battleship_plus_common::game::ship::{Ship, ShipID};
+use battleship_plus_common::game::ship_manager::envelope_to_points;
+use battleship_plus_common::game::PlayerID;
+use battleship_plus_common::types::Coordinate;
+
+use crate::game::actions::{apply_area_damage, ActionResult};
+use crate::game::data::Game;
+
+/// The tiles currently covered by a ship, none if the ship does not exist (anymore).
+pub(crate) fn ship_tiles(game: &Game, ship_id: &ShipID) -> Vec<Coordinate> {
+    game.ships
+        .get_by_id(ship_id)
+        .map_or(vec![], |ship| envelope_to_points(ship.envelope()).collect())
+}
+
+fn is_one_of(ship_types: &[i32], ship: &Ship) -> bool {
+    ship_types.contains(&(ship.ship_type() as i32))
+}
+
+/// Resolves the mines after a ship moved or rotated away from `tiles_before`.
+///
+/// Mines of the enemies below the ship detonate and damage it once each. Afterwards ships of the
+/// types in `Config.mine_laying_ship_types` leave a mine on every tile they left.
+pub(crate) fn resolve_mines(
+    game: &mut Game,
+    ship_id: &ShipID,
+    tiles_before: &[Coordinate],
+    result: ActionResult,
+) -> ActionResult {
+    let damage = game.config.mine_damage;
+    if damage == 0 {
+        return result;
+    }
+    let player_id = ship_id.0;
+    let allies = if game.team_a.contains(&player_id) {
+        &game.team_a
+    } else {
+        &game.team_b
+    };
+
+    let tiles_after = ship_tiles(game, ship_id);
+    let triggered: Vec<_> = tiles_after
+        .iter()
+        .filter(|tile| {
+            game.mines
+                .get(*tile)
+                .is_some_and(|owner| !allies.contains(owner))
+        })
+        .cloned()
+        .collect();
+    let areas: Vec<_> = triggered
+        .iter()
+        .map(|tile| {
+            game.mines.remove(tile);
+            AABB::from_point([tile.x as i32, tile.y as i32])
+        })
+        .collect();
+    let result = result.merge_area_damage(apply_area_damage(&mut game.ships, &areas, damage));
+
+    let lays_mines = game
+        .ships
+        .get_by_id(ship_id)
+        .is_some_and(|ship| is_one_of(&game.config.mine_laying_ship_types, ship));
+    if lays_mines {
+        let occupied: HashSet<_> = tiles_after.into_iter().collect();
+        for tile in tiles_before.iter().filter(|tile| !occupied.contains(*tile)) {
+            game.mines.insert(tile.clone(), player_id);
+        }
+    }
+
+    result
+}
+
+/// Sets a ship hit by a shot on fire if the shooter is of one of the types in
+/// `Config.incendiary_ship_types`. Hitting a burning ship again rekindles the fire.
+pub(crate) fn ignite(game: &mut Game, shooter: &Ship, target: ShipID) {
+    let config = &game.config;
+    if config.burn_damage > 0
+        && config.burn_turns > 0
+        && is_one_of(&config.incendiary_ship_types, shooter)
+    {
+        game.burning_ships.insert(target, config.burn_turns);
+    }
+}
+
+/// Damages the burning ships of the player whose turn begins, the fires go out after
+/// `Config.burn_turns` turns of the player.
+pub(crate) fn burn_ships(game: &mut Game, player_id: PlayerID) -> ActionResult {
+    // ships destroyed in the meantime no longer burn
+    let ships = &game.ships;
+    game.burning_ships
+        .retain(|ship_id, _| ships.get_by_id(ship_id).is_some());
+
+    let mut burning: Vec<_> = game
+        .burning_ships
+        .keys()
+        .filter(|(owner, _)| *owner == player_id)
+        .cloned()
+        .collect();
+    if burning.is_empty() {
+        return ActionResult::None;
+    }
+    burning.sort_unstable();
+
+    let areas: Vec<_> = burning
+        .iter()
+        .filter_map(|ship_id| game.ships.get_by_id(ship_id))
+        .map(|ship| ship.envelope())
+        .collect();
+    for ship_id in burning {
+        if let Some(turns_left) = game.burning_ships.get_mut(&ship_id) {
+            *turns_left -= 1;
+            if *turns_left == 0 {
+                game.burning_ships.remove(&ship_id);
+            }
+        }
+    }
+
+    apply_area_damage(&mut game.ships, &areas, game.config.burn_damage).into()
+}
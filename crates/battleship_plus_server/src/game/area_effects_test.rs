@@ -0,0 +1,121 @@
+use battleship_plus_common::game::ship::{Orientation, Ship};
+use battleship_plus_common::messages::ship_action_request::ActionProperties;
+use battleship_plus_common::types::{
+    Config, MoveDirection, MoveProperties, ShipType, ShootProperties, Teams,
+};
+
+use crate::config_provider::default_config_provider;
+use crate::game::actions::{Action, ActionResult};
+use crate::game::area_effects::ship_tiles;
+use crate::game::data::{Game, Turn};
+use crate::game::game_builder::GameBuilder;
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+
+fn destroyer(owner: u32, x: i32, y: i32) -> Ship {
+    GeneralShipBuilder::default()
+        .owner(owner)
+        .health(20)
+        .position(x, y)
+        .orientation(Orientation::North)
+        .movement(0, 0, 0, 0)
+        .cannon(10, 32, 0, 0)
+        .destroyer()
+        .build()
+}
+
+fn game_with_config(config: Config) -> Game {
+    GameBuilder::new()
+        .config(config)
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamB)
+        .ships([destroyer(1, 10, 10), destroyer(2, 20, 10)])
+        .turn_order(&[1, 2])
+        .turn(1, 10)
+        .build()
+}
+
+fn move_forward(game: &mut Game, player_id: u32) -> ActionResult {
+    Action::Move {
+        ship_id: (player_id, 0),
+        properties: MoveProperties {
+            direction: MoveDirection::Forward.into(),
+        },
+    }
+    .apply_on(game)
+    .expect("the ship is able to move")
+}
+
+#[tokio::test]
+async fn mine_triggers_when_ship_moves_onto_it() {
+    let mut game = game_with_config(Config {
+        mine_laying_ship_types: vec![ShipType::Destroyer.into()],
+        mine_damage: 5,
+        ..(*default_config_provider().game_config()).clone()
+    });
+
+    // the mine layer leaves a mine on the tile behind it, it does not trigger its own mines
+    let tiles_before = ship_tiles(&game, &(1, 0));
+    move_forward(&mut game, 1);
+    let laid: Vec<_> = tiles_before
+        .iter()
+        .filter(|tile| game.mines.contains_key(*tile))
+        .collect();
+    assert_eq!(laid.len(), 1);
+    assert_eq!(game.mines[laid[0]], 1);
+    assert_eq!(game.ships.ships_of(1)[0].health(), 20);
+
+    // an enemy ship moving onto the mine is damaged and clears it
+    let enemy_tiles = ship_tiles(&game, &(2, 0));
+    let mined_tile = game.ships.ships_of(2)[0]
+        .tiles_after(&ActionProperties::MoveProperties(MoveProperties {
+            direction: MoveDirection::Forward.into(),
+        }))
+        .into_iter()
+        .find(|tile| !enemy_tiles.contains(tile))
+        .unwrap();
+    game.mines.insert(mined_tile.clone(), 1);
+    game.turn = Some(Turn::new(2, 10));
+    match move_forward(&mut game, 2) {
+        ActionResult::Single {
+            inflicted_damage_by_ship,
+            ..
+        } => assert_eq!(inflicted_damage_by_ship[&(2, 0)], 5),
+        result => panic!("the mine was not triggered: {result:?}"),
+    }
+    assert_eq!(game.ships.ships_of(2)[0].health(), 15);
+    assert!(!game.mines.contains_key(&mined_tile));
+}
+
+#[tokio::test]
+async fn burning_ship_takes_damage_each_turn_of_its_owner() {
+    let mut game = game_with_config(Config {
+        incendiary_ship_types: vec![ShipType::Destroyer.into()],
+        burn_damage: 3,
+        burn_turns: 2,
+        ..(*default_config_provider().game_config()).clone()
+    });
+    let target = ship_tiles(&game, &(2, 0))[0].clone();
+    Action::Shoot {
+        ship_id: (1, 0),
+        properties: ShootProperties {
+            target: Some(target),
+        },
+    }
+    .apply_on(&mut game)
+    .expect("the target is in range");
+    assert_eq!(game.ships.ships_of(2)[0].health(), 10);
+
+    let (broadcast_tx, _broadcast_rx) = tokio::sync::broadcast::channel(16);
+    let mut health_per_turn = vec![];
+    for _ in 0..6 {
+        let (turn, _) = game
+            .clear_temp_vision_and_advance_turn(&[], &broadcast_tx)
+            .unwrap();
+        health_per_turn.push((turn.player_id, game.ships.ships_of(2)[0].health()));
+    }
+    // the fire burns during the first two turns of player 2 only
+    assert_eq!(
+        health_per_turn,
+        vec![(2, 7), (1, 7), (2, 4), (1, 4), (2, 4), (1, 4)]
+    );
+}
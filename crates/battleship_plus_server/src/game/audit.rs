@@ -0,0 +1,171 @@
+use std::fmt::{Debug, Formatter};
+
+use log::{debug, warn};
+use serde::Serialize;
+
+use battleship_plus_common::game::ship::{GetShipID, ShipID};
+use battleship_plus_common::game::PlayerID;
+
+use crate::game::actions::{Action, ActionResult};
+use crate::game::data::Game;
+use crate::game::registry::GameID;
+use crate::game::snapshot::LogSink;
+
+/// Appends an entry to the audit log for every executed action, so disputed games can be checked
+/// without reconstructing them from the replay log. Each entry is a single line of JSON.
+#[derive(Clone)]
+pub(crate) struct AuditLog {
+    game_id: GameID,
+    sink: LogSink,
+}
+
+impl Debug for AuditLog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("game_id", &self.game_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuditLog {
+    pub(crate) fn new(game_id: GameID, sink: LogSink) -> Self {
+        AuditLog { game_id, sink }
+    }
+
+    /// Records an action of the player along with the action points it cost.
+    pub(crate) fn record_action(
+        &self,
+        game: &Game,
+        player_id: PlayerID,
+        action: &Action,
+        action_points: u32,
+        result: &ActionResult,
+    ) {
+        let ship_number = action.ship_id().map(|(_, ship_number)| ship_number);
+        self.write(audit_entry_json(
+            self.game_id,
+            game.turn_number(),
+            player_id,
+            action_name(action),
+            ship_number,
+            action_points,
+            result,
+        ));
+    }
+
+    /// Records the damage burning ships of the player took as their turn began.
+    pub(crate) fn record_burn(&self, game: &Game, player_id: PlayerID, result: &ActionResult) {
+        self.write(audit_entry_json(
+            self.game_id,
+            game.turn_number(),
+            player_id,
+            "Burn",
+            None,
+            0,
+            result,
+        ));
+    }
+
+    fn write(&self, entry: String) {
+        debug!("Audit: {entry}");
+
+        let mut sink = match self.sink.lock() {
+            Ok(sink) => sink,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Err(e) = writeln!(sink, "{entry}").and_then(|_| sink.flush()) {
+            warn!(
+                "Unable to write the audit entry of game {}: {e}",
+                self.game_id
+            );
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    game: GameID,
+    turn: u32,
+    player: PlayerID,
+    action: &'a str,
+    ship: Option<u32>,
+    action_points: u32,
+    damage: u32,
+    destroyed: Vec<DestroyedShip>,
+}
+
+#[derive(Serialize)]
+struct DestroyedShip {
+    player: PlayerID,
+    number: u32,
+}
+
+/// Renders an audit entry: who acted with which ship, the action points spent, the damage dealt
+/// and the ships destroyed. The steps of an engine boost are summed up.
+fn audit_entry_json(
+    game_id: GameID,
+    turn: u32,
+    player_id: PlayerID,
+    action: &str,
+    ship_number: Option<u32>,
+    action_points: u32,
+    result: &ActionResult,
+) -> String {
+    let mut ships_destroyed = vec![];
+    let damage = sum_outcome(result, &mut ships_destroyed);
+    ships_destroyed.sort_unstable();
+
+    let entry = AuditEntry {
+        game: game_id,
+        turn,
+        player: player_id,
+        action,
+        ship: ship_number,
+        action_points,
+        damage,
+        destroyed: ships_destroyed
+            .into_iter()
+            .map(|(player, number)| DestroyedShip { player, number })
+            .collect(),
+    };
+    serde_json::to_string(&entry).expect("the audit entry is valid JSON")
+}
+
+/// Adds up the damage dealt by the action, the destroyed ships are collected on the way.
+fn sum_outcome(result: &ActionResult, ships_destroyed: &mut Vec<ShipID>) -> u32 {
+    match result {
+        ActionResult::None => 0,
+        ActionResult::Single {
+            inflicted_damage_by_ship,
+            ships_destroyed: destroyed,
+            ..
+        } => {
+            ships_destroyed.extend(destroyed.iter().map(|ship| ship.id()));
+            inflicted_damage_by_ship.values().sum()
+        }
+        ActionResult::EngineBoost(_, steps) => steps
+            .iter()
+            .flatten()
+            .map(|step| sum_outcome(step, ships_destroyed))
+            .sum(),
+    }
+}
+
+fn action_name(action: &Action) -> &'static str {
+    match action {
+        Action::TeamSwitch { .. } => "TeamSwitch",
+        Action::SetReady { .. } => "SetReady",
+        Action::PlaceShips { .. } => "PlaceShips",
+        Action::Move { .. } => "Move",
+        Action::Rotate { .. } => "Rotate",
+        Action::Shoot { .. } => "Shoot",
+        Action::ScoutPlane { .. } => "ScoutPlane",
+        Action::PredatorMissile { .. } => "PredatorMissile",
+        Action::EngineBoost { .. } => "EngineBoost",
+        Action::Torpedo { .. } => "Torpedo",
+        Action::MultiMissile { .. } => "MultiMissile",
+        Action::ShipSwap { .. } => "ShipSwap",
+        Action::EndTurn => "EndTurn",
+        Action::None => "None",
+    }
+}
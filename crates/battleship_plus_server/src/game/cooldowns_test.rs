@@ -0,0 +1,76 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use battleship_plus_common::game::ship::{Cooldown, GetShipID, Ship};
+use battleship_plus_common::game::ship_manager::ShipManager;
+use battleship_plus_common::types::Config;
+
+use crate::config_provider::default_config_provider;
+use crate::game::data::{Game, Player};
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+use crate::game::states::GameState;
+
+fn cannon_on_cooldown(owner: u32) -> Ship {
+    GeneralShipBuilder::default()
+        .owner(owner)
+        .position(owner as i32 * 10, 0)
+        .cooldown(Cooldown::Cannon {
+            remaining_rounds: 3,
+        })
+        .destroyer()
+        .build()
+}
+
+fn owner_turns_until_cooled_down(owner_cooldown_reduction: u32) -> (u32, Vec<Cooldown>) {
+    let ships = vec![cannon_on_cooldown(1), cannon_on_cooldown(2)];
+    let mut game = Game {
+        config: Arc::new(Config {
+            owner_cooldown_reduction,
+            ..(*default_config_provider().game_config()).clone()
+        }),
+        state: GameState::InGame,
+        // only player 1 is left to take turns
+        players: HashMap::from([(
+            1,
+            Player {
+                id: 1,
+                ..Default::default()
+            },
+        )]),
+        team_a: HashSet::from([1]),
+        team_b: HashSet::from([2]),
+        ships: ShipManager::new_with_ships(ships.clone()),
+        ..Default::default()
+    };
+
+    let mut turns = 0;
+    while !game
+        .ships
+        .get_by_id(&ships[0].id())
+        .unwrap()
+        .cool_downs()
+        .is_empty()
+    {
+        assert_eq!(game.advance_turn().player_id, 1);
+        turns += 1;
+    }
+    let other_cooldowns = game.ships.get_by_id(&ships[1].id()).unwrap().cool_downs();
+    (turns, other_cooldowns)
+}
+
+#[tokio::test]
+async fn cooldowns_owner_reduction_clears_faster() {
+    let (turns, other_cooldowns) = owner_turns_until_cooled_down(0);
+    assert_eq!(turns, 3);
+    assert!(other_cooldowns.is_empty());
+
+    let (turns, other_cooldowns) = owner_turns_until_cooled_down(1);
+    assert_eq!(turns, 2);
+    // ships of other players only tick once per turn
+    assert_eq!(
+        other_cooldowns,
+        vec![Cooldown::Cannon {
+            remaining_rounds: 1
+        }]
+    );
+}
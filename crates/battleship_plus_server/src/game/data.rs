@@ -1,25 +1,35 @@
-use std::collections::hash_map::RandomState;
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::sync::Arc;
+use std::time::Instant;
 
-use rand::prelude::IteratorRandom;
-use rand::thread_rng;
-use rstar::{Envelope, RTreeObject, AABB};
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use rstar::AABB;
 
-use battleship_plus_common::game::ship::{Cooldown, Ship, ShipID};
+use battleship_plus_common::game::ship::{Cooldown, GetShipID, Ship, ShipID};
 use battleship_plus_common::game::ship_manager::{ShipManager, ShipPlacementError};
-use battleship_plus_common::game::PlayerID;
+use battleship_plus_common::game::{starting_action_points, PlayerID};
 use battleship_plus_common::messages::{ProtocolMessage, VisionEvent};
 use battleship_plus_common::types::{
-    Config, Coordinate, Direction, ShipAssignment, ShipType, Teams,
+    Config, Coordinate, Direction, ShipAssignment, ShipType, Teams, Tiebreaker,
 };
 use battleship_plus_common::util;
 use bevy_quinnet_server::ClientId;
 
 use crate::config_provider::default_config_provider;
+use crate::game::actions::ActionResult;
+use crate::game::area_effects::burn_ships;
+use crate::game::audit::AuditLog;
+use crate::game::lobby_file::{LobbyFile, SavedSeat};
+use crate::game::placement::{validate_placement, Placement};
+use crate::game::snapshot::SnapshotLog;
 use crate::game::states::GameState;
-use crate::server::MessageHandlerError;
+use crate::game::teams::{FillSmallerTeam, TeamAssignment};
+use crate::game::turn_queue::TurnQueue;
+use crate::server::{sorted_coordinates, MessageHandlerError};
 
 #[derive(Debug)]
 pub struct Game {
@@ -30,9 +40,33 @@ pub struct Game {
     pub(crate) team_b: HashSet<PlayerID>,
 
     pub(crate) ships: ShipManager,
+    /// Mines on the board and the players who laid them.
+    pub(crate) mines: HashMap<Coordinate, PlayerID>,
+    /// Burning ships and how many more turns of their owner they burn.
+    pub(crate) burning_ships: HashMap<ShipID, u32>,
 
     pub(crate) state: GameState,
     pub(crate) turn: Option<Turn>,
+    /// Players in the order they take their turns, players leaving the game are removed.
+    /// If empty, each turn goes to a random player.
+    pub(crate) turn_queue: TurnQueue,
+    /// Players yet to acknowledge the start of the game, the first turn is announced once none
+    /// are left. `None` after the announcement.
+    pub(crate) awaiting_start_acks: Option<HashSet<PlayerID>>,
+    /// Whether a ship was destroyed in this game already.
+    pub(crate) first_blood_taken: bool,
+    /// Number of turns that ended so far.
+    pub(crate) turns_played: u32,
+    pub(crate) snapshot_log: Option<SnapshotLog>,
+    pub(crate) audit_log: Option<AuditLog>,
+    pub(crate) lobby_file: Option<LobbyFile>,
+    /// Seats restored from the lobby file that no player took yet.
+    pub(crate) saved_seats: Vec<SavedSeat>,
+    pub(crate) team_assignment: Arc<dyn TeamAssignment>,
+    /// Seed of `rng`, a replay of the game starting from the same seed makes the same choices.
+    pub(crate) seed: u64,
+    /// Source of all randomness while the game is played, never use another one here.
+    pub(crate) rng: StdRng,
 }
 
 impl Default for Game {
@@ -43,37 +77,57 @@ impl Default for Game {
 
 impl Game {
     pub fn new(config: Arc<Config>) -> Self {
+        let mut ships = ShipManager::new();
+        ships.set_islands(&config.islands);
+        ships.set_line_of_sight_vision(config.line_of_sight_vision);
+        let seed = thread_rng().gen();
+
         Game {
             config,
             state: GameState::Lobby,
             players: Default::default(),
             team_a: Default::default(),
             team_b: Default::default(),
-            ships: Default::default(),
+            ships,
+            mines: Default::default(),
+            burning_ships: Default::default(),
             turn: Default::default(),
+            turn_queue: Default::default(),
+            awaiting_start_acks: None,
+            first_blood_taken: false,
+            turns_played: 0,
+            snapshot_log: None,
+            audit_log: None,
+            lobby_file: None,
+            saved_seats: vec![],
+            team_assignment: Arc::new(FillSmallerTeam),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
         }
     }
 
-    pub fn check_game_config(&self) -> Result<(), String> {
-        // check that the board is big enough to host all players
-        let player_count = self.config.team_size_a + self.config.team_size_b;
-        if util::quadrant_size(self.config.board_size, player_count) == 0 {
-            let min_board_length = util::quadrants_per_row(player_count);
-            // TODO Improve: Suggest actual minimal board size including placement of ships
-            return Err(format!(
-                "board is too small. Requires at least {min_board_length}x{min_board_length}",
-            ));
-        }
-
-        // TODO Implementation: Implement more config checks
+    /// Restarts the random choices of the game from the seed.
+    #[cfg(test)]
+    pub(crate) fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+    }
 
-        Ok(())
+    pub fn check_game_config(&self) -> Result<(), String> {
+        self.config.validate().map_err(|errors| {
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
     }
 
     pub fn can_change_into_preparation_phase(&self) -> bool {
         matches!(self.state, GameState::Lobby)
             && self.team_a.len() == self.config.team_size_a as usize
             && self.team_b.len() == self.config.team_size_b as usize
+            && self.players.len() >= self.config.min_players as usize
             && self.players.iter().all(|(_, p)| p.is_ready)
     }
 
@@ -103,10 +157,57 @@ impl Game {
         })
     }
 
+    /// The ship set the player has to place, which depends on their team.
+    pub(crate) fn ship_set_of(&self, player_id: PlayerID) -> Option<&Vec<i32>> {
+        match (
+            self.team_a.contains(&player_id),
+            self.team_b.contains(&player_id),
+        ) {
+            (true, false) => Some(&self.config.ship_set_team_a),
+            (false, true) => Some(&self.config.ship_set_team_b),
+            (false, false) => None,
+            _ => unreachable!(),
+        }
+    }
+
     pub fn board_bounds(&self) -> AABB<[i32; 2]> {
         AABB::from_corners([0; 2], [(self.config.board_size - 1) as i32; 2])
     }
 
+    /// The board bounds if the board wraps around at its edges.
+    pub fn wrap_bounds(&self) -> Option<AABB<[i32; 2]>> {
+        if self.config.wrap_edges {
+            Some(self.board_bounds())
+        } else {
+            None
+        }
+    }
+
+    /// Splits the players into groups whose members see what the ships of the whole group see.
+    /// All players form a single group if teammates share their vision, otherwise every player
+    /// forms their own.
+    pub(crate) fn vision_groups(&self, players: Vec<PlayerID>) -> Vec<Vec<PlayerID>> {
+        if self.config.share_team_vision {
+            vec![players]
+        } else {
+            players
+                .into_iter()
+                .map(|player_id| vec![player_id])
+                .collect()
+        }
+    }
+
+    /// The ship parts seen by any ship of the players.
+    pub(crate) fn ship_parts_seen_by_players(&self, players: &[PlayerID]) -> Vec<Coordinate> {
+        let ships: Vec<_> = self
+            .ships
+            .get_for_players(&players.iter().cloned().collect())
+            .into_iter()
+            .collect();
+        self.ships
+            .get_ship_parts_seen_by(&ships, self.wrap_bounds().as_ref())
+    }
+
     pub fn quadrants(&self) -> Vec<(u32, u32, u32)> {
         let player_count = self.config.team_size_a + self.config.team_size_b;
         let quadrant_size = util::quadrant_size(self.config.board_size, player_count);
@@ -127,6 +228,49 @@ impl Game {
             .collect()
     }
 
+    /// Hands out a quadrant to every player. With symmetric quadrants, the players of both teams
+    /// are paired up and each pair gets quadrants on opposite sides of the board's center.
+    /// Players left without a partner get one of the remaining quadrants.
+    pub(crate) fn assign_quadrants(&mut self) {
+        let quadrants = self.quadrants();
+        if self.players.len() > quadrants.len() {
+            panic!("board has less quadrants than players in the game");
+        }
+
+        // players are handed quadrants in a fixed order, so only the seed decides who gets which
+        let mut player_ids: Vec<_> = self.players.keys().cloned().collect();
+        player_ids.sort_unstable();
+        let mut free: Vec<_> = (0..quadrants.len()).collect();
+        free.shuffle(&mut self.rng);
+
+        let mut assignment = HashMap::new();
+        if self.config.symmetric_quadrants {
+            // quadrant i lies opposite of quadrant n - 1 - i, a center quadrant has no counterpart
+            let opposite = |quadrant: usize| quadrants.len() - 1 - quadrant;
+            let pairs = free
+                .iter()
+                .filter(|&&quadrant| quadrant < opposite(quadrant));
+            let team_a = player_ids.iter().filter(|id| self.team_a.contains(id));
+            let team_b = player_ids.iter().filter(|id| self.team_b.contains(id));
+            for ((&a, &b), &quadrant) in team_a.zip(team_b).zip(pairs) {
+                assignment.insert(a, quadrant);
+                assignment.insert(b, opposite(quadrant));
+            }
+            free.retain(|quadrant| !assignment.values().any(|taken| taken == quadrant));
+        }
+        for player_id in player_ids {
+            assignment
+                .entry(player_id)
+                .or_insert_with(|| free.pop().unwrap());
+        }
+
+        for (player_id, quadrant) in assignment {
+            if let Some(player) = self.players.get_mut(&player_id) {
+                player.quadrant = Some(quadrants[quadrant]);
+            }
+        }
+    }
+
     pub fn get_state(&self) -> GameState {
         self.state
     }
@@ -137,12 +281,46 @@ impl Game {
             .for_each(|(_, player)| player.is_ready = false);
     }
 
+    /// Adds a joining client to the lobby, it is placed into a team by the caller.
+    /// Games that left the lobby do not accept new players.
+    pub(crate) fn add_player(
+        &mut self,
+        player_id: PlayerID,
+        username: &str,
+    ) -> Result<(), JoinError> {
+        if !matches!(self.state, GameState::Lobby) {
+            return Err(JoinError::GameInProgress);
+        }
+        if self.players.contains_key(&player_id) {
+            return Err(JoinError::AlreadyJoined);
+        }
+        if self.players.values().any(|p| p.name == username) {
+            return Err(JoinError::UsernameTaken);
+        }
+
+        self.players.insert(
+            player_id,
+            Player {
+                id: player_id,
+                name: username.to_owned(),
+                is_ready: false,
+                quadrant: None,
+                last_action_key: None,
+                placed_ships: false,
+                seat_token: format!("{:032x}", thread_rng().gen::<u128>()),
+                ..Default::default()
+            },
+        );
+        Ok(())
+    }
+
     /// Removes a player from the game.
     /// Returns True when the game should be aborted.
     pub(crate) fn remove_player(&mut self, player_id: PlayerID) -> bool {
         if self.players.remove(&player_id).is_some() {
             self.team_a.remove(&player_id);
             self.team_b.remove(&player_id);
+            self.turn_queue.remove(player_id);
 
             match self.state {
                 GameState::Lobby => false,
@@ -154,51 +332,68 @@ impl Game {
         }
     }
 
+    /// Checks whether the player retransmitted an action request that was executed already.
+    /// Requests without an idempotency key are never considered retransmits.
+    pub(crate) fn is_retransmitted_action(&self, player_id: PlayerID, key: Option<u64>) -> bool {
+        key.is_some()
+            && self
+                .players
+                .get(&player_id)
+                .is_some_and(|player| player.last_action_key == key)
+    }
+
+    /// Action points the player has left in the current turn, 0 if it is not their turn.
+    pub(crate) fn action_points_left(&self, player_id: PlayerID) -> u32 {
+        self.turn
+            .as_ref()
+            .filter(|turn| turn.player_id == player_id)
+            .map_or(0, |turn| turn.action_points_left)
+    }
+
+    /// The action points left in the current turn as far as the viewer may know them. Players
+    /// always see their own, others only if `Config.action_points_visible` is set.
+    pub(crate) fn action_points_visible_to(&self, viewer: PlayerID) -> u32 {
+        match self.turn.as_ref() {
+            Some(turn) if turn.player_id == viewer || self.config.action_points_visible => {
+                turn.action_points_left
+            }
+            _ => 0,
+        }
+    }
+
+    pub(crate) fn remember_action_key(&mut self, player_id: PlayerID, key: Option<u64>) {
+        if let (Some(player), Some(_)) = (self.players.get_mut(&player_id), key) {
+            player.last_action_key = key;
+        }
+    }
+
     pub(crate) fn validate_placement_request(
         &self,
         player_id: PlayerID,
         assignments: &[ShipAssignment],
     ) -> Result<HashMap<ShipID, Ship>, ShipPlacementError> {
-        let ship_set = match (
-            self.team_a.contains(&player_id),
-            self.team_b.contains(&player_id),
-        ) {
-            (true, false) => &self.config.ship_set_team_a,
-            (false, true) => &self.config.ship_set_team_b,
-            (false, false) => return Err(ShipPlacementError::PlayerNotInGame),
-            _ => unreachable!(),
+        let ship_set = match self.ship_set_of(player_id) {
+            Some(ship_set) => ship_set,
+            None => return Err(ShipPlacementError::PlayerNotInGame),
         };
 
         let player = self.players.get(&player_id).unwrap();
         let (corner_x, corner_y, quadrant_size) = player.quadrant.unwrap();
         let quadrant = util::quadrant_from_corner((corner_x, corner_y), quadrant_size);
 
-        if (0..ship_set.len())
-            .map(|ship_number| (player_id, ship_number as u32) as ShipID)
-            .any(|ship_id| self.ships.get_by_id(&ship_id).is_some())
+        if player.placed_ships
+            || (0..ship_set.len())
+                .map(|ship_number| (player_id, ship_number as u32) as ShipID)
+                .any(|ship_id| self.ships.get_by_id(&ship_id).is_some())
         {
             return Err(ShipPlacementError::PlayerHasAlreadyPlacedShips);
         }
 
-        let assignments: HashMap<u32, ShipAssignment, RandomState> = HashMap::from_iter(
-            assignments
-                .iter()
-                .enumerate()
-                .map(|(i, assignment)| (i as u32, assignment.clone())),
-        );
-
-        if assignments.len() != ship_set.len() {
-            return Err(ShipPlacementError::InvalidShipSet);
-        }
-
-        let mut ship_manager = ShipManager::new();
-        for (ship_number, assignment) in assignments {
-            let ship_id: ShipID = (player_id, ship_number);
-            if ship_number >= ship_set.len() as u32 {
-                return Err(ShipPlacementError::InvalidShipNumber);
-            }
-
-            let ship_type = match ShipType::from_i32(ship_set[ship_number as usize]) {
+        // assignments beyond the ship set are left to the ship set check
+        let mut ships = Vec::with_capacity(ship_set.len());
+        for (ship_number, assignment) in assignments.iter().enumerate().take(ship_set.len()) {
+            let ship_id: ShipID = (player_id, ship_number as u32);
+            let ship_type = match ShipType::from_i32(ship_set[ship_number]) {
                 None => return Err(ShipPlacementError::InvalidShipType),
                 Some(t) => t,
             };
@@ -207,42 +402,56 @@ impl Game {
                 None => return Err(ShipPlacementError::InvalidShipDirection),
                 Some(d) => d,
             };
+            // an empty set allows every orientation
+            if !self.config.allowed_placement_orientations.is_empty()
+                && !self
+                    .config
+                    .allowed_placement_orientations
+                    .contains(&(direction as i32))
+            {
+                return Err(ShipPlacementError::DisallowedShipDirection);
+            }
 
             let position = match &assignment.coordinate {
-                None => return Err(ShipPlacementError::InvalidShipPosition),
+                None => return Err(ShipPlacementError::MissingShipPosition),
                 Some(v) => (v.x, v.y),
             };
 
-            let ship = Ship::new_from_type(
+            ships.push(Ship::new_from_type(
                 ship_type,
                 ship_id,
                 position,
                 direction.into(),
                 self.config.clone(),
-            );
-
-            if !quadrant.contains_envelope(&ship.envelope()) {
-                return Err(ShipPlacementError::ShipOutOfQuadrant);
-            }
-
-            ship_manager.place_ship(ship_id, ship)?;
+            ));
         }
 
-        Ok(ship_manager.into())
+        validate_placement(&Placement {
+            ships: &ships,
+            assignment_count: assignments.len(),
+            ship_set,
+            quadrant,
+            board: self.board_bounds(),
+            obstacles: &self.ships,
+        })?;
+
+        Ok(ships.into_iter().map(|ship| (ship.id(), ship)).collect())
     }
 
+    /// Ends the turn of the team and begins the next one. The burning ships of the player whose
+    /// turn begins take their damage right away, its result is returned along with the turn.
     pub(crate) fn clear_temp_vision_and_advance_turn(
         &mut self,
         team: &[PlayerID],
         broadcast_tx: &tokio::sync::broadcast::Sender<(Vec<ClientId>, ProtocolMessage)>,
-    ) -> Result<Turn, MessageHandlerError> {
+    ) -> Result<(Turn, ActionResult), MessageHandlerError> {
         if let Some(Turn { temp_vision, .. }) = self.turn.as_ref() {
             if !temp_vision.is_empty() {
                 broadcast_tx
                     .send((
                         team.to_vec(),
                         VisionEvent {
-                            vanished_ship_fields: temp_vision.iter().cloned().collect(),
+                            vanished_ship_fields: sorted_coordinates(temp_vision),
                             discovered_ship_fields: vec![],
                         }
                         .into(),
@@ -250,27 +459,108 @@ impl Game {
                     .map_err(|e| MessageHandlerError::Broadcast(e.into()))?;
             }
         }
+        if let Some(Turn {
+            revealed_to_enemies,
+            ..
+        }) = self.turn.as_ref()
+        {
+            let enemies: Vec<_> = self
+                .players
+                .keys()
+                .filter(|player_id| !team.contains(*player_id))
+                .cloned()
+                .collect();
+            for observers in self.vision_groups(enemies) {
+                // the enemies may have spotted the ships by themselves in the meantime
+                let seen_by_observers = self.ship_parts_seen_by_players(&observers);
+                let vanished = sorted_coordinates(
+                    revealed_to_enemies
+                        .iter()
+                        .filter(|tile| !seen_by_observers.contains(tile)),
+                );
+                if !vanished.is_empty() {
+                    broadcast_tx
+                        .send((
+                            observers,
+                            VisionEvent {
+                                vanished_ship_fields: vanished,
+                                discovered_ship_fields: vec![],
+                            }
+                            .into(),
+                        ))
+                        .map_err(|e| MessageHandlerError::Broadcast(e.into()))?;
+                }
+            }
+        }
+
+        let turn = self.advance_turn();
+        // burning ships take their damage as soon as the turn of their owner begins
+        let burn_result = burn_ships(self, turn.player_id);
+        if let (Some(audit_log), ActionResult::Single { .. }) =
+            (self.audit_log.as_ref(), &burn_result)
+        {
+            audit_log.record_burn(self, turn.player_id, &burn_result);
+        }
+        Ok((turn, burn_result))
+    }
+
+    /// Number of the current turn, counting from 1 for the first turn of the game.
+    pub(crate) fn turn_number(&self) -> u32 {
+        self.turns_played + 1
+    }
+
+    /// Ends the current turn, if there is one, and records a snapshot of the board.
+    pub(crate) fn end_turn(&mut self) {
+        let (player_id, action_points_left, abilities_used) = match self.turn.as_mut() {
+            Some(turn) => (
+                turn.player_id,
+                turn.action_points_left,
+                std::mem::take(&mut turn.abilities_used),
+            ),
+            None => return,
+        };
+        if let Some(player) = self.players.get_mut(&player_id) {
+            player.unspent_action_points = action_points_left;
+            player.abilities_used_last_turn = abilities_used;
+        }
 
-        Ok(self.advance_turn())
+        self.turns_played += 1;
+        if let Some(snapshot_log) = self.snapshot_log.as_ref() {
+            snapshot_log.record(self);
+        }
     }
 
     pub(crate) fn advance_turn(&mut self) -> Turn {
+        self.end_turn();
+
+        let player = match self.next_in_turn_order() {
+            Some(player_id) => &self.players[&player_id],
+            None => {
+                // players are iterated in a different order each run, only the seed may decide
+                let mut player_ids: Vec<_> = self.players.keys().collect();
+                player_ids.sort_unstable();
+                &self.players[*player_ids.choose(&mut self.rng).unwrap()]
+            }
+        };
         let turn = Turn::new(
-            *self
-                .players
-                .keys()
-                .choose_stable(&mut thread_rng())
-                .unwrap(),
-            self.config.action_point_gain,
+            player.id,
+            starting_action_points(&self.config, player.unspent_action_points),
         );
 
+        let owner_reduction = self.config.owner_cooldown_reduction;
         self.ships.iter_ships_mut().for_each(|(_, ship)| {
+            // ships of the player whose turn begins may cool down faster
+            let reduction = if ship.id().0 == turn.player_id {
+                1 + owner_reduction
+            } else {
+                1
+            };
             ship.cool_downs_mut().retain_mut(|cd| match cd {
                 Cooldown::Movement { remaining_rounds }
                 | Cooldown::Rotate { remaining_rounds }
                 | Cooldown::Cannon { remaining_rounds }
                 | Cooldown::Ability { remaining_rounds } => {
-                    *remaining_rounds = remaining_rounds.saturating_sub(1);
+                    *remaining_rounds = remaining_rounds.saturating_sub(reduction);
 
                     *remaining_rounds > 0
                 }
@@ -281,6 +571,56 @@ impl Game {
         turn
     }
 
+    /// Queues the players for their turns as the game starts. The teams take turns alternately
+    /// as far as their sizes allow, the order within the teams and the first team are drawn at
+    /// random.
+    pub(crate) fn queue_turns(&mut self) {
+        let mut teams = [&self.team_a, &self.team_b].map(|team| {
+            let mut player_ids: Vec<_> = team.iter().cloned().collect();
+            // players are iterated in a different order each run, only the seed may decide
+            player_ids.sort_unstable();
+            player_ids
+        });
+        teams
+            .iter_mut()
+            .for_each(|team| team.shuffle(&mut self.rng));
+        if self.rng.gen() {
+            teams.reverse();
+        }
+
+        let [first, second] = teams;
+        let mut order = Vec::with_capacity(first.len() + second.len());
+        for position in 0..first.len().max(second.len()) {
+            order.extend(first.get(position));
+            order.extend(second.get(position));
+        }
+        self.turn_queue = TurnQueue::new(order);
+    }
+
+    /// Number of turns before the turn of the player, 0 during their own turn and while each turn
+    /// goes to a random player.
+    pub(crate) fn turns_until(&self, player_id: PlayerID) -> u32 {
+        match self.turn.as_ref() {
+            Some(turn) if turn.player_id == player_id => 0,
+            _ => self
+                .turn_queue
+                .position_of(player_id)
+                .map_or(0, |position| position as u32 + 1),
+        }
+    }
+
+    /// Moves the turn queue on to the next player who is still in the game.
+    fn next_in_turn_order(&mut self) -> Option<PlayerID> {
+        loop {
+            match self.turn_queue.advance() {
+                Some(player_id) if !self.players.contains_key(&player_id) => {
+                    self.turn_queue.remove(player_id)
+                }
+                next_player => return next_player,
+            }
+        }
+    }
+
     pub(crate) fn game_result(&self) -> GameResult {
         match (
             self.ships.get_for_players(&self.team_a).len(),
@@ -292,6 +632,42 @@ impl Game {
             _ => GameResult::Pending,
         }
     }
+
+    /// Decides a game that ends before a team is eliminated, e.g. because it ran too long.
+    /// The criteria in `Config.tiebreakers` are compared in their order and the first one that
+    /// tells the teams apart decides the winner, the game is a draw if none does.
+    pub(crate) fn resolve_tie(&self) -> GameResult {
+        let ships_a = self.ships.get_for_players(&self.team_a).len() as u32;
+        let ships_b = self.ships.get_for_players(&self.team_b).len() as u32;
+        let (health_a, health_b) = self.ships.health_by_team(&self.team_a, &self.team_b);
+
+        for tiebreaker in self
+            .config
+            .tiebreakers
+            .iter()
+            .filter_map(|tiebreaker| Tiebreaker::from_i32(*tiebreaker))
+        {
+            let (score_a, score_b) = match tiebreaker {
+                Tiebreaker::ShipsRemaining => (ships_a, ships_b),
+                Tiebreaker::TotalHealth => (health_a, health_b),
+            };
+            match score_a.cmp(&score_b) {
+                Ordering::Greater => return GameResult::Win(Teams::TeamA),
+                Ordering::Less => return GameResult::Win(Teams::TeamB),
+                Ordering::Equal => {}
+            }
+        }
+        GameResult::Draw
+    }
+
+    /// The team winning a game that is aborted because a player left, the players still in the
+    /// game are compared by `resolve_tie`. Games that did not begin yet have no winner.
+    pub(crate) fn winner_on_abort(&self) -> Teams {
+        match (self.state, self.resolve_tie()) {
+            (GameState::InGame, GameResult::Win(team)) => team,
+            _ => Teams::None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -300,6 +676,20 @@ pub struct Player {
     pub(crate) name: String,
     pub(crate) is_ready: bool,
     pub(crate) quadrant: Option<(u32, u32, u32)>,
+    /// Idempotency key of the last executed action request.
+    pub(crate) last_action_key: Option<u64>,
+    /// Action points charged for the last executed action request.
+    pub(crate) last_action_cost: u32,
+    /// Set once the ships were placed, a placement cannot be revised.
+    pub(crate) placed_ships: bool,
+    /// Action points left at the end of the player's last turn.
+    pub(crate) unspent_action_points: u32,
+    /// Ships that used their ability during the player's last turn.
+    pub(crate) abilities_used_last_turn: HashSet<ShipID>,
+    /// The player is readied or kicked if they are not ready in the lobby by then.
+    pub(crate) ready_deadline: Option<Instant>,
+    /// Handed to the player on joining, it lets them take their seat again after a restart.
+    pub(crate) seat_token: String,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -307,6 +697,12 @@ pub struct Turn {
     pub(crate) player_id: PlayerID,
     pub(crate) action_points_left: u32,
     pub(crate) temp_vision: HashSet<Coordinate>,
+    /// Tiles of ships that fired during this turn, shown to their enemies until the turn ends.
+    pub(crate) revealed_to_enemies: HashSet<Coordinate>,
+    /// Number of moves each ship made during this turn.
+    pub(crate) ship_moves: HashMap<ShipID, u32>,
+    /// Ships that used their ability during this turn.
+    pub(crate) abilities_used: HashSet<ShipID>,
 }
 
 impl Turn {
@@ -315,6 +711,26 @@ impl Turn {
             player_id,
             action_points_left: initial_action_points,
             temp_vision: Default::default(),
+            revealed_to_enemies: Default::default(),
+            ship_moves: Default::default(),
+            abilities_used: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinError {
+    AlreadyJoined,
+    GameInProgress,
+    UsernameTaken,
+}
+
+impl Display for JoinError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JoinError::AlreadyJoined => f.write_str("you joined already"),
+            JoinError::GameInProgress => f.write_str("the game is already in progress"),
+            JoinError::UsernameTaken => f.write_str("username is already taken"),
         }
     }
 }
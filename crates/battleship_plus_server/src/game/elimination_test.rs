@@ -0,0 +1,37 @@
+use battleship_plus_common::game::ActionValidationError;
+use battleship_plus_common::types::Teams;
+
+use crate::game::actions::check_player_not_eliminated;
+use crate::game::game_builder::GameBuilder;
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+use crate::game::states::GameState;
+
+#[tokio::test]
+async fn eliminated_player_action_rejected() {
+    let mut game = GameBuilder::new()
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamA)
+        .player(3, Teams::TeamB)
+        .ships([1, 3].map(|owner| {
+            GeneralShipBuilder::default()
+                .owner(owner)
+                .position(owner as i32 * 10, 0)
+                .destroyer()
+                .build()
+        }))
+        .turn_order(&[2, 1, 3])
+        .turn(2, 10)
+        .build();
+
+    // it is their turn, but player 2 has no ships left
+    assert!(matches!(
+        check_player_not_eliminated(&game, 2),
+        Err(ActionValidationError::PlayerEliminated)
+    ));
+    assert!(check_player_not_eliminated(&game, 1).is_ok());
+    assert!(check_player_not_eliminated(&game, 3).is_ok());
+
+    // nobody has placed ships before the game started
+    game.state = GameState::Preparation;
+    assert!(check_player_not_eliminated(&game, 2).is_ok());
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use battleship_plus_common::game::ship::Ship;
+use battleship_plus_common::game::ship_manager::ShipManager;
+use battleship_plus_common::game::PlayerID;
+use battleship_plus_common::types::{Config, Teams};
+
+use crate::config_provider::default_config_provider;
+use crate::game::data::{Game, Player, Turn};
+use crate::game::states::GameState;
+use crate::game::turn_queue::TurnQueue;
+
+/// Sets up games for multi-turn scenarios without going through the lobby and preparation phase.
+/// The game is running already and uses the default configuration unless configured otherwise.
+#[derive(Debug, Clone)]
+pub struct GameBuilder {
+    config: Arc<Config>,
+    players: Vec<(PlayerID, Teams)>,
+    ships: Vec<Ship>,
+    turn_order: Vec<PlayerID>,
+    turn: Option<Turn>,
+    seed: Option<u64>,
+}
+
+impl Default for GameBuilder {
+    fn default() -> Self {
+        GameBuilder {
+            config: default_config_provider().game_config(),
+            players: vec![],
+            ships: vec![],
+            turn_order: vec![],
+            turn: None,
+            seed: None,
+        }
+    }
+}
+
+impl GameBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn config(&mut self, config: Config) -> &mut Self {
+        self.config = Arc::new(config);
+        self
+    }
+
+    /// Adds a player to the team, players of `Teams::None` are part of neither team.
+    pub fn player(&mut self, player_id: PlayerID, team: Teams) -> &mut Self {
+        self.players.push((player_id, team));
+        self
+    }
+
+    pub fn ships(&mut self, ships: impl IntoIterator<Item = Ship>) -> &mut Self {
+        self.ships.extend(ships);
+        self
+    }
+
+    pub fn turn_order(&mut self, turn_order: &[PlayerID]) -> &mut Self {
+        self.turn_order = turn_order.to_vec();
+        self
+    }
+
+    /// Lets the game start in the middle of the given turn.
+    pub fn turn(&mut self, player_id: PlayerID, action_points: u32) -> &mut Self {
+        self.turn = Some(Turn::new(player_id, action_points));
+        self
+    }
+
+    /// Makes the random choices of the game reproducible, otherwise the seed is random.
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn build(&mut self) -> Game {
+        let mut game = Game::new(self.config.clone());
+        game.state = GameState::InGame;
+        for &(player_id, team) in self.players.iter() {
+            game.players.insert(
+                player_id,
+                Player {
+                    id: player_id,
+                    name: format!("Player {player_id}"),
+                    placed_ships: !self.ships.is_empty(),
+                    ..Default::default()
+                },
+            );
+            match team {
+                Teams::TeamA => game.team_a.insert(player_id),
+                Teams::TeamB => game.team_b.insert(player_id),
+                Teams::None => false,
+            };
+        }
+        game.ships = ShipManager::new_with_ships(self.ships.clone());
+        game.ships.set_islands(&self.config.islands);
+        game.ships
+            .set_line_of_sight_vision(self.config.line_of_sight_vision);
+        game.turn_queue = TurnQueue::new(self.turn_order.clone());
+        if let Some(turn) = self.turn.as_ref() {
+            game.turn_queue.continue_after(turn.player_id);
+        }
+        game.turn = self.turn.clone();
+        if let Some(seed) = self.seed {
+            game.reseed(seed);
+        }
+        game
+    }
+}
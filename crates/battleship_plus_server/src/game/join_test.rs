@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use battleship_plus_common::types::Config;
+
+use crate::config_provider::default_config_provider;
+use crate::game::data::{Game, JoinError};
+use crate::game::states::GameState;
+
+#[tokio::test]
+async fn join_rejected_while_game_in_progress() {
+    let mut game = Game {
+        state: GameState::InGame,
+        team_a: HashSet::from([1]),
+        team_b: HashSet::from([2]),
+        ..Default::default()
+    };
+
+    assert_eq!(game.add_player(3, "late"), Err(JoinError::GameInProgress));
+    assert!(!game.players.contains_key(&3));
+
+    game.state = GameState::Preparation;
+    assert_eq!(game.add_player(3, "late"), Err(JoinError::GameInProgress));
+    assert!(!game.players.contains_key(&3));
+}
+
+#[tokio::test]
+async fn join_lobby() {
+    let mut game = Game {
+        state: GameState::Lobby,
+        ..Default::default()
+    };
+
+    assert_eq!(game.add_player(1, "first"), Ok(()));
+    assert_eq!(game.add_player(1, "first"), Err(JoinError::AlreadyJoined));
+    assert_eq!(game.add_player(2, "first"), Err(JoinError::UsernameTaken));
+    assert_eq!(game.add_player(2, "second"), Ok(()));
+    assert_eq!(game.players.len(), 2);
+}
+
+#[tokio::test]
+async fn readiness_below_min_players_stays_in_lobby() {
+    let mut game = Game::new(Arc::new(Config {
+        team_size_a: 1,
+        team_size_b: 0,
+        min_players: 2,
+        ..(*default_config_provider().game_config()).clone()
+    }));
+    assert!(game.check_game_config().is_err());
+
+    assert_eq!(game.add_player(1, "solo"), Ok(()));
+    game.team_a.insert(1);
+    game.players.get_mut(&1).unwrap().is_ready = true;
+    assert!(!game.can_change_into_preparation_phase());
+
+    game.config = Arc::new(Config {
+        min_players: 1,
+        ..(*game.config).clone()
+    });
+    assert!(game.can_change_into_preparation_phase());
+}
@@ -0,0 +1,190 @@
+use std::fmt::{Display, Formatter};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use log::warn;
+use tokio::sync::mpsc;
+
+use battleship_plus_common::game::PlayerID;
+use battleship_plus_common::types::Teams;
+
+use crate::game::data::Game;
+use crate::game::states::GameState;
+
+/// A seat in the lobby, a player joining with its token takes it over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SavedSeat {
+    pub(crate) name: String,
+    pub(crate) token: String,
+    pub(crate) team: Teams,
+    pub(crate) is_ready: bool,
+}
+
+#[derive(Debug)]
+pub(crate) enum LobbyFileError {
+    Io(std::io::Error),
+    /// The line, counted from 1, does not describe a seat.
+    Malformed(usize),
+}
+
+impl Display for LobbyFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LobbyFileError::Io(e) => write!(f, "{e}"),
+            LobbyFileError::Malformed(line) => write!(f, "line {line} is not a seat"),
+        }
+    }
+}
+
+/// Keeps the seats of a lobby in a file, so players can take their seats again after the server
+/// was restarted. Each line holds the team of a seat, whether its player was ready, the token of
+/// the seat and the name of the player, separated by tabs.
+#[derive(Debug, Clone)]
+pub(crate) struct LobbyFile {
+    path: PathBuf,
+    writes: mpsc::UnboundedSender<String>,
+}
+
+impl LobbyFile {
+    /// Starts the task writing the file, the lobby is saved in the order of the changes without
+    /// blocking the players of the game.
+    pub(crate) fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let (writes, mut contents) = mpsc::unbounded_channel::<String>();
+        let writer_path = path.clone();
+        tokio::spawn(async move {
+            while let Some(content) = contents.recv().await {
+                if let Err(e) = tokio::fs::write(&writer_path, content).await {
+                    warn!("Unable to save the lobby to {}: {e}", writer_path.display());
+                }
+            }
+        });
+        LobbyFile { path, writes }
+    }
+
+    /// The seats saved by an earlier run of the server, there are none without a file.
+    pub(crate) fn load(&self) -> Result<Vec<SavedSeat>, LobbyFileError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => parse_seats(&content),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => Err(LobbyFileError::Io(e)),
+        }
+    }
+
+    /// Replaces the saved seats with the current lobby of the game.
+    /// Games that left the lobby keep the seats they started with.
+    pub(crate) fn save(&self, game: &Game) {
+        if !matches!(game.state, GameState::Lobby) {
+            return;
+        }
+        if self.writes.send(format_seats(&seats_of(game))).is_err() {
+            warn!(
+                "Unable to save the lobby to {}: the writer stopped",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// The seats taken in the game and the saved seats nobody took yet, ordered by name.
+pub(crate) fn seats_of(game: &Game) -> Vec<SavedSeat> {
+    let mut seats: Vec<_> = game
+        .players
+        .values()
+        .filter_map(|player| {
+            let team = if game.team_a.contains(&player.id) {
+                Teams::TeamA
+            } else if game.team_b.contains(&player.id) {
+                Teams::TeamB
+            } else {
+                return None;
+            };
+            Some(SavedSeat {
+                name: player.name.clone(),
+                token: player.seat_token.clone(),
+                team,
+                is_ready: player.is_ready,
+            })
+        })
+        .chain(game.saved_seats.iter().cloned())
+        .collect();
+    seats.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    seats
+}
+
+pub(crate) fn format_seats(seats: &[SavedSeat]) -> String {
+    seats
+        .iter()
+        // a line break would split the seat, such names cannot be restored
+        .filter(|seat| !seat.name.contains(['\n', '\r']))
+        .map(|seat| {
+            let team = match seat.team {
+                Teams::TeamB => "B",
+                _ => "A",
+            };
+            let ready = if seat.is_ready { "ready" } else { "waiting" };
+            format!("{team}\t{ready}\t{}\t{}\n", seat.token, seat.name)
+        })
+        .collect()
+}
+
+pub(crate) fn parse_seats(content: &str) -> Result<Vec<SavedSeat>, LobbyFileError> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(index, line)| {
+            let malformed = || LobbyFileError::Malformed(index + 1);
+            let mut fields = line.splitn(4, '\t');
+            let team = match fields.next() {
+                Some("A") => Teams::TeamA,
+                Some("B") => Teams::TeamB,
+                _ => return Err(malformed()),
+            };
+            let is_ready = match fields.next() {
+                Some("ready") => true,
+                Some("waiting") => false,
+                _ => return Err(malformed()),
+            };
+            let token = match fields.next() {
+                Some(token) if !token.is_empty() => token.to_string(),
+                _ => return Err(malformed()),
+            };
+            let name = fields.next().ok_or_else(malformed)?.to_string();
+            Ok(SavedSeat {
+                name,
+                token,
+                team,
+                is_ready,
+            })
+        })
+        .collect()
+}
+
+/// Places a player who joined the game with the token of a saved seat on it, if its team has
+/// capacity left. The player keeps the token. Returns whether the player took a saved seat.
+pub(crate) fn claim_saved_seat(game: &mut Game, player_id: PlayerID, token: Option<&str>) -> bool {
+    let token = match token {
+        Some(token) => token,
+        None => return false,
+    };
+    let index = match game.saved_seats.iter().position(|seat| seat.token == token) {
+        Some(index) => index,
+        None => return false,
+    };
+    let (team, size) = match game.saved_seats[index].team {
+        Teams::TeamB => (&mut game.team_b, game.config.team_size_b),
+        _ => (&mut game.team_a, game.config.team_size_a),
+    };
+    if team.len() >= size as usize {
+        return false;
+    }
+    team.insert(player_id);
+
+    let seat = game.saved_seats.swap_remove(index);
+    if let Some(player) = game.players.get_mut(&player_id) {
+        player.is_ready = seat.is_ready;
+        player.seat_token = seat.token;
+    }
+    true
+}
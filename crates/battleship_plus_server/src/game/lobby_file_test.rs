@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+
+use battleship_plus_common::game::PlayerID;
+
+use crate::game::data::Game;
+use crate::game::lobby_file::{
+    claim_saved_seat, format_seats, parse_seats, seats_of, LobbyFileError,
+};
+
+fn team_names(game: &Game, team: &HashSet<PlayerID>) -> HashSet<String> {
+    team.iter()
+        .map(|id| game.players[id].name.clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn saved_lobby_restores_teams() {
+    let mut game = Game::default();
+    for (player_id, name) in [(1, "alice"), (2, "bob"), (3, "carol"), (4, "dave")] {
+        game.add_player(player_id, name).unwrap();
+    }
+    game.team_a = HashSet::from([1, 4]);
+    game.team_b = HashSet::from([2, 3]);
+    game.players.get_mut(&2).unwrap().is_ready = true;
+
+    let saved = format_seats(&seats_of(&game));
+    let mut restored = Game {
+        saved_seats: parse_seats(&saved).unwrap(),
+        ..Default::default()
+    };
+    assert_eq!(restored.saved_seats, seats_of(&game));
+
+    // after the restart, the players reconnect in a different order with new ids
+    for (player_id, name, old_id) in [
+        (10, "dave", 4),
+        (11, "bob", 2),
+        (12, "carol", 3),
+        (13, "alice", 1),
+    ] {
+        let token = game.players[&old_id].seat_token.clone();
+        restored.add_player(player_id, name).unwrap();
+        assert!(claim_saved_seat(&mut restored, player_id, Some(&token)));
+        assert_eq!(restored.players[&player_id].seat_token, token);
+    }
+    assert_eq!(
+        team_names(&restored, &restored.team_a),
+        team_names(&game, &game.team_a)
+    );
+    assert_eq!(
+        team_names(&restored, &restored.team_b),
+        team_names(&game, &game.team_b)
+    );
+    assert!(restored.players[&11].is_ready);
+    assert!(!restored.players[&13].is_ready);
+    assert!(restored.saved_seats.is_empty());
+
+    // strangers are placed as usual
+    restored.add_player(14, "eve").unwrap();
+    assert!(!claim_saved_seat(&mut restored, 14, None));
+}
+
+#[tokio::test]
+async fn saved_seat_requires_its_token() {
+    let mut game = Game::default();
+    game.add_player(1, "alice").unwrap();
+    game.team_b = HashSet::from([1]);
+    let mut restored = Game {
+        saved_seats: seats_of(&game),
+        ..Default::default()
+    };
+
+    // taking the name of a player is not enough to take their seat
+    restored.add_player(10, "alice").unwrap();
+    assert!(!claim_saved_seat(&mut restored, 10, None));
+    assert!(!claim_saved_seat(&mut restored, 10, Some("0123")));
+    assert!(restored.team_b.is_empty());
+    assert_eq!(restored.saved_seats.len(), 1);
+
+    let token = game.players[&1].seat_token.clone();
+    assert!(claim_saved_seat(&mut restored, 10, Some(&token)));
+    assert_eq!(restored.team_b, HashSet::from([10]));
+}
+
+#[tokio::test]
+async fn malformed_lobby_file_rejected() {
+    assert!(matches!(
+        parse_seats("A\tready\t01\talice\nC\twaiting\t02\tbob\n"),
+        Err(LobbyFileError::Malformed(2))
+    ));
+    assert!(matches!(
+        parse_seats("B\tready\t\talice\n"),
+        Err(LobbyFileError::Malformed(1))
+    ));
+    assert_eq!(parse_seats("").unwrap(), vec![]);
+}
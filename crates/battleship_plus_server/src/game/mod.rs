@@ -1,8 +1,52 @@
 pub(crate) mod actions;
+pub(crate) mod area_effects;
+pub(crate) mod audit;
 pub(crate) mod data;
+pub(crate) mod lobby_file;
+pub(crate) mod objectives;
+pub(crate) mod placement;
+pub(crate) mod ready_timeout;
+pub(crate) mod registry;
+pub(crate) mod snapshot;
 pub(crate) mod states;
+pub(crate) mod teams;
+pub(crate) mod turn_queue;
 
+#[cfg(test)]
+mod ability_repeat_test;
+#[cfg(test)]
+mod action_points_test;
 #[cfg(test)]
 mod actions_test;
 #[cfg(test)]
-mod ship_builder;
+mod area_effects_test;
+#[cfg(test)]
+mod cooldowns_test;
+#[cfg(test)]
+mod elimination_test;
+#[cfg(test)]
+pub(crate) mod game_builder;
+#[cfg(test)]
+mod join_test;
+#[cfg(test)]
+mod lobby_file_test;
+#[cfg(test)]
+mod objectives_test;
+#[cfg(test)]
+mod placement_test;
+#[cfg(test)]
+mod ready_timeout_test;
+#[cfg(test)]
+mod registry_test;
+#[cfg(test)]
+pub(crate) mod ship_builder;
+#[cfg(test)]
+mod snapshot_test;
+#[cfg(test)]
+mod teams_test;
+#[cfg(test)]
+mod tiebreak_test;
+#[cfg(test)]
+mod turn_order_test;
+#[cfg(test)]
+mod turn_queue_test;
@@ -0,0 +1,64 @@
+use battleship_plus_common::game::ship::{GetShipID, Ship};
+use battleship_plus_common::game::PlayerID;
+use battleship_plus_common::messages::ObjectiveEvent;
+use battleship_plus_common::types::{Objective, Teams};
+
+use crate::game::data::Game;
+
+/// Detects notable moments caused by the ships the attacker just destroyed:
+/// the first ship destroyed in the game and teams reduced to their last ship.
+/// The destroyed ships have to be removed from the game already.
+pub(crate) fn detect_objectives(
+    game: &mut Game,
+    attacker: PlayerID,
+    ships_destroyed: &[Ship],
+) -> Vec<ObjectiveEvent> {
+    let mut events = vec![];
+    if ships_destroyed.is_empty() {
+        return events;
+    }
+
+    if !game.first_blood_taken {
+        game.first_blood_taken = true;
+        events.push(ObjectiveEvent {
+            objective: Objective::FirstBlood.into(),
+            team: team_of(game, attacker).into(),
+            player_id: attacker,
+            remaining_health: 0,
+        });
+    }
+
+    let (health_a, health_b) = game.ships.health_by_team(&game.team_a, &game.team_b);
+    for (team, members, remaining_health) in [
+        (Teams::TeamA, &game.team_a, health_a),
+        (Teams::TeamB, &game.team_b, health_b),
+    ] {
+        // the ship count only drops, so a team lost its second to last ship right now
+        // if it has exactly one left and one of its ships was destroyed
+        let lost_ship = ships_destroyed
+            .iter()
+            .any(|ship| members.contains(&ship.id().0));
+        let remaining_ships = game.ships.get_for_players(members);
+        if lost_ship && remaining_ships.len() == 1 {
+            let (owner, _) = remaining_ships.into_iter().next().unwrap();
+            events.push(ObjectiveEvent {
+                objective: Objective::LastShip.into(),
+                team: team.into(),
+                player_id: owner,
+                remaining_health,
+            });
+        }
+    }
+
+    events
+}
+
+fn team_of(game: &Game, player_id: PlayerID) -> Teams {
+    if game.team_a.contains(&player_id) {
+        Teams::TeamA
+    } else if game.team_b.contains(&player_id) {
+        Teams::TeamB
+    } else {
+        Teams::None
+    }
+}
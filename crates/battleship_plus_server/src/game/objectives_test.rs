@@ -0,0 +1,65 @@
+use std::collections::HashSet;
+
+use battleship_plus_common::game::ship::Ship;
+use battleship_plus_common::game::ship_manager::ShipManager;
+use battleship_plus_common::types::{Objective, Teams};
+
+use crate::game::data::Game;
+use crate::game::objectives::detect_objectives;
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+use crate::game::states::GameState;
+
+fn destroyer(owner: u32, number: u32, health: u32) -> Ship {
+    GeneralShipBuilder::default()
+        .owner(owner)
+        .number(number)
+        .health(health)
+        .position(owner as i32 * 10, number as i32 * 10)
+        .destroyer()
+        .build()
+}
+
+fn destroy(game: &mut Game, ship: &Ship) -> Vec<Ship> {
+    game.ships.destroy_ships(vec![&ship.data().id]);
+    vec![ship.clone()]
+}
+
+#[tokio::test]
+async fn objectives_first_blood_once_per_game() {
+    let ships = vec![
+        destroyer(1, 0, 10),
+        destroyer(2, 0, 10),
+        destroyer(2, 1, 10),
+        destroyer(2, 2, 7),
+    ];
+    let mut game = Game {
+        state: GameState::InGame,
+        team_a: HashSet::from([1]),
+        team_b: HashSet::from([2]),
+        ships: ShipManager::new_with_ships(ships.clone()),
+        ..Default::default()
+    };
+
+    // nothing destroyed, nothing to announce
+    assert!(detect_objectives(&mut game, 1, &[]).is_empty());
+
+    let destroyed = destroy(&mut game, &ships[1]);
+    let events = detect_objectives(&mut game, 1, &destroyed);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].objective, Objective::FirstBlood as i32);
+    assert_eq!(events[0].team, Teams::TeamA as i32);
+    assert_eq!(events[0].player_id, 1);
+
+    // the next destruction leaves team B with its last ship, but is no first blood anymore
+    let destroyed = destroy(&mut game, &ships[2]);
+    let events = detect_objectives(&mut game, 1, &destroyed);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].objective, Objective::LastShip as i32);
+    assert_eq!(events[0].team, Teams::TeamB as i32);
+    assert_eq!(events[0].player_id, 2);
+    assert_eq!(events[0].remaining_health, 7);
+
+    // team A loses its only ship, which is no last ship event
+    let destroyed = destroy(&mut game, &ships[0]);
+    assert!(detect_objectives(&mut game, 2, &destroyed).is_empty());
+}
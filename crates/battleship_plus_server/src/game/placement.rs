@@ -0,0 +1,95 @@
+use rstar::{Envelope, RTreeObject, AABB};
+
+use battleship_plus_common::game::ship::{GetShipID, Ship};
+use battleship_plus_common::game::ship_manager::{ShipManager, ShipPlacementError};
+
+/// The ships a player wants to place, decoded from their assignments,
+/// together with everything they are checked against.
+pub(crate) struct Placement<'a> {
+    /// One ship per assignment, numbered in order of the assignments.
+    /// Assignments without an entry in the ship set have no ship.
+    pub(crate) ships: &'a [Ship],
+    pub(crate) assignment_count: usize,
+    pub(crate) ship_set: &'a [i32],
+    pub(crate) quadrant: AABB<[i32; 2]>,
+    pub(crate) board: AABB<[i32; 2]>,
+    pub(crate) obstacles: &'a ShipManager,
+}
+
+type PlacementCheck = fn(&Placement) -> Result<(), ShipPlacementError>;
+
+/// The checks run for every placement, the first one failing is reported.
+const PLACEMENT_CHECKS: [PlacementCheck; 5] = [
+    check_in_quadrant,
+    check_on_board,
+    check_no_self_collision,
+    check_no_obstacle_collision,
+    check_ship_set,
+];
+
+pub(crate) fn validate_placement(placement: &Placement) -> Result<(), ShipPlacementError> {
+    PLACEMENT_CHECKS
+        .iter()
+        .try_for_each(|check| check(placement))
+}
+
+fn check_in_quadrant(placement: &Placement) -> Result<(), ShipPlacementError> {
+    if placement
+        .ships
+        .iter()
+        .any(|ship| !placement.quadrant.contains_envelope(&ship.envelope()))
+    {
+        return Err(ShipPlacementError::ShipOutOfQuadrant);
+    }
+    Ok(())
+}
+
+/// Quadrants normally lie within the board, this catches quadrants reaching past its edges.
+fn check_on_board(placement: &Placement) -> Result<(), ShipPlacementError> {
+    if placement
+        .ships
+        .iter()
+        .any(|ship| !placement.board.contains_envelope(&ship.envelope()))
+    {
+        return Err(ShipPlacementError::ShipOutOfBounds);
+    }
+    Ok(())
+}
+
+fn check_no_self_collision(placement: &Placement) -> Result<(), ShipPlacementError> {
+    let ships = placement.ships;
+    if ships.iter().enumerate().any(|(i, ship)| {
+        ships[i + 1..]
+            .iter()
+            .any(|other| ship.envelope().intersects(&other.envelope()))
+    }) {
+        return Err(ShipPlacementError::Collision);
+    }
+    Ok(())
+}
+
+fn check_no_obstacle_collision(placement: &Placement) -> Result<(), ShipPlacementError> {
+    if placement
+        .ships
+        .iter()
+        .any(|ship| placement.obstacles.overlaps_island(&ship.envelope()))
+    {
+        return Err(ShipPlacementError::IslandCollision);
+    }
+    Ok(())
+}
+
+/// Every entry of the ship set has to be placed exactly once, as a ship of that type.
+fn check_ship_set(placement: &Placement) -> Result<(), ShipPlacementError> {
+    let ship_set = placement.ship_set;
+    if placement.assignment_count != ship_set.len()
+        || placement.ships.len() != ship_set.len()
+        || placement
+            .ships
+            .iter()
+            .any(|ship| ship_set.get(ship.id().1 as usize) != Some(&(ship.ship_type() as i32)))
+    {
+        return Err(ShipPlacementError::InvalidShipSet);
+    }
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use rstar::AABB;
+
+use battleship_plus_common::game::ship::Ship;
+use battleship_plus_common::game::ship_manager::{ShipManager, ShipPlacementError};
+use battleship_plus_common::types::{Coordinate, ShipType};
+
+use crate::game::placement::{validate_placement, Placement};
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+
+fn destroyer(number: u32, x: i32, y: i32) -> Ship {
+    GeneralShipBuilder::default()
+        .owner(1)
+        .number(number)
+        .position(x, y)
+        .destroyer()
+        .build()
+}
+
+fn obstacles() -> ShipManager {
+    let mut obstacles = ShipManager::new();
+    obstacles.set_islands(&[Coordinate { x: 5, y: 5 }]);
+    obstacles
+}
+
+/// Validates the ships against a 10x10 quadrant in the corner of a 20x20 board with an island
+/// at (5, 5) and a ship set of two destroyers.
+fn validate(ships: &[Ship]) -> Result<(), ShipPlacementError> {
+    validate_placement(&Placement {
+        ships,
+        assignment_count: ships.len(),
+        ship_set: &[ShipType::Destroyer as i32, ShipType::Destroyer as i32],
+        quadrant: AABB::from_corners([0, 0], [9, 9]),
+        board: AABB::from_corners([0, 0], [19, 19]),
+        obstacles: &obstacles(),
+    })
+}
+
+#[tokio::test]
+async fn placement_valid() {
+    assert!(validate(&[destroyer(0, 0, 0), destroyer(1, 2, 0)]).is_ok());
+}
+
+#[tokio::test]
+async fn placement_out_of_quadrant() {
+    // the destroyer reaches from (9, 9) to (9, 10)
+    assert!(matches!(
+        validate(&[destroyer(0, 0, 0), destroyer(1, 9, 9)]),
+        Err(ShipPlacementError::ShipOutOfQuadrant)
+    ));
+}
+
+#[tokio::test]
+async fn placement_out_of_board() {
+    let ships = [destroyer(0, 0, 0), destroyer(1, 4, 4)];
+    let result = validate_placement(&Placement {
+        ships: &ships,
+        assignment_count: ships.len(),
+        ship_set: &[ShipType::Destroyer as i32, ShipType::Destroyer as i32],
+        quadrant: AABB::from_corners([0, 0], [9, 9]),
+        board: AABB::from_corners([0, 0], [4, 4]),
+        obstacles: &ShipManager::new(),
+    });
+    assert!(matches!(result, Err(ShipPlacementError::ShipOutOfBounds)));
+}
+
+#[tokio::test]
+async fn placement_self_collision() {
+    assert!(matches!(
+        validate(&[destroyer(0, 0, 0), destroyer(1, 0, 1)]),
+        Err(ShipPlacementError::Collision)
+    ));
+}
+
+#[tokio::test]
+async fn placement_obstacle_collision() {
+    assert!(matches!(
+        validate(&[destroyer(0, 0, 0), destroyer(1, 5, 4)]),
+        Err(ShipPlacementError::IslandCollision)
+    ));
+}
+
+#[tokio::test]
+async fn placement_ship_set() {
+    assert!(matches!(
+        validate(&[destroyer(0, 0, 0)]),
+        Err(ShipPlacementError::InvalidShipSet)
+    ));
+
+    let ships = [destroyer(0, 0, 0), destroyer(1, 2, 0)];
+    let mut placement = Placement {
+        ships: &ships,
+        assignment_count: 3,
+        ship_set: &[ShipType::Destroyer as i32, ShipType::Destroyer as i32],
+        quadrant: AABB::from_corners([0, 0], [9, 9]),
+        board: AABB::from_corners([0, 0], [19, 19]),
+        obstacles: &ShipManager::new(),
+    };
+    assert!(matches!(
+        validate_placement(&placement),
+        Err(ShipPlacementError::InvalidShipSet)
+    ));
+
+    placement.assignment_count = 2;
+    placement.ship_set = &[ShipType::Destroyer as i32, ShipType::Submarine as i32];
+    assert!(matches!(
+        validate_placement(&placement),
+        Err(ShipPlacementError::InvalidShipSet)
+    ));
+}
+
+#[tokio::test]
+async fn placement_checks_in_order() {
+    // out of the quadrant and colliding with the other ship
+    assert!(matches!(
+        validate(&[destroyer(0, 9, 9), destroyer(1, 9, 10)]),
+        Err(ShipPlacementError::ShipOutOfQuadrant)
+    ));
+    // colliding with the other ship and the island
+    assert!(matches!(
+        validate(&[destroyer(0, 5, 4), destroyer(1, 5, 5)]),
+        Err(ShipPlacementError::Collision)
+    ));
+}
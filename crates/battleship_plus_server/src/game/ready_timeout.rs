@@ -0,0 +1,54 @@
+use std::time::{Duration, Instant};
+
+use battleship_plus_common::game::PlayerID;
+
+use crate::game::data::Game;
+use crate::game::states::GameState;
+
+/// How long players in the lobby have to get ready, there is no limit without
+/// `Config.auto_ready_timeout`.
+pub(crate) fn ready_timeout(game: &Game) -> Option<Duration> {
+    match game.config.auto_ready_timeout {
+        0 => None,
+        timeout => Some(Duration::from_millis(timeout as u64)),
+    }
+}
+
+/// Gives every player who is not ready until the deadline to get ready.
+pub(crate) fn set_ready_deadlines(game: &mut Game, deadline: Instant) {
+    game.players
+        .values_mut()
+        .filter(|player| !player.is_ready)
+        .for_each(|player| player.ready_deadline = Some(deadline));
+}
+
+/// Readies the players in the lobby who missed their deadline, or removes them from the game if
+/// `Config.kick_unready_players` is set. Returns the affected players ordered by id.
+pub(crate) fn enforce_ready_deadlines(game: &mut Game, now: Instant) -> Vec<PlayerID> {
+    if !matches!(game.state, GameState::Lobby) {
+        return vec![];
+    }
+
+    let mut overdue: Vec<_> = game
+        .players
+        .values()
+        .filter(|player| !player.is_ready)
+        .filter(|player| {
+            player
+                .ready_deadline
+                .is_some_and(|deadline| deadline <= now)
+        })
+        .map(|player| player.id)
+        .collect();
+    overdue.sort_unstable();
+
+    for &player_id in overdue.iter() {
+        if game.config.kick_unready_players {
+            game.remove_player(player_id);
+        } else if let Some(player) = game.players.get_mut(&player_id) {
+            player.is_ready = true;
+            player.ready_deadline = None;
+        }
+    }
+    overdue
+}
@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use battleship_plus_common::messages::SetReadyStateRequest;
+use battleship_plus_common::types::Config;
+
+use crate::config_provider::default_config_provider;
+use crate::game::actions::Action;
+use crate::game::data::Game;
+use crate::game::ready_timeout::{enforce_ready_deadlines, ready_timeout, set_ready_deadlines};
+
+fn lobby_with_ready_timeout(kick_unready_players: bool) -> Game {
+    let mut game = Game::new(Arc::new(Config {
+        auto_ready_timeout: 30_000,
+        kick_unready_players,
+        ..(*default_config_provider().game_config()).clone()
+    }));
+    for (player_id, name) in [(1, "alice"), (2, "bob"), (3, "carol")] {
+        game.add_player(player_id, name).unwrap();
+    }
+    game.team_a = HashSet::from([1, 3]);
+    game.team_b = HashSet::from([2]);
+    game.players.get_mut(&2).unwrap().is_ready = true;
+    game
+}
+
+#[tokio::test]
+async fn unready_players_readied_after_timeout() {
+    let mut game = lobby_with_ready_timeout(false);
+    let timeout = ready_timeout(&game).expect("the lobby has a ready timeout");
+    assert_eq!(timeout, Duration::from_secs(30));
+
+    let joined = Instant::now();
+    set_ready_deadlines(&mut game, joined + timeout);
+    assert!(enforce_ready_deadlines(&mut game, joined + timeout / 2).is_empty());
+    assert!(!game.players[&1].is_ready);
+
+    assert_eq!(
+        enforce_ready_deadlines(&mut game, joined + timeout),
+        vec![1, 3]
+    );
+    assert!(game.players.values().all(|player| player.is_ready));
+    assert_eq!(game.players.len(), 3);
+
+    // without a timeout nobody gets a deadline
+    let mut game = Game::default();
+    game.add_player(1, "alice").unwrap();
+    assert_eq!(ready_timeout(&game), None);
+}
+
+#[tokio::test]
+async fn unready_players_kicked_after_timeout_if_configured() {
+    let mut game = lobby_with_ready_timeout(true);
+    let joined = Instant::now();
+    let timeout = ready_timeout(&game).unwrap();
+    set_ready_deadlines(&mut game, joined + timeout);
+
+    let later = joined + Duration::from_secs(60);
+    assert_eq!(enforce_ready_deadlines(&mut game, later), vec![1, 3]);
+    assert_eq!(game.players.keys().collect::<Vec<_>>(), vec![&2]);
+    assert!(game.team_a.is_empty());
+    assert_eq!(game.team_b, HashSet::from([2]));
+
+    // ready players are left alone
+    assert!(enforce_ready_deadlines(&mut game, later).is_empty());
+}
+
+#[tokio::test]
+async fn deadline_is_dropped_once_players_set_their_ready_state() {
+    let mut game = lobby_with_ready_timeout(true);
+    let joined = Instant::now();
+    let timeout = ready_timeout(&game).unwrap();
+    set_ready_deadlines(&mut game, joined + timeout);
+
+    for ready_state in [true, false] {
+        Action::SetReady {
+            player_id: 1,
+            request: SetReadyStateRequest { ready_state },
+        }
+        .apply_on(&mut game)
+        .unwrap();
+    }
+
+    // player 1 is unready again, but the old deadline no longer applies
+    let later = joined + Duration::from_secs(60);
+    assert_eq!(enforce_ready_deadlines(&mut game, later), vec![3]);
+    assert!(game.players.contains_key(&1));
+}
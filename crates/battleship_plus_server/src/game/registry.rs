@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+
+use log::info;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::sync::{watch, RwLock};
+
+use battleship_plus_common::types::{Config, GamePhase, GameSummary};
+use bevy_quinnet_server::ClientId;
+
+use crate::game::audit::AuditLog;
+use crate::game::data::Game;
+use crate::game::lobby_file::{LobbyFile, SavedSeat};
+use crate::game::snapshot::{LogSink, SnapshotLog};
+use crate::game::states::GameState;
+use crate::game::teams::{FillSmallerTeam, TeamAssignment};
+use crate::status_endpoint::CurrentGame;
+
+pub type GameID = u32;
+
+/// A game hosted by the server together with the channel signaling its end.
+#[derive(Debug, Clone)]
+pub(crate) struct RegisteredGame {
+    pub(crate) id: GameID,
+    pub(crate) game: Arc<RwLock<Game>>,
+    pub(crate) game_end_tx: UnboundedSender<()>,
+}
+
+#[derive(Debug, Clone)]
+pub enum GameRegistryError {
+    AlreadyJoined,
+    GameNotJoinable(GameID),
+    TooManyGames,
+}
+
+impl Display for GameRegistryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameRegistryError::AlreadyJoined => f.write_str("you joined already"),
+            GameRegistryError::GameNotJoinable(id) => {
+                f.write_str(format!("game {id} is not accepting players").as_str())
+            }
+            GameRegistryError::TooManyGames => f.write_str("no more games can be opened"),
+        }
+    }
+}
+
+/// Keeps track of all games running on the server and which client plays in which game.
+pub(crate) struct GameRegistry {
+    config: Arc<Config>,
+    max_games: usize,
+    next_game_id: GameID,
+    games: HashMap<GameID, RegisteredGame>,
+    clients: HashMap<ClientId, GameID>,
+    ended_games_tx: UnboundedSender<GameID>,
+    latest_game_tx: watch::Sender<CurrentGame>,
+    snapshot_sink: Option<LogSink>,
+    audit_sink: Option<LogSink>,
+    team_assignment: Arc<dyn TeamAssignment>,
+    /// Handed to the next game opened, together with the seats restored from it.
+    lobby_file: Option<(LobbyFile, Vec<SavedSeat>)>,
+}
+
+impl Debug for GameRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GameRegistry")
+            .field("max_games", &self.max_games)
+            .field("next_game_id", &self.next_game_id)
+            .field("games", &self.games)
+            .field("clients", &self.clients)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GameRegistry {
+    /// Creates an empty registry. The ids of ended games are sent through `ended_games_tx`.
+    pub(crate) fn new(
+        config: Arc<Config>,
+        max_games: usize,
+        ended_games_tx: UnboundedSender<GameID>,
+    ) -> Self {
+        GameRegistry {
+            config,
+            max_games: max_games.max(1),
+            next_game_id: 1,
+            games: Default::default(),
+            clients: Default::default(),
+            ended_games_tx,
+            latest_game_tx: watch::channel(None).0,
+            snapshot_sink: None,
+            audit_sink: None,
+            team_assignment: Arc::new(FillSmallerTeam),
+            lobby_file: None,
+        }
+    }
+
+    /// Makes games opened from now on log a snapshot of their board at the end of each turn.
+    pub(crate) fn log_snapshots_to(&mut self, sink: LogSink) {
+        self.snapshot_sink = Some(sink);
+    }
+
+    /// Makes games opened from now on log every executed action and its outcome.
+    pub(crate) fn log_audit_to(&mut self, sink: LogSink) {
+        self.audit_sink = Some(sink);
+    }
+
+    /// Makes games opened from now on place joining players into teams with the strategy.
+    pub(crate) fn assign_teams_with(&mut self, team_assignment: Arc<dyn TeamAssignment>) {
+        self.team_assignment = team_assignment;
+    }
+
+    /// Makes the next game opened keep its lobby in the file and offer the saved seats to
+    /// joining players. Only a single game is kept in the file.
+    pub(crate) fn keep_lobby_in(&mut self, lobby_file: LobbyFile, saved_seats: Vec<SavedSeat>) {
+        self.lobby_file = Some((lobby_file, saved_seats));
+    }
+
+    /// Follows the most recently opened game that has not been removed yet.
+    pub(crate) fn watch_latest_game(&self) -> watch::Receiver<CurrentGame> {
+        self.latest_game_tx.subscribe()
+    }
+
+    /// Assigns the client to a game.
+    /// Without a requested game the client is placed into a lobby with free seats, a new game is
+    /// opened if there is none. A requested game is opened if it does not exist yet.
+    pub(crate) async fn join(
+        &mut self,
+        client_id: ClientId,
+        requested_game: Option<GameID>,
+    ) -> Result<RegisteredGame, GameRegistryError> {
+        if self.clients.contains_key(&client_id) {
+            return Err(GameRegistryError::AlreadyJoined);
+        }
+
+        let game = match requested_game {
+            Some(game_id) => match self.games.get(&game_id).cloned() {
+                Some(game) if is_lobby(&game).await => game,
+                Some(_) => return Err(GameRegistryError::GameNotJoinable(game_id)),
+                None => self.open_game(game_id)?,
+            },
+            None => match self.find_open_lobby(true).await {
+                Some(game) => game,
+                None => match self.open_game(self.next_game_id) {
+                    Ok(game) => game,
+                    // fall back to overfilling a lobby, players may still leave or switch teams
+                    Err(e) => self.find_open_lobby(false).await.ok_or(e)?,
+                },
+            },
+        };
+
+        self.clients.insert(client_id, game.id);
+        Ok(game)
+    }
+
+    /// Removes the client from the registry and returns the game it was part of.
+    pub(crate) fn leave(&mut self, client_id: ClientId) -> Option<RegisteredGame> {
+        self.clients
+            .remove(&client_id)
+            .and_then(|game_id| self.games.get(&game_id))
+            .cloned()
+    }
+
+    pub(crate) fn game_of(&self, client_id: ClientId) -> Option<RegisteredGame> {
+        self.clients
+            .get(&client_id)
+            .and_then(|game_id| self.games.get(game_id))
+            .cloned()
+    }
+
+    /// Summarizes all games in the order of their ids, so clients can choose which one to join.
+    pub(crate) async fn list_games(&self) -> Vec<GameSummary> {
+        let max_player_count = self.config.team_size_a + self.config.team_size_b;
+
+        let mut game_ids: Vec<_> = self.games.keys().cloned().collect();
+        game_ids.sort_unstable();
+        let mut summaries = Vec::with_capacity(game_ids.len());
+        for game_id in game_ids {
+            let g = self.games[&game_id].game.read().await;
+            summaries.push(GameSummary {
+                game_id,
+                name: format!("{} #{game_id}", self.config.server_name),
+                player_count: g.players.len() as u32,
+                max_player_count,
+                phase: GamePhase::from(g.state).into(),
+            });
+        }
+        summaries
+    }
+
+    /// Removes an ended game, its clients may join another game afterwards.
+    pub(crate) async fn remove_game(&mut self, game_id: GameID) {
+        let removed = match self.games.remove(&game_id) {
+            Some(removed) => removed,
+            None => return,
+        };
+        self.clients.retain(|_, id| *id != game_id);
+
+        // the next game opened keeps its lobby in the file instead
+        let mut game = removed.game.write().await;
+        if let Some(lobby_file) = game.lobby_file.take() {
+            let saved_seats = if matches!(game.state, GameState::Lobby) {
+                std::mem::take(&mut game.saved_seats)
+            } else {
+                vec![]
+            };
+            self.lobby_file = Some((lobby_file, saved_seats));
+        }
+        drop(game);
+
+        let removed_latest = matches!(
+            self.latest_game_tx.borrow().as_ref(),
+            Some(latest) if !self.games.values().any(|g| Arc::ptr_eq(&g.game, latest))
+        );
+        if removed_latest {
+            let latest = self.games.values().max_by_key(|g| g.id);
+            self.latest_game_tx
+                .send_replace(latest.map(|g| g.game.clone()));
+        }
+        info!("Game {game_id} removed");
+    }
+
+    async fn find_open_lobby(&self, with_free_seats: bool) -> Option<RegisteredGame> {
+        let seats = (self.config.team_size_a + self.config.team_size_b) as usize;
+
+        let mut game_ids: Vec<_> = self.games.keys().cloned().collect();
+        game_ids.sort_unstable();
+        for game_id in game_ids {
+            let game = &self.games[&game_id];
+            let g = game.game.read().await;
+            if matches!(g.state, GameState::Lobby) && (!with_free_seats || g.players.len() < seats)
+            {
+                return Some(game.clone());
+            }
+        }
+        None
+    }
+
+    fn open_game(&mut self, game_id: GameID) -> Result<RegisteredGame, GameRegistryError> {
+        if self.games.len() >= self.max_games {
+            return Err(GameRegistryError::TooManyGames);
+        }
+
+        let (game_end_tx, mut game_end_rx) = mpsc::unbounded_channel();
+        let ended_games_tx = self.ended_games_tx.clone();
+        tokio::spawn(async move {
+            if game_end_rx.recv().await.is_some() {
+                let _ = ended_games_tx.send(game_id);
+            }
+            // later end signals of the same game are ignored
+            while game_end_rx.recv().await.is_some() {}
+        });
+
+        let mut new_game = Game::new(self.config.clone());
+        new_game.snapshot_log = self
+            .snapshot_sink
+            .clone()
+            .map(|sink| SnapshotLog::new(game_id, sink));
+        new_game.audit_log = self
+            .audit_sink
+            .clone()
+            .map(|sink| AuditLog::new(game_id, sink));
+        new_game.team_assignment = self.team_assignment.clone();
+        if let Some((lobby_file, saved_seats)) = self.lobby_file.take() {
+            new_game.lobby_file = Some(lobby_file);
+            new_game.saved_seats = saved_seats;
+        }
+
+        let game = RegisteredGame {
+            id: game_id,
+            game: Arc::new(RwLock::new(new_game)),
+            game_end_tx,
+        };
+        self.games.insert(game_id, game.clone());
+        while self.games.contains_key(&self.next_game_id) {
+            self.next_game_id += 1;
+        }
+        self.latest_game_tx.send_replace(Some(game.game.clone()));
+
+        info!("Game {game_id} opened");
+        Ok(game)
+    }
+}
+
+async fn is_lobby(game: &RegisteredGame) -> bool {
+    matches!(game.game.read().await.state, GameState::Lobby)
+}
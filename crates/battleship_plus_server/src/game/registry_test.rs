@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use battleship_plus_common::types::{Config, GamePhase};
+
+use crate::game::registry::GameRegistry;
+use crate::game::states::GameState;
+
+#[tokio::test]
+async fn game_list_reflects_open_and_running_games() {
+    let config = Arc::new(Config {
+        server_name: String::from("Test"),
+        team_size_a: 1,
+        team_size_b: 1,
+        ..Default::default()
+    });
+    let (ended_games_tx, _ended_games_rx) = mpsc::unbounded_channel();
+    let mut registry = GameRegistry::new(config, 2, ended_games_tx);
+    assert!(registry.list_games().await.is_empty());
+
+    let open = registry.join(1, Some(1)).await.unwrap();
+    let running = registry.join(2, Some(2)).await.unwrap();
+    running.game.write().await.state = GameState::InGame;
+
+    let games = registry.list_games().await;
+    assert_eq!(games.len(), 2);
+
+    assert_eq!(games[0].game_id, open.id);
+    assert_eq!(games[0].name, "Test #1");
+    assert_eq!(games[0].player_count, 0);
+    assert_eq!(games[0].max_player_count, 2);
+    assert_eq!(games[0].phase, GamePhase::Lobby as i32);
+
+    assert_eq!(games[1].game_id, running.id);
+    assert_eq!(games[1].phase, GamePhase::InGame as i32);
+
+    // running games are listed but cannot be joined
+    assert!(registry.join(3, Some(running.id)).await.is_err());
+    assert!(registry.join(4, Some(open.id)).await.is_ok());
+
+    registry.remove_game(running.id).await;
+    let games = registry.list_games().await;
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0].game_id, open.id);
+}
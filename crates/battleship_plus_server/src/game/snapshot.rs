@@ -0,0 +1,107 @@
+use std::fmt::{Debug, Formatter};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use log::{debug, warn};
+use serde::Serialize;
+
+use battleship_plus_common::game::ship::GetShipID;
+use battleship_plus_common::game::PlayerID;
+
+use crate::game::data::Game;
+use crate::game::registry::GameID;
+
+/// Destination of a replay or audit log, shared by all games of the server.
+pub(crate) type LogSink = Arc<Mutex<dyn Write + Send>>;
+
+/// Appends a snapshot of the board to the replay log at the end of every turn.
+/// Each snapshot is a single line of JSON, so games can be reconstructed turn by turn
+/// without replaying every action.
+#[derive(Clone)]
+pub(crate) struct SnapshotLog {
+    game_id: GameID,
+    sink: LogSink,
+}
+
+impl Debug for SnapshotLog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapshotLog")
+            .field("game_id", &self.game_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SnapshotLog {
+    pub(crate) fn new(game_id: GameID, sink: LogSink) -> Self {
+        SnapshotLog { game_id, sink }
+    }
+
+    pub(crate) fn record(&self, game: &Game) {
+        let snapshot = board_snapshot_json(self.game_id, game);
+        debug!("Board snapshot: {snapshot}");
+
+        let mut sink = match self.sink.lock() {
+            Ok(sink) => sink,
+            // a panic while writing at most garbled a line, later snapshots are still useful
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Err(e) = writeln!(sink, "{snapshot}").and_then(|_| sink.flush()) {
+            warn!(
+                "Unable to write the board snapshot of game {}: {e}",
+                self.game_id
+            );
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BoardSnapshot {
+    game: GameID,
+    turn: u32,
+    player: Option<PlayerID>,
+    ships: Vec<ShipSnapshot>,
+    seed: u64,
+}
+
+#[derive(Serialize)]
+struct ShipSnapshot {
+    player: PlayerID,
+    number: u32,
+    #[serde(rename = "type")]
+    ship_type: String,
+    x: i32,
+    y: i32,
+    orientation: String,
+    health: u32,
+}
+
+/// Renders the complete board of a game: the turn that just ended, the player who took it and
+/// every remaining ship. Unlike the status endpoint this includes hidden information.
+/// The seed of the game is repeated on every line, so random choices can be replayed as well.
+pub(crate) fn board_snapshot_json(game_id: GameID, game: &Game) -> String {
+    let mut ships: Vec<_> = game.ships.iter_ships().map(|(_, ship)| ship).collect();
+    ships.sort_unstable_by_key(|ship| ship.id());
+
+    let snapshot = BoardSnapshot {
+        game: game_id,
+        turn: game.turns_played,
+        player: game.turn.as_ref().map(|turn| turn.player_id),
+        ships: ships
+            .iter()
+            .map(|ship| {
+                let ((player, number), (x, y)) = (ship.id(), ship.position());
+                ShipSnapshot {
+                    player,
+                    number,
+                    ship_type: format!("{:?}", ship.ship_type()),
+                    x,
+                    y,
+                    orientation: format!("{:?}", ship.orientation()),
+                    health: ship.health(),
+                }
+            })
+            .collect(),
+        seed: game.seed,
+    };
+    serde_json::to_string(&snapshot).expect("the board snapshot is valid JSON")
+}
@@ -0,0 +1,78 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use battleship_plus_common::game::ship_manager::ShipManager;
+
+use crate::game::data::{Game, Player};
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+use crate::game::snapshot::SnapshotLog;
+use crate::game::states::GameState;
+
+#[tokio::test]
+async fn snapshot_recorded_per_turn() {
+    let ships = vec![
+        GeneralShipBuilder::default()
+            .owner(1)
+            .position(2, 3)
+            .health(4)
+            .destroyer()
+            .build(),
+        GeneralShipBuilder::default()
+            .owner(2)
+            .position(10, 10)
+            .destroyer()
+            .build(),
+    ];
+    let replay_log = Arc::new(Mutex::new(Vec::<u8>::new()));
+    let mut game = Game {
+        state: GameState::InGame,
+        players: HashMap::from([
+            (
+                1,
+                Player {
+                    id: 1,
+                    ..Default::default()
+                },
+            ),
+            (
+                2,
+                Player {
+                    id: 2,
+                    ..Default::default()
+                },
+            ),
+        ]),
+        team_a: HashSet::from([1]),
+        team_b: HashSet::from([2]),
+        ships: ShipManager::new_with_ships(ships),
+        snapshot_log: Some(SnapshotLog::new(7, replay_log.clone())),
+        ..Default::default()
+    };
+
+    // the first turn begins without another one ending
+    game.advance_turn();
+    assert!(replay_log.lock().unwrap().is_empty());
+
+    let mut players = vec![];
+    for _ in 0..2 {
+        players.push(game.turn.as_ref().unwrap().player_id);
+        game.advance_turn();
+    }
+    // the game ends with the third turn
+    players.push(game.turn.as_ref().unwrap().player_id);
+    game.end_turn();
+
+    let replay_log = String::from_utf8(replay_log.lock().unwrap().clone()).unwrap();
+    let snapshots: Vec<_> = replay_log.lines().collect();
+    assert_eq!(snapshots.len(), 3);
+    for (turn, (snapshot, player_id)) in snapshots.iter().zip(players).enumerate() {
+        assert!(snapshot.starts_with(&format!(
+            "{{\"game\":7,\"turn\":{},\"player\":{player_id},\"ships\":[",
+            turn + 1
+        )));
+        assert!(snapshot.contains(
+            "{\"player\":1,\"number\":0,\"type\":\"Destroyer\",\"x\":2,\"y\":3,\"orientation\":\"North\",\"health\":4}"
+        ));
+        assert!(snapshot.contains("{\"player\":2,\"number\":0,"));
+    }
+}
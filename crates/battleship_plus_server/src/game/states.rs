@@ -4,6 +4,7 @@ use log::debug;
 use tokio::sync::RwLockWriteGuard;
 
 use battleship_plus_common::messages::{EventMessage, ProtocolMessage};
+use battleship_plus_common::types::GamePhase;
 
 use crate::game::actions::{Action, ActionExecutionError, ActionResult};
 use crate::game::data::Game;
@@ -25,16 +26,30 @@ impl Display for GameState {
     }
 }
 
+impl From<GameState> for GamePhase {
+    fn from(state: GameState) -> Self {
+        match state {
+            GameState::Lobby => GamePhase::Lobby,
+            GameState::Preparation => GamePhase::Preparation,
+            GameState::InGame => GamePhase::InGame,
+        }
+    }
+}
+
+/// Whether only the server may send the message.
+pub(crate) fn is_client_bound(msg: &ProtocolMessage) -> bool {
+    matches!(
+        msg,
+        ProtocolMessage::StatusMessage(_) | ProtocolMessage::ServerAdvertisement(_)
+    ) || EventMessage::try_from(msg.clone()).is_ok()
+}
+
 impl GameState {
     pub(crate) fn validate_inbound_message_allowed(
         &self,
         msg: &ProtocolMessage,
     ) -> Result<(), String> {
-        if matches!(
-            msg,
-            ProtocolMessage::StatusMessage(_) | ProtocolMessage::ServerAdvertisement(_)
-        ) || EventMessage::try_from(msg.clone()).is_ok()
-        {
+        if is_client_bound(msg) {
             return Err(format!("{msg:?} is not allowed as server-bound message"));
         }
 
@@ -42,18 +57,26 @@ impl GameState {
             GameState::Lobby => matches!(
                 msg,
                 ProtocolMessage::ServerConfigRequest(_)
+                    | ProtocolMessage::GameListRequest(_)
+                    | ProtocolMessage::Keepalive(_)
                     | ProtocolMessage::JoinRequest(_)
                     | ProtocolMessage::TeamSwitchRequest(_)
                     | ProtocolMessage::SetReadyStateRequest(_)
             ),
             GameState::Preparation => matches!(
                 msg,
-                ProtocolMessage::ServerConfigRequest(_) | ProtocolMessage::SetPlacementRequest(_)
+                ProtocolMessage::ServerConfigRequest(_)
+                    | ProtocolMessage::GameListRequest(_)
+                    | ProtocolMessage::Keepalive(_)
+                    | ProtocolMessage::SetPlacementRequest(_)
             ),
             GameState::InGame => matches!(
                 msg,
                 ProtocolMessage::ServerConfigRequest(_)
+                    | ProtocolMessage::GameListRequest(_)
+                    | ProtocolMessage::Keepalive(_)
                     | ProtocolMessage::ServerStateRequest(_)
+                    | ProtocolMessage::GameStartAck(_)
                     | ProtocolMessage::ActionRequest(_)
             ),
         } {
@@ -79,6 +102,7 @@ impl GameState {
                     | Action::EngineBoost { .. }
                     | Action::Torpedo { .. }
                     | Action::MultiMissile { .. }
+                    | Action::ShipSwap { .. }
             ),
         }
     }
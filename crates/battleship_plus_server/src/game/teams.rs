@@ -0,0 +1,69 @@
+use std::fmt::Debug;
+
+use tokio::macros::support::thread_rng_n;
+
+use battleship_plus_common::game::PlayerID;
+use battleship_plus_common::messages::JoinRequest;
+use battleship_plus_common::types::Teams;
+
+use crate::game::data::Game;
+
+/// Decides which team a joining player is placed into.
+pub(crate) trait TeamAssignment: Debug + Send + Sync {
+    /// Picks team A or team B for a player who joined with the request.
+    /// The player is not in any team yet.
+    fn assign(&self, game: &Game, request: &JoinRequest) -> Teams;
+}
+
+/// Fills the team with capacity left and less players first, otherwise picks a random team.
+#[derive(Debug, Default)]
+pub(crate) struct FillSmallerTeam;
+
+impl TeamAssignment for FillSmallerTeam {
+    fn assign(&self, game: &Game, _request: &JoinRequest) -> Teams {
+        let mut teams = [
+            (Teams::TeamA, game.team_a.len(), game.config.team_size_a),
+            (Teams::TeamB, game.team_b.len(), game.config.team_size_b),
+        ];
+        teams.sort_by_key(|(_, players, _)| *players);
+        match teams
+            .iter()
+            .find(|(_, players, size)| *players < *size as usize)
+        {
+            Some((team, _, _)) => *team,
+            None => match thread_rng_n(2) {
+                0 => Teams::TeamA,
+                1 => Teams::TeamB,
+                _ => panic!("the universe just broke"),
+            },
+        }
+    }
+}
+
+/// Honors the team requested by the player if it has capacity left.
+/// Otherwise, or without a request, the player is placed like with [`FillSmallerTeam`].
+#[derive(Debug, Default)]
+pub(crate) struct RequestedTeam;
+
+impl TeamAssignment for RequestedTeam {
+    fn assign(&self, game: &Game, request: &JoinRequest) -> Teams {
+        let requested = request.team.and_then(Teams::from_i32);
+        let has_capacity = match requested {
+            Some(Teams::TeamA) => game.team_a.len() < game.config.team_size_a as usize,
+            Some(Teams::TeamB) => game.team_b.len() < game.config.team_size_b as usize,
+            _ => false,
+        };
+        match requested {
+            Some(team) if has_capacity => team,
+            _ => FillSmallerTeam.assign(game, request),
+        }
+    }
+}
+
+/// Places a player who joined the game into a team using the strategy of the game.
+pub(crate) fn place_into_team(game: &mut Game, player_id: PlayerID, request: &JoinRequest) {
+    match game.team_assignment.assign(game, request) {
+        Teams::TeamB => game.team_b.insert(player_id),
+        _ => game.team_a.insert(player_id),
+    };
+}
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use battleship_plus_common::game::PlayerID;
+use battleship_plus_common::messages::JoinRequest;
+use battleship_plus_common::types::{Config, Teams};
+
+use crate::config_provider::default_config_provider;
+use crate::game::data::{Game, Player};
+use crate::game::teams::{place_into_team, FillSmallerTeam, RequestedTeam, TeamAssignment};
+
+fn join_request(team: Option<Teams>) -> JoinRequest {
+    JoinRequest {
+        username: "player".to_string(),
+        game_id: None,
+        team: team.map(Into::into),
+        seat_token: None,
+    }
+}
+
+fn game_with_team_sizes(team_size_a: u32, team_size_b: u32) -> Game {
+    Game::new(Arc::new(Config {
+        team_size_a,
+        team_size_b,
+        ..(*default_config_provider().game_config()).clone()
+    }))
+}
+
+#[tokio::test]
+async fn fill_smaller_team() {
+    let mut game = game_with_team_sizes(2, 2);
+    game.team_a = HashSet::from([1]);
+
+    let request = join_request(Some(Teams::TeamA));
+    assert_eq!(FillSmallerTeam.assign(&game, &request), Teams::TeamB);
+
+    // the default strategy of a game
+    place_into_team(&mut game, 2, &request);
+    assert!(game.team_b.contains(&2));
+}
+
+#[tokio::test]
+async fn requested_team_honored() {
+    let mut game = game_with_team_sizes(2, 2);
+    game.team_a = HashSet::from([1]);
+    game.team_assignment = Arc::new(RequestedTeam);
+
+    let request = join_request(Some(Teams::TeamA));
+    assert_eq!(RequestedTeam.assign(&game, &request), Teams::TeamA);
+
+    place_into_team(&mut game, 2, &request);
+    assert_eq!(game.team_a, HashSet::from([1, 2]));
+    assert!(game.team_b.is_empty());
+}
+
+#[tokio::test]
+async fn requested_team_full_falls_back() {
+    let mut game = game_with_team_sizes(1, 2);
+    game.team_a = HashSet::from([1]);
+
+    assert_eq!(
+        RequestedTeam.assign(&game, &join_request(Some(Teams::TeamA))),
+        Teams::TeamB
+    );
+    // no request and invalid requests are placed like by the default strategy
+    game.team_b = HashSet::from([2]);
+    for request in [
+        join_request(None),
+        join_request(Some(Teams::None)),
+        JoinRequest {
+            team: Some(42),
+            seat_token: None,
+            ..join_request(None)
+        },
+    ] {
+        assert_eq!(RequestedTeam.assign(&game, &request), Teams::TeamB);
+    }
+}
+
+#[tokio::test]
+async fn symmetric_quadrants_mirror_teams() {
+    let mut game = Game::new(Arc::new(Config {
+        team_size_a: 3,
+        team_size_b: 3,
+        symmetric_quadrants: true,
+        ..(*default_config_provider().game_config()).clone()
+    }));
+    for player_id in 1..=6 {
+        game.players.insert(
+            player_id,
+            Player {
+                id: player_id,
+                ..Default::default()
+            },
+        );
+    }
+    game.team_a = HashSet::from([1, 3, 5]);
+    game.team_b = HashSet::from([2, 4, 6]);
+    game.reseed(3);
+    game.assign_quadrants();
+
+    // corners of opposite quadrants add up to the corners of the outermost quadrants
+    let quadrants = game.quadrants();
+    let (first, last) = (quadrants.first().unwrap(), quadrants.last().unwrap());
+    let mirrored =
+        |(x, y, size): (u32, u32, u32)| (first.0 + last.0 - x, first.1 + last.1 - y, size);
+    let quadrants_of = |team: &HashSet<PlayerID>| -> HashSet<_> {
+        team.iter()
+            .map(|id| game.players[id].quadrant.unwrap())
+            .collect()
+    };
+    let (quadrants_a, quadrants_b) = (quadrants_of(&game.team_a), quadrants_of(&game.team_b));
+    assert_eq!(quadrants_a.len(), 3);
+    assert_eq!(
+        quadrants_a
+            .into_iter()
+            .map(mirrored)
+            .collect::<HashSet<_>>(),
+        quadrants_b
+    );
+}
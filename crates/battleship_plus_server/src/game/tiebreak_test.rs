@@ -0,0 +1,109 @@
+use battleship_plus_common::game::ship::Ship;
+use battleship_plus_common::types::{Config, Teams, Tiebreaker};
+
+use crate::config_provider::default_config_provider;
+use crate::game::data::{Game, GameResult};
+use crate::game::game_builder::GameBuilder;
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+use crate::game::states::GameState;
+
+fn destroyer(owner: u32, id: u32, health: u32) -> Ship {
+    GeneralShipBuilder::default()
+        .owner(owner)
+        .number(id)
+        .health(health)
+        .position(id as i32 * 10, owner as i32 * 10)
+        .destroyer()
+        .build()
+}
+
+fn game_with(tiebreakers: &[Tiebreaker], ships: impl IntoIterator<Item = Ship>) -> Game {
+    GameBuilder::new()
+        .config(Config {
+            tiebreakers: tiebreakers
+                .iter()
+                .map(|&tiebreaker| tiebreaker.into())
+                .collect(),
+            ..(*default_config_provider().game_config()).clone()
+        })
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamB)
+        .ships(ships)
+        .build()
+}
+
+const DEFAULT_ORDER: [Tiebreaker; 2] = [Tiebreaker::ShipsRemaining, Tiebreaker::TotalHealth];
+
+#[tokio::test]
+async fn tiebreak_most_ships_remaining_wins() {
+    let game = game_with(
+        &DEFAULT_ORDER,
+        [
+            destroyer(1, 0, 10),
+            destroyer(1, 1, 10),
+            destroyer(2, 0, 50),
+        ],
+    );
+    assert!(matches!(game.resolve_tie(), GameResult::Win(Teams::TeamA)));
+}
+
+#[tokio::test]
+async fn tiebreak_most_health_wins_with_equal_ships() {
+    let game = game_with(
+        &DEFAULT_ORDER,
+        [
+            destroyer(1, 0, 20),
+            destroyer(2, 0, 10),
+            destroyer(2, 1, 15),
+        ],
+    );
+    assert!(matches!(game.resolve_tie(), GameResult::Win(Teams::TeamB)));
+
+    let game = game_with(&DEFAULT_ORDER, [destroyer(1, 0, 20), destroyer(2, 0, 15)]);
+    assert!(matches!(game.resolve_tie(), GameResult::Win(Teams::TeamA)));
+}
+
+#[tokio::test]
+async fn tiebreak_draw_if_no_criterion_decides() {
+    let game = game_with(&DEFAULT_ORDER, [destroyer(1, 0, 20), destroyer(2, 0, 20)]);
+    assert!(matches!(game.resolve_tie(), GameResult::Draw));
+
+    // without any criteria, every forced resolution is a draw
+    let game = game_with(&[], [destroyer(1, 0, 50), destroyer(2, 0, 20)]);
+    assert!(matches!(game.resolve_tie(), GameResult::Draw));
+}
+
+#[tokio::test]
+async fn tiebreak_criteria_are_applied_in_configured_order() {
+    let ships = [
+        destroyer(1, 0, 10),
+        destroyer(1, 1, 10),
+        destroyer(2, 0, 50),
+    ];
+    let game = game_with(
+        &[Tiebreaker::TotalHealth, Tiebreaker::ShipsRemaining],
+        ships,
+    );
+    assert!(matches!(game.resolve_tie(), GameResult::Win(Teams::TeamB)));
+}
+
+#[tokio::test]
+async fn tiebreak_decides_games_aborted_by_leaving_players() {
+    let game = game_with(
+        &DEFAULT_ORDER,
+        [
+            destroyer(1, 0, 10),
+            destroyer(2, 0, 10),
+            destroyer(2, 1, 10),
+        ],
+    );
+    assert_eq!(game.winner_on_abort(), Teams::TeamB);
+
+    // the ships of a player who left no longer count for their team
+    let mut game = game_with(&DEFAULT_ORDER, [destroyer(1, 0, 10), destroyer(2, 0, 50)]);
+    assert!(game.remove_player(2));
+    assert_eq!(game.winner_on_abort(), Teams::TeamA);
+
+    game.state = GameState::Preparation;
+    assert_eq!(game.winner_on_abort(), Teams::None);
+}
@@ -0,0 +1,101 @@
+use battleship_plus_common::types::{ActionPointCarryover, Config, Teams};
+
+use crate::config_provider::default_config_provider;
+use crate::game::data::GameResult;
+use crate::game::game_builder::GameBuilder;
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+
+#[tokio::test]
+async fn turn_order_is_cycled() {
+    let mut game = GameBuilder::new()
+        .config(Config {
+            action_point_gain: 7,
+            action_point_carryover: ActionPointCarryover::Reset.into(),
+            ..(*default_config_provider().game_config()).clone()
+        })
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamA)
+        .player(3, Teams::TeamB)
+        .player(4, Teams::TeamB)
+        .ships([1, 3].map(|owner| {
+            GeneralShipBuilder::default()
+                .owner(owner)
+                .position(owner as i32 * 10, 0)
+                .destroyer()
+                .build()
+        }))
+        .turn_order(&[3, 1, 4, 2])
+        .build();
+
+    let turns: Vec<_> = (0..6).map(|_| game.advance_turn()).collect();
+    assert_eq!(
+        turns.iter().map(|turn| turn.player_id).collect::<Vec<_>>(),
+        vec![3, 1, 4, 2, 3, 1]
+    );
+    assert!(turns.iter().all(|turn| turn.action_points_left == 7));
+    // players without ships keep their turns
+    assert!(matches!(game.game_result(), GameResult::Pending));
+}
+
+#[tokio::test]
+async fn turn_order_skips_players_that_left() {
+    let mut game = GameBuilder::new()
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamA)
+        .player(3, Teams::TeamB)
+        .turn_order(&[1, 2, 3])
+        .turn(1, 0)
+        .build();
+
+    game.remove_player(2);
+    assert_eq!(game.advance_turn().player_id, 3);
+    assert_eq!(game.advance_turn().player_id, 1);
+
+    // the player leaves during their own turn
+    game.remove_player(1);
+    assert_eq!(game.advance_turn().player_id, 3);
+    assert_eq!(game.advance_turn().player_id, 3);
+}
+
+#[tokio::test]
+async fn turn_number_counts_turns() {
+    let mut game = GameBuilder::new()
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamB)
+        .turn_order(&[1, 2])
+        .build();
+
+    // the first turn starts without one ending
+    assert_eq!(game.turn_number(), 1);
+    game.advance_turn();
+    assert_eq!(game.turn_number(), 1);
+    for turn_number in 2..=5 {
+        game.advance_turn();
+        assert_eq!(game.turn_number(), turn_number);
+    }
+}
+
+#[tokio::test]
+async fn turns_are_queued_alternately_by_team() {
+    let mut game = GameBuilder::new()
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamA)
+        .player(3, Teams::TeamA)
+        .player(4, Teams::TeamB)
+        .seed(42)
+        .build();
+    game.queue_turns();
+
+    let turns: Vec<_> = (0..4).map(|_| game.advance_turn().player_id).collect();
+    let teams: Vec<_> = turns.iter().map(|id| game.team_a.contains(id)).collect();
+    // the smaller team is out of players after one turn
+    assert!(teams == [true, false, true, true] || teams == [false, true, true, true]);
+    let mut players = turns.clone();
+    players.sort_unstable();
+    assert_eq!(players, vec![1, 2, 3, 4]);
+
+    // everyone knows how long they have to wait, the turns go on in the same order
+    let waiting: Vec<_> = turns.iter().map(|&id| game.turns_until(id)).collect();
+    assert_eq!(waiting, vec![1, 2, 3, 0]);
+    assert_eq!(game.advance_turn().player_id, turns[0]);
+}
@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+
+use battleship_plus_common::game::PlayerID;
+
+/// Players in the order they take their turns. The queue goes round in circles, players are
+/// only passed over if they were skipped or removed.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TurnQueue {
+    order: Vec<PlayerID>,
+    /// Position in `order` the search for the next turn starts at.
+    next: usize,
+    /// Players sitting out their next turn.
+    skipped: HashSet<PlayerID>,
+}
+
+impl TurnQueue {
+    pub(crate) fn new(order: Vec<PlayerID>) -> Self {
+        TurnQueue {
+            order,
+            next: 0,
+            skipped: HashSet::new(),
+        }
+    }
+
+    /// Moves on to the player whose turn is next and returns them.
+    /// Skipped players are passed over once each, `None` if the queue is empty.
+    pub(crate) fn advance(&mut self) -> Option<PlayerID> {
+        if self.order.is_empty() {
+            return None;
+        }
+        // after one round every skipped player sat out their turn, so the search ends then
+        for offset in 0..=self.order.len() {
+            let position = (self.next + offset) % self.order.len();
+            let player_id = self.order[position];
+            if !self.skipped.remove(&player_id) {
+                self.next = (position + 1) % self.order.len();
+                return Some(player_id);
+            }
+        }
+        None
+    }
+
+    /// Lets the player sit out their next turn.
+    #[allow(dead_code)] // no rule makes players sit out a turn yet
+    pub(crate) fn skip(&mut self, player_id: PlayerID) {
+        if self.order.contains(&player_id) {
+            self.skipped.insert(player_id);
+        }
+    }
+
+    /// Takes the player out of the queue for good, the order of the others stays the same.
+    /// If it is the turn of the player, their successor is still next.
+    pub(crate) fn remove(&mut self, player_id: PlayerID) {
+        let position = match self.order.iter().position(|&id| id == player_id) {
+            Some(position) => position,
+            None => return,
+        };
+        self.order.remove(position);
+        self.skipped.remove(&player_id);
+        if position < self.next {
+            self.next -= 1;
+        }
+        if self.next >= self.order.len() {
+            self.next = 0;
+        }
+    }
+
+    /// Counts the turns before the turn of the player, 0 if the player is next.
+    /// Turns sat out by skipped players are not counted, `None` if the player is not queued.
+    pub(crate) fn position_of(&self, player_id: PlayerID) -> Option<usize> {
+        if !self.order.contains(&player_id) {
+            return None;
+        }
+        // every player comes up within two rounds, even if they are skipped
+        let mut queue = self.clone();
+        (0..2 * self.order.len()).position(|_| queue.advance() == Some(player_id))
+    }
+
+    /// Continues the queue after the player, as if their turn was the last one.
+    /// Players not in the queue leave it unchanged.
+    #[cfg(test)]
+    pub(crate) fn continue_after(&mut self, player_id: PlayerID) {
+        if let Some(position) = self.order.iter().position(|&id| id == player_id) {
+            self.next = (position + 1) % self.order.len();
+        }
+    }
+}
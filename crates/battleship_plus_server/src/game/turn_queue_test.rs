@@ -0,0 +1,72 @@
+use battleship_plus_common::game::PlayerID;
+
+use crate::game::turn_queue::TurnQueue;
+
+fn turns(queue: &mut TurnQueue, count: usize) -> Vec<Option<PlayerID>> {
+    (0..count).map(|_| queue.advance()).collect()
+}
+
+#[tokio::test]
+async fn turn_queue_advances_in_circles() {
+    let mut queue = TurnQueue::new(vec![3, 1, 2]);
+    assert_eq!(turns(&mut queue, 5), [3, 1, 2, 3, 1].map(Some).to_vec());
+
+    assert_eq!(TurnQueue::default().advance(), None);
+}
+
+#[tokio::test]
+async fn turn_queue_skips_players_once() {
+    let mut queue = TurnQueue::new(vec![1, 2, 3]);
+    queue.skip(2);
+    queue.skip(4);
+    assert_eq!(turns(&mut queue, 5), [1, 3, 1, 2, 3].map(Some).to_vec());
+
+    // if everybody sits out, the round is over and the next turn is played again
+    queue.skip(1);
+    queue.skip(2);
+    queue.skip(3);
+    assert_eq!(turns(&mut queue, 2), [1, 2].map(Some).to_vec());
+}
+
+#[tokio::test]
+async fn turn_queue_remove_keeps_successor_next() {
+    let mut queue = TurnQueue::new(vec![1, 2, 3, 4]);
+    assert_eq!(turns(&mut queue, 2), [1, 2].map(Some).to_vec());
+
+    // it is the turn of player 2, who leaves
+    queue.remove(2);
+    assert_eq!(queue.advance(), Some(3));
+
+    // a player before the current one leaves
+    queue.remove(1);
+    assert_eq!(turns(&mut queue, 3), [4, 3, 4].map(Some).to_vec());
+
+    // the last player in the order leaves while the queue wraps around
+    queue.remove(4);
+    queue.remove(5);
+    assert_eq!(turns(&mut queue, 2), [3, 3].map(Some).to_vec());
+
+    queue.remove(3);
+    assert_eq!(queue.advance(), None);
+}
+
+#[tokio::test]
+async fn turn_queue_position_counts_turns_ahead() {
+    let mut queue = TurnQueue::new(vec![1, 2, 3, 4]);
+    queue.advance();
+    assert_eq!(queue.position_of(2), Some(0));
+    assert_eq!(queue.position_of(4), Some(2));
+    assert_eq!(queue.position_of(1), Some(3));
+    assert_eq!(queue.position_of(5), None);
+
+    // the turns of skipped players are not waited for
+    queue.skip(3);
+    assert_eq!(queue.position_of(4), Some(1));
+    // a skipped player waits for a whole round more
+    assert_eq!(queue.position_of(3), Some(4));
+
+    queue.continue_after(3);
+    queue.remove(1);
+    assert_eq!(queue.position_of(4), Some(0));
+    assert_eq!(queue.position_of(2), Some(1));
+}
@@ -1,16 +1,22 @@
-use log::info;
+use log::{error, info};
 
 use crate::server::spawn_server_task;
 use crate::server_advertisement::spawn_timer_task;
 
+mod admin;
 mod config_provider;
 mod game;
 mod server;
 mod server_advertisement;
+mod status_endpoint;
 mod tasks;
 
+#[cfg(test)]
+mod config_provider_test;
 #[cfg(test)]
 mod server_test;
+#[cfg(test)]
+mod status_endpoint_test;
 
 #[tokio::main]
 async fn main() {
@@ -18,7 +24,17 @@ async fn main() {
 
     info!("Battleship Plus server startup");
 
-    let cfg = config_provider::default_config_provider();
+    let cfg = config_provider::server_config_provider()
+        .and_then(|cfg| cfg.validate_server_config().map(|_| cfg));
+    let cfg = match cfg {
+        Ok(cfg) => cfg,
+        Err(errors) => {
+            for e in errors {
+                error!("Invalid server config: {e}");
+            }
+            std::process::exit(1);
+        }
+    };
 
     let announcement_ctrl = spawn_timer_task(cfg.as_ref()).await;
 
@@ -1,13 +1,12 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
+use std::fs::OpenOptions;
 use std::net::{Ipv4Addr, Ipv6Addr};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use log::{debug, error, info, trace, warn};
-use rand::seq::SliceRandom;
-use rand::thread_rng;
-use tokio::macros::support::thread_rng_n;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::{Receiver, Sender};
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::{mpsc, RwLock, RwLockWriteGuard};
@@ -17,10 +16,10 @@ use battleship_plus_common::game::{ActionValidationError, PlayerID};
 use battleship_plus_common::messages::ship_action_request::ActionProperties;
 use battleship_plus_common::messages::status_message::Data;
 use battleship_plus_common::messages::{
-    ship_action_event, DestructionEvent, GameOverEvent, GameStart, HitEvent, JoinResponse,
-    LobbyChangeEvent, NextTurn, PlacementPhase, PlacementResponse, ProtocolMessage,
+    ship_action_event, DestructionEvent, GameListResponse, GameOverEvent, GameStart, HitEvent,
+    JoinResponse, LobbyChangeEvent, NextTurn, PlacementPhase, PlacementResponse, ProtocolMessage,
     ServerConfigResponse, ServerStateResponse, SetReadyStateRequest, SetReadyStateResponse,
-    ShipActionEvent, ShipActionResponse, SplashEvent, StatusCode, StatusMessage,
+    ShipActionEvent, ShipActionRequest, ShipActionResponse, SplashEvent, StatusCode, StatusMessage,
     TeamSwitchResponse, VisionEvent,
 };
 use battleship_plus_common::types::{
@@ -30,13 +29,22 @@ use battleship_plus_common::types::{
 use battleship_plus_common::{protocol_name, protocol_name_with_version};
 use bevy_quinnet_server::certificate::CertificateRetrievalMode;
 use bevy_quinnet_server::{
-    ClientId, Endpoint, EndpointEvent, QuinnetError, Server, ServerConfigurationData,
+    ClientId, ClientIdPool, Endpoint, EndpointEvent, QuinnetError, Server, ServerConfigurationData,
 };
 
+use crate::admin::{spawn_admin_console_task, AdminCommand};
 use crate::config_provider::ConfigProvider;
-use crate::game::actions::{Action, ActionExecutionError, ActionResult};
-use crate::game::data::{Game, GameResult, Player, Turn};
-use crate::game::states::GameState;
+use crate::game::actions::{
+    check_player_not_eliminated, Action, ActionExecutionError, ActionResult,
+};
+use crate::game::data::{Game, GameResult, JoinError, Player};
+use crate::game::lobby_file::{claim_saved_seat, LobbyFile};
+use crate::game::objectives::detect_objectives;
+use crate::game::ready_timeout::{enforce_ready_deadlines, ready_timeout, set_ready_deadlines};
+use crate::game::registry::{GameID, GameRegistry, GameRegistryError, RegisteredGame};
+use crate::game::states::{is_client_bound, GameState};
+use crate::game::teams::{place_into_team, RequestedTeam};
+use crate::status_endpoint::spawn_status_endpoint_task;
 use crate::tasks::{upgrade_oneshot, TaskControl};
 
 pub fn spawn_server_task(cfg: Arc<dyn ConfigProvider + Send + Sync>) -> TaskControl {
@@ -50,6 +58,10 @@ type BroadcastChannel = (
     Receiver<(Vec<ClientId>, ProtocolMessage)>,
 );
 
+pub(crate) fn broadcast_channel(capacity: usize) -> BroadcastChannel {
+    tokio::sync::broadcast::channel(capacity.max(1))
+}
+
 pub async fn server_task(
     cfg: Arc<dyn ConfigProvider + Send + Sync>,
     stop: tokio::sync::oneshot::Receiver<()>,
@@ -77,13 +89,17 @@ pub async fn server_task(
         Some(domain) => String::from(domain),
     };
 
-    let mut server6 = Server::new_standalone();
+    // both endpoints hand out the ids of the players, so they have to come from one pool
+    let client_ids = ClientIdPool::default();
+
+    let mut server6 = Server::new_standalone().with_client_ids(client_ids.clone());
     let server6 = match server6.start_endpoint_with_alpn(
         ServerConfigurationData::new(
             ascii_host.clone(),
             addr6.port(),
             Ipv6Addr::UNSPECIFIED.to_string(),
-        ),
+        )
+        .with_max_pending_bytes(cfg.server_config().max_pending_bytes),
         CertificateRetrievalMode::LoadFromFileOrGenerateSelfSigned {
             cert_file: "./certificate6.pem".to_string(),
             key_file: "./key6.pem".to_string(),
@@ -112,13 +128,14 @@ pub async fn server_task(
             // TODO: Find a nice way to support dual stack and non dual stack OSs
             server4 = None;
         } else {
-            let mut s4 = Server::new_standalone();
+            let mut s4 = Server::new_standalone().with_client_ids(client_ids);
             server4 = match s4.start_endpoint_with_alpn(
                 ServerConfigurationData::new(
                     ascii_host.clone(),
                     addr4.port(),
                     Ipv4Addr::UNSPECIFIED.to_string(),
-                ),
+                )
+                .with_max_pending_bytes(cfg.server_config().max_pending_bytes),
                 CertificateRetrievalMode::LoadFromFileOrGenerateSelfSigned {
                     cert_file: "./certificate4.pem".to_string(),
                     key_file: "./key4.pem".to_string(),
@@ -137,65 +154,128 @@ pub async fn server_task(
 
     info!("Endpoints initialized");
 
-    loop {
-        let game = Game::default();
-
-        // check game config
-        if let Err(e) = game.check_game_config() {
-            error!("Game config check failed: {e}");
-        } else {
-            let game = Arc::new(RwLock::new(game));
-            let (game_end_tx, mut game_end_rx) = mpsc::unbounded_channel();
+    // check game config
+    if let Err(e) = Game::new(cfg.game_config()).check_game_config() {
+        error!("Game config check failed: {e}");
+        return;
+    }
 
-            let (broadcast_tx, broadcast_rx): BroadcastChannel =
-                tokio::sync::broadcast::channel(128);
+    let (ended_games_tx, mut ended_games_rx) = mpsc::unbounded_channel();
+    let mut registry = GameRegistry::new(
+        cfg.game_config(),
+        cfg.server_config().max_games,
+        ended_games_tx,
+    );
+    if let Some(path) = cfg.server_config().snapshot_log {
+        match OpenOptions::new().create(true).append(true).open(path) {
+            Ok(file) => {
+                info!("Logging board snapshots to {path}");
+                registry.log_snapshots_to(Arc::new(Mutex::new(file)));
+            }
+            Err(e) => warn!("Unable to open the snapshot log {path}: {e}"),
+        }
+    }
+    if let Some(path) = cfg.server_config().audit_log {
+        match OpenOptions::new().create(true).append(true).open(path) {
+            Ok(file) => {
+                info!("Logging executed actions to {path}");
+                registry.log_audit_to(Arc::new(Mutex::new(file)));
+            }
+            Err(e) => warn!("Unable to open the audit log {path}: {e}"),
+        }
+    }
+    if let Some(path) = cfg.server_config().lobby_file {
+        let lobby_file = LobbyFile::new(path);
+        match lobby_file.load() {
+            Ok(saved_seats) => {
+                info!(
+                    "Keeping the lobby in {path}, {} seats restored",
+                    saved_seats.len()
+                );
+                registry.keep_lobby_in(lobby_file, saved_seats);
+            }
+            Err(e) => warn!("Unable to restore the lobby from {path}: {e}"),
+        }
+    }
+    if cfg.server_config().honor_requested_team {
+        registry.assign_teams_with(Arc::new(RequestedTeam));
+    }
+    let registry = Arc::new(RwLock::new(registry));
 
-            let servers: Vec<_> = [server6.clone(), server4.clone()]
-                .iter()
-                .filter(|e| e.is_some())
-                .map(|s| s.as_ref().unwrap().clone())
-                .collect();
+    // the status endpoint stops on its own once the registry is dropped
+    if let Some(status_address) = cfg.server_config().status_address {
+        let latest_game = registry.read().await.watch_latest_game();
+        spawn_status_endpoint_task(status_address, latest_game).await;
+    }
 
-            let handles: Vec<_> = servers
-                .iter()
-                .map(|server| {
-                    let (cancel_tx, cancel_rx) = mpsc::unbounded_channel();
+    let (broadcast_tx, broadcast_rx) = broadcast_channel(cfg.server_config().broadcast_capacity);
 
-                    (
-                        tokio::spawn(endpoint_task(
-                            cfg.game_config(),
-                            server.clone(),
-                            broadcast_tx.clone(),
-                            broadcast_rx.resubscribe(),
-                            game.clone(),
-                            game_end_tx.clone(),
-                            cancel_rx,
-                        )),
-                        cancel_tx,
-                    )
-                })
-                .collect();
+    // without a console the sender is dropped right away and the channel is closed
+    let mut admin_commands = if cfg.server_config().admin_console {
+        spawn_admin_console_task()
+    } else {
+        mpsc::unbounded_channel().1
+    };
+    let mut admin_console_open = true;
+    let (admin_tx, _) = tokio::sync::broadcast::channel(16);
 
-            info!("New game initialized");
+    let servers: Vec<_> = [server6, server4]
+        .iter()
+        .filter(|e| e.is_some())
+        .map(|s| s.as_ref().unwrap().clone())
+        .collect();
 
-            tokio::select! {
-                _ = game_end_rx.recv() => {},
-                _ = stop.recv() => return,
-            }
+    let handles: Vec<_> = servers
+        .iter()
+        .map(|server| {
+            let (cancel_tx, cancel_rx) = mpsc::unbounded_channel();
+
+            (
+                tokio::spawn(endpoint_task(
+                    cfg.game_config(),
+                    server.clone(),
+                    broadcast_tx.clone(),
+                    broadcast_rx.resubscribe(),
+                    registry.clone(),
+                    admin_tx.subscribe(),
+                    cancel_rx,
+                )),
+                cancel_tx,
+            )
+        })
+        .collect();
 
-            // TODO: find a better way to wait for queues
-            // let queues run out
-            tokio::time::sleep(Duration::from_secs(3)).await;
-            info!("Game finished");
+    loop {
+        tokio::select! {
+            ended_game = ended_games_rx.recv() => {
+                let game_id = match ended_game {
+                    Some(game_id) => game_id,
+                    None => continue,
+                };
+                tokio::spawn(remove_ended_game(
+                    registry.clone(),
+                    game_id,
+                    cfg.server_config().game_end_drain_time,
+                ));
+            },
+            command = admin_commands.recv(), if admin_console_open => match command {
+                Some(command) => {
+                    info!("Executing admin command {command:?}");
+                    // endpoints ignore commands for clients they do not know
+                    let _ = admin_tx.send(command);
+                }
+                None => admin_console_open = false,
+            },
+            _ = stop.recv() => break,
+        }
+    }
 
-            for h in handles {
-                h.1.send(())
-                    .expect("unable to notify endpoint tasks to cancel");
+    for h in handles {
+        h.1.send(())
+            .expect("unable to notify endpoint tasks to cancel");
 
-                if let Err(e) = h.0.await {
-                    error!("server task finished with an error {e}");
-                }
-            }
+        if let Err(e) = h.0.await {
+            error!("server task finished with an error {e}");
         }
     }
 }
@@ -205,8 +285,8 @@ async fn endpoint_task(
     server: Arc<RwLock<Server>>,
     broadcast_tx: tokio::sync::broadcast::Sender<(Vec<ClientId>, ProtocolMessage)>,
     mut broadcast_rx: tokio::sync::broadcast::Receiver<(Vec<ClientId>, ProtocolMessage)>,
-    game: Arc<RwLock<Game>>,
-    game_end_tx: mpsc::UnboundedSender<()>,
+    registry: Arc<RwLock<GameRegistry>>,
+    mut admin_rx: Receiver<AdminCommand>,
     mut cancel_rx: mpsc::UnboundedReceiver<()>,
 ) {
     loop {
@@ -219,6 +299,9 @@ async fn endpoint_task(
             biased;
             _ = cancel_rx.recv() => return,
             broadcast = broadcast_rx.recv() => {
+                if let Err(RecvError::Lagged(skipped)) = broadcast {
+                    warn!("Skipped {skipped} broadcasts, the broadcast capacity may be too small");
+                }
                 if let Ok((ids, msg)) = broadcast {
                     debug!("broadcast to {ids:?}: {msg:?}");
 
@@ -235,34 +318,53 @@ async fn endpoint_task(
                 }
                 continue;
             },
+            Ok(command) = admin_rx.recv() => {
+                match command {
+                    AdminCommand::Trace { client_id, enabled } => {
+                        if server.endpoint().set_protocol_tracing(client_id, enabled).is_ok() {
+                            info!("Protocol tracing for client {client_id} enabled: {enabled}");
+                        }
+                    }
+                }
+                continue;
+            },
             event = server.endpoint_mut().next_event() => {
                 match event {
                     EndpointEvent::Payload(p) => {
                         debug!("Client {} sent: {p:?}", p.client_id);
                         p
                     }
-                    EndpointEvent::Connect(client_id) => {
-                        info!("Client {client_id} connected");
+                    EndpointEvent::Connect(client_id, address) => {
+                        info!("Client {client_id} connected from {address}");
                         continue;
                     }
-                    EndpointEvent::Disconnect(client_it) => {
-                        info!("Client {client_it} disconnected");
+                    EndpointEvent::Disconnect(client_it, reason) => {
+                        info!("Client {client_it} disconnected ({reason:?})");
+                        let RegisteredGame { id: game_id, game, game_end_tx } =
+                            match registry.write().await.leave(client_it) {
+                                Some(registered_game) => registered_game,
+                                None => continue,
+                            };
                         let mut game = game.write().await;
                         if game.remove_player(client_it) {
-                            info!("Ending game due lost connection to player {client_it}...");
-                            debug!("Disconnecting all clients...");
+                            info!("Ending game {game_id} due lost connection to player {client_it}...");
+                            debug!("Disconnecting all clients of game {game_id}...");
 
-                            server.endpoint().try_broadcast_message(GameOverEvent {
+                            server.endpoint().try_send_group_message(game.players.keys(), GameOverEvent {
                                 reason: GameEndReason::Disconnect.into(),
-                                winner: Teams::None.into(),
+                                winner: game.winner_on_abort().into(),
+                                revealed_ships: vec![],
                             }.into());
 
-                            if let Err(e) = server.endpoint_mut().disconnect_all_clients() {
-                                error!("Unable to disconnect all client: {e}");
+                            for &player_id in game.players.keys() {
+                                if let Err(e) = server.endpoint_mut().disconnect_client(player_id) {
+                                    debug!("Unable to disconnect client {player_id}: {e}");
+                                }
                             }
                             game_end_tx.send(()).expect("unable to notify the end of the game");
                         }
                         if matches!(game.state, GameState::Lobby) {
+                            save_lobby(&game);
                             if let Err(e) = broadcast_lobby_change_event(
                                 game.team_a.iter().cloned(),
                                 game.team_b.iter().cloned(),
@@ -272,6 +374,13 @@ async fn endpoint_task(
                             }
                         }
 
+                        // close lobbies nobody is waiting in anymore
+                        let abandoned = matches!(game.state, GameState::Lobby) && game.players.is_empty();
+                        drop(game);
+                        if abandoned {
+                            registry.write().await.remove_game(game_id).await;
+                        }
+
                         continue;
                     }
                     EndpointEvent::UnsupportedVersionMessage{client_id, version} => {
@@ -286,6 +395,13 @@ async fn endpoint_task(
                         };
                         continue;
                     }
+                    EndpointEvent::ProtocolError{client_id, description} => {
+                        warn!("Client {client_id} sent a malformed message: {description}. Aborting connection..");
+                        if let Err(error) = server.endpoint_mut().disconnect_client(client_id) {
+                            debug!("Unable to disconnect client {client_id}: {error}");
+                        }
+                        continue;
+                    }
                     EndpointEvent::SocketClosed => {
                         debug!("Server socket closed");
                         continue;
@@ -308,8 +424,7 @@ async fn endpoint_task(
             ep,
             payload.client_id,
             payload.msg.as_ref().unwrap(),
-            &game,
-            &game_end_tx,
+            &registry,
             &broadcast_tx,
         )
         .await
@@ -345,13 +460,87 @@ async fn handle_message(
     ep: &mut Endpoint,
     client_id: ClientId,
     msg: &ProtocolMessage,
-    game: &Arc<RwLock<Game>>,
-    game_end_tx: &UnboundedSender<()>,
+    registry: &Arc<RwLock<GameRegistry>>,
     broadcast_tx: &Sender<(Vec<ClientId>, ProtocolMessage)>,
 ) -> Result<(), MessageHandlerError> {
+    let registered_game = match msg {
+        // keepalives only keep NAT mappings of quiet connections open
+        ProtocolMessage::Keepalive(_) => {
+            trace!("Keepalive from client {client_id}");
+            return Ok(());
+        }
+        // the server config is independent of any game
+        ProtocolMessage::ServerConfigRequest(_) => {
+            return ep
+                .send_message(
+                    client_id,
+                    status_with_data(
+                        StatusCode::Ok,
+                        ServerConfigResponse {
+                            config: Some(cfg.as_ref().clone()),
+                        }
+                        .into(),
+                    ),
+                )
+                .map_err(MessageHandlerError::Network)
+        }
+        // the game list is requested before joining
+        ProtocolMessage::GameListRequest(_) => {
+            let games = registry.read().await.list_games().await;
+            return ep
+                .send_message(
+                    client_id,
+                    status_with_data(StatusCode::Ok, GameListResponse { games }.into()),
+                )
+                .map_err(MessageHandlerError::Network);
+        }
+        ProtocolMessage::JoinRequest(props) => {
+            match registry.write().await.join(client_id, props.game_id).await {
+                Ok(registered_game) => Some(registered_game),
+                Err(e) => {
+                    let code = match e {
+                        GameRegistryError::GameNotJoinable(_) => StatusCode::GameInProgress,
+                        _ => StatusCode::BadRequest,
+                    };
+                    ep.send_message(client_id, status_with_msg(code, e.to_string().as_str()))
+                        .map_err(MessageHandlerError::Network)?;
+
+                    return Err(MessageHandlerError::InvalidInboundMessage(e.to_string()));
+                }
+            }
+        }
+        _ => registry.read().await.game_of(client_id),
+    };
+    let RegisteredGame {
+        id: game_id,
+        game,
+        game_end_tx,
+    } = match registered_game {
+        Some(registered_game) => registered_game,
+        None => {
+            ep.send_message(
+                client_id,
+                status_with_msg(StatusCode::BadRequest, "not joined"),
+            )
+            .map_err(MessageHandlerError::Network)?;
+
+            return Err(MessageHandlerError::InvalidInboundMessage(format!(
+                "{msg:?} requires to join a game first"
+            )));
+        }
+    };
+    let (game, game_end_tx) = (&game, &game_end_tx);
+
     {
         let g = game.read().await;
         if let Err(reason) = g.state.validate_inbound_message_allowed(msg) {
+            if let ProtocolMessage::JoinRequest(_) = msg {
+                drop(g);
+                return reject_join(ep, registry, client_id, JoinError::GameInProgress).await;
+            }
+            if is_client_bound(msg) {
+                return reject_client_bound_message(ep, client_id, msg, cfg.strict_protocol);
+            }
             ep.send_message(
                 client_id,
                 status_with_msg(StatusCode::BadRequest, "message not allowed now"),
@@ -363,53 +552,23 @@ async fn handle_message(
     }
 
     match msg {
-        // common
-        ProtocolMessage::ServerConfigRequest(_) => ep
-            .send_message(
-                client_id,
-                status_with_data(
-                    StatusCode::Ok,
-                    ServerConfigResponse {
-                        config: Some(cfg.as_ref().clone()),
-                    }
-                    .into(),
-                ),
-            )
-            .map_err(MessageHandlerError::Network),
-
         // lobby
         ProtocolMessage::JoinRequest(props) => {
-            {
-                let g = game.read().await;
-                if g.players.contains_key(&client_id) {
-                    ep.send_message(
-                        client_id,
-                        status_with_msg(StatusCode::BadRequest, "you joined already"),
-                    )
-                    .map_err(MessageHandlerError::Network)?;
-                }
-                if g.players.values().any(|p| p.name == props.username) {
-                    ep.send_message(
-                        client_id,
-                        status_with_msg(StatusCode::UsernameIsTaken, "username is already taken"),
-                    )
-                    .map_err(MessageHandlerError::Network)?;
-                }
-            }
-
+            // the state is checked again under the write lock,
+            // the game may have started since the validation above
             let mut g = game.write().await;
-            g.players.insert(
-                client_id,
-                Player {
-                    id: client_id,
-                    name: props.username.clone(),
-                    is_ready: false,
-                    quadrant: None,
-                },
-            );
+            if let Err(e) = g.add_player(client_id, &props.username) {
+                drop(g);
+                return reject_join(ep, registry, client_id, e).await;
+            }
 
-            place_into_team(client_id, &mut g);
-            g.unready_players();
+            // players taking their seat from before a restart keep the lobby as it was
+            let claimed_seat = claim_saved_seat(&mut g, client_id, props.seat_token.as_deref());
+            if !claimed_seat {
+                place_into_team(&mut g, client_id, props);
+                g.unready_players();
+            }
+            save_lobby(&g);
 
             ep.send_message(
                 client_id,
@@ -417,18 +576,41 @@ async fn handle_message(
                     StatusCode::Ok,
                     JoinResponse {
                         player_id: client_id,
+                        game_id,
+                        seat_token: g.players[&client_id].seat_token.clone(),
                     }
                     .into(),
                 ),
             )
             .map_err(MessageHandlerError::Network)?;
+            // returning players get their view of the game without asking for it
+            if claimed_seat {
+                if let Some(state) = server_state_of(&g, client_id) {
+                    ep.send_message(
+                        client_id,
+                        status_with_data(
+                            StatusCode::Ok,
+                            ServerStateResponse { state: Some(state) }.into(),
+                        ),
+                    )
+                    .map_err(MessageHandlerError::Network)?;
+                }
+            }
 
             broadcast_lobby_change_event(
                 g.team_a.iter().cloned(),
                 g.team_b.iter().cloned(),
                 g.players.clone(),
                 broadcast_tx,
-            )
+            )?;
+
+            // joins usually unready the lobby, so everyone who is not ready gets a new deadline
+            if let Some(timeout) = ready_timeout(&g) {
+                set_ready_deadlines(&mut g, Instant::now() + timeout);
+                schedule_ready_deadlines(game_id, game, registry, broadcast_tx, timeout);
+            }
+
+            try_start_preparation(&mut g, broadcast_tx).map(|_| ())
         }
         ProtocolMessage::TeamSwitchRequest(_) => {
             let action = Action::TeamSwitch {
@@ -438,13 +620,14 @@ async fn handle_message(
             let mut g = game.write().await;
             let state = g.get_state();
             if let Err(e) = state.execute_action(action, &mut g) {
-                action_validation_error_reply(ep, client_id, e, game_end_tx)
+                action_validation_error_reply(ep, client_id, e, g.players.keys(), game_end_tx)
             } else {
                 ep.send_message(
                     client_id,
                     status_with_data(StatusCode::Ok, TeamSwitchResponse {}.into()),
                 )
                 .map_err(MessageHandlerError::Network)?;
+                save_lobby(&g);
 
                 broadcast_lobby_change_event(
                     g.team_a.iter().cloned(),
@@ -465,13 +648,14 @@ async fn handle_message(
             let mut g = game.write().await;
             let state = g.get_state();
             if let Err(e) = state.execute_action(action, &mut g) {
-                action_validation_error_reply(ep, client_id, e, game_end_tx)
+                action_validation_error_reply(ep, client_id, e, g.players.keys(), game_end_tx)
             } else {
                 ep.send_message(
                     client_id,
                     status_with_data(StatusCode::Ok, SetReadyStateResponse {}.into()),
                 )
                 .map_err(MessageHandlerError::Network)?;
+                save_lobby(&g);
 
                 broadcast_lobby_change_event(
                     g.team_a.iter().cloned(),
@@ -480,19 +664,7 @@ async fn handle_message(
                     broadcast_tx,
                 )?;
 
-                if g.can_change_into_preparation_phase() {
-                    g.state = GameState::Preparation;
-                    info!("GamePhase: Preparation");
-                    let quadrants = g.quadrants();
-
-                    broadcast_game_preparation_start(
-                        g.players.values_mut().collect(),
-                        quadrants,
-                        broadcast_tx,
-                    )?;
-                }
-
-                Ok(())
+                try_start_preparation(&mut g, broadcast_tx).map(|_| ())
             }
         }
 
@@ -501,9 +673,16 @@ async fn handle_message(
             let action = Action::from((client_id, request));
 
             let mut g = game.write().await;
-            g.get_state()
-                .execute_action(action, &mut g)
-                .map_err(MessageHandlerError::Protocol)?;
+            let state = g.get_state();
+            if let Err(e) = state.execute_action(action, &mut g) {
+                return action_validation_error_reply(
+                    ep,
+                    client_id,
+                    e,
+                    g.players.keys(),
+                    game_end_tx,
+                );
+            }
 
             ep.send_message(
                 client_id,
@@ -512,20 +691,19 @@ async fn handle_message(
             .map_err(MessageHandlerError::Network)?;
 
             if g.can_change_into_game_phase() {
-                info!("GamePhase: InGame");
-                g.state = GameState::InGame;
-                g.advance_turn();
-                broadcast_game_start(&g, broadcast_tx)?;
+                start_game(&mut g, game, broadcast_tx)?;
             }
 
             Ok(())
         }
+        ProtocolMessage::GameStartAck(_) => {
+            acknowledge_game_start(&mut *game.write().await, client_id, broadcast_tx)
+        }
 
         // game
         ProtocolMessage::ServerStateRequest(_) => {
-            let g = game.read().await;
-            let players = match g.players.get(&client_id) {
-                Some(p) => p,
+            let state = match server_state_of(&*game.read().await, client_id) {
+                Some(state) => state,
                 None => {
                     return ep
                         .send_message(
@@ -536,49 +714,46 @@ async fn handle_message(
                 }
             };
 
-            let ship_sets = get_ships_by_team(&g);
-            let visible_hostile_ships = (
-                g.ships.get_ship_parts_seen_by(
-                    &ship_sets.0.iter().map(|ship| ship.id()).collect::<Vec<_>>(),
-                ),
-                g.ships.get_ship_parts_seen_by(
-                    &ship_sets.1.iter().map(|ship| ship.id()).collect::<Vec<_>>(),
-                ),
-            );
-            let ship_sets: (Vec<_>, Vec<_>) = (
-                ship_sets
-                    .0
-                    .iter()
-                    .map(|ship| create_ship_state(ship))
-                    .collect(),
-                ship_sets
-                    .1
-                    .iter()
-                    .map(|ship| create_ship_state(ship))
-                    .collect(),
-            );
-
             ep.send_message(
                 client_id,
                 status_with_data(
                     StatusCode::Ok,
-                    ServerStateResponse {
-                        state: Some(get_server_state_for_player(
-                            players,
-                            &g,
-                            ship_sets,
-                            visible_hostile_ships,
-                        )),
-                    }
-                    .into(),
+                    ServerStateResponse { state: Some(state) }.into(),
                 ),
             )
             .map_err(MessageHandlerError::Network)
         }
         ProtocolMessage::ActionRequest(request) => {
             let action = Action::from((client_id, request));
+            // only an explicit request ends the turn, a malformed one must not burn it
+            if let Action::None = action {
+                return Err(MessageHandlerError::Protocol(
+                    ActionExecutionError::BadRequest(String::from("action properties are missing")),
+                ));
+            }
 
             let mut g = game.write().await;
+            if g.is_retransmitted_action(client_id, request.idempotency_key) {
+                debug!("Client {client_id} retransmitted an action, replying without executing it again");
+                let response = ShipActionResponse {
+                    action_points_charged: g.players[&client_id].last_action_cost,
+                    action_points_left: g.action_points_left(client_id),
+                };
+                return ep
+                    .send_message(client_id, ship_action_response(response))
+                    .map_err(MessageHandlerError::Network);
+            }
+
+            if let Err(e) = check_player_not_eliminated(&g, client_id) {
+                return action_validation_error_reply(
+                    ep,
+                    client_id,
+                    ActionExecutionError::Validation(e),
+                    g.players.keys(),
+                    game_end_tx,
+                );
+            }
+
             let turn = match g.turn.as_ref() {
                 None => {
                     return Err(MessageHandlerError::Protocol(
@@ -588,7 +763,7 @@ async fn handle_message(
                 Some(t) => t,
             };
 
-            if turn.player_id != client_id {
+            if turn.player_id != client_id || g.awaiting_start_acks.is_some() {
                 return Err(MessageHandlerError::Protocol(
                     ActionExecutionError::Validation(ActionValidationError::NotPlayersTurn),
                 ));
@@ -605,193 +780,299 @@ async fn handle_message(
             .iter()
             .cloned()
             .collect::<Vec<_>>();
-            if let Action::None = action {
-                let turn = g.clear_temp_vision_and_advance_turn(team.as_slice(), broadcast_tx)?;
-                return ep
-                    .broadcast_message(
-                        NextTurn {
-                            next_player_id: turn.player_id,
-                            position_in_queue: 0, //TODO
-                        }
-                        .into(),
-                    )
-                    .map_err(MessageHandlerError::Network);
-            }
-
-            let action_result = g
-                .get_state()
-                .execute_action(action.clone(), &mut g)
-                .map_err(MessageHandlerError::Protocol)?;
-
-            broadcast_tx
-                .send((
-                    team.clone(),
-                    ShipActionEvent {
-                        ship_number: request.ship_number,
-                        action_properties: request.action_properties.clone().map(|p| match p {
-                            ActionProperties::MoveProperties(p) => {
-                                ship_action_event::ActionProperties::MoveProperties(p)
-                            }
-                            ActionProperties::ShootProperties(p) => {
-                                ship_action_event::ActionProperties::ShootProperties(p)
-                            }
-                            ActionProperties::RotateProperties(p) => {
-                                ship_action_event::ActionProperties::RotateProperties(p)
-                            }
-                            ActionProperties::TorpedoProperties(p) => {
-                                ship_action_event::ActionProperties::TorpedoProperties(p)
-                            }
-                            ActionProperties::ScoutPlaneProperties(p) => {
-                                ship_action_event::ActionProperties::ScoutPlaneProperties(p)
-                            }
-                            ActionProperties::MultiMissileProperties(p) => {
-                                ship_action_event::ActionProperties::MultiMissileProperties(p)
-                            }
-                            ActionProperties::PredatorMissileProperties(p) => {
-                                ship_action_event::ActionProperties::PredatorMissileProperties(p)
-                            }
-                            ActionProperties::EngineBoostProperties(p) => {
-                                ship_action_event::ActionProperties::EngineBoostProperties(p)
-                            }
-                        }),
-                    }
-                    .into(),
-                ))
-                .map_err(|e| MessageHandlerError::Broadcast(e.into()))?;
-
-            match action_result {
-                ActionResult::None => Ok(()),
-                ActionResult::Single {
+            if let Action::EndTurn = action {
+                g.remember_action_key(client_id, request.idempotency_key);
+                let (turn, burn_result) =
+                    g.clear_temp_vision_and_advance_turn(team.as_slice(), broadcast_tx)?;
+                if let ActionResult::Single {
                     lost_vision_at,
-                    temp_vision_at,
-                    gain_vision_at,
-                    ships_destroyed,
                     inflicted_damage_at,
-                    gain_enemy_vision,
+                    ships_destroyed,
                     lost_enemy_vision,
                     splash_tiles,
                     ..
-                } => {
-                    match handle_action_result(
+                } = burn_result
+                {
+                    let result = handle_action_result(
                         &mut g,
-                        client_id,
+                        turn.player_id,
                         broadcast_tx,
-                        gain_vision_at,
-                        temp_vision_at,
+                        HashSet::new(),
+                        HashSet::new(),
                         lost_vision_at,
-                        gain_enemy_vision,
+                        HashSet::new(),
                         lost_enemy_vision,
                         inflicted_damage_at,
                         ships_destroyed,
                         splash_tiles,
-                    ) {
-                        Ok(GameResult::Pending) => Ok(()),
-                        Ok(result) => broadcast_game_result(
-                            result,
-                            g.players.keys().cloned().collect(),
-                            broadcast_tx,
-                            game_end_tx,
-                        ),
-                        Err(e) => Err(e),
+                    )?;
+                    if !matches!(result, GameResult::Pending) {
+                        return broadcast_game_result(result, &mut g, broadcast_tx, game_end_tx);
                     }
                 }
-                ActionResult::EngineBoost(direction, results) => {
-                    for res in results {
-                        match res {
-                            Ok(ActionResult::Single {
-                                lost_vision_at,
-                                temp_vision_at,
-                                gain_vision_at,
-                                ships_destroyed,
-                                inflicted_damage_at,
-                                gain_enemy_vision,
-                                lost_enemy_vision,
-                                splash_tiles,
-                                ..
-                            }) => {
-                                if let Action::EngineBoost { .. } = action {
-                                    broadcast_tx
-                                        .send((
-                                            team.clone(),
-                                            ShipActionEvent {
-                                                ship_number: request.ship_number,
-                                                action_properties: Some(
-                                                    MoveProperties {
-                                                        direction: direction.into(),
-                                                    }
-                                                    .into(),
-                                                ),
-                                            }
-                                            .into(),
-                                        ))
-                                        .map_err(|e| MessageHandlerError::Broadcast(e.into()))?;
-                                }
-
-                                match handle_action_result(
-                                    &mut g,
-                                    client_id,
-                                    broadcast_tx,
-                                    gain_vision_at,
-                                    temp_vision_at,
-                                    lost_vision_at,
-                                    gain_enemy_vision,
-                                    lost_enemy_vision,
-                                    inflicted_damage_at,
-                                    ships_destroyed,
-                                    splash_tiles,
-                                ) {
-                                    Ok(GameResult::Pending) => Ok(()),
-                                    Ok(result) => broadcast_game_result(
-                                        result,
-                                        g.players.keys().cloned().collect(),
-                                        broadcast_tx,
-                                        game_end_tx,
-                                    ),
-                                    Err(e) => Err(e),
-                                }?
-                            }
-                            Err(_) => ep
-                                .send_message(
-                                    client_id,
-                                    status_with_msg(StatusCode::OkWithWarning, "OK, with warning"),
-                                )
-                                .map_err(MessageHandlerError::Network)?,
-                            _ => unreachable!(),
-                        }
-                    }
+                return broadcast_next_turn(&g, broadcast_tx);
+            }
 
-                    Ok(())
-                }
-            }?;
+            let (action_result, response) = execute_charged_action(&mut g, client_id, action)
+                .map_err(MessageHandlerError::Protocol)?;
+            g.remember_action_key(client_id, request.idempotency_key);
 
-            ep.send_message(
+            let engine_boost_step_failed = broadcast_action_outcome(
+                &mut g,
                 client_id,
-                status_with_data(StatusCode::Ok, ShipActionResponse::default().into()),
-            )
-            .map_err(MessageHandlerError::Network)?;
+                &team,
+                request,
+                action_result,
+                broadcast_tx,
+                game_end_tx,
+            )?;
+            if engine_boost_step_failed {
+                ep.send_message(
+                    client_id,
+                    status_with_msg(StatusCode::OkWithWarning, "OK, with warning"),
+                )
+                .map_err(MessageHandlerError::Network)?;
+            }
+
+            ep.send_message(client_id, ship_action_response(response))
+                .map_err(MessageHandlerError::Network)?;
 
             Ok(())
         }
 
         // received a client-bound message
-        _ => {
-            warn!("Client {} sent a client-bound message {:?}", client_id, msg);
-            ep.send_message(
+        _ => reject_client_bound_message(ep, client_id, msg, cfg.strict_protocol),
+    }
+}
+
+/// Clients sending client-bound messages are only disconnected if the config demands a strict
+/// protocol, debugging clients may send them by accident.
+fn reject_client_bound_message(
+    ep: &mut Endpoint,
+    client_id: ClientId,
+    msg: &ProtocolMessage,
+    strict_protocol: bool,
+) -> Result<(), MessageHandlerError> {
+    warn!("Client {} sent a client-bound message {:?}", client_id, msg);
+    ep.send_message(
+        client_id,
+        status_with_msg(
+            StatusCode::BadRequest,
+            "unable to process client-bound messages",
+        ),
+    )
+    .map_err(MessageHandlerError::Network)?;
+    if strict_protocol {
+        ep.disconnect_client(client_id)
+            .map_err(MessageHandlerError::Network)
+    } else {
+        Ok(())
+    }
+}
+
+/// Removes an ended game from the registry once the drain time passed.
+pub(crate) async fn remove_ended_game(
+    registry: Arc<RwLock<GameRegistry>>,
+    game_id: GameID,
+    drain_time: Duration,
+) {
+    // let the queues of the clients run out
+    tokio::time::sleep(drain_time).await;
+    info!("Game {game_id} finished");
+    registry.write().await.remove_game(game_id).await;
+}
+
+/// Orders tiles by row and then by column, so the same tiles are always sent in the same order.
+pub(crate) fn sorted_coordinates<'a>(
+    tiles: impl IntoIterator<Item = &'a Coordinate>,
+) -> Vec<Coordinate> {
+    let mut tiles: Vec<_> = tiles.into_iter().cloned().collect();
+    tiles.sort_unstable_by_key(|Coordinate { x, y }| (*y, *x));
+    tiles
+}
+
+/// Broadcasts the action of a ship to the team of its owner, followed by its outcome.
+/// The action event is sent whatever the outcome, so the team also sees actions without any
+/// visible effect, e.g. a move that neither reveals nor hides a ship. Returns whether a step of
+/// an engine boost failed.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn broadcast_action_outcome(
+    g: &mut Game,
+    client_id: ClientId,
+    team: &[ClientId],
+    request: &ShipActionRequest,
+    action_result: ActionResult,
+    broadcast_tx: &Sender<(Vec<ClientId>, ProtocolMessage)>,
+    game_end_tx: &UnboundedSender<()>,
+) -> Result<bool, MessageHandlerError> {
+    let mut step_failed = false;
+    broadcast_tx
+        .send((
+            team.to_vec(),
+            ShipActionEvent {
+                ship_number: request.ship_number,
+                action_properties: request.action_properties.clone().map(|p| match p {
+                    ActionProperties::MoveProperties(p) => {
+                        ship_action_event::ActionProperties::MoveProperties(p)
+                    }
+                    ActionProperties::ShootProperties(p) => {
+                        ship_action_event::ActionProperties::ShootProperties(p)
+                    }
+                    ActionProperties::RotateProperties(p) => {
+                        ship_action_event::ActionProperties::RotateProperties(p)
+                    }
+                    ActionProperties::TorpedoProperties(p) => {
+                        ship_action_event::ActionProperties::TorpedoProperties(p)
+                    }
+                    ActionProperties::ScoutPlaneProperties(p) => {
+                        ship_action_event::ActionProperties::ScoutPlaneProperties(p)
+                    }
+                    ActionProperties::MultiMissileProperties(p) => {
+                        ship_action_event::ActionProperties::MultiMissileProperties(p)
+                    }
+                    ActionProperties::PredatorMissileProperties(p) => {
+                        ship_action_event::ActionProperties::PredatorMissileProperties(p)
+                    }
+                    ActionProperties::EngineBoostProperties(p) => {
+                        ship_action_event::ActionProperties::EngineBoostProperties(p)
+                    }
+                    ActionProperties::ShipSwapProperties(p) => {
+                        ship_action_event::ActionProperties::ShipSwapProperties(p)
+                    }
+                }),
+            }
+            .into(),
+        ))
+        .map_err(|e| MessageHandlerError::Broadcast(e.into()))?;
+
+    match action_result {
+        ActionResult::None => Ok(()),
+        ActionResult::Single {
+            lost_vision_at,
+            temp_vision_at,
+            gain_vision_at,
+            ships_destroyed,
+            inflicted_damage_at,
+            gain_enemy_vision,
+            lost_enemy_vision,
+            splash_tiles,
+            ..
+        } => {
+            match handle_action_result(
+                g,
                 client_id,
-                status_with_msg(
-                    StatusCode::BadRequest,
-                    "unable to process client-bound messages",
-                ),
-            )
-            .map_err(MessageHandlerError::Network)?;
-            ep.disconnect_client(client_id)
-                .map_err(MessageHandlerError::Network)
+                broadcast_tx,
+                gain_vision_at,
+                temp_vision_at,
+                lost_vision_at,
+                gain_enemy_vision,
+                lost_enemy_vision,
+                inflicted_damage_at,
+                ships_destroyed,
+                splash_tiles,
+            ) {
+                Ok(GameResult::Pending) => Ok(()),
+                Ok(result) => broadcast_game_result(result, g, broadcast_tx, game_end_tx),
+                Err(e) => Err(e),
+            }
         }
+        ActionResult::EngineBoost(direction, results) => {
+            for res in results {
+                match res {
+                    Ok(ActionResult::Single {
+                        lost_vision_at,
+                        temp_vision_at,
+                        gain_vision_at,
+                        ships_destroyed,
+                        inflicted_damage_at,
+                        gain_enemy_vision,
+                        lost_enemy_vision,
+                        splash_tiles,
+                        ..
+                    }) => {
+                        broadcast_tx
+                            .send((
+                                team.to_vec(),
+                                ShipActionEvent {
+                                    ship_number: request.ship_number,
+                                    action_properties: Some(
+                                        MoveProperties {
+                                            direction: direction.into(),
+                                        }
+                                        .into(),
+                                    ),
+                                }
+                                .into(),
+                            ))
+                            .map_err(|e| MessageHandlerError::Broadcast(e.into()))?;
+
+                        match handle_action_result(
+                            g,
+                            client_id,
+                            broadcast_tx,
+                            gain_vision_at,
+                            temp_vision_at,
+                            lost_vision_at,
+                            gain_enemy_vision,
+                            lost_enemy_vision,
+                            inflicted_damage_at,
+                            ships_destroyed,
+                            splash_tiles,
+                        ) {
+                            Ok(GameResult::Pending) => Ok(()),
+                            Ok(result) => {
+                                broadcast_game_result(result, g, broadcast_tx, game_end_tx)
+                            }
+                            Err(e) => Err(e),
+                        }?
+                    }
+                    Err(_) => step_failed = true,
+                    _ => unreachable!(),
+                }
+            }
+
+            Ok(())
+        }
+    }?;
+    Ok(step_failed)
+}
+
+/// Executes an action of the player and tells them how many action points it cost and how many
+/// they have left, so their client does not need to predict it. The action is recorded in the
+/// audit log of the game, if it has one.
+pub(crate) fn execute_charged_action(
+    game: &mut RwLockWriteGuard<Game>,
+    player_id: PlayerID,
+    action: Action,
+) -> Result<(ActionResult, ShipActionResponse), ActionExecutionError> {
+    let action_points_before = game.action_points_left(player_id);
+    let audited_action = game.audit_log.is_some().then(|| action.clone());
+    let action_result = game.get_state().execute_action(action, game)?;
+    let action_points_left = game.action_points_left(player_id);
+    let action_points_charged = action_points_before.saturating_sub(action_points_left);
+    if let Some(player) = game.players.get_mut(&player_id) {
+        player.last_action_cost = action_points_charged;
     }
+    if let (Some(audit_log), Some(action)) = (game.audit_log.as_ref(), audited_action) {
+        audit_log.record_action(
+            game,
+            player_id,
+            &action,
+            action_points_charged,
+            &action_result,
+        );
+    }
+    Ok((
+        action_result,
+        ShipActionResponse {
+            action_points_charged,
+            action_points_left,
+        },
+    ))
 }
 
 #[allow(clippy::too_many_arguments)]
-fn handle_action_result(
+pub(crate) fn handle_action_result(
     g: &mut Game,
     client_id: ClientId,
     broadcast_tx: &tokio::sync::broadcast::Sender<(Vec<ClientId>, ProtocolMessage)>,
@@ -805,28 +1086,32 @@ fn handle_action_result(
     splash_tiles: HashSet<Coordinate>,
 ) -> Result<GameResult, MessageHandlerError> {
     // vision events
-    let ally_vision_gain = gain_vision_at
-        .iter()
-        .chain(
+    let ally_vision_gain = sorted_coordinates(
+        gain_vision_at.iter().chain(
             temp_vision_at
                 .iter()
                 .filter(|&c| g.turn.as_mut().unwrap().temp_vision.insert(c.clone())),
-        )
-        .cloned()
-        .collect::<Vec<_>>();
+        ),
+    );
 
     let (allies, enemies) = match (g.team_a.contains(&client_id), g.team_b.contains(&client_id)) {
         (true, false) => (&g.team_a, &g.team_b),
         (false, true) => (&g.team_b, &g.team_a),
         _ => unreachable!(),
     };
+    // the vision of the acting ship is only shared with the allies if the config says so
+    let observers = if g.config.share_team_vision {
+        allies.iter().cloned().collect()
+    } else {
+        vec![client_id]
+    };
 
     if !ally_vision_gain.is_empty() || !lost_vision_at.is_empty() {
         if let Err(e) = broadcast_tx.send((
-            allies.iter().cloned().collect(),
+            observers,
             VisionEvent {
                 discovered_ship_fields: ally_vision_gain,
-                vanished_ship_fields: lost_vision_at.iter().cloned().collect(),
+                vanished_ship_fields: sorted_coordinates(&lost_vision_at),
             }
             .into(),
         )) {
@@ -835,24 +1120,38 @@ fn handle_action_result(
     }
 
     if !gain_enemy_vision.is_empty() || !lost_enemy_vision.is_empty() {
-        if let Err(e) = broadcast_tx.send((
-            enemies.iter().cloned().collect(),
-            VisionEvent {
-                discovered_ship_fields: gain_enemy_vision.iter().cloned().collect(),
-                vanished_ship_fields: lost_enemy_vision.iter().cloned().collect(),
+        for observers in g.vision_groups(enemies.iter().cloned().collect()) {
+            // the gained vision is that of the whole enemy team, without sharing every enemy
+            // only discovers what their own ships see and what was revealed to all of them
+            let discovered_ship_fields = if g.config.share_team_vision {
+                sorted_coordinates(&gain_enemy_vision)
+            } else {
+                let seen = g.ship_parts_seen_by_players(&observers);
+                let revealed = g.turn.as_ref().map(|turn| &turn.revealed_to_enemies);
+                sorted_coordinates(gain_enemy_vision.iter().filter(|tile| {
+                    seen.contains(*tile) || revealed.is_some_and(|r| r.contains(*tile))
+                }))
+            };
+            if let Err(e) = broadcast_tx.send((
+                observers,
+                VisionEvent {
+                    discovered_ship_fields,
+                    vanished_ship_fields: sorted_coordinates(&lost_enemy_vision),
+                }
+                .into(),
+            )) {
+                return Err(MessageHandlerError::Broadcast(e.into()));
             }
-            .into(),
-        )) {
-            return Err(MessageHandlerError::Broadcast(e.into()));
         }
     }
 
     // hit events
-    for (c, &damage) in inflicted_damage_at.iter() {
+    for c in sorted_coordinates(inflicted_damage_at.keys()) {
+        let damage = inflicted_damage_at[&c];
         if let Err(e) = broadcast_tx.send((
             g.players.keys().cloned().collect(),
             HitEvent {
-                coordinate: c.clone().into(),
+                coordinate: c.into(),
                 damage,
             }
             .into(),
@@ -880,12 +1179,21 @@ fn handle_action_result(
         }
     }
 
+    // objective events
+    if g.config.announce_objectives {
+        for event in detect_objectives(g, client_id, &ships_destroyed) {
+            if let Err(e) = broadcast_tx.send((g.players.keys().cloned().collect(), event.into())) {
+                return Err(MessageHandlerError::Broadcast(e.into()));
+            }
+        }
+    }
+
     // splash events
     if !splash_tiles.is_empty() {
         if let Err(e) = broadcast_tx.send((
             g.players.keys().cloned().collect(),
             SplashEvent {
-                coordinate: splash_tiles.iter().cloned().collect(),
+                coordinate: sorted_coordinates(&splash_tiles),
             }
             .into(),
         )) {
@@ -896,31 +1204,33 @@ fn handle_action_result(
     Ok(g.game_result())
 }
 
-/// Tries to fill the player into the team with capacity left and less players first.
-/// Otherwise random.
-fn place_into_team(player_id: ClientId, game: &mut RwLockWriteGuard<Game>) {
-    let mut a = game.team_a.clone();
-    let mut b = game.team_b.clone();
+/// Declines a join request, the client is released from the game it was assigned to
+/// so it may join another one.
+async fn reject_join(
+    ep: &mut Endpoint,
+    registry: &Arc<RwLock<GameRegistry>>,
+    client_id: ClientId,
+    error: JoinError,
+) -> Result<(), MessageHandlerError> {
+    let code = match error {
+        JoinError::AlreadyJoined => StatusCode::BadRequest,
+        JoinError::GameInProgress => StatusCode::GameInProgress,
+        JoinError::UsernameTaken => StatusCode::UsernameIsTaken,
+    };
+    if error != JoinError::AlreadyJoined {
+        registry.write().await.leave(client_id);
+    }
+    ep.send_message(client_id, status_with_msg(code, error.to_string().as_str()))
+        .map_err(MessageHandlerError::Network)?;
 
-    let mut teams = [
-        (&mut a, game.config.team_size_a),
-        (&mut b, game.config.team_size_b),
-    ];
-    teams.sort_by_key(|(team, _)| team.len());
-    if let Some((team, _)) = teams
-        .iter_mut()
-        .find(|(team, size)| team.len() < *size as usize)
-    {
-        team.insert(player_id);
+    Err(MessageHandlerError::InvalidInboundMessage(
+        error.to_string(),
+    ))
+}
 
-        game.team_a = a;
-        game.team_b = b;
-    } else {
-        match thread_rng_n(2) {
-            0 => game.team_a.insert(player_id),
-            1 => game.team_b.insert(player_id),
-            _ => panic!("the universe just broke"),
-        };
+fn save_lobby(game: &Game) {
+    if let Some(lobby_file) = game.lobby_file.as_ref() {
+        lobby_file.save(game);
     }
 }
 
@@ -940,20 +1250,97 @@ fn broadcast_lobby_change_event(
     }
 }
 
-fn broadcast_game_preparation_start(
-    players: Vec<&mut Player>,
-    mut quadrants: Vec<(u32, u32, u32)>,
-    broadcast_tx: &tokio::sync::broadcast::Sender<(Vec<ClientId>, ProtocolMessage)>,
-) -> Result<(), MessageHandlerError> {
-    if players.len() > quadrants.len() {
-        panic!("board has less quadrants than players in the game");
+/// Enforces the ready deadlines of a lobby once the timeout passed. Kicked players are removed from
+/// the registry as well and receive the lobby without themselves.
+fn schedule_ready_deadlines(
+    game_id: GameID,
+    game: &Arc<RwLock<Game>>,
+    registry: &Arc<RwLock<GameRegistry>>,
+    broadcast_tx: &Sender<(Vec<ClientId>, ProtocolMessage)>,
+    timeout: Duration,
+) {
+    let (game, registry, broadcast_tx) = (game.clone(), registry.clone(), broadcast_tx.clone());
+    tokio::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        let mut g = game.write().await;
+        let overdue = enforce_ready_deadlines(&mut g, Instant::now());
+        if overdue.is_empty() {
+            return;
+        }
+        let kicked = if g.config.kick_unready_players {
+            info!(
+                "Kicking players {overdue:?} from game {game_id}, they did not get ready in time"
+            );
+            overdue
+        } else {
+            info!("Readying players {overdue:?} of game {game_id}, they did not get ready in time");
+            vec![]
+        };
+        save_lobby(&g);
+
+        let lobby_change = LobbyChangeEvent {
+            team_state_a: build_team_states(g.team_a.iter().cloned(), &g.players),
+            team_state_b: build_team_states(g.team_b.iter().cloned(), &g.players),
+        };
+        let recipients = g.players.keys().chain(kicked.iter()).cloned().collect();
+        if let Err(e) = broadcast_tx.send((recipients, lobby_change.into())) {
+            error!("unable to broadcast LobbyChangeEvent: {e:#?}");
+        }
+        if let Err(e) = try_start_preparation(&mut g, &broadcast_tx) {
+            error!("Unable to start the preparation of game {game_id}: {e}");
+        }
+
+        let abandoned = matches!(g.state, GameState::Lobby) && g.players.is_empty();
+        drop(g);
+        if !kicked.is_empty() {
+            let mut registry = registry.write().await;
+            for player_id in kicked {
+                registry.leave(player_id);
+            }
+            if abandoned {
+                registry.remove_game(game_id).await;
+            }
+        }
+    });
+}
+
+/// Moves the game into the preparation phase and hands out the quadrants, if every seat is taken
+/// and all players are ready. Returns whether the phase changed.
+/// Players readying up at the same time all end up here, only the first call under the write lock
+/// of the game sees it in the lobby and broadcasts the start of the preparation.
+pub(crate) fn try_start_preparation(
+    g: &mut Game,
+    broadcast_tx: &Sender<(Vec<ClientId>, ProtocolMessage)>,
+) -> Result<bool, MessageHandlerError> {
+    if !g.can_change_into_preparation_phase() {
+        return Ok(false);
     }
+    g.state = GameState::Preparation;
+    info!("GamePhase: Preparation");
+    g.assign_quadrants();
 
-    quadrants.shuffle(&mut thread_rng());
+    broadcast_game_preparation_start(g, broadcast_tx)?;
+    Ok(true)
+}
 
-    for p in players {
-        p.quadrant = Some(quadrants.pop().unwrap());
+fn broadcast_game_preparation_start(
+    g: &Game,
+    broadcast_tx: &tokio::sync::broadcast::Sender<(Vec<ClientId>, ProtocolMessage)>,
+) -> Result<(), MessageHandlerError> {
+    // only the locations of the quadrants are revealed, the ships placed in them stay hidden
+    let quadrant_corners = if g.config.reveal_opponent_quadrants {
+        let corners: Vec<_> = g
+            .players
+            .values()
+            .filter_map(|p| p.quadrant)
+            .map(|(x, y, _)| Coordinate { x, y })
+            .collect();
+        sorted_coordinates(&corners)
+    } else {
+        vec![]
+    };
 
+    for p in g.players.values() {
         // This function does not send the messages directly through the endpoint struct.
         // Instead it queues them in the broadcast channel.
         // Doing so will ensure that this broadcast will be sent in order with other broadcasts.
@@ -966,6 +1353,7 @@ fn broadcast_game_preparation_start(
                         y: p.quadrant.unwrap().1,
                     }),
                     quadrant_size: p.quadrant.unwrap().2,
+                    quadrant_corners: quadrant_corners.clone(),
                 }
                 .into(),
             ))
@@ -980,18 +1368,21 @@ fn broadcast_game_start(
     broadcast_tx: &tokio::sync::broadcast::Sender<(Vec<ClientId>, ProtocolMessage)>,
 ) -> Result<(), MessageHandlerError> {
     let (team_ships_a, team_ships_b) = get_ships_by_team(game);
+    let wrap_bounds = game.wrap_bounds();
 
     let visible_hostile_ships_a = game.ships.get_ship_parts_seen_by(
         &team_ships_a
             .iter()
             .map(|ship| ship.id())
             .collect::<Vec<_>>(),
+        wrap_bounds.as_ref(),
     );
     let visible_hostile_ships_b = game.ships.get_ship_parts_seen_by(
         &team_ships_b
             .iter()
             .map(|ship| ship.id())
             .collect::<Vec<_>>(),
+        wrap_bounds.as_ref(),
     );
 
     let team_ships_a: Vec<_> = team_ships_a
@@ -1024,57 +1415,143 @@ fn broadcast_game_start(
             .map_err(|e| MessageHandlerError::Broadcast(Box::new(e)))?;
     }
 
-    let turn = game.turn.as_ref().unwrap();
-    broadcast_tx
-        .send((
-            vec![turn.player_id],
-            ProtocolMessage::NextTurn(NextTurn {
-                next_player_id: turn.player_id,
-                position_in_queue: 0, // TODO
-            }),
-        ))
-        .map_err(|e| MessageHandlerError::Broadcast(Box::new(e)))?;
+    Ok(())
+}
+
+/// Moves the game into the game phase and announces its start.
+/// If `Config.game_start_ack_timeout` is set, the first turn is only announced once every player
+/// acknowledged the start or the timeout elapsed, so slow clients have the initial state in time.
+pub(crate) fn start_game(
+    g: &mut Game,
+    game: &Arc<RwLock<Game>>,
+    broadcast_tx: &Sender<(Vec<ClientId>, ProtocolMessage)>,
+) -> Result<(), MessageHandlerError> {
+    info!("GamePhase: InGame");
+    g.state = GameState::InGame;
+    g.queue_turns();
+    g.advance_turn();
+    broadcast_game_start(g, broadcast_tx)?;
+
+    if g.config.game_start_ack_timeout == 0 {
+        return broadcast_first_turn(g, broadcast_tx);
+    }
+    g.awaiting_start_acks = Some(g.players.keys().cloned().collect());
+
+    let timeout = Duration::from_millis(g.config.game_start_ack_timeout as u64);
+    let (game, broadcast_tx) = (game.clone(), broadcast_tx.clone());
+    tokio::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        let mut g = game.write().await;
+        if let Some(missing) = g.awaiting_start_acks.as_ref() {
+            warn!("Players {missing:?} did not acknowledge the game start in time");
+            if let Err(e) = broadcast_first_turn(&mut g, &broadcast_tx) {
+                error!("Unable to announce the first turn: {e}");
+            }
+        }
+    });
 
     Ok(())
 }
 
-fn broadcast_game_result(
-    result: GameResult,
-    players: Vec<ClientId>,
+pub(crate) fn acknowledge_game_start(
+    game: &mut Game,
+    player_id: PlayerID,
     broadcast_tx: &Sender<(Vec<ClientId>, ProtocolMessage)>,
-    game_end_tx: &UnboundedSender<()>,
 ) -> Result<(), MessageHandlerError> {
-    match result {
-        GameResult::Pending => return Ok(()),
-        GameResult::Draw => broadcast_tx
-            .send((
-                players,
-                GameOverEvent {
-                    reason: GameEndReason::Regular.into(),
-                    winner: Teams::None.into(),
-                }
-                .into(),
-            ))
-            .map(|_| ())
-            .map_err(|e| MessageHandlerError::Broadcast(e.into()))?,
-        GameResult::Win(team) => broadcast_tx
+    let everyone_acknowledged = match game.awaiting_start_acks.as_mut() {
+        Some(awaiting) => awaiting.remove(&player_id) && awaiting.is_empty(),
+        // the first turn was announced already or without waiting
+        None => false,
+    };
+
+    if everyone_acknowledged {
+        broadcast_first_turn(game, broadcast_tx)
+    } else {
+        Ok(())
+    }
+}
+
+fn broadcast_first_turn(
+    game: &mut Game,
+    broadcast_tx: &Sender<(Vec<ClientId>, ProtocolMessage)>,
+) -> Result<(), MessageHandlerError> {
+    game.awaiting_start_acks = None;
+    broadcast_next_turn(game, broadcast_tx)
+}
+
+/// Announces the current turn, every player is told how many turns they have to wait.
+fn broadcast_next_turn(
+    game: &Game,
+    broadcast_tx: &Sender<(Vec<ClientId>, ProtocolMessage)>,
+) -> Result<(), MessageHandlerError> {
+    let next_player_id = game.turn.as_ref().unwrap().player_id;
+    let mut player_ids: Vec<_> = game.players.keys().cloned().collect();
+    player_ids.sort_unstable();
+    for player_id in player_ids {
+        broadcast_tx
             .send((
-                players,
-                GameOverEvent {
-                    reason: GameEndReason::Regular.into(),
-                    winner: team.into(),
+                vec![player_id],
+                NextTurn {
+                    next_player_id,
+                    position_in_queue: game.turns_until(player_id),
+                    turn_number: game.turn_number(),
+                    action_points: game.action_points_visible_to(player_id),
                 }
                 .into(),
             ))
-            .map(|_| ())
-            .map_err(|e| MessageHandlerError::Broadcast(e.into()))?,
+            .map_err(|e| MessageHandlerError::Broadcast(e.into()))?;
     }
 
+    Ok(())
+}
+
+fn broadcast_game_result(
+    result: GameResult,
+    game: &mut Game,
+    broadcast_tx: &Sender<(Vec<ClientId>, ProtocolMessage)>,
+    game_end_tx: &UnboundedSender<()>,
+) -> Result<(), MessageHandlerError> {
+    let winner = match result {
+        GameResult::Pending => return Ok(()),
+        GameResult::Draw => Teams::None,
+        GameResult::Win(team) => team,
+    };
+    // the game ends with the current turn
+    game.end_turn();
+
+    broadcast_tx
+        .send((
+            game.players.keys().cloned().collect(),
+            game_over_event(game, winner).into(),
+        ))
+        .map_err(|e| MessageHandlerError::Broadcast(e.into()))?;
+
     game_end_tx.send(()).expect("unable to end game");
 
     Ok(())
 }
 
+/// Announces the regular end of the game.
+/// The remaining ships of both teams are revealed if `Config.reveal_on_game_end` is set.
+pub(crate) fn game_over_event(game: &Game, winner: Teams) -> GameOverEvent {
+    let revealed_ships = if game.config.reveal_on_game_end {
+        let (team_ships_a, team_ships_b) = get_ships_by_team(game);
+        team_ships_a
+            .into_iter()
+            .chain(team_ships_b)
+            .map(create_ship_state)
+            .collect()
+    } else {
+        vec![]
+    };
+
+    GameOverEvent {
+        reason: GameEndReason::Regular.into(),
+        winner: winner.into(),
+        revealed_ships,
+    }
+}
+
 fn get_ships_by_team(game: &Game) -> (Vec<&Ship>, Vec<&Ship>) {
     game.ships.iter_ships().fold(
         (Vec::new(), Vec::new()),
@@ -1097,22 +1574,61 @@ fn get_ships_by_team(game: &Game) -> (Vec<&Ship>, Vec<&Ship>) {
     )
 }
 
+/// The complete game state as seen by a player. Sending it resynchronizes a client
+/// that missed events, instead of replaying them.
+/// Returns None if the player is not part of a team.
+pub(crate) fn server_state_of(game: &Game, player_id: PlayerID) -> Option<ServerState> {
+    let player = game.players.get(&player_id)?;
+    if !game.team_a.contains(&player_id) && !game.team_b.contains(&player_id) {
+        return None;
+    }
+
+    let (team_ships_a, team_ships_b) = get_ships_by_team(game);
+    let wrap_bounds = game.wrap_bounds();
+    let visible_hostile_ships = (
+        game.ships.get_ship_parts_seen_by(
+            &team_ships_a
+                .iter()
+                .map(|ship| ship.id())
+                .collect::<Vec<_>>(),
+            wrap_bounds.as_ref(),
+        ),
+        game.ships.get_ship_parts_seen_by(
+            &team_ships_b
+                .iter()
+                .map(|ship| ship.id())
+                .collect::<Vec<_>>(),
+            wrap_bounds.as_ref(),
+        ),
+    );
+    let team_ships = (
+        team_ships_a
+            .iter()
+            .map(|ship| create_ship_state(ship))
+            .collect(),
+        team_ships_b
+            .iter()
+            .map(|ship| create_ship_state(ship))
+            .collect(),
+    );
+
+    Some(get_server_state_for_player(
+        player,
+        game,
+        team_ships,
+        visible_hostile_ships,
+    ))
+}
+
 fn get_server_state_for_player(
     player: &Player,
     game: &Game,
     (team_ships_a, team_ships_b): (Vec<ShipState>, Vec<ShipState>),
     (visible_hostile_ships_a, visible_hostile_ships_b): (Vec<Coordinate>, Vec<Coordinate>),
 ) -> ServerState {
-    let action_points_left = match game.turn {
-        Some(Turn {
-            player_id,
-            action_points_left,
-            ..
-        }) if player_id == player.id => action_points_left,
-        _ => 0,
-    };
+    let action_points_left = game.action_points_left(player.id);
 
-    match (
+    let mut state = match (
         game.team_a.contains(&player.id),
         game.team_b.contains(&player.id),
     ) {
@@ -1127,7 +1643,12 @@ fn get_server_state_for_player(
             visible_hostile_ships: visible_hostile_ships_b,
         },
         _ => unreachable!(),
+    };
+    // the vision of the team includes that of the allies
+    if !game.config.share_team_vision {
+        state.visible_hostile_ships = game.ship_parts_seen_by_players(&[player.id]);
     }
+    state
 }
 
 fn game_start_for_player(
@@ -1163,10 +1684,11 @@ fn build_team_states(
         .collect()
 }
 
-fn action_validation_error_reply(
+fn action_validation_error_reply<'a>(
     ep: &mut Endpoint,
     client_id: ClientId,
     error: ActionExecutionError,
+    players: impl Iterator<Item = &'a ClientId>,
     game_end_tx: &mpsc::UnboundedSender<()>,
 ) -> Result<(), MessageHandlerError> {
     match error.clone() {
@@ -1197,6 +1719,14 @@ fn action_validation_error_reply(
                     .map_err(MessageHandlerError::Network),
             ActionValidationError::InvalidShipType => ep.send_message(client_id, status_with_msg(StatusCode::InvalidMove, "the selected ship is not able to perform the requested action"))
                 .map_err(MessageHandlerError::Network),
+            ActionValidationError::MoveLimitReached => ep.send_message(client_id, status_with_msg(StatusCode::InvalidMove, "the ship cannot move again this turn"))
+                .map_err(MessageHandlerError::Network),
+            ActionValidationError::AbilityRepeated => ep.send_message(client_id, status_with_msg(StatusCode::InvalidMove, "the ship used its ability in your last turn already"))
+                .map_err(MessageHandlerError::Network),
+            ActionValidationError::Collision => ep.send_message(client_id, status_with_msg(StatusCode::InvalidMove, "the destination is occupied by another ship"))
+                .map_err(MessageHandlerError::Network),
+            ActionValidationError::PlayerEliminated => ep.send_message(client_id, status_with_msg(StatusCode::InvalidMove, "you have no ships left"))
+                .map_err(MessageHandlerError::Network),
             ActionValidationError::Ignored => ep.send_message(client_id, status_with_msg(StatusCode::InvalidMove, "the requested action was ignored"))
                 .map_err(MessageHandlerError::Network),
         },
@@ -1209,7 +1739,7 @@ fn action_validation_error_reply(
                 .map_err(MessageHandlerError::Network)
         }
         ActionExecutionError::InconsistentState(s) => {
-            if let Err(e) = ep.broadcast_message(status_with_msg(StatusCode::ServerError, format!("server detected an inconsistent state: {s}").as_str())) {
+            if let Err(e) = ep.send_group_message(players, status_with_msg(StatusCode::ServerError, format!("server detected an inconsistent state: {s}").as_str())) {
                 error!("detected inconsistent state: {e}");
             }
 
@@ -1234,6 +1764,11 @@ fn status_with_data(code: StatusCode, data: Data) -> ProtocolMessage {
     status_response(code, "", Some(data))
 }
 
+// Also sent again to clients retransmitting an executed action.
+fn ship_action_response(response: ShipActionResponse) -> ProtocolMessage {
+    status_with_data(StatusCode::Ok, response.into())
+}
+
 fn status_response(code: StatusCode, message: &str, data: Option<Data>) -> ProtocolMessage {
     StatusMessage {
         code: code.into(),
@@ -1243,7 +1778,8 @@ fn status_response(code: StatusCode, message: &str, data: Option<Data>) -> Proto
     .into()
 }
 
-fn create_ship_state(ship: &Ship) -> ShipState {
+/// The state of a ship as shown to its team, including its current health.
+pub(crate) fn create_ship_state(ship: &Ship) -> ShipState {
     let player_id = ship.get_player_id();
     ShipState {
         ship_type: ship.ship_type() as i32,
@@ -1252,7 +1788,7 @@ fn create_ship_state(ship: &Ship) -> ShipState {
             y: ship.position().1 as u32,
         }),
         direction: <Orientation as Into<Direction>>::into(ship.orientation()) as i32,
-        health: ship.data().health,
+        health: ship.health(),
         owner_id: player_id,
         remaining_cooldown_move: ship
             .cool_downs()
@@ -1287,12 +1823,31 @@ pub enum MessageHandlerError {
 
 impl Display for MessageHandlerError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str("MessageHandlerError: ")?;
         match self {
-            MessageHandlerError::Network(e) => f.write_str(format!("{e}").as_str()),
-            MessageHandlerError::Protocol(e) => f.write_str(format!("{e:?}").as_str()),
-            MessageHandlerError::Broadcast(e) => f.write_str(format!("{e:?}").as_str()),
-            MessageHandlerError::InvalidInboundMessage(msg) => f.write_str(msg),
+            MessageHandlerError::Network(e) => write!(f, "network error: {e}"),
+            MessageHandlerError::Protocol(e) => write!(f, "unable to execute action, {e}"),
+            MessageHandlerError::Broadcast(e) => {
+                // the message itself is left out, it may be a complete game state
+                let (recipients, _) = &e.0;
+                write!(
+                    f,
+                    "unable to broadcast to clients {recipients:?}, the broadcast channel is closed"
+                )
+            }
+            MessageHandlerError::InvalidInboundMessage(msg) => {
+                write!(f, "invalid inbound message: {msg}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MessageHandlerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MessageHandlerError::Network(e) => Some(e),
+            MessageHandlerError::Protocol(e) => Some(e),
+            MessageHandlerError::Broadcast(e) => Some(e.as_ref()),
+            MessageHandlerError::InvalidInboundMessage(_) => None,
         }
     }
 }
@@ -60,6 +60,7 @@ pub(crate) async fn spawn_timer_task(cfg: &dyn ConfigProvider) -> Option<TaskCon
     let mut timer = time::interval(cfg.server_config().announcement_interval);
 
     let server_name = cfg.game_config().server_name.clone();
+    let config_hash = cfg.game_config().config_hash();
     let game_port_v4 = cfg.server_config().game_address_v4.port();
     let game_port_v6 = cfg.server_config().game_address_v6.port();
     let announce_v4 = cfg.server_config().announcement_address_v4;
@@ -77,6 +78,7 @@ pub(crate) async fn spawn_timer_task(cfg: &dyn ConfigProvider) -> Option<TaskCon
                     sock_v4.as_ref().unwrap().borrow(),
                     game_port_v4,
                     server_name.as_str(),
+                    config_hash,
                     announce_v4.into(),
                 )
                 .await
@@ -91,6 +93,7 @@ pub(crate) async fn spawn_timer_task(cfg: &dyn ConfigProvider) -> Option<TaskCon
                     sock_v6.as_ref().unwrap().borrow(),
                     game_port_v6,
                     server_name.as_str(),
+                    config_hash,
                     announce_v6.into(),
                 )
                 .await
@@ -115,11 +118,13 @@ pub(crate) async fn dispatch_announcement(
     socket: &UdpSocket,
     port: u16,
     display_name: &str,
+    config_hash: u64,
     dst: SocketAddr,
 ) -> Result<(), String> {
     let message = ProtocolMessage::ServerAdvertisement(messages::ServerAdvertisement {
         port: port as u32,
         display_name: String::from(display_name),
+        config_hash,
     });
 
     let mut socket = UdpFramed::new(socket, BattleshipPlusCodec::default());
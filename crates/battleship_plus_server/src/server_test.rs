@@ -1,28 +1,47 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::iter::zip;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use futures::{SinkExt, StreamExt};
 use once_cell::sync::Lazy;
-use quinn::{crypto, ClientConfig, Connection, Endpoint, RecvStream, SendStream, VarInt};
-use tokio::sync::Mutex;
-use tokio_util::codec::{FramedRead, FramedWrite};
+use quinn::{crypto, ClientConfig};
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tokio::sync::{Mutex, RwLock};
 
-use battleship_plus_common::codec::BattleshipPlusCodec;
+use battleship_plus_common::connection::ServerConnection;
+use battleship_plus_common::game::ship::{Orientation, Ship, ShipData, ShipID};
+use battleship_plus_common::game::ship_manager::ShipManager;
 use battleship_plus_common::game::PlayerID;
+use battleship_plus_common::messages::ship_action_request::ActionProperties;
 use battleship_plus_common::messages::status_message::Data;
 use battleship_plus_common::messages::{
-    JoinRequest, JoinResponse, LobbyChangeEvent, PlacementPhase, ProtocolMessage,
-    SetReadyStateRequest, SetReadyStateResponse, StatusCode, StatusMessage, TeamSwitchRequest,
-    TeamSwitchResponse,
+    JoinResponse, Keepalive, LobbyChangeEvent, NextTurn, PlacementPhase, ProtocolMessage,
+    ServerStateResponse, SetPlacementRequest, SetReadyStateRequest, SetReadyStateResponse,
+    ShipActionEvent, ShipActionRequest, ShipActionResponse, SplashEvent, StatusCode, StatusMessage,
+    TeamSwitchRequest, TeamSwitchResponse,
+};
+use battleship_plus_common::types::{
+    Config, Coordinate, Direction, MoveDirection, MoveProperties, PlayerLobbyState, ServerState,
+    ShipAssignment, ShipType, ShootProperties, Teams,
 };
-use battleship_plus_common::types::{Coordinate, PlayerLobbyState};
 use battleship_plus_common::{protocol_name, protocol_name_with_version};
+use bevy_quinnet_server::{ClientId, QuinnetError};
 
-use crate::config_provider::{default_config_provider, ConfigProvider};
-use crate::server::spawn_server_task;
+use crate::config_provider::{default_config_provider, ConfigProvider, ServerConfig};
+use crate::game::actions::{Action, ActionExecutionError, ActionResult};
+use crate::game::audit::AuditLog;
+use crate::game::data::{Game, Player, Turn};
+use crate::game::game_builder::GameBuilder;
+use crate::game::registry::GameRegistry;
+use crate::game::ship_builder::{GeneralShipBuilder, ShipBuilder};
+use crate::game::states::GameState;
+use crate::server::{
+    acknowledge_game_start, broadcast_action_outcome, broadcast_channel, create_ship_state,
+    execute_charged_action, game_over_event, handle_action_result, remove_ended_game,
+    server_state_of, spawn_server_task, start_game, try_start_preparation, MessageHandlerError,
+};
 
 type TestLock = Arc<Mutex<()>>;
 
@@ -35,20 +54,47 @@ enum Team {
 }
 
 struct Client {
-    connection: Connection,
-    reader: FramedRead<RecvStream, BattleshipPlusCodec>,
-    writer: FramedWrite<SendStream, BattleshipPlusCodec>,
+    connection: ServerConnection,
     state: PlayerLobbyState,
     team: Team,
+    game_id: u32,
 }
 
 impl Client {
     async fn send(&mut self, msg: ProtocolMessage) {
-        Self::send_msg_inner(&mut self.writer, msg).await
+        if let Err(e) = self.connection.send(msg).await {
+            panic!("unable to send message: {e}");
+        }
     }
 
     async fn receive(&mut self) -> ProtocolMessage {
-        Self::receive_msg_inner(&mut self.reader).await
+        match self.connection.receive().await {
+            Ok(msg) => msg,
+            Err(e) => panic!("unable to receive ProtocolMessage: {e}"),
+        }
+    }
+
+    /// Skips other messages until a `NextTurn` arrives.
+    /// Returns `None` if there is no `NextTurn` within half a second.
+    async fn receive_next_turn(&mut self) -> Option<NextTurn> {
+        self.connection.set_timeout(Duration::from_millis(500));
+        let next_turn = loop {
+            match self.connection.receive().await {
+                Ok(ProtocolMessage::NextTurn(next_turn)) => break Some(next_turn),
+                Ok(_) => continue,
+                Err(_) => break None,
+            }
+        };
+        self.connection.set_timeout(Duration::from_secs(5));
+        next_turn
+    }
+
+    async fn assert_no_message(&mut self) {
+        self.connection.set_timeout(Duration::from_millis(500));
+        if let Ok(msg) = self.connection.receive().await {
+            panic!("Expected no message, got {msg:#?}");
+        }
+        self.connection.set_timeout(Duration::from_secs(5));
     }
 
     async fn switch_team(&mut self) {
@@ -149,28 +195,35 @@ impl Client {
     }
 
     async fn disconnect(self) {
-        self.connection.close(VarInt::from_u32(0), &[])
+        self.connection.close()
     }
 
     async fn connect(
         bind_addr: SocketAddr,
         addr: SocketAddr,
         client_config: Arc<dyn crypto::ClientConfig>,
-    ) -> Connection {
-        let mut ep = Endpoint::client(bind_addr).expect("unable to create Endpoint on {addr}");
-        ep.set_default_client_config(ClientConfig::new(client_config));
-        ep.connect(addr, &addr.ip().to_string())
-            .expect("unable to connect to server")
-            .await
-            .expect("unable to connect to server")
+    ) -> ServerConnection {
+        match ServerConnection::connect(bind_addr, addr, ClientConfig::new(client_config)).await {
+            Ok(connection) => connection,
+            Err(e) => panic!("unable to connect to server: {e}"),
+        }
     }
 
     async fn connect_ipv4(
         cfg: Arc<dyn ConfigProvider>,
         client_config: Arc<dyn crypto::ClientConfig>,
         username: &str,
+        game_id: Option<u32>,
     ) -> Client {
-        let connection = Self::connect(
+        let connection = Self::connection_ipv4(cfg, client_config).await;
+        Self::join(connection, username, game_id).await
+    }
+
+    async fn connection_ipv4(
+        cfg: Arc<dyn ConfigProvider>,
+        client_config: Arc<dyn crypto::ClientConfig>,
+    ) -> ServerConnection {
+        Self::connect(
             SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0).into(),
             SocketAddrV4::new(
                 match option_env!("TEST_CLIENT_IP4") {
@@ -182,15 +235,14 @@ impl Client {
             .into(),
             client_config,
         )
-        .await;
-
-        Self::join(connection, username).await
+        .await
     }
 
     async fn connect_ipv6(
         cfg: Arc<dyn ConfigProvider>,
         client_config: Arc<dyn crypto::ClientConfig>,
         username: &str,
+        game_id: Option<u32>,
     ) -> Client {
         let connection = Self::connect(
             SocketAddrV6::new(Ipv6Addr::UNSPECIFIED, 0, 0, 0).into(),
@@ -208,41 +260,26 @@ impl Client {
         )
         .await;
 
-        Self::join(connection, username).await
+        Self::join(connection, username, game_id).await
     }
 
-    async fn join(connection: Connection, username: &str) -> Client {
-        let (tx, rx) = match connection.open_bi().await {
-            Ok(stream) => stream,
-            Err(e) => panic!("unable to open bidirectional stream: {e}"),
+    async fn join(
+        mut connection: ServerConnection,
+        username: &str,
+        game_id: Option<u32>,
+    ) -> Client {
+        let (player_id, game_id) = match connection.join(username, game_id).await {
+            Ok(JoinResponse {
+                player_id, game_id, ..
+            }) => (player_id, game_id),
+            Err(e) => panic!("unable to join: {e}"),
         };
 
-        let mut reader = FramedRead::new(rx, BattleshipPlusCodec::default());
-        let mut writer = FramedWrite::new(tx, BattleshipPlusCodec::default());
-
-        Self::send_msg_inner(
-            &mut writer,
-            ProtocolMessage::JoinRequest(JoinRequest {
-                username: username.to_string(),
-            }),
-        )
-        .await;
-        let msg = Self::receive_msg_inner(&mut reader).await;
-
-        let player_id = match msg {
-            ProtocolMessage::StatusMessage(StatusMessage {
-                code,
-                data: Some(Data::JoinResponse(JoinResponse { player_id })),
-                ..
-            }) => {
-                assert_eq!(StatusCode::from_i32(code), Some(StatusCode::Ok));
-                player_id
-            }
-            _ => panic!("Expected JoinResponse, got {msg:#?}"),
+        let msg = match connection.receive().await {
+            Ok(msg) => msg,
+            Err(e) => panic!("unable to receive ProtocolMessage: {e}"),
         };
 
-        let msg = Self::receive_msg_inner(&mut reader).await;
-
         let predicate = |state: &&PlayerLobbyState| {
             state.player_id == player_id && state.name == *username && !state.ready
         };
@@ -268,36 +305,9 @@ impl Client {
 
         Client {
             connection,
-            reader,
-            writer,
             state,
             team,
-        }
-    }
-
-    async fn send_msg_inner(
-        writer: &mut FramedWrite<SendStream, BattleshipPlusCodec>,
-        msg: ProtocolMessage,
-    ) {
-        match tokio::time::timeout(Duration::from_secs(5), writer.send(msg)).await {
-            Err(e) => panic!("send message timed out: {e}"),
-            Ok(res) => res.expect("unable to send {msg:#?}"),
-        }
-        match tokio::time::timeout(Duration::from_secs(5), writer.flush()).await {
-            Err(e) => panic!("flush message timed out: {e}"),
-            Ok(res) => res.expect("unable to send {msg:#?}"),
-        }
-    }
-
-    async fn receive_msg_inner(
-        reader: &mut FramedRead<RecvStream, BattleshipPlusCodec>,
-    ) -> ProtocolMessage {
-        match tokio::time::timeout(Duration::from_secs(5), reader.next()).await {
-            Err(e) => panic!("receive message timed out: {e}"),
-            Ok(res) => res
-                .expect("unable to receive ProtocolMessage")
-                .expect("unable to receive ProtocolMessage")
-                .expect("unable to receive ProtocolMessage"),
+            game_id,
         }
     }
 }
@@ -326,6 +336,21 @@ impl rustls::client::ServerCertVerifier for SkipServerVerification {
     }
 }
 
+fn test_client_config() -> Arc<rustls::ClientConfig> {
+    let mut client_config = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(SkipServerVerification::new())
+        .with_no_client_auth();
+    client_config
+        .alpn_protocols
+        .push(protocol_name_with_version().into_bytes());
+    client_config
+        .alpn_protocols
+        .push(protocol_name().into_bytes());
+
+    Arc::new(client_config)
+}
+
 #[tokio::test]
 async fn lobby_e2e() {
     pretty_env_logger::init_timed();
@@ -339,18 +364,7 @@ async fn lobby_e2e() {
     let client_count: usize =
         (cfg.game_config().team_size_a + cfg.game_config().team_size_a) as usize;
 
-    let mut client_config = rustls::ClientConfig::builder()
-        .with_safe_defaults()
-        .with_custom_certificate_verifier(SkipServerVerification::new())
-        .with_no_client_auth();
-    client_config
-        .alpn_protocols
-        .push(protocol_name_with_version().into_bytes());
-    client_config
-        .alpn_protocols
-        .push(protocol_name().into_bytes());
-
-    let client_config = Arc::new(client_config);
+    let client_config = test_client_config();
 
     // create clients and connect to socket
     let mut clients = Vec::with_capacity(client_count);
@@ -360,6 +374,7 @@ async fn lobby_e2e() {
                 cfg.clone(),
                 client_config.clone(),
                 format!("User{i}").as_str(),
+                None,
             )
             .await
         } else {
@@ -367,6 +382,7 @@ async fn lobby_e2e() {
                 cfg.clone(),
                 client_config.clone(),
                 format!("User{i}").as_str(),
+                None,
             )
             .await
         });
@@ -490,6 +506,1204 @@ async fn lobby_e2e() {
     server_ctrl.stop().await;
 }
 
+/// Default configuration with two concurrent games on separate ports.
+struct MultiGameConfig;
+
+impl ConfigProvider for MultiGameConfig {
+    fn game_config(&self) -> Arc<Config> {
+        default_config_provider().game_config()
+    }
+
+    fn server_config(&self) -> Arc<ServerConfig> {
+        Arc::new(ServerConfig {
+            game_address_v4: SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 30405),
+            game_address_v6: SocketAddrV6::new(Ipv6Addr::UNSPECIFIED, 30405, 0, 0),
+            status_address: None,
+            max_games: 2,
+            ..*default_config_provider().server_config()
+        })
+    }
+}
+
+#[tokio::test]
+async fn multiple_games_e2e() {
+    let _lock = TEST_LOCK.lock().await;
+    let cfg: Arc<dyn ConfigProvider + Send + Sync> = Arc::new(MultiGameConfig);
+
+    let server_ctrl = spawn_server_task(cfg.clone());
+    let client_config = test_client_config();
+
+    let mut first =
+        Client::connect_ipv4(cfg.clone(), client_config.clone(), "First", Some(1)).await;
+    let mut second =
+        Client::connect_ipv4(cfg.clone(), client_config.clone(), "Second", Some(2)).await;
+    assert_eq!(first.game_id, 1);
+    assert_eq!(second.game_id, 2);
+    first.assert_no_message().await;
+
+    // clients without a requested game fill up the first lobby with free seats
+    let mut third = Client::connect_ipv6(cfg.clone(), client_config.clone(), "Third", None).await;
+    assert_eq!(third.game_id, 1);
+    Client::switch_team_check_broadcasts([&mut first].into_iter(), 1, 1).await;
+    second.assert_no_message().await;
+
+    // lobby events stay within their game
+    second.set_ready(true).await;
+    let assert_map = HashMap::from([(second.state.player_id, true)]);
+    Client::set_ready_check_broadcasts([&mut second].into_iter(), assert_map).await;
+    first.assert_no_message().await;
+    third.assert_no_message().await;
+
+    third.switch_team().await;
+    let (team_a_count, team_b_count) = match third.team {
+        Team::A => (2, 0),
+        Team::B => (0, 2),
+    };
+    Client::switch_team_check_broadcasts(
+        [&mut first, &mut third].into_iter(),
+        team_a_count,
+        team_b_count,
+    )
+    .await;
+    second.assert_no_message().await;
+
+    server_ctrl.stop().await;
+}
+
+/// Two players with a single destroyer each, so a game is set up quickly. The endpoints listen
+/// on different ports, otherwise the server only starts the IPv6 endpoint.
+struct DuelConfig;
+
+impl ConfigProvider for DuelConfig {
+    fn game_config(&self) -> Arc<Config> {
+        Arc::new(Config {
+            team_size_a: 1,
+            team_size_b: 1,
+            ship_set_team_a: vec![ShipType::Destroyer.into()],
+            ship_set_team_b: vec![ShipType::Destroyer.into()],
+            ..(*default_config_provider().game_config()).clone()
+        })
+    }
+
+    fn server_config(&self) -> Arc<ServerConfig> {
+        Arc::new(ServerConfig {
+            game_address_v4: SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 30505),
+            game_address_v6: SocketAddrV6::new(Ipv6Addr::UNSPECIFIED, 30506, 0, 0),
+            status_address: None,
+            ..*default_config_provider().server_config()
+        })
+    }
+}
+
+/// Connects one player to each endpoint and plays the duel until its first turn starts.
+/// Returns the clients by player and the player taking the first turn.
+async fn start_duel_on_both_endpoints(
+    cfg: Arc<dyn ConfigProvider + Send + Sync>,
+) -> (HashMap<PlayerID, Client>, PlayerID) {
+    let client_config = test_client_config();
+
+    let mut first = Client::connect_ipv4(cfg.clone(), client_config.clone(), "First", None).await;
+    let second = Client::connect_ipv6(cfg.clone(), client_config.clone(), "Second", None).await;
+    Client::switch_team_check_broadcasts([&mut first].into_iter(), 1, 1).await;
+
+    // the endpoints do not hand out the same player id twice
+    assert_ne!(first.state.player_id, second.state.player_id);
+    let mut clients = HashMap::from([
+        (first.state.player_id, first),
+        (second.state.player_id, second),
+    ]);
+    let ids: Vec<_> = clients.keys().cloned().collect();
+    let mut assert_map: HashMap<_, _> = ids.iter().map(|&id| (id, false)).collect();
+    for id in ids.iter() {
+        clients.get_mut(id).unwrap().set_ready(true).await;
+        assert_map.insert(*id, true);
+        Client::set_ready_check_broadcasts(clients.values_mut(), assert_map.clone()).await;
+    }
+    let corners = Client::check_preparation_start_broadcast(clients.values_mut()).await;
+
+    for (id, client) in clients.iter_mut() {
+        client
+            .send(
+                SetPlacementRequest {
+                    assignments: vec![ShipAssignment {
+                        coordinate: Some(corners[id].clone()),
+                        direction: Direction::North.into(),
+                    }],
+                }
+                .into(),
+            )
+            .await;
+    }
+
+    // every player learns who takes the first turn and how many turns they have to wait
+    let mut turn_player_ids = HashSet::new();
+    for (id, client) in clients.iter_mut() {
+        let next_turn = client
+            .receive_next_turn()
+            .await
+            .expect("every player learns about the first turn");
+        let position_in_queue = if next_turn.next_player_id == *id {
+            0
+        } else {
+            1
+        };
+        assert_eq!(next_turn.position_in_queue, position_in_queue);
+        turn_player_ids.insert(next_turn.next_player_id);
+    }
+    assert_eq!(turn_player_ids.len(), 1);
+    let turn_player_id = turn_player_ids.into_iter().next().unwrap();
+
+    (clients, turn_player_id)
+}
+
+#[tokio::test]
+async fn next_turn_reaches_clients_on_both_endpoints() {
+    let _lock = TEST_LOCK.lock().await;
+    let cfg: Arc<dyn ConfigProvider + Send + Sync> = Arc::new(DuelConfig);
+
+    let server_ctrl = spawn_server_task(cfg.clone());
+    let (mut clients, turn_player_id) = start_duel_on_both_endpoints(cfg).await;
+
+    // ending the turn is announced to the clients of both endpoints
+    clients
+        .get_mut(&turn_player_id)
+        .unwrap()
+        .send(
+            ShipActionRequest {
+                end_turn: true,
+                ..Default::default()
+            }
+            .into(),
+        )
+        .await;
+    let mut next_players = HashSet::new();
+    for client in clients.values_mut() {
+        let next_turn = client
+            .receive_next_turn()
+            .await
+            .expect("every player learns about the next turn");
+        next_players.insert(next_turn.next_player_id);
+    }
+    assert_eq!(next_players.len(), 1);
+
+    server_ctrl.stop().await;
+}
+
+#[tokio::test]
+async fn turns_are_enforced_across_endpoints() {
+    let _lock = TEST_LOCK.lock().await;
+    let cfg: Arc<dyn ConfigProvider + Send + Sync> = Arc::new(DuelConfig);
+
+    let server_ctrl = spawn_server_task(cfg.clone());
+    let (mut clients, turn_player_id) = start_duel_on_both_endpoints(cfg).await;
+    let waiting_player_id = *clients.keys().find(|&&id| id != turn_player_id).unwrap();
+    let end_turn = || -> ProtocolMessage {
+        ShipActionRequest {
+            end_turn: true,
+            ..Default::default()
+        }
+        .into()
+    };
+
+    // the player of the other endpoint is rejected during the turn, which goes on
+    for (acting, waiting) in [
+        (turn_player_id, waiting_player_id),
+        (waiting_player_id, turn_player_id),
+    ] {
+        let waiting_client = clients.get_mut(&waiting).unwrap();
+        waiting_client.send(end_turn()).await;
+        match waiting_client.receive().await {
+            ProtocolMessage::StatusMessage(StatusMessage { code, .. }) => {
+                assert_eq!(StatusCode::from_i32(code), Some(StatusCode::BadRequest))
+            }
+            msg => panic!("Expected BadRequest, got {msg:#?}"),
+        }
+        clients.get_mut(&acting).unwrap().assert_no_message().await;
+
+        // only the player on turn is able to end it
+        clients.get_mut(&acting).unwrap().send(end_turn()).await;
+        for client in clients.values_mut() {
+            let next_turn = client
+                .receive_next_turn()
+                .await
+                .expect("every player learns about the next turn");
+            assert_eq!(next_turn.next_player_id, waiting);
+        }
+    }
+
+    server_ctrl.stop().await;
+}
+
+#[tokio::test]
+async fn retransmitted_actions_are_not_executed_again() {
+    let _lock = TEST_LOCK.lock().await;
+    let cfg: Arc<dyn ConfigProvider + Send + Sync> = Arc::new(DuelConfig);
+
+    let server_ctrl = spawn_server_task(cfg.clone());
+    let (mut clients, turn_player_id) = start_duel_on_both_endpoints(cfg).await;
+    let waiting_player_id = *clients.keys().find(|&&id| id != turn_player_id).unwrap();
+    let end_turn = |idempotency_key| -> ProtocolMessage {
+        ShipActionRequest {
+            end_turn: true,
+            idempotency_key,
+            ..Default::default()
+        }
+        .into()
+    };
+
+    clients
+        .get_mut(&turn_player_id)
+        .unwrap()
+        .send(end_turn(Some(7)))
+        .await;
+    for client in clients.values_mut() {
+        let next_turn = client
+            .receive_next_turn()
+            .await
+            .expect("every player learns about the next turn");
+        assert_eq!(next_turn.next_player_id, waiting_player_id);
+    }
+
+    // the retransmit is answered, but does not end the turn of the other player
+    let client = clients.get_mut(&turn_player_id).unwrap();
+    client.send(end_turn(Some(7))).await;
+    match client.receive().await {
+        ProtocolMessage::StatusMessage(StatusMessage {
+            code,
+            data: Some(Data::ShipActionResponse(ShipActionResponse { .. })),
+            ..
+        }) => assert_eq!(StatusCode::from_i32(code), Some(StatusCode::Ok)),
+        msg => panic!("Expected ShipActionResponse, got {msg:#?}"),
+    }
+    for client in clients.values_mut() {
+        client.assert_no_message().await;
+    }
+
+    // a new key is a new request, which is rejected outside of the player's turn
+    let client = clients.get_mut(&turn_player_id).unwrap();
+    client.send(end_turn(Some(8))).await;
+    match client.receive().await {
+        ProtocolMessage::StatusMessage(StatusMessage { code, .. }) => {
+            assert_eq!(StatusCode::from_i32(code), Some(StatusCode::BadRequest))
+        }
+        msg => panic!("Expected BadRequest, got {msg:#?}"),
+    }
+
+    server_ctrl.stop().await;
+}
+
+#[tokio::test]
+async fn keepalives_are_ignored() {
+    let _lock = TEST_LOCK.lock().await;
+    let cfg: Arc<dyn ConfigProvider + Send + Sync> = Arc::new(DuelConfig);
+
+    let server_ctrl = spawn_server_task(cfg.clone());
+    let client_config = test_client_config();
+
+    let mut first = Client::connect_ipv4(cfg.clone(), client_config.clone(), "First", None).await;
+    for _ in 0..3 {
+        first.send(Keepalive {}.into()).await;
+    }
+    first.assert_no_message().await;
+
+    // the lobby is unchanged and keeps working
+    let mut second = Client::connect_ipv6(cfg.clone(), client_config.clone(), "Second", None).await;
+    Client::switch_team_check_broadcasts([&mut first].into_iter(), 1, 1).await;
+    second.send(Keepalive {}.into()).await;
+    first.set_ready(true).await;
+    let assert_map = HashMap::from([
+        (first.state.player_id, true),
+        (second.state.player_id, false),
+    ]);
+    Client::set_ready_check_broadcasts([&mut first, &mut second].into_iter(), assert_map).await;
+    second.assert_no_message().await;
+
+    server_ctrl.stop().await;
+}
+
+/// The duel setup, with or without the action points of a player visible to their opponent.
+struct ActionPointsConfig {
+    action_points_visible: bool,
+}
+
+impl ConfigProvider for ActionPointsConfig {
+    fn game_config(&self) -> Arc<Config> {
+        Arc::new(Config {
+            action_points_visible: self.action_points_visible,
+            ..(*DuelConfig.game_config()).clone()
+        })
+    }
+
+    fn server_config(&self) -> Arc<ServerConfig> {
+        DuelConfig.server_config()
+    }
+}
+
+/// Ends the first turn of a duel. Returns the action points the player on turn next is told
+/// about and those their opponent is told about.
+async fn action_points_announced_for_second_turn(action_points_visible: bool) -> (u32, u32) {
+    let cfg: Arc<dyn ConfigProvider + Send + Sync> = Arc::new(ActionPointsConfig {
+        action_points_visible,
+    });
+    let server_ctrl = spawn_server_task(cfg.clone());
+    let (mut clients, first_player_id) = start_duel_on_both_endpoints(cfg).await;
+    let second_player_id = *clients.keys().find(|&&id| id != first_player_id).unwrap();
+
+    clients
+        .get_mut(&first_player_id)
+        .unwrap()
+        .send(
+            ShipActionRequest {
+                end_turn: true,
+                ..Default::default()
+            }
+            .into(),
+        )
+        .await;
+    let mut announced = HashMap::new();
+    for (id, client) in clients.iter_mut() {
+        let next_turn = client
+            .receive_next_turn()
+            .await
+            .expect("every player learns about the next turn");
+        assert_eq!(next_turn.next_player_id, second_player_id);
+        announced.insert(*id, next_turn.action_points);
+    }
+
+    server_ctrl.stop().await;
+    (announced[&second_player_id], announced[&first_player_id])
+}
+
+#[tokio::test]
+async fn action_points_are_hidden_from_opponents() {
+    let _lock = TEST_LOCK.lock().await;
+    let (own, opponent) = action_points_announced_for_second_turn(false).await;
+    assert!(own > 0);
+    assert_eq!(opponent, 0);
+}
+
+#[tokio::test]
+async fn action_points_are_shown_to_opponents_if_visible() {
+    let _lock = TEST_LOCK.lock().await;
+    let (own, opponent) = action_points_announced_for_second_turn(true).await;
+    assert!(own > 0);
+    assert_eq!(opponent, own);
+}
+
+/// The duel setup, with or without disconnecting clients that break the protocol.
+struct ProtocolConfig {
+    strict_protocol: bool,
+}
+
+impl ConfigProvider for ProtocolConfig {
+    fn game_config(&self) -> Arc<Config> {
+        Arc::new(Config {
+            strict_protocol: self.strict_protocol,
+            ..(*DuelConfig.game_config()).clone()
+        })
+    }
+
+    fn server_config(&self) -> Arc<ServerConfig> {
+        DuelConfig.server_config()
+    }
+}
+
+/// Joins a game and sends a client-bound event, which the server has to reject.
+async fn send_client_bound_message(cfg: Arc<dyn ConfigProvider + Send + Sync>) -> Client {
+    let mut client = Client::connect_ipv4(cfg, test_client_config(), "Examiner", None).await;
+    client.send(NextTurn::default().into()).await;
+    client
+}
+
+fn assert_bad_request(msg: ProtocolMessage) {
+    match msg {
+        ProtocolMessage::StatusMessage(StatusMessage { code, .. }) => {
+            assert_eq!(StatusCode::from_i32(code), Some(StatusCode::BadRequest))
+        }
+        msg => panic!("Expected BadRequest, got {msg:#?}"),
+    }
+}
+
+#[tokio::test]
+async fn lenient_protocol_keeps_client_bound_senders() {
+    let _lock = TEST_LOCK.lock().await;
+    let cfg: Arc<dyn ConfigProvider + Send + Sync> = Arc::new(ProtocolConfig {
+        strict_protocol: false,
+    });
+    let server_ctrl = spawn_server_task(cfg.clone());
+
+    let mut client = send_client_bound_message(cfg).await;
+    assert_bad_request(client.receive().await);
+    // the connection is still usable
+    client.set_ready(true).await;
+
+    server_ctrl.stop().await;
+}
+
+#[tokio::test]
+async fn strict_protocol_disconnects_client_bound_senders() {
+    let _lock = TEST_LOCK.lock().await;
+    let cfg: Arc<dyn ConfigProvider + Send + Sync> = Arc::new(ProtocolConfig {
+        strict_protocol: true,
+    });
+    let server_ctrl = spawn_server_task(cfg.clone());
+
+    let mut client = send_client_bound_message(cfg).await;
+    // the rejection races with closing the connection and may not arrive
+    if let Ok(msg) = client.connection.receive().await {
+        assert_bad_request(msg);
+        assert!(client.connection.receive().await.is_err());
+    }
+
+    server_ctrl.stop().await;
+}
+
+/// A ship facing north, its type and balancing are left to the test.
+fn ship_at(ship_id: ShipID, x: i32, y: i32) -> GeneralShipBuilder {
+    let mut builder = GeneralShipBuilder::default();
+    builder
+        .id(ship_id)
+        .position(x, y)
+        .orientation(Orientation::North);
+    builder
+}
+
+#[tokio::test]
+async fn game_over_reveals_remaining_ships_if_configured() {
+    let mut game = GameBuilder::new()
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamB)
+        .ships([
+            ship_at((1, 0), 0, 0).health(10).destroyer().build(),
+            ship_at((2, 0), 5, 0).health(10).destroyer().build(),
+        ])
+        .build();
+
+    let event = game_over_event(&game, Teams::TeamA);
+    assert_eq!(event.winner, Teams::TeamA as i32);
+    assert!(event.revealed_ships.is_empty());
+
+    game.config = Arc::new(Config {
+        reveal_on_game_end: true,
+        ..game.config.as_ref().clone()
+    });
+    let event = game_over_event(&game, Teams::TeamA);
+    assert_eq!(event.revealed_ships.len(), 2);
+    let enemy_ship = event
+        .revealed_ships
+        .iter()
+        .find(|ship| ship.owner_id == 2)
+        .expect("the enemy ship is revealed");
+    assert_eq!(enemy_ship.position, Some(Coordinate { x: 5, y: 0 }));
+    assert_eq!(enemy_ship.health, 10);
+}
+
+#[tokio::test]
+async fn server_state_resyncs_player_view() {
+    let destroyer = |ship_id: ShipID, x: i32, y: i32| {
+        ship_at(ship_id, x, y)
+            .health(10 + ship_id.0)
+            .vision(16)
+            .destroyer()
+            .build()
+    };
+    let game = GameBuilder::new()
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamB)
+        .player(3, Teams::None)
+        .ships([
+            destroyer((1, 0), 0, 0),
+            destroyer((1, 1), 50, 0),
+            destroyer((2, 0), 5, 0),
+            destroyer((2, 1), 100, 100),
+        ])
+        .turn(1, 7)
+        .build();
+
+    let state = server_state_of(&game, 1).expect("player 1 is part of a team");
+    assert_eq!(state.action_points, 7);
+    let mut own_ships: Vec<_> = state
+        .team_ships
+        .iter()
+        .map(|ship| (ship.owner_id, ship.position.clone(), ship.health))
+        .collect();
+    own_ships.sort_by_key(|(_, position, _)| position.as_ref().map(|p| p.x));
+    assert_eq!(
+        own_ships,
+        vec![
+            (1, Some(Coordinate { x: 0, y: 0 }), 11),
+            (1, Some(Coordinate { x: 50, y: 0 }), 11),
+        ]
+    );
+    // only the nearby enemy ship is in vision range
+    assert!(!state.visible_hostile_ships.is_empty());
+    assert!(state
+        .visible_hostile_ships
+        .iter()
+        .all(|Coordinate { x, .. }| *x == 5));
+
+    let state = server_state_of(&game, 2).expect("player 2 is part of a team");
+    assert_eq!(state.action_points, 0);
+    assert_eq!(state.team_ships.len(), 2);
+    assert!(state.team_ships.iter().all(|ship| ship.owner_id == 2));
+
+    // joined, but in no team
+    assert!(server_state_of(&game, 3).is_none());
+    assert!(server_state_of(&game, 4).is_none());
+}
+
+#[tokio::test]
+async fn ship_state_reports_current_health() {
+    let balancing = default_config_provider()
+        .game_config()
+        .destroyer_balancing
+        .clone()
+        .unwrap();
+    let initial_health = balancing.common_balancing.as_ref().unwrap().initial_health;
+    let damaged = Ship::Destroyer {
+        balancing: Arc::from(balancing),
+        data: ShipData {
+            id: (1, 0),
+            health: initial_health - 3,
+            pos_x: 4,
+            pos_y: 2,
+            orientation: Orientation::North,
+        },
+        cooldowns: Default::default(),
+    };
+    assert_eq!(damaged.initial_health(), initial_health);
+
+    let state = create_ship_state(&damaged);
+    assert_eq!(state.health, initial_health - 3);
+    assert_eq!(state.owner_id, 1);
+
+    // a reconnecting owner sees the same health
+    let game = Game {
+        state: GameState::InGame,
+        players: HashMap::from([(
+            1,
+            Player {
+                id: 1,
+                ..Default::default()
+            },
+        )]),
+        ships: ShipManager::new_with_ships(vec![damaged]),
+        team_a: HashSet::from([1]),
+        ..Default::default()
+    };
+    let resync = server_state_of(&game, 1).expect("player 1 is part of a team");
+    assert_eq!(resync.team_ships, vec![state]);
+}
+
+#[tokio::test]
+async fn ship_action_response_reports_charged_action_points() {
+    let shoot_cost = 2;
+    let game = RwLock::new(
+        GameBuilder::new()
+            .player(1, Teams::TeamA)
+            .player(2, Teams::TeamB)
+            .ships([
+                ship_at((1, 0), 0, 0)
+                    .health(10)
+                    .cannon(20, 32, shoot_cost, 0)
+                    .destroyer()
+                    .build(),
+                ship_at((2, 0), 5, 0).health(10).destroyer().build(),
+            ])
+            .turn(1, 7)
+            .build(),
+    );
+    let mut game = game.write().await;
+
+    let (_, response) = execute_charged_action(
+        &mut game,
+        1,
+        Action::Shoot {
+            ship_id: (1, 0),
+            properties: ShootProperties {
+                target: Some(Coordinate { x: 5, y: 0 }),
+            },
+        },
+    )
+    .expect("the enemy ship is in range");
+    assert_eq!(response.action_points_charged, shoot_cost);
+    assert_eq!(response.action_points_left, 7 - shoot_cost);
+    assert_eq!(game.players[&1].last_action_cost, shoot_cost);
+}
+
+#[tokio::test]
+async fn executed_actions_are_audited() {
+    let (shoot_cost, shoot_damage) = (2, 20);
+    let destroyer = |ship_id: ShipID, x: i32, health: u32| {
+        ship_at(ship_id, x, 0)
+            .health(health)
+            .cannon(shoot_damage, 32, shoot_cost, 0)
+            .destroyer()
+            .build()
+    };
+    let audit_log = Arc::new(std::sync::Mutex::new(Vec::<u8>::new()));
+    let mut game = GameBuilder::new()
+        .player(1, Teams::TeamA)
+        .player(2, Teams::TeamB)
+        .ships([
+            destroyer((1, 0), 0, 10),
+            destroyer((1, 1), 1, 10),
+            destroyer((2, 0), 5, 2 * shoot_damage),
+        ])
+        .turn(1, 2 * shoot_cost)
+        .build();
+    game.turns_played = 2;
+    game.audit_log = Some(AuditLog::new(7, audit_log.clone()));
+    let game = RwLock::new(game);
+    let mut game = game.write().await;
+    let shoot = |ship_id: ShipID| Action::Shoot {
+        ship_id,
+        properties: ShootProperties {
+            target: Some(Coordinate { x: 5, y: 0 }),
+        },
+    };
+
+    // the second shot destroys the enemy ship, the shot of a missing ship is rejected
+    execute_charged_action(&mut game, 1, shoot((1, 0))).expect("the enemy ship is in range");
+    assert!(execute_charged_action(&mut game, 1, shoot((1, 5))).is_err());
+    execute_charged_action(&mut game, 1, shoot((1, 1))).expect("the enemy ship is in range");
+
+    let audit_log = String::from_utf8(audit_log.lock().unwrap().clone()).unwrap();
+    assert_eq!(
+        audit_log.lines().collect::<Vec<_>>(),
+        vec![
+            format!("{{\"game\":7,\"turn\":3,\"player\":1,\"action\":\"Shoot\",\"ship\":0,\"action_points\":{shoot_cost},\"damage\":{shoot_damage},\"destroyed\":[]}}"),
+            format!("{{\"game\":7,\"turn\":3,\"player\":1,\"action\":\"Shoot\",\"ship\":1,\"action_points\":{shoot_cost},\"damage\":{shoot_damage},\"destroyed\":[{{\"player\":2,\"number\":0}}]}}"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn ship_action_event_is_broadcast_without_visible_effects() {
+    let destroyer = |ship_id: ShipID, x: i32| {
+        ship_at(ship_id, x, 0)
+            .health(10)
+            .vision(16)
+            .movement(1, 0, 1, 0)
+            .destroyer()
+            .build()
+    };
+    // the ships are out of sight of each other, moving changes nothing the opponent could see
+    let game = RwLock::new(
+        GameBuilder::new()
+            .player(1, Teams::TeamA)
+            .player(2, Teams::TeamB)
+            .ships([destroyer((1, 0), 0), destroyer((2, 0), 200)])
+            .turn(1, 7)
+            .build(),
+    );
+    let mut game = game.write().await;
+    let request = ShipActionRequest {
+        ship_number: 0,
+        action_properties: Some(ActionProperties::MoveProperties(MoveProperties {
+            direction: MoveDirection::Forward.into(),
+        })),
+        ..Default::default()
+    };
+    let (game_end_tx, _game_end_rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let (action_result, _) = execute_charged_action(&mut game, 1, Action::from((1, &request)))
+        .expect("the ship is able to move");
+    for action_result in [action_result, ActionResult::None] {
+        let (tx, mut rx) = broadcast_channel(64);
+        let step_failed = broadcast_action_outcome(
+            &mut game,
+            1,
+            &[1],
+            &request,
+            action_result,
+            &tx,
+            &game_end_tx,
+        )
+        .unwrap();
+        assert!(!step_failed);
+
+        let mut messages = vec![];
+        while let Ok(message) = rx.try_recv() {
+            messages.push(message);
+        }
+        assert!(matches!(
+            messages.first(),
+            Some((recipients, ProtocolMessage::ShipActionEvent(ShipActionEvent { ship_number: 0, .. })))
+                if recipients == &vec![1]
+        ));
+        assert!(!messages.iter().any(|(_, message)| matches!(
+            message,
+            ProtocolMessage::HitEvent(_) | ProtocolMessage::DestructionEvent(_)
+        )));
+    }
+}
+
+#[tokio::test]
+async fn unshared_team_vision_stays_with_owner() {
+    let destroyer = |ship_id: ShipID, x: i32, y: i32| {
+        ship_at(ship_id, x, y)
+            .health(10)
+            .vision(16)
+            .destroyer()
+            .build()
+    };
+    // the allied ships are far apart, only the ship of player 3 sees the enemy
+    let game_with_shared_vision = |share_team_vision: bool| {
+        GameBuilder::new()
+            .config(Config {
+                share_team_vision,
+                ..(*default_config_provider().game_config()).clone()
+            })
+            .player(1, Teams::TeamA)
+            .player(2, Teams::TeamB)
+            .player(3, Teams::TeamA)
+            .ships([
+                destroyer((1, 0), 0, 0),
+                destroyer((3, 0), 100, 100),
+                destroyer((2, 0), 103, 100),
+            ])
+            .turn(1, 7)
+            .build()
+    };
+    let enemy_tiles_seen_by = |game: &Game, player_id: PlayerID| {
+        server_state_of(game, player_id)
+            .expect("the player is part of a team")
+            .visible_hostile_ships
+            .into_iter()
+            .filter(|Coordinate { x, .. }| *x == 103)
+            .count()
+    };
+
+    let shared = game_with_shared_vision(true);
+    assert!(enemy_tiles_seen_by(&shared, 3) > 0);
+    assert_eq!(
+        enemy_tiles_seen_by(&shared, 1),
+        enemy_tiles_seen_by(&shared, 3)
+    );
+
+    let unshared = game_with_shared_vision(false);
+    assert_eq!(
+        enemy_tiles_seen_by(&unshared, 3),
+        enemy_tiles_seen_by(&shared, 3)
+    );
+    assert_eq!(enemy_tiles_seen_by(&unshared, 1), 0);
+}
+
+#[tokio::test]
+async fn action_results_are_broadcast_in_a_stable_order() {
+    let tiles: Vec<_> = (0..8)
+        .flat_map(|x| (0..8).map(move |y| Coordinate { x, y }))
+        .collect();
+    let broadcast_result = |tiles: &[Coordinate]| {
+        let mut game = Game {
+            state: GameState::InGame,
+            players: HashMap::from([(1, Player::default()), (2, Player::default())]),
+            team_a: HashSet::from([1]),
+            team_b: HashSet::from([2]),
+            turn: Some(Turn::new(1, 7)),
+            ..Default::default()
+        };
+        let (tx, mut rx) = broadcast_channel(128);
+        let tile_set = || tiles.iter().cloned().collect::<HashSet<_>>();
+        handle_action_result(
+            &mut game,
+            1,
+            &tx,
+            tile_set(),
+            HashSet::new(),
+            tile_set(),
+            HashSet::new(),
+            tile_set(),
+            tiles.iter().map(|tile| (tile.clone(), 1)).collect(),
+            vec![],
+            tile_set(),
+        )
+        .unwrap();
+        let mut messages = vec![];
+        while let Ok((_, message)) = rx.try_recv() {
+            messages.push(message);
+        }
+        messages
+    };
+
+    // every run hashes the tiles differently
+    let messages = broadcast_result(&tiles);
+    let reversed: Vec<_> = tiles.iter().rev().cloned().collect();
+    for _ in 0..4 {
+        assert_eq!(broadcast_result(&tiles), messages);
+        assert_eq!(broadcast_result(&reversed), messages);
+    }
+
+    let splash_tiles = messages
+        .iter()
+        .find_map(|message| match message {
+            ProtocolMessage::SplashEvent(SplashEvent { coordinate }) => Some(coordinate),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(splash_tiles.len(), tiles.len());
+    assert!(splash_tiles
+        .windows(2)
+        .all(|pair| (pair[0].y, pair[0].x) < (pair[1].y, pair[1].x)));
+}
+
+#[tokio::test]
+async fn broadcast_capacity_absorbs_bursts() {
+    let default_capacity = default_config_provider().server_config().broadcast_capacity;
+    let burst = default_capacity + 1;
+    let send_burst = |tx: &tokio::sync::broadcast::Sender<(Vec<ClientId>, ProtocolMessage)>| {
+        for _ in 0..burst {
+            tx.send((vec![1], PlacementPhase::default().into()))
+                .expect("a receiver is subscribed");
+        }
+    };
+
+    let (tx, mut rx) = broadcast_channel(default_capacity);
+    send_burst(&tx);
+    assert!(matches!(rx.recv().await, Err(RecvError::Lagged(1))));
+
+    let (tx, mut rx) = broadcast_channel(2 * default_capacity);
+    send_burst(&tx);
+    for _ in 0..burst {
+        assert!(rx.recv().await.is_ok());
+    }
+    assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+}
+
+#[tokio::test]
+async fn first_turn_waits_for_game_start_acks() {
+    let new_game = |game_start_ack_timeout: u32| {
+        let mut game = GameBuilder::new()
+            .config(Config {
+                game_start_ack_timeout,
+                ..(*default_config_provider().game_config()).clone()
+            })
+            .player(1, Teams::TeamA)
+            .player(2, Teams::TeamB)
+            .ships([
+                ship_at((1, 0), 10, 0).health(10).destroyer().build(),
+                ship_at((2, 0), 20, 0).health(10).destroyer().build(),
+            ])
+            .build();
+        game.state = GameState::Preparation;
+        Arc::new(RwLock::new(game))
+    };
+    let (tx, mut rx) = broadcast_channel(16);
+    let expect_game_start = |rx: &mut tokio::sync::broadcast::Receiver<_>| {
+        for _ in 0..2 {
+            assert!(matches!(
+                rx.try_recv(),
+                Ok((_, ProtocolMessage::GameStart(_)))
+            ));
+        }
+    };
+
+    // the first turn is announced to everyone along with their position in the turn queue
+    let expect_first_turn = |rx: &mut tokio::sync::broadcast::Receiver<_>,
+                             turn_player_id: PlayerID| {
+        for player_id in [1, 2] {
+            match rx.try_recv() {
+                Ok((ids, ProtocolMessage::NextTurn(next_turn))) => {
+                    assert_eq!(ids, vec![player_id]);
+                    assert_eq!(next_turn.next_player_id, turn_player_id);
+                    let position_in_queue = if player_id == turn_player_id { 0 } else { 1 };
+                    assert_eq!(next_turn.position_in_queue, position_in_queue);
+                }
+                msg => panic!("Expected NextTurn, got {msg:#?}"),
+            }
+        }
+    };
+
+    // without a timeout the first turn is announced right away
+    let game = new_game(0);
+    start_game(&mut *game.write().await, &game, &tx).unwrap();
+    expect_game_start(&mut rx);
+    let turn_player_id = game.read().await.turn.as_ref().unwrap().player_id;
+    expect_first_turn(&mut rx, turn_player_id);
+
+    // the first turn is announced once everyone acknowledged the start
+    let game = new_game(60_000);
+    start_game(&mut *game.write().await, &game, &tx).unwrap();
+    expect_game_start(&mut rx);
+    for player_id in [1, 1] {
+        acknowledge_game_start(&mut *game.write().await, player_id, &tx).unwrap();
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+    }
+    acknowledge_game_start(&mut *game.write().await, 2, &tx).unwrap();
+    let turn_player_id = game.read().await.turn.as_ref().unwrap().player_id;
+    expect_first_turn(&mut rx, turn_player_id);
+    // late acknowledgements are ignored
+    acknowledge_game_start(&mut *game.write().await, 2, &tx).unwrap();
+    assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+
+    // or after the timeout, if a player does not respond
+    let game = new_game(50);
+    start_game(&mut *game.write().await, &game, &tx).unwrap();
+    expect_game_start(&mut rx);
+    acknowledge_game_start(&mut *game.write().await, 1, &tx).unwrap();
+    assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+    let next_turn = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .expect("the first turn is announced after the timeout");
+    assert!(matches!(next_turn, Ok((_, ProtocolMessage::NextTurn(_)))));
+    assert!(game.read().await.awaiting_start_acks.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_ready_ups_start_preparation_once() {
+    let player_ids: Vec<PlayerID> = vec![1, 2, 3, 4];
+    let game = Arc::new(RwLock::new(Game {
+        players: player_ids
+            .iter()
+            .map(|&id| {
+                (
+                    id,
+                    Player {
+                        id,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect(),
+        team_a: HashSet::from([1, 2]),
+        team_b: HashSet::from([3, 4]),
+        ..Default::default()
+    }));
+    let (tx, mut rx) = broadcast_channel(64);
+
+    // every player readies up twice, all at the same time
+    let start = Arc::new(tokio::sync::Barrier::new(2 * player_ids.len()));
+    let tasks: Vec<_> = player_ids
+        .iter()
+        .chain(player_ids.iter())
+        .map(|&player_id| {
+            let (game, tx, start) = (game.clone(), tx.clone(), start.clone());
+            tokio::spawn(async move {
+                start.wait().await;
+                let mut g = game.write().await;
+                g.players.get_mut(&player_id).unwrap().is_ready = true;
+                try_start_preparation(&mut g, &tx).unwrap()
+            })
+        })
+        .collect();
+    let mut transitions = 0;
+    for task in tasks {
+        if task.await.unwrap() {
+            transitions += 1;
+        }
+    }
+    assert_eq!(transitions, 1);
+    assert!(matches!(game.read().await.state, GameState::Preparation));
+
+    let mut receivers = vec![];
+    while let Ok((ids, msg)) = rx.try_recv() {
+        assert!(matches!(msg, ProtocolMessage::PlacementPhase(_)));
+        receivers.extend(ids);
+    }
+    receivers.sort_unstable();
+    assert_eq!(receivers, player_ids);
+}
+
+#[tokio::test]
+async fn placement_phase_reveals_opponent_quadrants_if_configured() {
+    let quadrant_corners_received = |reveal_opponent_quadrants: bool| {
+        let mut game = Game {
+            config: Arc::new(Config {
+                reveal_opponent_quadrants,
+                ..(*default_config_provider().game_config()).clone()
+            }),
+            players: [1, 2, 3, 4]
+                .map(|id| {
+                    (
+                        id,
+                        Player {
+                            id,
+                            is_ready: true,
+                            ..Default::default()
+                        },
+                    )
+                })
+                .into(),
+            team_a: HashSet::from([1, 2]),
+            team_b: HashSet::from([3, 4]),
+            ..Default::default()
+        };
+        let (tx, mut rx) = broadcast_channel(64);
+        assert!(try_start_preparation(&mut game, &tx).unwrap());
+
+        let mut own_corners = HashSet::new();
+        let mut received = vec![];
+        while let Ok((_, msg)) = rx.try_recv() {
+            match msg {
+                ProtocolMessage::PlacementPhase(PlacementPhase {
+                    corner: Some(corner),
+                    quadrant_corners,
+                    ..
+                }) => {
+                    own_corners.insert(corner);
+                    received.push(quadrant_corners.into_iter().collect::<HashSet<_>>());
+                }
+                msg => panic!("expected PlacementPhase with corner, got {msg:#?}"),
+            }
+        }
+        assert_eq!(own_corners.len(), 4);
+        (own_corners, received)
+    };
+
+    let (all_corners, received) = quadrant_corners_received(true);
+    assert_eq!(received.len(), 4);
+    assert!(received.iter().all(|corners| *corners == all_corners));
+
+    // placement stays blind by default
+    let (_, received) = quadrant_corners_received(false);
+    assert!(received.iter().all(HashSet::is_empty));
+}
+
+#[tokio::test]
+async fn message_handler_errors_describe_their_cause() {
+    use std::error::Error;
+
+    let broadcast_error = tokio::sync::broadcast::error::SendError((
+        vec![1, 2],
+        ProtocolMessage::from(NextTurn::default()),
+    ));
+    let errors = [
+        MessageHandlerError::Network(QuinnetError::UnknownClient(7)),
+        MessageHandlerError::Protocol(ActionExecutionError::BadRequest(
+            "action properties are missing".to_string(),
+        )),
+        MessageHandlerError::Protocol(ActionExecutionError::OutOfState(GameState::Lobby)),
+        MessageHandlerError::Broadcast(Box::new(broadcast_error)),
+        MessageHandlerError::InvalidInboundMessage("not joined".to_string()),
+    ];
+    let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages.iter().collect::<HashSet<_>>().len(),
+        messages.len(),
+        "{messages:?}"
+    );
+
+    // the messages name the details of the cause
+    for (message, detail) in zip(
+        &messages,
+        [
+            "7",
+            "action properties are missing",
+            "Lobby",
+            "[1, 2]",
+            "not joined",
+        ],
+    ) {
+        assert!(message.contains(detail), "{message} misses {detail}");
+    }
+
+    // causes are chained, except for messages rejected by the server itself
+    assert!(errors[0].source().unwrap().is::<QuinnetError>());
+    assert!(errors[1].source().unwrap().is::<ActionExecutionError>());
+    assert!(errors[3].source().is_some());
+    assert!(errors[4].source().is_none());
+}
+
+#[tokio::test]
+async fn ended_game_is_removed_after_drain_time() {
+    let (ended_games_tx, _ended_games_rx) = tokio::sync::mpsc::unbounded_channel();
+    let registry = Arc::new(RwLock::new(GameRegistry::new(
+        default_config_provider().game_config(),
+        1,
+        ended_games_tx,
+    )));
+    let game_id = registry.write().await.join(1, None).await.unwrap().id;
+
+    // the game is kept while its queues drain
+    let draining = tokio::time::timeout(
+        Duration::from_millis(50),
+        remove_ended_game(registry.clone(), game_id, Duration::from_secs(5)),
+    )
+    .await;
+    assert!(draining.is_err());
+    assert!(registry.read().await.game_of(1).is_some());
+
+    // without a drain time the game is removed right away
+    tokio::time::timeout(
+        Duration::from_millis(50),
+        remove_ended_game(registry.clone(), game_id, Duration::ZERO),
+    )
+    .await
+    .expect("the game is removed without waiting");
+    assert!(registry.read().await.game_of(1).is_none());
+}
+
+/// The duel setup, keeping the lobby in a file.
+struct LobbyFileConfig {
+    lobby_file: &'static str,
+}
+
+impl ConfigProvider for LobbyFileConfig {
+    fn game_config(&self) -> Arc<Config> {
+        DuelConfig.game_config()
+    }
+
+    fn server_config(&self) -> Arc<ServerConfig> {
+        Arc::new(ServerConfig {
+            lobby_file: Some(self.lobby_file),
+            ..*DuelConfig.server_config()
+        })
+    }
+}
+
+#[tokio::test]
+async fn claimed_seat_is_sent_the_server_state() {
+    let _lock = TEST_LOCK.lock().await;
+    let path = std::env::temp_dir().join(format!("bs_plus_lobby_{}", std::process::id()));
+    std::fs::write(&path, "B\tready\tseat-token\tReturning\n").unwrap();
+    let cfg: Arc<dyn ConfigProvider + Send + Sync> = Arc::new(LobbyFileConfig {
+        lobby_file: Box::leak(path.to_string_lossy().into_owned().into_boxed_str()),
+    });
+    let server_ctrl = spawn_server_task(cfg.clone());
+
+    let mut connection = Client::connection_ipv4(cfg, test_client_config()).await;
+    let response = match connection.rejoin("Returning", "seat-token").await {
+        Ok(response) => response,
+        Err(e) => panic!("unable to rejoin: {e}"),
+    };
+    assert_eq!(response.seat_token, "seat-token");
+
+    // nothing is placed in the lobby yet, the player sees no ships and has no action points
+    match connection.receive().await {
+        Ok(ProtocolMessage::StatusMessage(StatusMessage {
+            code,
+            data: Some(Data::ServerStateResponse(ServerStateResponse { state })),
+            ..
+        })) => {
+            assert_eq!(StatusCode::from_i32(code), Some(StatusCode::Ok));
+            assert_eq!(state, Some(ServerState::default()));
+        }
+        msg => panic!("Expected ServerStateResponse, got {msg:#?}"),
+    }
+    // the seat is taken as it was saved
+    match connection.receive().await {
+        Ok(ProtocolMessage::LobbyChangeEvent(LobbyChangeEvent {
+            team_state_a,
+            team_state_b,
+        })) => {
+            assert!(team_state_a.is_empty());
+            assert_eq!(
+                team_state_b,
+                vec![PlayerLobbyState {
+                    player_id: response.player_id,
+                    name: String::from("Returning"),
+                    ready: true,
+                }]
+            );
+        }
+        msg => panic!("Expected LobbyChangeEvent, got {msg:#?}"),
+    }
+
+    server_ctrl.stop().await;
+    let _ = std::fs::remove_file(path);
+}
+
 // TODO Implement: Fuzzy test
 // TODO Test: player disconnect and reconnect and check player ready states
 // TODO Test: player switch teams and check player ready states
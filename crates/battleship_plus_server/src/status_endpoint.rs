@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{debug, info, warn};
+use serde::Serialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{watch, RwLock};
+
+use battleship_plus_common::game::PlayerID;
+
+use crate::game::data::Game;
+
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+/// Clients that take longer to send their request are answered with a timeout,
+/// so stalled connections do not pile up.
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+pub(crate) type CurrentGame = Option<Arc<RwLock<Game>>>;
+
+/// Serves the public state of the current game as JSON over plain HTTP,
+/// so spectating web dashboards do not have to speak the game protocol.
+/// The task stops as soon as the sender of `current_game` is dropped.
+/// Returns the address the endpoint is listening on.
+pub(crate) async fn spawn_status_endpoint_task(
+    address: SocketAddr,
+    current_game: watch::Receiver<CurrentGame>,
+) -> Option<SocketAddr> {
+    let listener = match TcpListener::bind(address).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            warn!("Unable to serve game status on {address}: {e}");
+            return None;
+        }
+    };
+    let local_address = listener.local_addr().ok()?;
+    info!("Serving game status on http://{local_address}/status");
+
+    tokio::spawn(status_endpoint_task(listener, current_game));
+    Some(local_address)
+}
+
+async fn status_endpoint_task(
+    listener: TcpListener,
+    mut current_game: watch::Receiver<CurrentGame>,
+) {
+    loop {
+        tokio::select! {
+            changed = current_game.changed() => {
+                if changed.is_err() {
+                    // the server task is gone
+                    return;
+                }
+            }
+            connection = listener.accept() => match connection {
+                Ok((stream, peer)) => {
+                    let game = current_game.borrow().clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_status_request(stream, game).await {
+                            debug!("Unable to answer status request from {peer}: {e}");
+                        }
+                    });
+                }
+                Err(e) => warn!("Unable to accept status connection: {e}"),
+            }
+        }
+    }
+}
+
+async fn handle_status_request(
+    mut stream: TcpStream,
+    game: CurrentGame,
+) -> Result<(), std::io::Error> {
+    let (status, body) =
+        match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+            Ok(request) => {
+                let request_line = String::from_utf8_lossy(&request?)
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                let mut parts = request_line.split_whitespace();
+
+                match (parts.next(), parts.next()) {
+                    (Some("GET"), Some("/") | Some("/status")) => match game {
+                        Some(game) => ("200 OK", game_status_json(&*game.read().await)),
+                        None => ("503 Service Unavailable", error_json("no game initialized")),
+                    },
+                    (Some("GET"), Some(_)) => ("404 Not Found", error_json("not found")),
+                    _ => ("405 Method Not Allowed", error_json("method not allowed")),
+                }
+            }
+            Err(_) => ("408 Request Timeout", error_json("request timeout")),
+        };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Reads the request up to the end of its headers, bodies are never needed.
+async fn read_request(stream: &mut TcpStream) -> Result<Vec<u8>, std::io::Error> {
+    let mut request = Vec::with_capacity(1024);
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 || request.len() + read > MAX_REQUEST_SIZE {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+    Ok(request)
+}
+
+#[derive(Serialize)]
+struct GameStatus<'a> {
+    server_name: &'a str,
+    phase: String,
+    teams: Vec<TeamStatus<'a>>,
+}
+
+#[derive(Serialize)]
+struct TeamStatus<'a> {
+    team: &'static str,
+    players: Vec<PlayerStatus<'a>>,
+    remaining_ships: usize,
+}
+
+#[derive(Serialize)]
+struct PlayerStatus<'a> {
+    id: PlayerID,
+    name: &'a str,
+    ready: bool,
+    remaining_ships: usize,
+}
+
+/// Renders the publicly known parts of a game: its phase, the roster of both teams and how many
+/// ships every player has left. Positions and other hidden information are never included.
+pub(crate) fn game_status_json(game: &Game) -> String {
+    let status = GameStatus {
+        server_name: &game.config.server_name,
+        phase: game.state.to_string(),
+        teams: vec![
+            team_status(game, "TeamA", &game.team_a),
+            team_status(game, "TeamB", &game.team_b),
+        ],
+    };
+    serde_json::to_string(&status).expect("the game status is valid JSON")
+}
+
+fn team_status<'a>(
+    game: &'a Game,
+    team_name: &'static str,
+    team: &HashSet<PlayerID>,
+) -> TeamStatus<'a> {
+    let mut player_ids: Vec<_> = team.iter().cloned().collect();
+    player_ids.sort_unstable();
+
+    let players = player_ids
+        .iter()
+        .filter_map(|player_id| game.players.get(player_id))
+        .map(|player| PlayerStatus {
+            id: player.id,
+            name: &player.name,
+            ready: player.is_ready,
+            remaining_ships: game.ships.ships_of(player.id).len(),
+        })
+        .collect();
+
+    TeamStatus {
+        team: team_name,
+        players,
+        remaining_ships: game.ships.get_for_players(team).len(),
+    }
+}
+
+fn error_json(error: &str) -> String {
+    serde_json::json!({ "error": error }).to_string()
+}
@@ -0,0 +1,117 @@
+use std::collections::{HashMap, HashSet};
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::sync::Arc;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{watch, RwLock};
+
+use crate::game::data::{Game, Player};
+use crate::game::states::GameState;
+use crate::status_endpoint::{
+    game_status_json, spawn_status_endpoint_task, CurrentGame, REQUEST_TIMEOUT,
+};
+
+fn lobby_with_two_players() -> Game {
+    Game {
+        players: HashMap::from([
+            (
+                1,
+                Player {
+                    id: 1,
+                    name: String::from("Alice"),
+                    is_ready: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                2,
+                Player {
+                    id: 2,
+                    name: String::from("Bob \"the Bold\""),
+                    ..Default::default()
+                },
+            ),
+        ]),
+        team_a: HashSet::from([1]),
+        team_b: HashSet::from([2]),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn status_json_reflects_phase_and_roster() {
+    let mut game = lobby_with_two_players();
+
+    let json = game_status_json(&game);
+    assert!(json.contains("\"phase\":\"Lobby\""));
+    assert!(json.contains(
+        "{\"team\":\"TeamA\",\"players\":[{\"id\":1,\"name\":\"Alice\",\"ready\":true,\"remaining_ships\":0}],\"remaining_ships\":0}"
+    ));
+    assert!(json.contains(
+        "{\"team\":\"TeamB\",\"players\":[{\"id\":2,\"name\":\"Bob \\\"the Bold\\\"\",\"ready\":false,\"remaining_ships\":0}],\"remaining_ships\":0}"
+    ));
+
+    // phase changes and leaving players are visible immediately
+    game.state = GameState::Preparation;
+    game.remove_player(2);
+    let json = game_status_json(&game);
+    assert!(json.contains("\"phase\":\"Preparation\""));
+    assert!(json.contains("Alice"));
+    assert!(!json.contains("Bob"));
+}
+
+#[tokio::test]
+async fn status_endpoint_serves_current_game() {
+    let game = Arc::new(RwLock::new(lobby_with_two_players()));
+    let (current_game_tx, current_game_rx) = watch::channel::<CurrentGame>(Some(game.clone()));
+
+    let address = spawn_status_endpoint_task(
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)),
+        current_game_rx,
+    )
+    .await
+    .expect("unable to start status endpoint");
+
+    game.write().await.state = GameState::InGame;
+
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    stream
+        .write_all(b"GET /status HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("Content-Type: application/json\r\n"));
+    assert!(response.ends_with(&game_status_json(&*game.read().await)));
+    assert!(response.contains("\"phase\":\"InGame\""));
+
+    drop(current_game_tx);
+}
+
+#[tokio::test]
+async fn stalled_status_request_times_out() {
+    let (current_game_tx, current_game_rx) = watch::channel::<CurrentGame>(None);
+
+    let address = spawn_status_endpoint_task(
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)),
+        current_game_rx,
+    )
+    .await
+    .expect("unable to start status endpoint");
+
+    // the headers are never finished
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    stream.write_all(b"GET /status HTTP/1.1\r\n").await.unwrap();
+    let mut response = String::new();
+    tokio::time::timeout(2 * REQUEST_TIMEOUT, stream.read_to_string(&mut response))
+        .await
+        .expect("the request was not timed out")
+        .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"));
+
+    drop(current_game_tx);
+}
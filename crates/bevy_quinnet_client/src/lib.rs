@@ -16,7 +16,7 @@ use futures_util::StreamExt;
 #[cfg(not(feature = "bevy"))]
 use log::{error, info, trace, warn};
 use once_cell::sync::Lazy;
-use quinn::{ClientConfig, Endpoint};
+use quinn::{ClientConfig, Endpoint, VarInt};
 use rustls::KeyLogFile;
 #[cfg(feature = "bevy")]
 use serde::Deserialize;
@@ -38,7 +38,9 @@ use tokio_util::codec::{FramedRead, FramedWrite};
 
 use battleship_plus_common::{codec::BattleshipPlusCodec, messages::ProtocolMessage};
 pub use bevy_quinnet_common::{ConnectionId, QuinnetError};
-use bevy_quinnet_common::{DEFAULT_KILL_MESSAGE_QUEUE_SIZE, DEFAULT_MESSAGE_QUEUE_SIZE};
+use bevy_quinnet_common::{
+    DEFAULT_KILL_MESSAGE_QUEUE_SIZE, DEFAULT_MESSAGE_QUEUE_SIZE, LEAVE_ERROR_CODE,
+};
 
 use self::certificate::{
     load_known_hosts_store_from_config, CertConnectionAbortEvent, CertInteractionEvent,
@@ -154,6 +156,7 @@ pub(crate) struct ConnectionSpawnConfig {
     to_sync_client: mpsc::Sender<InternalAsyncMessage>,
     close_sender: broadcast::Sender<()>,
     close_receiver: broadcast::Receiver<()>,
+    leave_receiver: oneshot::Receiver<String>,
     to_server_receiver: mpsc::Receiver<ProtocolMessage>,
     from_server_sender: mpsc::Sender<Option<ProtocolMessage>>,
 }
@@ -165,6 +168,7 @@ pub struct Connection {
     sender: mpsc::Sender<ProtocolMessage>,
     receiver: mpsc::Receiver<Option<ProtocolMessage>>,
     close_sender: broadcast::Sender<()>,
+    leave_sender: Option<oneshot::Sender<String>>,
     pub(crate) internal_receiver: mpsc::Receiver<InternalAsyncMessage>,
 }
 
@@ -208,8 +212,18 @@ impl Connection {
         }
     }
 
-    /// Disconnect from the server on this connection. This does not send any message to the server, and simply closes all the connection's tasks locally.
-    fn disconnect(&mut self) -> Result<(), QuinnetError> {
+    /// Leave the server on this connection. The server is told that the client left on purpose and
+    /// why, before all the connection's tasks are closed locally.
+    pub fn disconnect(&mut self, reason: &str) -> Result<(), QuinnetError> {
+        if let Some(leave_sender) = self.leave_sender.take() {
+            // the connection task is gone if the connection was never established or already lost
+            let _ = leave_sender.send(reason.to_string());
+        }
+        self.close_tasks()
+    }
+
+    /// Closes all the connection's tasks locally without sending anything to the server.
+    fn close_tasks(&mut self) -> Result<(), QuinnetError> {
         let close_send_result = self.close_sender.send(());
         if self.is_connected() && close_send_result.is_err() {
             return Err(QuinnetError::ChannelClosed);
@@ -320,12 +334,14 @@ impl Client {
         // Create a close channel for this connection
         let (close_sender, close_receiver): (broadcast::Sender<()>, broadcast::Receiver<()>) =
             broadcast::channel(DEFAULT_KILL_MESSAGE_QUEUE_SIZE);
+        let (leave_sender, leave_receiver) = oneshot::channel();
 
         let connection = Connection {
             state: ConnectionState::Disconnected,
             sender: to_server_sender,
             receiver: from_server_receiver,
             close_sender: close_sender.clone(),
+            leave_sender: Some(leave_sender),
             internal_receiver: from_async_client,
         };
 
@@ -338,6 +354,7 @@ impl Client {
                     to_sync_client,
                     close_sender,
                     close_receiver,
+                    leave_receiver,
                     to_server_receiver,
                     from_server_sender,
                 },
@@ -383,7 +400,7 @@ impl Client {
         connection_id: ConnectionId,
         default_id: &mut Option<ConnectionId>,
     ) -> Result<(), QuinnetError> {
-        connection.disconnect()?;
+        connection.close_tasks()?;
         if let Some(id) = default_id {
             if connection_id == *id {
                 *default_id = None;
@@ -699,6 +716,14 @@ async fn connection_task(mut spawn_config: ConnectionSpawnConfig, alpns: Vec<Str
 
             let mut frame_send = FramedWrite::new(send, BattleshipPlusCodec::default());
 
+            let leave_receiver = spawn_config.leave_receiver;
+            let _leave = tokio::spawn(async move {
+                // the sender is dropped without a reason when the connection is closed otherwise
+                if let Ok(reason) = leave_receiver.await {
+                    connection.close(VarInt::from_u32(LEAVE_ERROR_CODE), reason.as_bytes());
+                }
+            });
+
             let close_sender_clone = spawn_config.close_sender.clone();
             let _network_sends = tokio::spawn(async move {
                 tokio::select! {
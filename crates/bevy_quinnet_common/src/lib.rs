@@ -1,4 +1,4 @@
-use std::{fmt, io, net::AddrParseError, sync::PoisonError};
+use std::{fmt, io, net::AddrParseError, sync::PoisonError, time::Duration};
 
 use base64::Engine;
 use rcgen::RcgenError;
@@ -6,6 +6,8 @@ use rcgen::RcgenError;
 pub const DEFAULT_MESSAGE_QUEUE_SIZE: usize = 150;
 pub const DEFAULT_KILL_MESSAGE_QUEUE_SIZE: usize = 10;
 pub const DEFAULT_KEEP_ALIVE_INTERVAL_S: u64 = 4;
+/// QUIC application error code a client closes its connection with when it leaves on purpose.
+pub const LEAVE_ERROR_CODE: u32 = 1;
 
 pub type ClientId = u32;
 pub type ConnectionId = u64;
@@ -41,6 +43,8 @@ pub enum QuinnetError {
     IoError(#[from] io::Error),
     #[error("Rustls protocol error")]
     RustlsError(#[from] rustls::Error),
+    #[error("Idle timeout `{0:?}` is either zero or too large")]
+    InvalidIdleTimeout(Duration),
 }
 
 impl<T> From<PoisonError<T>> for QuinnetError {
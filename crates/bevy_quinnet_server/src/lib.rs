@@ -1,7 +1,10 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     net::{SocketAddr, ToSocketAddrs},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
     time::Duration,
 };
 
@@ -10,10 +13,11 @@ use bevy::prelude::*;
 use futures::sink::SinkExt;
 use futures_util::StreamExt;
 #[cfg(not(feature = "bevy"))]
-use log::{debug, error, info, trace};
-use quinn::{Endpoint as QuinnEndpoint, ServerConfig};
+use log::{debug, error, info, trace, warn};
+use quinn::{ConnectionError, Endpoint as QuinnEndpoint, IdleTimeout, ServerConfig, VarInt};
 #[cfg(feature = "bevy")]
 use serde::Deserialize;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::runtime::Runtime;
 use tokio::{
     runtime,
@@ -29,13 +33,21 @@ use battleship_plus_common::{
     messages::ProtocolMessage,
 };
 pub use bevy_quinnet_common::{ClientId, QuinnetError};
-use bevy_quinnet_common::{DEFAULT_KILL_MESSAGE_QUEUE_SIZE, DEFAULT_MESSAGE_QUEUE_SIZE};
+use bevy_quinnet_common::{
+    DEFAULT_KILL_MESSAGE_QUEUE_SIZE, DEFAULT_MESSAGE_QUEUE_SIZE, LEAVE_ERROR_CODE,
+};
 
 use self::certificate::{retrieve_certificate, CertificateRetrievalMode, ServerCertificate};
 
 pub mod certificate;
 
 pub const DEFAULT_INTERNAL_MESSAGE_CHANNEL_SIZE: usize = 100;
+/// Bytes of messages that may be queued for a single client before it is disconnected.
+pub const DEFAULT_MAX_PENDING_BYTES: usize = 4 * 1024 * 1024;
+/// Time without any traffic after which a connection is considered lost.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Messages written to a client before its stream is flushed.
+const MAX_BATCH_SIZE: usize = 32;
 
 #[cfg_attr(feature = "bevy", derive(Resource, Deref, DerefMut))]
 pub struct AsyncRuntime(pub Runtime);
@@ -44,12 +56,26 @@ pub struct AsyncRuntime(pub Runtime);
 pub struct ConnectionEvent {
     /// Id of the client who connected
     pub id: ClientId,
+    /// Address the client connected from
+    pub address: SocketAddr,
 }
 
 /// ConnectionLost event raised when a client is considered disconnected from the server. Raised in the CoreStage::PreUpdate stage.
 pub struct ConnectionLostEvent {
     /// Id of the client who lost connection
     pub id: ClientId,
+    pub reason: DisconnectReason,
+}
+
+/// Why the connection to a client ended.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// The connection was closed by either side or failed.
+    ConnectionLost,
+    /// The client announced that it leaves before closing the connection.
+    Left,
+    /// The client did not read its messages and too many bytes piled up for it.
+    Backpressure,
 }
 
 /// Configuration of the server, used when the server starts
@@ -59,6 +85,15 @@ pub struct ServerConfigurationData {
     host: String,
     port: u16,
     local_bind_host: String,
+    #[cfg_attr(feature = "bevy", serde(default = "default_max_pending_bytes"))]
+    max_pending_bytes: usize,
+    #[cfg_attr(feature = "bevy", serde(default))]
+    idle_timeout: Option<Duration>,
+}
+
+#[cfg(feature = "bevy")]
+fn default_max_pending_bytes() -> usize {
+    DEFAULT_MAX_PENDING_BYTES
 }
 
 impl ServerConfigurationData {
@@ -86,7 +121,35 @@ impl ServerConfigurationData {
             host,
             port,
             local_bind_host,
+            max_pending_bytes: DEFAULT_MAX_PENDING_BYTES,
+            idle_timeout: None,
+        }
+    }
+
+    /// Limits the bytes of messages queued for a single client, a client exceeding it is
+    /// disconnected. Defaults to [`DEFAULT_MAX_PENDING_BYTES`].
+    pub fn with_max_pending_bytes(mut self, max_pending_bytes: usize) -> Self {
+        self.max_pending_bytes = max_pending_bytes;
+        self
+    }
+
+    /// Sets the time without any traffic after which a client is considered disconnected.
+    /// Defaults to [`DEFAULT_IDLE_TIMEOUT`].
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    /// The idle timeout in the form of the transport config. A zero timeout would disable it,
+    /// so it is rejected along with timeouts QUIC is unable to express.
+    fn max_idle_timeout(&self) -> Result<IdleTimeout, QuinnetError> {
+        let idle_timeout = self.idle_timeout.unwrap_or(DEFAULT_IDLE_TIMEOUT);
+        if idle_timeout.is_zero() {
+            return Err(QuinnetError::InvalidIdleTimeout(idle_timeout));
         }
+        idle_timeout
+            .try_into()
+            .map_err(|_| QuinnetError::InvalidIdleTimeout(idle_timeout))
     }
 }
 
@@ -102,21 +165,106 @@ pub struct ClientPayload {
 #[derive(Debug)]
 pub(crate) enum InternalAsyncMessage {
     ClientConnected(ClientConnection),
-    ClientLostConnection(ClientId),
-    UnsupportedVersionMessage { client_id: ClientId, version: u8 },
+    ClientLostConnection(ClientId, DisconnectReason),
+    UnsupportedVersionMessage {
+        client_id: ClientId,
+        version: u8,
+    },
+    ProtocolError {
+        client_id: ClientId,
+        description: String,
+    },
 }
 
 #[derive(Debug)]
 pub(crate) struct ClientConnection {
     client_id: ClientId,
+    address: SocketAddr,
     sender: mpsc::Sender<ProtocolMessage>,
     close_sender: broadcast::Sender<()>,
+    tracing: Arc<AtomicBool>,
+    backlog: Arc<Backlog>,
+}
+
+/// Bytes of the messages queued for a client which its sender task did not write yet, along with
+/// whether the client left on its own.
+#[derive(Debug, Default)]
+pub(crate) struct Backlog {
+    pending_bytes: AtomicUsize,
+    exceeded: AtomicBool,
+    left: AtomicBool,
+}
+
+impl Backlog {
+    /// Adds a message to the backlog, returns false if the backlog exceeds the limit with it.
+    fn push(&self, bytes: usize, max_pending_bytes: usize) -> bool {
+        let pending_bytes = self.pending_bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        if pending_bytes > max_pending_bytes {
+            self.exceeded.store(true, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+
+    fn pop(&self, bytes: usize) {
+        self.pending_bytes.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    fn disconnect_reason(&self) -> DisconnectReason {
+        if self.exceeded.load(Ordering::Relaxed) {
+            DisconnectReason::Backpressure
+        } else if self.left.load(Ordering::Relaxed) {
+            DisconnectReason::Left
+        } else {
+            DisconnectReason::ConnectionLost
+        }
+    }
+}
+
+/// Ids of the connected clients. An id is only handed out again once the loss of its connection
+/// was processed, so a late message of a previous client is never attributed to a new one.
+#[derive(Debug, Default)]
+pub(crate) struct ClientIds {
+    in_use: HashSet<ClientId>,
+    last: ClientId,
+}
+
+impl ClientIds {
+    /// Takes the next free id after the last one handed out, starting over at 1 after the
+    /// largest id. `None` if every id is in use.
+    fn allocate(&mut self) -> Option<ClientId> {
+        let id = (self.last.saturating_add(1)..=ClientId::MAX)
+            .chain(1..=self.last)
+            .find(|id| !self.in_use.contains(id))?;
+        self.in_use.insert(id);
+        self.last = id;
+        Some(id)
+    }
+
+    fn release(&mut self, client_id: ClientId) {
+        self.in_use.remove(&client_id);
+    }
+}
+
+/// Frees the id of a client whose connection is gone, a poisoned lock still holds valid ids.
+fn release_client_id(client_ids: &Mutex<ClientIds>, client_id: ClientId) {
+    client_ids
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .release(client_id);
 }
 
+/// The client ids handed out by the endpoints of a server. Endpoints started with the same pool
+/// never give the same id to two clients, e.g. the IPv4 and IPv6 endpoint of one game server.
+#[derive(Debug, Clone, Default)]
+pub struct ClientIdPool(Arc<Mutex<ClientIds>>);
+
 pub struct Endpoint {
     clients: HashMap<ClientId, ClientConnection>,
+    client_ids: Arc<Mutex<ClientIds>>,
     payloads_receiver: mpsc::Receiver<ClientPayload>,
     close_sender: broadcast::Sender<()>,
+    max_pending_bytes: usize,
 
     #[cfg(not(feature = "bevy"))]
     pub(crate) internal_receiver_closed: bool,
@@ -130,15 +278,38 @@ impl Endpoint {
         message: ProtocolMessage,
     ) -> Result<(), QuinnetError> {
         if let Some(client) = self.clients.get(&client_id) {
-            match client.sender.try_send(message) {
-                Ok(_) => Ok(()),
-                Err(err) => match err {
+            self.enqueue(client, message)
+        } else {
+            Err(QuinnetError::UnknownClient(client_id))
+        }
+    }
+
+    /// Queues a message for the sender task of the client.
+    /// A client with too many bytes queued already is told to close instead.
+    fn enqueue(
+        &self,
+        client: &ClientConnection,
+        message: ProtocolMessage,
+    ) -> Result<(), QuinnetError> {
+        let bytes = message.encoded_len();
+        if !client.backlog.push(bytes, self.max_pending_bytes) {
+            client.backlog.pop(bytes);
+            warn!(
+                "Client {} does not read its messages, disconnecting it",
+                client.client_id
+            );
+            let _ = client.close_sender.send(());
+            return Err(QuinnetError::FullQueue);
+        }
+        match client.sender.try_send(message) {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                client.backlog.pop(bytes);
+                match err {
                     mpsc::error::TrySendError::Full(_) => Err(QuinnetError::FullQueue),
                     mpsc::error::TrySendError::Closed(_) => Err(QuinnetError::ChannelClosed),
-                },
+                }
             }
-        } else {
-            Err(QuinnetError::UnknownClient(client_id))
         }
     }
 
@@ -173,15 +344,7 @@ impl Endpoint {
 
     pub fn broadcast_message(&self, message: ProtocolMessage) -> Result<(), QuinnetError> {
         for (_, client_connection) in self.clients.iter() {
-            match client_connection.sender.try_send(message.clone()) {
-                Ok(_) => {}
-                Err(err) => {
-                    return match err {
-                        mpsc::error::TrySendError::Full(_) => Err(QuinnetError::FullQueue),
-                        mpsc::error::TrySendError::Closed(_) => Err(QuinnetError::ChannelClosed),
-                    }
-                }
-            };
+            self.enqueue(client_connection, message.clone())?;
         }
         Ok(())
     }
@@ -200,17 +363,21 @@ impl Endpoint {
             message = self.internal_receiver.recv(), if !self.internal_receiver_closed => {
                 match message {
                     Some(InternalAsyncMessage::ClientConnected(connection)) => {
-                        let id = connection.client_id;
+                        let (id, address) = (connection.client_id, connection.address);
                         self.clients.insert(id, connection);
-                        EndpointEvent::Connect(id)
+                        EndpointEvent::Connect(id, address)
                     },
-                    Some(InternalAsyncMessage::ClientLostConnection(client_id)) => {
+                    Some(InternalAsyncMessage::ClientLostConnection(client_id, reason)) => {
                         self.clients.remove(&client_id);
-                        EndpointEvent::Disconnect(client_id)
+                        release_client_id(&self.client_ids, client_id);
+                        EndpointEvent::Disconnect(client_id, reason)
                     },
                     Some(InternalAsyncMessage::UnsupportedVersionMessage{client_id, version}) => {
                         EndpointEvent::UnsupportedVersionMessage{client_id, version}
                     },
+                    Some(InternalAsyncMessage::ProtocolError{client_id, description}) => {
+                        EndpointEvent::ProtocolError{client_id, description}
+                    },
                     None => {
                         self.internal_receiver_closed = true;
                         EndpointEvent::SocketClosed
@@ -246,6 +413,26 @@ impl Endpoint {
         }
     }
 
+    /// Enables or disables logging every message sent to or received from the client.
+    pub fn set_protocol_tracing(
+        &self,
+        client_id: ClientId,
+        enabled: bool,
+    ) -> Result<(), QuinnetError> {
+        match self.clients.get(&client_id) {
+            Some(client_connection) => {
+                client_connection.tracing.store(enabled, Ordering::Relaxed);
+                Ok(())
+            }
+            None => Err(QuinnetError::UnknownClient(client_id)),
+        }
+    }
+
+    /// Number of clients currently connected.
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
     pub fn disconnect_client(&mut self, client_id: ClientId) -> Result<(), QuinnetError> {
         match self.clients.remove(&client_id) {
             Some(client_connection) => match client_connection.close_sender.send(()) {
@@ -275,6 +462,7 @@ impl Endpoint {
 pub struct Server {
     runtime: runtime::Handle,
     endpoint: Option<Endpoint>,
+    client_ids: ClientIdPool,
 }
 
 impl Server {
@@ -283,9 +471,16 @@ impl Server {
         Server {
             runtime: runtime::Handle::current(),
             endpoint: None,
+            client_ids: ClientIdPool::default(),
         }
     }
 
+    /// Hands out the client ids of the endpoint from the given pool instead of a pool of its own.
+    pub fn with_client_ids(mut self, client_ids: ClientIdPool) -> Self {
+        self.client_ids = client_ids;
+        self
+    }
+
     pub fn endpoint(&self) -> &Endpoint {
         self.endpoint.as_ref().unwrap()
     }
@@ -318,6 +513,7 @@ impl Server {
         cert_mode: CertificateRetrievalMode,
         alpns: Vec<String>,
     ) -> Result<ServerCertificate, QuinnetError> {
+        let max_idle_timeout = config.max_idle_timeout()?;
         let server_adr_str = format!("{}:{}", config.local_bind_host, config.port);
         let server_addr = server_adr_str
             .to_socket_addrs()?
@@ -344,7 +540,7 @@ impl Server {
 
         Arc::get_mut(&mut server_config.transport)
             .ok_or(QuinnetError::LockAcquisitionFailure)?
-            .max_idle_timeout(Duration::from_secs(60).try_into().ok());
+            .max_idle_timeout(Some(max_idle_timeout));
 
         let (from_clients_sender, from_clients_receiver) =
             mpsc::channel::<ClientPayload>(DEFAULT_MESSAGE_QUEUE_SIZE);
@@ -354,8 +550,11 @@ impl Server {
         let (endpoint_close_sender, endpoint_close_receiver) =
             broadcast::channel(DEFAULT_KILL_MESSAGE_QUEUE_SIZE);
 
+        let client_ids = self.client_ids.0.clone();
+
         info!("Starting endpoint on: {} ...", server_adr_str);
 
+        let client_ids_for_endpoint_task = client_ids.clone();
         self.runtime.spawn(async move {
             endpoint_task(
                 server_config,
@@ -363,14 +562,17 @@ impl Server {
                 to_sync_server.clone(),
                 endpoint_close_receiver,
                 from_clients_sender.clone(),
+                client_ids_for_endpoint_task,
             )
             .await;
         });
 
         self.endpoint = Some(Endpoint {
             clients: HashMap::new(),
+            client_ids,
             payloads_receiver: from_clients_receiver,
             close_sender: endpoint_close_sender,
+            max_pending_bytes: config.max_pending_bytes,
             internal_receiver: from_async_server,
             #[cfg(not(feature = "bevy"))]
             internal_receiver_closed: false,
@@ -401,8 +603,8 @@ async fn endpoint_task(
     to_sync_server: mpsc::Sender<InternalAsyncMessage>,
     mut close_receiver: broadcast::Receiver<()>,
     from_clients_sender: mpsc::Sender<ClientPayload>,
+    client_ids: Arc<Mutex<ClientIds>>,
 ) {
-    let mut client_gen_id: ClientId = 0;
     let mut client_id_mappings = HashMap::new();
 
     let endpoint = QuinnEndpoint::server(endpoint_config, endpoint_adr)
@@ -418,8 +620,17 @@ async fn endpoint_task(
                 match connecting.await {
                     Err(err) => error!("An incoming connection failed: {}", err),
                     Ok(connection) => {
-                        client_gen_id += 1; // TODO Fix: Better id generation/check
-                        let client_id = client_gen_id;
+                        let client_id = client_ids
+                            .lock()
+                            .unwrap_or_else(|poisoned| poisoned.into_inner())
+                            .allocate();
+                        let client_id = match client_id {
+                            Some(client_id) => client_id,
+                            None => {
+                                error!("No client id left for the connection from {}", connection.remote_address());
+                                continue;
+                            }
+                        };
                         client_id_mappings.insert(connection.stable_id(), client_id);
 
                         handle_client_connection(
@@ -463,6 +674,16 @@ async fn handle_client_connection(
     let close_sender_for_sender_task = client_close_sender.clone();
     let close_sender_for_receiver_task = client_close_sender.clone();
 
+    let tracing = Arc::new(AtomicBool::new(false));
+    let tracing_for_sender_task = tracing.clone();
+    let tracing_for_receiver_task = tracing.clone();
+
+    let backlog = Arc::new(Backlog::default());
+    let backlog_for_sender_task = backlog.clone();
+    let backlog_for_receiver_task = backlog.clone();
+
+    let connection_for_teardown = connection.clone();
+    let connection_for_receiver_task = connection.clone();
     tokio::spawn(async move {
         if let Ok((send_stream, recv_stream)) = connection.accept_bi().await {
             tokio::spawn(async move {
@@ -473,6 +694,8 @@ async fn handle_client_connection(
                     client_close_receiver,
                     close_sender_for_sender_task,
                     to_sync_server_clone_for_sender_task,
+                    tracing_for_sender_task,
+                    backlog_for_sender_task,
                 )
                 .await
             });
@@ -485,6 +708,9 @@ async fn handle_client_connection(
                     close_sender_for_receiver_task,
                     from_clients_sender,
                     to_sync_server_clone_for_receiver_task,
+                    tracing_for_receiver_task,
+                    backlog_for_receiver_task,
+                    move || connection_for_receiver_task.close_reason(),
                 )
                 .await
             });
@@ -492,35 +718,90 @@ async fn handle_client_connection(
     });
 
     // Signal the sync server of this new connection
-    to_sync_server
-        .send(InternalAsyncMessage::ClientConnected(ClientConnection {
+    let signaled = signal_client_connected(
+        to_sync_server,
+        ClientConnection {
             client_id,
+            address: connection_for_teardown.remote_address(),
             sender: to_client_sender,
             close_sender: client_close_sender.clone(),
-        }))
+            tracing,
+            backlog,
+        },
+    )
+    .await;
+    if !signaled {
+        connection_for_teardown.close(VarInt::from_u32(0), b"server shutting down");
+    }
+}
+
+/// Hands a new client over to the sync server. If the sync server is gone, e.g. because it is
+/// shutting down, the tasks of the client are told to stop and false is returned.
+async fn signal_client_connected(
+    to_sync_server: &mpsc::Sender<InternalAsyncMessage>,
+    connection: ClientConnection,
+) -> bool {
+    let client_id = connection.client_id;
+    let close_sender = connection.close_sender.clone();
+    match to_sync_server
+        .send(InternalAsyncMessage::ClientConnected(connection))
         .await
-        .expect("Failed to signal connection to sync client");
+    {
+        Ok(()) => true,
+        Err(_) => {
+            warn!("Unable to signal the connection of client {client_id}, closing it");
+            // no task may be subscribed yet, in which case there is nothing to stop
+            let _ = close_sender.send(());
+            false
+        }
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn client_sender_task(
     client_id: ClientId,
-    send_stream: quinn::SendStream,
+    send_stream: impl AsyncWrite + Unpin,
     mut to_client_receiver: mpsc::Receiver<ProtocolMessage>,
     mut close_receiver: broadcast::Receiver<()>,
     close_sender: broadcast::Sender<()>,
     to_sync_server: mpsc::Sender<InternalAsyncMessage>,
+    tracing: Arc<AtomicBool>,
+    backlog: Arc<Backlog>,
 ) {
     let mut framed_send_stream = FramedWrite::new(send_stream, BattleshipPlusCodec::default());
 
     tokio::select! {
+        // polled first, so a client that is never out of messages can still be disconnected
+        biased;
         _ = close_receiver.recv() => {
             trace!("Sending half of stream forced to disconnect for client: {}", client_id)
         }
         _ = async {
+            let mut batch = Vec::with_capacity(MAX_BATCH_SIZE);
             while let Some(message) = to_client_receiver.recv().await {
-                // TODO Perf: Batch frames for a send_all
+                // whatever else is queued already goes out with the same flush
+                batch.push(message);
+                while batch.len() < MAX_BATCH_SIZE {
+                    match to_client_receiver.try_recv() {
+                        Ok(message) => batch.push(message),
+                        Err(_) => break,
+                    }
+                }
+                let mut bytes = 0;
+                let mut sent = Ok(());
+                for message in batch.drain(..) {
+                    trace_message(client_id, "<-", &message, &tracing);
+                    bytes += message.encoded_len();
+                    if sent.is_ok() {
+                        sent = framed_send_stream.feed(&message).await;
+                    }
+                }
+                if sent.is_ok() {
+                    sent = SinkExt::<&ProtocolMessage>::flush(&mut framed_send_stream).await;
+                }
+                backlog.pop(bytes);
                 // TODO Clean: Error handling
-                if let Err(err) = framed_send_stream.send(message.clone()).await {
+                if let Err(err) = sent {
                     error!("Error while sending to client {}: {}", client_id, err);
                     error!("Client {} seems disconnected, closing resources", client_id);
                     if close_sender.send(()).is_err() {
@@ -532,24 +813,32 @@ async fn client_sender_task(
     }
     trace!("Sending half of stream closed for client: {}", client_id);
     if let Err(e) = to_sync_server
-        .send(InternalAsyncMessage::ClientLostConnection(client_id))
+        .send(InternalAsyncMessage::ClientLostConnection(
+            client_id,
+            backlog.disconnect_reason(),
+        ))
         .await
     {
         debug!("Failed to signal connection lost to sync server: {e}")
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn client_receiver_task(
     client_id: ClientId,
-    recv_stream: quinn::RecvStream,
+    recv_stream: impl AsyncRead + Unpin,
     mut close_receiver: broadcast::Receiver<()>,
     close_sender: broadcast::Sender<()>,
     from_clients_sender: mpsc::Sender<ClientPayload>,
     to_sync_server: mpsc::Sender<InternalAsyncMessage>,
+    tracing: Arc<AtomicBool>,
+    backlog: Arc<Backlog>,
+    close_reason: impl FnOnce() -> Option<ConnectionError>,
 ) {
-    tokio::select! {
+    let stream_ended = tokio::select! {
         _ = close_receiver.recv() => {
-            trace!("Receiving half of stream forced to disconnect for client: {}", client_id)
+            trace!("Receiving half of stream forced to disconnect for client: {}", client_id);
+            false
         }
         _ = async {
             let mut frame_recv = FramedRead::new(recv_stream, BattleshipPlusCodec::default());
@@ -559,6 +848,9 @@ async fn client_receiver_task(
             while let Some(result) = frame_recv.next().await {
                 match result {
                     Ok(message) => {
+                        if let Some(message) = &message {
+                            trace_message(client_id, "->", message, &tracing);
+                        }
                         from_client_sender
                             .send(ClientPayload {
                                 client_id,
@@ -573,15 +865,32 @@ async fn client_receiver_task(
                             .await
                             .expect("Failed to signal message with unsupported version to sync server");
                     }
+                    Err(CodecError::Protocol(description)) => {
+                        // the stream can not be resynchronized after a malformed frame
+                        let _ = to_sync_server
+                            .send(InternalAsyncMessage::ProtocolError{client_id, description})
+                            .await;
+                        break;
+                    }
                     Err(_other_error) => {
                         break;
                     }
                 }
             }
             trace!("Receiving half of stream ended for client: {}", client_id)
-        } => {}
-    }
+        } => true
+    };
     trace!("Receiving half of stream closed for client: {}", client_id);
+    // must be known before the sender task is stopped, as it reports the disconnect
+    if let Some(error) = stream_ended.then(close_reason).flatten() {
+        match leave_reason(&error) {
+            Some(reason) => {
+                info!("Client {client_id} left: {reason}");
+                backlog.left.store(true, Ordering::Relaxed);
+            }
+            None => info!("Client {client_id} dropped the connection: {error}"),
+        }
+    }
     if close_sender.send(()).is_err() {
         error!(
             "Failed to close all client streams & resources for client {}",
@@ -591,16 +900,45 @@ async fn client_receiver_task(
         // per default the writer task should notify the sync server but now we could not reach it
         // so we notify the close on our own behalf.
         let _ = to_sync_server
-            .send(InternalAsyncMessage::ClientLostConnection(client_id))
+            .send(InternalAsyncMessage::ClientLostConnection(
+                client_id,
+                backlog.disconnect_reason(),
+            ))
             .await;
     }
 }
 
+/// The reason given by a client that closed its connection because it leaves, None if the
+/// connection ended in any other way.
+fn leave_reason(error: &ConnectionError) -> Option<String> {
+    match error {
+        ConnectionError::ApplicationClosed(close)
+            if close.error_code == VarInt::from_u32(LEAVE_ERROR_CODE) =>
+        {
+            Some(String::from_utf8_lossy(&close.reason).into_owned())
+        }
+        _ => None,
+    }
+}
+
+// Logged above debug level, so a single client can be followed without the noise of all others.
+fn trace_message(
+    client_id: ClientId,
+    direction: &str,
+    message: &ProtocolMessage,
+    tracing: &AtomicBool,
+) {
+    if tracing.load(Ordering::Relaxed) {
+        info!("[client {client_id}] {direction} {message:?}");
+    }
+}
+
 #[cfg(feature = "bevy")]
 fn create_server(mut commands: Commands, runtime: Res<AsyncRuntime>) {
     commands.insert_resource(Server {
         endpoint: None,
         runtime: runtime.handle().clone(),
+        client_ids: ClientIdPool::default(),
     });
 }
 
@@ -615,17 +953,27 @@ fn update_sync_server(
         while let Ok(message) = endpoint.internal_receiver.try_recv() {
             match message {
                 InternalAsyncMessage::ClientConnected(connection) => {
-                    let id = connection.client_id;
+                    let (id, address) = (connection.client_id, connection.address);
                     endpoint.clients.insert(id, connection);
-                    connection_events.send(ConnectionEvent { id });
+                    connection_events.send(ConnectionEvent { id, address });
                 }
-                InternalAsyncMessage::ClientLostConnection(client_id) => {
+                InternalAsyncMessage::ClientLostConnection(client_id, reason) => {
                     endpoint.clients.remove(&client_id);
-                    connection_lost_events.send(ConnectionLostEvent { id: client_id });
+                    release_client_id(&endpoint.client_ids, client_id);
+                    connection_lost_events.send(ConnectionLostEvent {
+                        id: client_id,
+                        reason,
+                    });
                 }
                 InternalAsyncMessage::UnsupportedVersionMessage { client_id, version } => {
                     warn!("received message with unsupported version {version} on connection {client_id}")
                 }
+                InternalAsyncMessage::ProtocolError {
+                    client_id,
+                    description,
+                } => {
+                    warn!("received malformed message on connection {client_id}: {description}")
+                }
             }
         }
     }
@@ -658,9 +1006,453 @@ impl Plugin for QuinnetServerPlugin {
 #[derive(Debug)]
 pub enum EndpointEvent {
     Payload(Box<ClientPayload>),
-    Connect(ClientId),
-    Disconnect(ClientId),
-    UnsupportedVersionMessage { client_id: ClientId, version: u8 },
+    Connect(ClientId, SocketAddr),
+    Disconnect(ClientId, DisconnectReason),
+    UnsupportedVersionMessage {
+        client_id: ClientId,
+        version: u8,
+    },
+    ProtocolError {
+        client_id: ClientId,
+        description: String,
+    },
     SocketClosed,
     NoMorePayloads,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::net::SocketAddr;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use futures::sink::SinkExt;
+    use futures_util::StreamExt;
+    use tokio::sync::{broadcast, mpsc};
+    use tokio_util::codec::{FramedRead, FramedWrite};
+
+    use quinn::{ApplicationClose, ConnectionError, VarInt};
+
+    use battleship_plus_common::codec::BattleshipPlusCodec;
+    use battleship_plus_common::messages::{NextTurn, ProtocolMessage, ServerConfigRequest};
+    use bevy_quinnet_common::{DEFAULT_MESSAGE_QUEUE_SIZE, LEAVE_ERROR_CODE};
+
+    #[cfg(not(feature = "bevy"))]
+    use super::EndpointEvent;
+    use super::{
+        client_receiver_task, client_sender_task, signal_client_connected, Backlog,
+        ClientConnection, ClientId, ClientIds, ClientPayload, DisconnectReason, Endpoint,
+        InternalAsyncMessage, QuinnetError, ServerConfigurationData, MAX_BATCH_SIZE,
+    };
+    #[cfg(not(feature = "bevy"))]
+    use super::{CertificateRetrievalMode, ClientIdPool, Server};
+
+    struct CapturingLogger(Mutex<Vec<String>>);
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(Vec::new()));
+
+    fn client_address() -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], 4000))
+    }
+
+    #[tokio::test]
+    async fn malformed_frame_raises_protocol_error() {
+        // valid header announcing a two byte payload which is no PacketPayload
+        let frame = [battleship_plus_common::PROTOCOL_VERSION, 0, 2, 0xff, 0xff];
+
+        let (close_sender, close_receiver) = broadcast::channel(1);
+        let (from_clients_sender, mut from_clients_receiver) =
+            mpsc::channel::<ClientPayload>(DEFAULT_MESSAGE_QUEUE_SIZE);
+        let (to_sync_server, mut internal_receiver) = mpsc::channel(1);
+        let _close_subscriber = close_sender.subscribe();
+
+        client_receiver_task(
+            42,
+            &frame[..],
+            close_receiver,
+            close_sender,
+            from_clients_sender,
+            to_sync_server,
+            Default::default(),
+            Default::default(),
+            || None,
+        )
+        .await;
+
+        assert!(matches!(
+            internal_receiver.recv().await,
+            Some(InternalAsyncMessage::ProtocolError { client_id: 42, .. })
+        ));
+        assert!(from_clients_receiver.recv().await.is_none());
+    }
+
+    #[cfg(not(feature = "bevy"))]
+    #[tokio::test]
+    async fn protocol_tracing_is_limited_to_traced_clients() {
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Info);
+
+        let mut frame = FramedWrite::new(Vec::new(), BattleshipPlusCodec::default());
+        let message: ProtocolMessage = ServerConfigRequest {}.into();
+        frame.send(message).await.unwrap();
+        let frame = frame.into_inner();
+
+        for (client_id, traced) in [(7, true), (8, false)] {
+            let (close_sender, close_receiver) = broadcast::channel(1);
+            let (from_clients_sender, mut from_clients_receiver) =
+                mpsc::channel::<ClientPayload>(DEFAULT_MESSAGE_QUEUE_SIZE);
+            let (to_sync_server, _internal_receiver) = mpsc::channel(1);
+            let _close_subscriber = close_sender.subscribe();
+
+            client_receiver_task(
+                client_id,
+                &frame[..],
+                close_receiver,
+                close_sender,
+                from_clients_sender,
+                to_sync_server,
+                Arc::new(AtomicBool::new(traced)),
+                Default::default(),
+                || None,
+            )
+            .await;
+            assert!(from_clients_receiver.recv().await.is_some());
+        }
+
+        let logs = LOGGER.0.lock().unwrap();
+        assert!(logs
+            .iter()
+            .any(|line| line.starts_with("[client 7] ->") && line.contains("ServerConfigRequest")));
+        assert!(!logs.iter().any(|line| line.starts_with("[client 8]")));
+    }
+
+    #[cfg(not(feature = "bevy"))]
+    #[tokio::test]
+    async fn leaving_client_is_told_apart_from_dropped_connection() {
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Info);
+
+        let left = ConnectionError::ApplicationClosed(ApplicationClose {
+            error_code: VarInt::from_u32(LEAVE_ERROR_CODE),
+            reason: "game over".into(),
+        });
+        for (client_id, error, expected_reason) in [
+            (21, left, DisconnectReason::Left),
+            (
+                22,
+                ConnectionError::TimedOut,
+                DisconnectReason::ConnectionLost,
+            ),
+        ] {
+            let (close_sender, close_receiver) = broadcast::channel(1);
+            let sender_close_receiver = close_sender.subscribe();
+            let (from_clients_sender, _from_clients_receiver) =
+                mpsc::channel::<ClientPayload>(DEFAULT_MESSAGE_QUEUE_SIZE);
+            let (_sender, to_client_receiver) = mpsc::channel(DEFAULT_MESSAGE_QUEUE_SIZE);
+            let (to_sync_server, mut internal_receiver) = mpsc::channel(1);
+            let backlog = Arc::new(Backlog::default());
+
+            // the stream ends as the client closed the connection
+            client_receiver_task(
+                client_id,
+                &b""[..],
+                close_receiver,
+                close_sender.clone(),
+                from_clients_sender,
+                to_sync_server.clone(),
+                Default::default(),
+                backlog.clone(),
+                move || Some(error),
+            )
+            .await;
+            client_sender_task(
+                client_id,
+                Vec::new(),
+                to_client_receiver,
+                sender_close_receiver,
+                close_sender,
+                to_sync_server,
+                Default::default(),
+                backlog,
+            )
+            .await;
+
+            match internal_receiver.recv().await {
+                Some(InternalAsyncMessage::ClientLostConnection(id, reason)) => {
+                    assert_eq!(id, client_id);
+                    assert_eq!(reason, expected_reason);
+                }
+                _ => panic!("the sync server was not told about client {client_id}"),
+            }
+        }
+
+        let logs = LOGGER.0.lock().unwrap();
+        assert!(logs.iter().any(|line| line == "Client 21 left: game over"));
+        assert!(logs
+            .iter()
+            .any(|line| line.starts_with("Client 22 dropped the connection")));
+    }
+
+    #[tokio::test]
+    async fn closed_sync_channel_tears_down_connection() {
+        let (to_sync_server, internal_receiver) = mpsc::channel(1);
+        drop(internal_receiver);
+        let (sender, _to_client_receiver) = mpsc::channel(DEFAULT_MESSAGE_QUEUE_SIZE);
+        let (close_sender, mut close_receiver) = broadcast::channel(1);
+
+        let signaled = signal_client_connected(
+            &to_sync_server,
+            ClientConnection {
+                client_id: 42,
+                address: client_address(),
+                sender,
+                close_sender,
+                tracing: Default::default(),
+                backlog: Default::default(),
+            },
+        )
+        .await;
+
+        assert!(!signaled);
+        // the tasks of the client are told to stop
+        assert!(close_receiver.recv().await.is_ok());
+    }
+
+    #[test]
+    fn idle_timeout_is_stored() {
+        let config = ServerConfigurationData::new("127.0.0.1".into(), 6000, "0.0.0.0".into());
+        assert_eq!(config.idle_timeout, None);
+
+        let config = config.with_idle_timeout(Duration::from_secs(5));
+        assert_eq!(config.idle_timeout, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn invalid_idle_timeout_is_rejected() {
+        let config = ServerConfigurationData::new("127.0.0.1".into(), 6000, "0.0.0.0".into());
+        assert!(config.max_idle_timeout().is_ok());
+
+        for idle_timeout in [Duration::ZERO, Duration::MAX] {
+            let config = config.clone().with_idle_timeout(idle_timeout);
+            assert!(matches!(
+                config.max_idle_timeout(),
+                Err(QuinnetError::InvalidIdleTimeout(timeout)) if timeout == idle_timeout
+            ));
+        }
+    }
+
+    #[test]
+    fn client_ids_in_use_are_skipped() {
+        let mut client_ids = ClientIds::default();
+        assert_eq!(client_ids.allocate(), Some(1));
+        assert_eq!(client_ids.allocate(), Some(2));
+        assert_eq!(client_ids.allocate(), Some(3));
+
+        // released ids are only reused after the largest id, ids still in use never
+        client_ids.release(2);
+        assert_eq!(client_ids.allocate(), Some(4));
+        client_ids.last = ClientId::MAX - 1;
+        assert_eq!(client_ids.allocate(), Some(ClientId::MAX));
+        assert_eq!(client_ids.allocate(), Some(2));
+        assert_eq!(client_ids.allocate(), Some(5));
+    }
+
+    #[cfg(not(feature = "bevy"))]
+    #[tokio::test]
+    async fn endpoints_sharing_a_pool_hand_out_distinct_client_ids() {
+        let client_ids = ClientIdPool::default();
+        let mut servers = vec![];
+        for _ in 0..2 {
+            let mut server = Server::new_standalone().with_client_ids(client_ids.clone());
+            server
+                .start_endpoint(
+                    ServerConfigurationData::new(
+                        "localhost".to_string(),
+                        0,
+                        "127.0.0.1".to_string(),
+                    ),
+                    CertificateRetrievalMode::GenerateSelfSigned,
+                )
+                .unwrap();
+            servers.push(server);
+        }
+
+        let allocate = |server: &Server| server.endpoint().client_ids.lock().unwrap().allocate();
+        assert_eq!(allocate(&servers[0]), Some(1));
+        assert_eq!(allocate(&servers[1]), Some(2));
+        assert_eq!(allocate(&servers[0]), Some(3));
+
+        // a server of its own starts over
+        let mut other = Server::new_standalone();
+        other
+            .start_endpoint(
+                ServerConfigurationData::new("localhost".to_string(), 0, "127.0.0.1".to_string()),
+                CertificateRetrievalMode::GenerateSelfSigned,
+            )
+            .unwrap();
+        assert_eq!(allocate(&other), Some(1));
+    }
+
+    #[cfg(not(feature = "bevy"))]
+    #[tokio::test]
+    async fn lost_connection_frees_the_client_id() {
+        let (close_sender, _close_receiver) = broadcast::channel(1);
+        let (sender, _to_client_receiver) = mpsc::channel(1);
+        let (_, payloads_receiver) = mpsc::channel(1);
+        let (to_sync_server, internal_receiver) = mpsc::channel(1);
+        let mut endpoint = Endpoint {
+            clients: HashMap::new(),
+            client_ids: Default::default(),
+            payloads_receiver,
+            close_sender: broadcast::channel(1).0,
+            max_pending_bytes: usize::MAX,
+            internal_receiver_closed: false,
+            internal_receiver,
+        };
+        let client_id = endpoint.client_ids.lock().unwrap().allocate().unwrap();
+
+        to_sync_server
+            .send(InternalAsyncMessage::ClientConnected(ClientConnection {
+                client_id,
+                address: client_address(),
+                sender,
+                close_sender,
+                tracing: Default::default(),
+                backlog: Default::default(),
+            }))
+            .await
+            .unwrap();
+        assert!(matches!(
+            endpoint.next_event().await,
+            EndpointEvent::Connect(id, address) if id == client_id && address == client_address()
+        ));
+        assert_eq!(endpoint.client_count(), 1);
+
+        to_sync_server
+            .send(InternalAsyncMessage::ClientLostConnection(
+                client_id,
+                DisconnectReason::ConnectionLost,
+            ))
+            .await
+            .unwrap();
+        endpoint.next_event().await;
+        assert_eq!(endpoint.client_count(), 0);
+        assert!(endpoint.client_ids.lock().unwrap().in_use.is_empty());
+    }
+
+    #[tokio::test]
+    async fn client_exceeding_backlog_is_disconnected() {
+        let (close_sender, close_receiver) = broadcast::channel(1);
+        let (sender, to_client_receiver) = mpsc::channel(DEFAULT_MESSAGE_QUEUE_SIZE);
+        let (to_sync_server, mut internal_receiver) = mpsc::channel(1);
+        let (_, payloads_receiver) = mpsc::channel(1);
+        let client = ClientConnection {
+            client_id: 42,
+            address: client_address(),
+            sender,
+            close_sender: close_sender.clone(),
+            tracing: Default::default(),
+            backlog: Default::default(),
+        };
+        let backlog = client.backlog.clone();
+
+        let message: ProtocolMessage = ServerConfigRequest {}.into();
+        let max_pending_bytes = 3 * message.encoded_len();
+        let (endpoint_close_sender, _) = broadcast::channel(1);
+        let (_, internal_endpoint_receiver) = mpsc::channel(1);
+        let endpoint = Endpoint {
+            clients: HashMap::from([(42, client)]),
+            client_ids: Default::default(),
+            payloads_receiver,
+            close_sender: endpoint_close_sender,
+            max_pending_bytes,
+            #[cfg(not(feature = "bevy"))]
+            internal_receiver_closed: false,
+            internal_receiver: internal_endpoint_receiver,
+        };
+
+        // the client never reads, so nothing is taken from its queue
+        for _ in 0..3 {
+            assert!(endpoint.send_message(42, message.clone()).is_ok());
+        }
+        assert!(endpoint.send_message(42, message.clone()).is_err());
+
+        // the sender task of the client stops and reports why
+        client_sender_task(
+            42,
+            Vec::new(),
+            to_client_receiver,
+            close_receiver,
+            close_sender,
+            to_sync_server,
+            Default::default(),
+            backlog,
+        )
+        .await;
+        assert!(matches!(
+            internal_receiver.recv().await,
+            Some(InternalAsyncMessage::ClientLostConnection(
+                42,
+                DisconnectReason::Backpressure
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn queued_messages_are_sent_in_order_across_batches() {
+        let (close_sender, close_receiver) = broadcast::channel(1);
+        let (sender, to_client_receiver) = mpsc::channel(DEFAULT_MESSAGE_QUEUE_SIZE);
+        let (to_sync_server, _internal_receiver) = mpsc::channel(1);
+        let backlog = Arc::new(Backlog::default());
+
+        let messages: Vec<ProtocolMessage> = (0..2 * MAX_BATCH_SIZE as u32 + 1)
+            .map(|next_player_id| {
+                NextTurn {
+                    next_player_id,
+                    ..Default::default()
+                }
+                .into()
+            })
+            .collect();
+        for message in &messages {
+            assert!(backlog.push(message.encoded_len(), usize::MAX));
+            sender.send(message.clone()).await.unwrap();
+        }
+        drop(sender);
+
+        let mut sent = Vec::new();
+        client_sender_task(
+            42,
+            &mut sent,
+            to_client_receiver,
+            close_receiver,
+            close_sender,
+            to_sync_server,
+            Default::default(),
+            backlog.clone(),
+        )
+        .await;
+
+        let received: Vec<ProtocolMessage> =
+            FramedRead::new(&sent[..], BattleshipPlusCodec::default())
+                .filter_map(|result| async move { result.unwrap() })
+                .collect()
+                .await;
+        assert_eq!(received, messages);
+        assert_eq!(backlog.pending_bytes.load(Ordering::Relaxed), 0);
+    }
+}
@@ -1,3 +1,6 @@
+-- Splits the QUIC streams into Battleship Plus messages and hands their payloads to the protobuf
+-- dissector. The fields are decoded with the .proto files in res/encoding, so add that directory
+-- to the protobuf search paths of Wireshark.
 bs_plus_protocol = Proto("bs_plus", "Battleship Plus Protocol")
 version_type = ProtoField.uint8("bs_plus.version", "Version", base.DEC)
 length_type = ProtoField.uint16("bs_plus.length", "Payload Length", base.DEC)